@@ -0,0 +1,47 @@
+-- Certificate state as last seen in contract events
+CREATE TABLE IF NOT EXISTS certificates (
+    cert_id         TEXT PRIMARY KEY,
+    owner           TEXT NOT NULL,
+    metadata_hash   TEXT NOT NULL,
+    status          TEXT NOT NULL DEFAULT 'active',
+    disputed        BOOLEAN NOT NULL DEFAULT FALSE,
+    issued_ledger   BIGINT NOT NULL,
+    issued_at       TIMESTAMPTZ,
+    updated_ledger  BIGINT NOT NULL,
+    updated_at      TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS certificates_owner_idx ON certificates (owner);
+
+-- Every lifecycle event, in ledger order
+CREATE TABLE IF NOT EXISTS certificate_events (
+    id                TEXT PRIMARY KEY,
+    cert_id           TEXT NOT NULL,
+    event             TEXT NOT NULL,
+    ledger            BIGINT NOT NULL,
+    ledger_closed_at  TIMESTAMPTZ,
+    transaction_hash  TEXT NOT NULL,
+    data              JSONB NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS certificate_events_cert_idx ON certificate_events (cert_id, ledger);
+
+-- Ownership changes, one row per transfer event
+CREATE TABLE IF NOT EXISTS transfers (
+    event_id          TEXT PRIMARY KEY REFERENCES certificate_events (id),
+    cert_id           TEXT NOT NULL,
+    from_owner        TEXT NOT NULL,
+    to_owner          TEXT NOT NULL,
+    ledger            BIGINT NOT NULL,
+    ledger_closed_at  TIMESTAMPTZ,
+    transaction_hash  TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS transfers_cert_idx ON transfers (cert_id, ledger);
+
+-- Where the indexer resumes after a restart
+CREATE TABLE IF NOT EXISTS indexer_state (
+    id      SMALLINT PRIMARY KEY DEFAULT 1 CHECK (id = 1),
+    cursor  TEXT,
+    ledger  BIGINT NOT NULL
+);
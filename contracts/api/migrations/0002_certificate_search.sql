@@ -0,0 +1,20 @@
+-- Item details used to search the index
+ALTER TABLE certificates ADD COLUMN IF NOT EXISTS brand TEXT;
+ALTER TABLE certificates ADD COLUMN IF NOT EXISTS model TEXT;
+ALTER TABLE certificates ADD COLUMN IF NOT EXISTS serial_number TEXT;
+ALTER TABLE certificates ADD COLUMN IF NOT EXISTS material TEXT;
+ALTER TABLE certificates ADD COLUMN IF NOT EXISTS manufacture_year INTEGER;
+ALTER TABLE certificates ADD COLUMN IF NOT EXISTS collection_id BIGINT;
+
+ALTER TABLE certificates ADD COLUMN IF NOT EXISTS search_text TSVECTOR
+    GENERATED ALWAYS AS (
+        to_tsvector('simple',
+            cert_id || ' ' || metadata_hash || ' ' ||
+            coalesce(brand, '') || ' ' || coalesce(model, '') || ' ' ||
+            coalesce(serial_number, '') || ' ' || coalesce(material, ''))
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS certificates_brand_idx ON certificates (lower(brand));
+CREATE INDEX IF NOT EXISTS certificates_collection_idx ON certificates (collection_id);
+CREATE INDEX IF NOT EXISTS certificates_issued_at_idx ON certificates (issued_at);
+CREATE INDEX IF NOT EXISTS certificates_search_idx ON certificates USING GIN (search_text);
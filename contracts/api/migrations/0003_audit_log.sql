@@ -0,0 +1,32 @@
+-- Operations requested through the API, hash-chained in insertion order
+CREATE TABLE IF NOT EXISTS audit_log (
+    id                BIGSERIAL PRIMARY KEY,
+    occurred_at       TIMESTAMPTZ NOT NULL,
+    actor             TEXT NOT NULL,
+    network           TEXT NOT NULL,
+    action            TEXT NOT NULL,
+    path              TEXT NOT NULL,
+    payload_hash      TEXT NOT NULL,
+    status            INTEGER NOT NULL,
+    outcome           TEXT NOT NULL,
+    transaction_hash  TEXT,
+    job_id            TEXT,
+    prev_hash         TEXT NOT NULL,
+    entry_hash        TEXT NOT NULL UNIQUE
+);
+
+CREATE INDEX IF NOT EXISTS audit_log_actor_idx ON audit_log (actor, id);
+CREATE INDEX IF NOT EXISTS audit_log_action_idx ON audit_log (action, id);
+CREATE INDEX IF NOT EXISTS audit_log_occurred_at_idx ON audit_log (occurred_at);
+
+-- Entries can only be appended
+CREATE OR REPLACE FUNCTION audit_log_append_only() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS audit_log_append_only ON audit_log;
+CREATE TRIGGER audit_log_append_only
+    BEFORE UPDATE OR DELETE OR TRUNCATE ON audit_log
+    FOR EACH STATEMENT EXECUTE FUNCTION audit_log_append_only();
@@ -0,0 +1,8 @@
+-- Contact details of certificate owners who asked to be notified of changes
+CREATE TABLE IF NOT EXISTS notification_preferences (
+    address     TEXT PRIMARY KEY,
+    email       TEXT,
+    phone       TEXT,
+    events      TEXT[] NOT NULL,
+    updated_at  TIMESTAMPTZ NOT NULL
+);
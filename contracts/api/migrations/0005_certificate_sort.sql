@@ -0,0 +1,13 @@
+-- Ledger of each certificate's latest transfer, NULL until it first changes hands
+ALTER TABLE certificates ADD COLUMN IF NOT EXISTS last_transfer_ledger BIGINT;
+
+UPDATE certificates
+SET last_transfer_ledger = latest.ledger
+FROM (SELECT cert_id, max(ledger) AS ledger FROM transfers GROUP BY cert_id) AS latest
+WHERE certificates.cert_id = latest.cert_id
+  AND certificates.last_transfer_ledger IS NULL;
+
+-- Keyset pagination for the sort orders offered by the list endpoints
+CREATE INDEX IF NOT EXISTS certificates_issued_ledger_idx ON certificates (issued_ledger, cert_id);
+CREATE INDEX IF NOT EXISTS certificates_last_transfer_idx
+    ON certificates ((COALESCE(last_transfer_ledger, 0)), cert_id);
@@ -0,0 +1,397 @@
+use anyhow::{anyhow, Result};
+use axum::{
+    body::{to_bytes, Body},
+    extract::{MatchedPath, Request, State},
+    http::{header::AUTHORIZATION, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use tracing::{error, info};
+
+use crate::{
+    auth::{Authenticator, Claims},
+    models::{AuditEntry, AuditPage, AuditVerification, ErrorResponse},
+    networks::Network,
+    pagination::AuditCursor,
+};
+
+/// `prev_hash` of the first entry
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Values of an entry's `outcome`
+pub const AUDIT_OUTCOMES: &[&str] = &["success", "failure"];
+
+/// Advisory lock serializing appends, so the hash chain has no forks
+const APPEND_LOCK: i64 = 0x5645_5249_4155_4454;
+
+/// Columns selected into an `AuditEntry`
+const AUDIT_COLUMNS: &str = "id, occurred_at, actor, network, action, path, payload_hash, status, \
+    outcome, transaction_hash, job_id, prev_hash, entry_hash";
+
+/// Tunables for the audit log
+#[derive(Debug, Clone)]
+pub struct AuditSettings {
+    pub enabled: bool,
+    pub database_url: String,
+    /// Larger request bodies are refused on audited routes
+    pub max_body_bytes: usize,
+}
+
+impl Default for AuditSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            database_url: String::new(),
+            max_body_bytes: 1024 * 1024,
+        }
+    }
+}
+
+/// Optional filters applied when reading the audit log
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub outcome: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// One operation about to be appended
+struct AuditRecord {
+    occurred_at: DateTime<Utc>,
+    actor: String,
+    network: String,
+    action: String,
+    path: String,
+    payload_hash: String,
+    status: u16,
+    outcome: &'static str,
+    transaction_hash: Option<String>,
+    job_id: Option<String>,
+}
+
+/// Append-only record of the operations the API was asked to perform
+///
+/// Each entry carries the SHA-256 of its predecessor's hash and its own
+/// fields, so editing or dropping an entry breaks the chain from that point
+/// on. The table also refuses updates and deletes.
+#[derive(Clone)]
+pub struct AuditLog {
+    pool: PgPool,
+    max_body_bytes: usize,
+}
+
+impl AuditLog {
+    /// Connect to the database and apply migrations, if enabled
+    pub async fn connect(settings: &AuditSettings) -> Result<Option<Self>> {
+        if !settings.enabled {
+            info!("Audit log disabled");
+            return Ok(None);
+        }
+        if settings.database_url.is_empty() {
+            return Err(anyhow!("DATABASE_URL is required when the audit log is enabled"));
+        }
+
+        let pool = PgPoolOptions::new()
+            .max_connections(5)
+            .connect(&settings.database_url)
+            .await
+            .map_err(|e| anyhow!("Failed to connect to database: {}", e))?;
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .map_err(|e| anyhow!("Failed to run database migrations: {}", e))?;
+
+        Ok(Some(Self {
+            pool,
+            max_body_bytes: settings.max_body_bytes,
+        }))
+    }
+
+    async fn append(&self, record: AuditRecord) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("SELECT pg_advisory_xact_lock($1)")
+            .bind(APPEND_LOCK)
+            .execute(&mut *tx)
+            .await?;
+
+        let prev_hash: Option<String> =
+            sqlx::query_scalar("SELECT entry_hash FROM audit_log ORDER BY id DESC LIMIT 1")
+                .fetch_optional(&mut *tx)
+                .await?;
+        let prev_hash = prev_hash.unwrap_or_else(|| GENESIS_HASH.to_string());
+        let entry_hash = entry_hash(
+            &prev_hash,
+            record.occurred_at,
+            &record.actor,
+            &record.network,
+            &record.action,
+            &record.path,
+            &record.payload_hash,
+            record.status,
+            record.outcome,
+            record.transaction_hash.as_deref(),
+            record.job_id.as_deref(),
+        );
+
+        sqlx::query(
+            "INSERT INTO audit_log
+                 (occurred_at, actor, network, action, path, payload_hash, status, outcome,
+                  transaction_hash, job_id, prev_hash, entry_hash)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+        )
+        .bind(record.occurred_at)
+        .bind(&record.actor)
+        .bind(&record.network)
+        .bind(&record.action)
+        .bind(&record.path)
+        .bind(&record.payload_hash)
+        .bind(i32::from(record.status))
+        .bind(record.outcome)
+        .bind(record.transaction_hash.as_deref())
+        .bind(record.job_id.as_deref())
+        .bind(&prev_hash)
+        .bind(&entry_hash)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Page through entries matching `filter`, newest first, starting before `cursor`
+    pub async fn list(
+        &self,
+        filter: &AuditFilter,
+        limit: u32,
+        cursor: Option<AuditCursor>,
+    ) -> Result<AuditPage> {
+        let sql = format!(
+            "SELECT {} FROM audit_log
+             WHERE ($1::bigint IS NULL OR id < $1)
+               AND ($3::text IS NULL OR actor = $3)
+               AND ($4::text IS NULL OR action = $4)
+               AND ($5::text IS NULL OR outcome = $5)
+               AND ($6::timestamptz IS NULL OR occurred_at >= $6)
+               AND ($7::timestamptz IS NULL OR occurred_at < $7)
+             ORDER BY id DESC
+             LIMIT $2",
+            AUDIT_COLUMNS
+        );
+
+        // Fetch one extra row to learn whether another page follows
+        let mut entries = sqlx::query_as::<_, AuditEntry>(&sql)
+            .bind(cursor.map(|cursor| cursor.before))
+            .bind(i64::from(limit) + 1)
+            .bind(filter.actor.as_deref())
+            .bind(filter.action.as_deref())
+            .bind(filter.outcome.as_deref())
+            .bind(filter.from)
+            .bind(filter.to)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let next_cursor = if entries.len() > limit as usize {
+            entries.truncate(limit as usize);
+            entries.last().map(|entry| AuditCursor { before: entry.id }.encode())
+        } else {
+            None
+        };
+
+        Ok(AuditPage {
+            entries,
+            next_cursor,
+        })
+    }
+
+    /// Recompute the hash chain from the first entry
+    pub async fn verify(&self) -> Result<AuditVerification> {
+        let sql = format!(
+            "SELECT {} FROM audit_log WHERE id > $1 ORDER BY id LIMIT 1000",
+            AUDIT_COLUMNS
+        );
+        let mut prev_hash = GENESIS_HASH.to_string();
+        let mut last_id = 0;
+        let mut checked = 0;
+
+        loop {
+            let entries = sqlx::query_as::<_, AuditEntry>(&sql)
+                .bind(last_id)
+                .fetch_all(&self.pool)
+                .await?;
+            if entries.is_empty() {
+                break;
+            }
+
+            for entry in entries {
+                let expected = entry_hash(
+                    &prev_hash,
+                    entry.occurred_at,
+                    &entry.actor,
+                    &entry.network,
+                    &entry.action,
+                    &entry.path,
+                    &entry.payload_hash,
+                    entry.status,
+                    &entry.outcome,
+                    entry.transaction_hash.as_deref(),
+                    entry.job_id.as_deref(),
+                );
+                if entry.prev_hash != prev_hash || entry.entry_hash != expected {
+                    return Ok(AuditVerification {
+                        valid: false,
+                        checked,
+                        broken_at: Some(entry.id),
+                    });
+                }
+
+                checked += 1;
+                last_id = entry.id;
+                prev_hash = entry.entry_hash;
+            }
+        }
+
+        Ok(AuditVerification {
+            valid: true,
+            checked,
+            broken_at: None,
+        })
+    }
+}
+
+/// Hash of an entry's fields chained to its predecessor's hash
+#[allow(clippy::too_many_arguments)]
+fn entry_hash(
+    prev_hash: &str,
+    occurred_at: DateTime<Utc>,
+    actor: &str,
+    network: &str,
+    action: &str,
+    path: &str,
+    payload_hash: &str,
+    status: u16,
+    outcome: &str,
+    transaction_hash: Option<&str>,
+    job_id: Option<&str>,
+) -> String {
+    // Microseconds, the precision Postgres keeps
+    let fields = [
+        prev_hash.to_string(),
+        occurred_at.timestamp_micros().to_string(),
+        actor.to_string(),
+        network.to_string(),
+        action.to_string(),
+        path.to_string(),
+        payload_hash.to_string(),
+        status.to_string(),
+        outcome.to_string(),
+        transaction_hash.unwrap_or_default().to_string(),
+        job_id.unwrap_or_default().to_string(),
+    ];
+    hex::encode(Sha256::digest(fields.join("\n").as_bytes()))
+}
+
+/// Middleware state for [`record_audit`]
+#[derive(Clone)]
+pub struct AuditGuard {
+    log: Option<AuditLog>,
+    auth: Authenticator,
+}
+
+impl AuditGuard {
+    pub fn new(log: Option<AuditLog>, auth: Authenticator) -> Self {
+        Self { log, auth }
+    }
+}
+
+/// Record the request in the audit log once it has been answered
+///
+/// Runs before authorization, so refused attempts are recorded too. The actor
+/// is the subject of a tenant API key or a valid bearer token, or
+/// `anonymous`. Failing to write the entry is logged but doesn't change the
+/// response, since the operation has already happened.
+pub async fn record_audit(State(guard): State<AuditGuard>, request: Request, next: Next) -> Response {
+    let Some(log) = guard.log else {
+        return next.run(request).await;
+    };
+
+    let actor = request
+        .extensions()
+        .get::<Claims>()
+        .map(|claims| claims.sub.clone())
+        .or_else(|| {
+            let token = request
+                .headers()
+                .get(AUTHORIZATION)?
+                .to_str()
+                .ok()?
+                .strip_prefix("Bearer ")?;
+            guard.auth.verify(token).ok().map(|claims| claims.sub)
+        })
+        .unwrap_or_else(|| "anonymous".to_string());
+    let network = request
+        .extensions()
+        .get::<Network>()
+        .map(|network| network.name.clone())
+        .unwrap_or_default();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    let action = format!("{} {}", request.method(), route);
+    let path = request.uri().path().to_string();
+
+    let (parts, body) = request.into_parts();
+    let body = match to_bytes(body, log.max_body_bytes).await {
+        Ok(body) => body,
+        Err(_) => {
+            return (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                Json(ErrorResponse::new(
+                    format!("Request body exceeds {} bytes", log.max_body_bytes),
+                    413,
+                )),
+            )
+                .into_response();
+        }
+    };
+    let payload_hash = hex::encode(Sha256::digest(&body));
+    let occurred_at = Utc::now();
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    let status = response.status();
+
+    // Pick the transaction hash or job ID out of the response
+    let (parts, body) = response.into_parts();
+    let body = to_bytes(body, usize::MAX).await.unwrap_or_default();
+    let data = serde_json::from_slice::<Value>(&body)
+        .ok()
+        .and_then(|json| json.get("data").cloned())
+        .unwrap_or_default();
+    let field = |name: &str| data.get(name).and_then(Value::as_str).map(str::to_string);
+
+    let record = AuditRecord {
+        occurred_at,
+        actor,
+        network,
+        action,
+        path,
+        payload_hash,
+        status: status.as_u16(),
+        outcome: if status.is_success() { "success" } else { "failure" },
+        transaction_hash: field("transaction_hash"),
+        job_id: (status == StatusCode::ACCEPTED).then(|| field("id")).flatten(),
+    };
+    if let Err(e) = log.append(record).await {
+        error!("Failed to write audit entry: {}", e);
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
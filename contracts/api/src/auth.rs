@@ -0,0 +1,206 @@
+use anyhow::{anyhow, Result};
+use axum::{
+    extract::Request,
+    http::{header::AUTHORIZATION, StatusCode},
+    Json,
+};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tracing::warn;
+
+use crate::{models::ErrorResponse, policy::Policy};
+
+/// Routes reserved for operators
+pub const ADMIN: &[Role] = &[Role::Admin];
+
+/// Routes that create or move certificates
+pub const ISSUERS: &[Role] = &[Role::Admin, Role::Issuer];
+
+/// Routes that only read, but aren't public
+pub const READERS: &[Role] = &[Role::Admin, Role::Issuer, Role::ReadOnly];
+
+/// Routes scoped to the account a SEP-10 session was issued for
+pub const OWNERS: &[Role] = &[Role::Owner];
+
+/// Routes open to certificate owners as well as staff
+pub const HOLDERS: &[Role] = &[Role::Admin, Role::Issuer, Role::Owner];
+
+/// Tunables for bearer-token authentication
+#[derive(Debug, Clone, Default)]
+pub struct AuthSettings {
+    pub enabled: bool,
+    pub jwt_secret: String,
+    /// Expected `iss` claim; any issuer is accepted when empty
+    pub jwt_issuer: String,
+}
+
+/// Role granted by a token's `roles` claim
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Role {
+    Admin,
+    Issuer,
+    ReadOnly,
+    /// Holder of a Stellar account, proven through SEP-10
+    Owner,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::Issuer => "issuer",
+            Role::ReadOnly => "read-only",
+            Role::Owner => "owner",
+        }
+    }
+}
+
+/// Claims carried by an API token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String,
+    #[serde(default)]
+    pub roles: Vec<Role>,
+    pub exp: u64,
+    /// Tenant the token is scoped to; platform tokens carry none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+}
+
+/// Validates HS256 bearer tokens
+///
+/// When authentication is disabled every request is let through unchecked,
+/// except on routes guarded with [`Authenticator::require_token`].
+#[derive(Clone)]
+pub struct Authenticator {
+    enabled: bool,
+    can_issue: bool,
+    issuer: String,
+    key: Arc<DecodingKey>,
+    encoding_key: Arc<EncodingKey>,
+    validation: Arc<Validation>,
+}
+
+impl Authenticator {
+    pub fn new(settings: &AuthSettings) -> Result<Self> {
+        if settings.enabled && settings.jwt_secret.is_empty() {
+            return Err(anyhow!("JWT_SECRET is required when authentication is enabled"));
+        }
+
+        let mut validation = Validation::new(Algorithm::HS256);
+        if !settings.jwt_issuer.is_empty() {
+            validation.set_issuer(&[settings.jwt_issuer.as_str()]);
+        }
+
+        Ok(Self {
+            enabled: settings.enabled,
+            can_issue: !settings.jwt_secret.is_empty(),
+            issuer: settings.jwt_issuer.clone(),
+            key: Arc::new(DecodingKey::from_secret(settings.jwt_secret.as_bytes())),
+            encoding_key: Arc::new(EncodingKey::from_secret(settings.jwt_secret.as_bytes())),
+            validation: Arc::new(validation),
+        })
+    }
+
+    /// Whether role checks are enforced
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Whether a signing secret is configured, so tokens can be issued
+    pub fn can_issue(&self) -> bool {
+        self.can_issue
+    }
+
+    /// Sign a token for `sub` granting `roles`, valid for `ttl_secs`
+    ///
+    /// A token issued for a `tenant` is only accepted with its `X-Tenant`.
+    pub fn issue(&self, sub: &str, roles: &[Role], ttl_secs: u64, tenant: Option<&str>) -> Result<String> {
+        if !self.can_issue {
+            return Err(anyhow!("JWT_SECRET is required to issue tokens"));
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let claims = IssuedClaims {
+            sub,
+            roles,
+            exp: now + ttl_secs,
+            iat: now,
+            iss: (!self.issuer.is_empty()).then_some(self.issuer.as_str()),
+            tenant,
+        };
+
+        encode(&Header::new(Algorithm::HS256), &claims, &self.encoding_key)
+            .map_err(|e| anyhow!("Failed to sign token: {}", e))
+    }
+
+    /// Decode a token, checking its signature, expiry and issuer
+    pub fn verify(&self, token: &str) -> Result<Claims> {
+        if !self.can_issue {
+            return Err(anyhow!("Token authentication is not configured (JWT_SECRET)"));
+        }
+        decode::<Claims>(token, &self.key, &self.validation)
+            .map(|data| data.claims)
+            .map_err(|e| anyhow!("Invalid token: {}", e))
+    }
+
+    /// Policy admitting tokens with any of `roles`
+    pub fn require(&self, roles: &'static [Role]) -> Policy {
+        Policy::new(self.clone(), roles, false)
+    }
+
+    /// Like [`Authenticator::require`], but checked even when authentication is disabled
+    ///
+    /// Used on routes that act on behalf of the token's subject and have no
+    /// meaning without one.
+    pub fn require_token(&self, roles: &'static [Role]) -> Policy {
+        Policy::new(self.clone(), roles, true)
+    }
+}
+
+/// Claims written into tokens issued by this server
+#[derive(Serialize)]
+struct IssuedClaims<'a> {
+    sub: &'a str,
+    roles: &'a [Role],
+    exp: u64,
+    iat: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    iss: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tenant: Option<&'a str>,
+}
+
+/// Verify the request's bearer token
+pub fn bearer_claims(
+    auth: &Authenticator,
+    request: &Request,
+) -> Result<Claims, (StatusCode, Json<ErrorResponse>)> {
+    let token = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(|| {
+            (
+                StatusCode::UNAUTHORIZED,
+                Json(ErrorResponse::unauthorized("Missing bearer token".to_string())),
+            )
+        })?;
+
+    auth.verify(token).map_err(|e| {
+        warn!("Rejected token: {}", e);
+        (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse::unauthorized(e.to_string())),
+        )
+    })
+}
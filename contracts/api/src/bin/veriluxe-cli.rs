@@ -0,0 +1,420 @@
+//! Administrative command line for the VeriLuxe contract
+//!
+//! Talks to Soroban RPC directly with the API's own client and settings, so
+//! maintenance tasks don't need a running API server.
+
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use tracing_subscriber::{filter::LevelFilter, EnvFilter};
+use veriluxe_api::{
+    config::{Config, ConfigOptions},
+    import::{ImportFormat, ImportManager, ImportStatus},
+    models::{CertId, ContractEvent, MetadataHash, StellarAddress},
+    secrets::SecretStore,
+    signer::load_signer,
+    soroban_client::{SorobanClient, TransactionOutcome},
+};
+
+/// Administer the VeriLuxe certificate contract
+#[derive(Debug, Parser)]
+#[command(name = "veriluxe-cli", version, about)]
+struct Cli {
+    /// TOML settings file [default: config.toml, if present]
+    #[arg(long, global = true, env = "CONFIG_FILE")]
+    config: Option<PathBuf>,
+
+    /// Profile of the settings file to apply
+    #[arg(long, global = true, env = "CONFIG_PROFILE")]
+    profile: Option<String>,
+
+    /// Contract to use instead of `FASHION_AUTH_CONTRACT_ID`
+    #[arg(long, global = true)]
+    contract_id: Option<String>,
+
+    /// Return after submitting a transaction, without waiting for its result
+    #[arg(long, global = true)]
+    no_wait: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Upload the contract wasm and create a new contract instance
+    Deploy {
+        /// Contract wasm [default: SANDBOX_WASM_PATH]
+        #[arg(long)]
+        wasm: Option<PathBuf>,
+    },
+    /// Initialize the contract with its admin
+    Init {
+        /// Admin account [default: the configured admin signer]
+        #[arg(long)]
+        admin: Option<StellarAddress>,
+    },
+    /// Issue a certificate
+    Issue {
+        cert_id: CertId,
+        metadata_hash: MetadataHash,
+        owner: StellarAddress,
+    },
+    /// Issue the certificates of a CSV file in batch transactions
+    ///
+    /// The file has `cert_id`, `metadata_hash` and `owner_address` columns.
+    /// Rows are validated first, certificates that already exist are
+    /// skipped, and each row's outcome and transaction hash are written to
+    /// the results file.
+    IssueBatch {
+        #[arg(long)]
+        csv: PathBuf,
+        /// Results file [default: <csv>.results.csv]
+        #[arg(long)]
+        results: Option<PathBuf>,
+        /// Certificates per transaction [default: IMPORT_BATCH_SIZE]
+        #[arg(long)]
+        batch_size: Option<usize>,
+    },
+    /// Check a certificate against a metadata hash
+    Verify { cert_id: CertId, metadata_hash: MetadataHash },
+    /// Revoke a certificate
+    Revoke { cert_id: CertId },
+    /// Build a transfer for the current owner to sign, then `submit`
+    Transfer { cert_id: CertId, new_owner: StellarAddress },
+    /// Submit a transaction signed elsewhere, as base64 XDR
+    Submit { transaction: String },
+    /// Show a certificate
+    Get { cert_id: CertId },
+    /// Check whether a certificate exists
+    Exists { cert_id: CertId },
+    /// Show the contract's admin
+    Admin,
+    /// Show the contract instance's TTL and the latest ledger
+    Ttl,
+    /// Print the contract's events as they are emitted
+    Watch {
+        /// First ledger to print events from [default: the latest ledger]
+        ///
+        /// Must be within the RPC's event retention window.
+        #[arg(long)]
+        from_ledger: Option<u32>,
+        /// Only events of these kinds, e.g. `transfer`
+        #[arg(long = "event")]
+        events: Vec<String>,
+        /// Only events of this certificate
+        #[arg(long)]
+        cert_id: Option<String>,
+        #[arg(long, value_enum, default_value = "text")]
+        format: WatchFormat,
+        /// Seconds between polls for new events
+        #[arg(long, default_value_t = 5)]
+        interval: u64,
+        /// Stop once the latest ledger has been reached
+        #[arg(long)]
+        no_follow: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum WatchFormat {
+    /// One line per event, for people
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+/// Events requested per `getEvents` call while watching
+const WATCH_PAGE_LIMIT: u32 = 100;
+
+/// Result of a submitted transaction
+#[derive(Serialize)]
+struct Submitted {
+    hash: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ledger: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<veriluxe_api::models::CallSummary>,
+}
+
+#[tokio::main]
+async fn main() {
+    if let Err(e) = run(Cli::parse()).await {
+        eprintln!("Error: {:#}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    // Client logs go to stderr, leaving stdout to the command's JSON output
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::builder()
+                .with_default_directive(LevelFilter::WARN.into())
+                .from_env_lossy(),
+        )
+        .with_writer(std::io::stderr)
+        .init();
+
+    let deploying = matches!(cli.command, Command::Deploy { .. });
+    let mut options = ConfigOptions {
+        file: cli.config,
+        profile: cli.profile,
+        sandbox: false,
+        contract_optional: deploying || cli.contract_id.is_some(),
+        secrets: SecretStore::default(),
+    };
+    options.secrets = SecretStore::connect(Config::secrets_settings(&options)?).await?;
+    let (mut config, _) = Config::load(&options)?;
+    if let Some(contract_id) = cli.contract_id {
+        config.fashion_auth_contract_id = contract_id;
+    }
+
+    let signer = load_signer(&config.signer)?;
+    let client = SorobanClient::new(
+        config.soroban_rpc_url.clone(),
+        config.soroban_network_passphrase.clone(),
+        config.fashion_auth_contract_id.clone(),
+        signer.clone(),
+    )
+    .with_http_client(config.http.build_client()?)
+    .with_retry(config.retry.clone())
+    .with_circuit_breaker(config.circuit.clone())
+    .with_fees(config.fees.clone(), None);
+
+    let wait = (!cli.no_wait).then(|| Duration::from_secs(config.submit.wait_timeout_secs));
+    let poll_interval = Duration::from_millis(config.submit.poll_interval_ms);
+
+    match cli.command {
+        Command::Deploy { wasm } => {
+            let path = wasm.unwrap_or_else(|| PathBuf::from(&config.sandbox.wasm_path));
+            let wasm = std::fs::read(&path)
+                .map_err(|e| anyhow!("Failed to read contract wasm {}: {}", path.display(), e))?;
+            let timeout = Duration::from_secs(config.submit.wait_timeout_secs.max(60));
+            let contract_id = client.deploy_contract(&wasm, rand::random(), timeout).await?;
+            print_json(&serde_json::json!({ "contract_id": contract_id }))
+        }
+        Command::Init { admin } => {
+            let admin = match admin {
+                Some(admin) => admin,
+                None => StellarAddress::parse(&signer.address()).map_err(|e| anyhow!("{}", e))?,
+            };
+            let hash = client.init(&admin).await?;
+            print_json(&finish(&client, hash, wait, poll_interval).await?)
+        }
+        Command::Issue { cert_id, metadata_hash, owner } => {
+            let hash = client.issue_certificate(&cert_id, &metadata_hash, &owner).await?;
+            print_json(&finish(&client, hash, wait, poll_interval).await?)
+        }
+        Command::IssueBatch { csv, results, batch_size } => {
+            let mut settings = config.import.clone();
+            if let Some(batch_size) = batch_size {
+                settings.batch_size = batch_size;
+            }
+            let results = results.unwrap_or_else(|| csv.with_extension("results.csv"));
+            issue_batch(client, ImportManager::new(settings, config.submit.clone()), &csv, &results)
+                .await
+        }
+        Command::Verify { cert_id, metadata_hash } => {
+            let is_valid = client.verify_certificate(&cert_id, &metadata_hash).await?;
+            print_json(&serde_json::json!({ "cert_id": cert_id, "is_valid": is_valid }))
+        }
+        Command::Revoke { cert_id } => {
+            let hash = client.revoke_certificate(&cert_id).await?;
+            print_json(&finish(&client, hash, wait, poll_interval).await?)
+        }
+        Command::Transfer { cert_id, new_owner } => {
+            print_json(&client.prepare_transfer(&cert_id, &new_owner).await?)
+        }
+        Command::Submit { transaction } => {
+            let hash = client.submit_signed_transaction(&transaction).await?;
+            print_json(&finish(&client, hash, wait, poll_interval).await?)
+        }
+        Command::Get { cert_id } => print_json(&client.get_certificate_details(&cert_id).await?),
+        Command::Exists { cert_id } => {
+            let exists = client.certificate_exists(&cert_id).await?;
+            print_json(&serde_json::json!({ "cert_id": cert_id, "exists": exists }))
+        }
+        Command::Admin => {
+            print_json(&serde_json::json!({ "admin": client.get_admin().await? }))
+        }
+        Command::Watch { from_ledger, events, cert_id, format, interval, no_follow } => {
+            let filter = |event: &ContractEvent| {
+                (events.is_empty() || events.contains(&event.event))
+                    && cert_id.as_ref().map_or(true, |cert_id| &event.cert_id == cert_id)
+            };
+            let interval = Duration::from_secs(interval.max(1));
+            watch(&client, from_ledger, filter, format, interval, !no_follow).await
+        }
+        Command::Ttl => {
+            let live_until_ledger = client.get_contract_instance_live_until().await?;
+            let latest_ledger = client.get_latest_ledger().await?;
+            print_json(&serde_json::json!({
+                "live_until_ledger": live_until_ledger,
+                "latest_ledger": latest_ledger,
+            }))
+        }
+    }
+}
+
+/// Wait for a submitted transaction, unless `--no-wait` was given
+///
+/// A transaction that fails on chain is reported as an error, after its
+/// result has been printed.
+async fn finish(
+    client: &SorobanClient,
+    hash: String,
+    wait: Option<Duration>,
+    poll_interval: Duration,
+) -> Result<Submitted> {
+    let Some(timeout) = wait else {
+        return Ok(Submitted { hash, status: None, ledger: None, result: None });
+    };
+
+    match client.wait_for_transaction(&hash, timeout, poll_interval).await? {
+        TransactionOutcome::Success { ledger, summary } => Ok(Submitted {
+            hash,
+            status: Some("confirmed"),
+            ledger: Some(ledger),
+            result: Some(summary),
+        }),
+        TransactionOutcome::Failed { ledger, summary } => {
+            let message = summary.failure_message(&hash, ledger);
+            print_json(&Submitted {
+                hash,
+                status: Some("failed"),
+                ledger: Some(ledger),
+                result: Some(summary),
+            })?;
+            Err(anyhow!("{}", message))
+        }
+    }
+}
+
+/// Run an import of `csv`, reporting progress on stderr, and write its results
+///
+/// Fails when any row was invalid or couldn't be issued.
+async fn issue_batch(
+    client: SorobanClient,
+    imports: ImportManager,
+    csv: &Path,
+    results: &Path,
+) -> Result<()> {
+    let body = std::fs::read_to_string(csv)
+        .map_err(|e| anyhow!("Failed to read {}: {}", csv.display(), e))?;
+    let certificates = ImportFormat::Csv.read(&body)?;
+    if certificates.is_empty() {
+        return Err(anyhow!("{} has no certificates", csv.display()));
+    }
+
+    let id = imports.start(Arc::new(client), certificates).await.id;
+    let report = loop {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let report = imports
+            .get(&id)
+            .await
+            .ok_or_else(|| anyhow!("Import {} disappeared", id))?;
+        eprint!(
+            "\r{} issued, {} skipped, {} invalid, {} failed, {} pending of {}",
+            report.issued, report.skipped, report.invalid, report.failed, report.pending, report.total
+        );
+        std::io::stderr().flush().ok();
+        if report.status == ImportStatus::Completed {
+            eprintln!();
+            break report;
+        }
+    };
+
+    std::fs::write(results, report.results_csv())
+        .map_err(|e| anyhow!("Failed to write {}: {}", results.display(), e))?;
+    print_json(&serde_json::json!({
+        "total": report.total,
+        "issued": report.issued,
+        "skipped": report.skipped,
+        "invalid": report.invalid,
+        "failed": report.failed,
+        "results": results,
+    }))?;
+
+    if report.invalid + report.failed > 0 {
+        return Err(anyhow!(
+            "{} rows were not issued; see {}",
+            report.invalid + report.failed,
+            results.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Print events from `from_ledger` on, polling `getEvents` every `interval`
+async fn watch(
+    client: &SorobanClient,
+    from_ledger: Option<u32>,
+    filter: impl Fn(&ContractEvent) -> bool,
+    format: WatchFormat,
+    interval: Duration,
+    follow: bool,
+) -> Result<()> {
+    let mut start_ledger = match from_ledger {
+        Some(ledger) => ledger,
+        None => client.get_latest_ledger().await?,
+    };
+    let mut cursor: Option<String> = None;
+    loop {
+        let page = client
+            .get_events(start_ledger, cursor.as_deref(), WATCH_PAGE_LIMIT)
+            .await?;
+        let caught_up = page.events.len() < WATCH_PAGE_LIMIT as usize;
+
+        for event in page.events.iter().filter(|event| filter(event)) {
+            match format {
+                WatchFormat::Json => println!("{}", serde_json::to_string(event)?),
+                WatchFormat::Text => println!("{}", describe(event)),
+            }
+        }
+        std::io::stdout().flush().ok();
+
+        start_ledger = start_ledger.max(page.latest_ledger);
+        if page.cursor.is_some() {
+            cursor = page.cursor;
+        }
+        if caught_up {
+            if !follow {
+                return Ok(());
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+}
+
+/// An event as one line, e.g. `2024-01-01T00:00:00Z ledger 123456 transfer CERT001 from=GABC... to=GDEF... tx abc123...`
+fn describe(event: &ContractEvent) -> String {
+    let fields = match &event.data {
+        serde_json::Value::Object(fields) => fields
+            .iter()
+            .map(|(name, value)| match value {
+                serde_json::Value::String(value) => format!(" {}={}", name, value),
+                value => format!(" {}={}", name, value),
+            })
+            .collect(),
+        serde_json::Value::Null => String::new(),
+        data => format!(" {}", data),
+    };
+    format!(
+        "{} ledger {} {} {}{} tx {}",
+        event.ledger_closed_at, event.ledger, event.event, event.cert_id, fields, event.transaction_hash
+    )
+}
+
+fn print_json(value: &impl Serialize) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
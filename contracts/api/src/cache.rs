@@ -0,0 +1,249 @@
+use anyhow::{anyhow, Result};
+use redis::aio::ConnectionManager;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+/// Tunables for caching certificate reads
+#[derive(Debug, Clone)]
+pub struct CacheSettings {
+    pub enabled: bool,
+    pub ttl_secs: u64,
+    /// Certificates kept by the in-memory cache
+    pub max_entries: usize,
+    /// How long a certificate isn't cached after the API submits a write for it
+    pub write_hold_secs: u64,
+    /// Share the cache through Redis instead of keeping it in memory
+    pub redis_url: String,
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_secs: 30,
+            max_entries: 10_000,
+            write_hold_secs: 30,
+            redis_url: String::new(),
+        }
+    }
+}
+
+/// Caches contract reads per certificate
+///
+/// Entries are grouped by certificate ID, so a write drops everything known
+/// about that certificate at once. Reads of a written certificate aren't
+/// cached again until the write hold has passed, which keeps state read
+/// before the transaction lands from being served for a whole TTL. Cache
+/// failures are logged and treated as misses.
+#[derive(Clone)]
+pub struct ResponseCache {
+    ttl: Duration,
+    hold: Duration,
+    backend: Option<Backend>,
+}
+
+#[derive(Clone)]
+enum Backend {
+    Memory(Arc<RwLock<MemoryStore>>),
+    Redis { conn: ConnectionManager, prefix: String },
+}
+
+struct MemoryStore {
+    max_entries: usize,
+    entries: HashMap<String, HashMap<String, (Instant, Value)>>,
+    held: HashMap<String, Instant>,
+}
+
+/// A cached value as stored in Redis
+#[derive(Serialize, Deserialize)]
+struct StoredValue {
+    stored_at: u64,
+    value: Value,
+}
+
+impl ResponseCache {
+    /// A cache that never stores anything
+    pub fn disabled() -> Self {
+        Self {
+            ttl: Duration::ZERO,
+            hold: Duration::ZERO,
+            backend: None,
+        }
+    }
+
+    /// Set up the cache, connecting to Redis if configured
+    ///
+    /// `namespace` separates the keys of registries sharing a Redis server.
+    pub async fn connect(settings: CacheSettings, namespace: &str) -> Result<Self> {
+        if !settings.enabled {
+            info!("Response cache disabled");
+            return Ok(Self::disabled());
+        }
+
+        let backend = if settings.redis_url.is_empty() {
+            info!("Caching certificate reads in memory for {}s", settings.ttl_secs);
+            Backend::Memory(Arc::new(RwLock::new(MemoryStore {
+                max_entries: settings.max_entries,
+                entries: HashMap::new(),
+                held: HashMap::new(),
+            })))
+        } else {
+            let conn = redis::Client::open(settings.redis_url.as_str())
+                .map_err(|e| anyhow!("Invalid REDIS_URL: {}", e))?
+                .get_connection_manager()
+                .await
+                .map_err(|e| anyhow!("Failed to connect to Redis: {}", e))?;
+            info!("Caching certificate reads in Redis for {}s", settings.ttl_secs);
+            Backend::Redis {
+                conn,
+                prefix: format!("veriluxe:{}", namespace),
+            }
+        };
+
+        Ok(Self {
+            ttl: Duration::from_secs(settings.ttl_secs),
+            hold: Duration::from_secs(settings.write_hold_secs),
+            backend: Some(backend),
+        })
+    }
+
+    /// Cached `field` of a certificate, if fresh
+    pub async fn get<T: DeserializeOwned>(&self, cert_id: &str, field: &str) -> Option<T> {
+        let value = match self.backend.as_ref()? {
+            Backend::Memory(store) => {
+                let store = store.read().await;
+                let (stored_at, value) = store.entries.get(cert_id)?.get(field)?;
+                (stored_at.elapsed() <= self.ttl).then(|| value.clone())?
+            }
+            Backend::Redis { conn, prefix } => {
+                let raw: Option<String> = redis::cmd("HGET")
+                    .arg(cert_key(prefix, cert_id))
+                    .arg(field)
+                    .query_async(&mut conn.clone())
+                    .await
+                    .map_err(|e| warn!("Cache read failed: {}", e))
+                    .ok()?;
+                let stored: StoredValue = serde_json::from_str(&raw?).ok()?;
+                (now_secs().saturating_sub(stored.stored_at) <= self.ttl.as_secs())
+                    .then_some(stored.value)?
+            }
+        };
+
+        serde_json::from_value(value).ok()
+    }
+
+    /// Remember `field` of a certificate, unless it was written recently
+    pub async fn put<T: Serialize>(&self, cert_id: &str, field: &str, value: &T) {
+        let Some(backend) = &self.backend else {
+            return;
+        };
+        let Ok(value) = serde_json::to_value(value) else {
+            return;
+        };
+
+        match backend {
+            Backend::Memory(store) => {
+                let mut store = store.write().await;
+                let now = Instant::now();
+                store.held.retain(|_, until| *until > now);
+                if store.held.contains_key(cert_id) {
+                    return;
+                }
+
+                if !store.entries.contains_key(cert_id) && store.entries.len() >= store.max_entries {
+                    let ttl = self.ttl;
+                    store.entries.retain(|_, fields| {
+                        fields.retain(|_, (stored_at, _)| stored_at.elapsed() <= ttl);
+                        !fields.is_empty()
+                    });
+                    if store.entries.len() >= store.max_entries {
+                        return;
+                    }
+                }
+
+                store
+                    .entries
+                    .entry(cert_id.to_string())
+                    .or_default()
+                    .insert(field.to_string(), (now, value));
+            }
+            Backend::Redis { conn, prefix } => {
+                let mut conn = conn.clone();
+                let held: Result<bool, _> = redis::cmd("EXISTS")
+                    .arg(hold_key(prefix, cert_id))
+                    .query_async(&mut conn)
+                    .await;
+                match held {
+                    Ok(false) => {}
+                    Ok(true) => return,
+                    Err(e) => {
+                        warn!("Cache read failed: {}", e);
+                        return;
+                    }
+                }
+
+                let stored = StoredValue {
+                    stored_at: now_secs(),
+                    value,
+                };
+                let Ok(stored) = serde_json::to_string(&stored) else {
+                    return;
+                };
+                let key = cert_key(prefix, cert_id);
+                let result: Result<(), _> = redis::pipe()
+                    .cmd("HSET").arg(&key).arg(field).arg(stored).ignore()
+                    .cmd("EXPIRE").arg(&key).arg(self.ttl.as_secs()).ignore()
+                    .query_async(&mut conn)
+                    .await;
+                if let Err(e) = result {
+                    warn!("Cache write failed: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Drop everything cached for a certificate the API is about to write
+    pub async fn invalidate(&self, cert_id: &str) {
+        match &self.backend {
+            None => {}
+            Some(Backend::Memory(store)) => {
+                let mut store = store.write().await;
+                store.entries.remove(cert_id);
+                store.held.insert(cert_id.to_string(), Instant::now() + self.hold);
+            }
+            Some(Backend::Redis { conn, prefix }) => {
+                let result: Result<(), _> = redis::pipe()
+                    .cmd("DEL").arg(cert_key(prefix, cert_id)).ignore()
+                    .cmd("SET").arg(hold_key(prefix, cert_id)).arg(1).arg("EX")
+                    .arg(self.hold.as_secs().max(1)).ignore()
+                    .query_async(&mut conn.clone())
+                    .await;
+                if let Err(e) = result {
+                    warn!("Cache invalidation failed for {}: {}", cert_id, e);
+                }
+            }
+        }
+    }
+}
+
+fn cert_key(prefix: &str, cert_id: &str) -> String {
+    format!("{}:cert:{}", prefix, cert_id)
+}
+
+fn hold_key(prefix: &str, cert_id: &str) -> String {
+    format!("{}:hold:{}", prefix, cert_id)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
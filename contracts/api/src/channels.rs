@@ -0,0 +1,123 @@
+use anyhow::{anyhow, Result};
+use sha2::{Digest, Sha256};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+use tracing::info;
+
+use crate::signer::{LocalSigner, Signer};
+
+/// Tunables for the channel account pool
+#[derive(Debug, Clone, Default)]
+pub struct ChannelSettings {
+    /// Secret keys (S... or hex) of the channel accounts
+    pub secret_keys: Vec<String>,
+    /// Secret the channel keys are derived from when no keys are listed
+    pub seed: String,
+    /// Channel accounts derived from `seed`
+    pub count: u32,
+}
+
+/// One source account, used by a single transaction at a time
+struct Channel {
+    signer: Arc<dyn Signer>,
+    in_use: Arc<Mutex<()>>,
+}
+
+/// A channel account checked out for one transaction
+///
+/// The account is returned to the pool when the lease is dropped, once the
+/// transaction has been submitted and its sequence number consumed.
+pub struct ChannelLease {
+    pub signer: Arc<dyn Signer>,
+    _guard: OwnedMutexGuard<()>,
+}
+
+/// Accounts that source and pay for contract invocations in place of the admin
+///
+/// Each account has its own sequence number, so with N channels up to N
+/// transactions can be in flight in the same ledger. The admin key only signs
+/// the invocation's authorization entries.
+#[derive(Clone)]
+pub struct ChannelPool {
+    channels: Arc<Vec<Channel>>,
+    next: Arc<AtomicUsize>,
+}
+
+impl ChannelPool {
+    /// Load the configured channel keys, if any
+    pub fn load(settings: &ChannelSettings) -> Result<Option<Self>> {
+        let secrets: Vec<String> = if !settings.secret_keys.is_empty() {
+            settings.secret_keys.clone()
+        } else if settings.count > 0 {
+            if settings.seed.is_empty() {
+                return Err(anyhow!("CHANNEL_SEED is required to derive channel accounts"));
+            }
+            (0..settings.count)
+                .map(|index| derive_secret(&settings.seed, index))
+                .collect()
+        } else {
+            return Ok(None);
+        };
+
+        let channels = secrets
+            .iter()
+            .enumerate()
+            .map(|(index, secret)| {
+                let signer = LocalSigner::from_secret(secret)
+                    .map_err(|e| anyhow!("Invalid channel key #{}: {}", index + 1, e))?;
+                Ok(Channel {
+                    signer: Arc::new(signer),
+                    in_use: Arc::new(Mutex::new(())),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let pool = Self {
+            channels: Arc::new(channels),
+            next: Arc::new(AtomicUsize::new(0)),
+        };
+        info!("Submitting through channel accounts: {}", pool.addresses().join(", "));
+        Ok(Some(pool))
+    }
+
+    /// Accounts in the pool, which must exist and hold enough XLM for fees
+    pub fn addresses(&self) -> Vec<String> {
+        self.channels
+            .iter()
+            .map(|channel| channel.signer.address())
+            .collect()
+    }
+
+    /// Check out the next idle channel, round-robin, waiting if all are busy
+    pub async fn acquire(&self) -> ChannelLease {
+        let start = self.next.fetch_add(1, Ordering::Relaxed) % self.channels.len();
+
+        for offset in 0..self.channels.len() {
+            let channel = &self.channels[(start + offset) % self.channels.len()];
+            if let Ok(guard) = channel.in_use.clone().try_lock_owned() {
+                return ChannelLease {
+                    signer: channel.signer.clone(),
+                    _guard: guard,
+                };
+            }
+        }
+
+        let channel = &self.channels[start];
+        ChannelLease {
+            signer: channel.signer.clone(),
+            _guard: channel.in_use.clone().lock_owned().await,
+        }
+    }
+}
+
+/// Hex secret of the `index`th channel account derived from `seed`
+fn derive_secret(seed: &str, index: u32) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(b"veriluxe-channel:");
+    hasher.update(seed.as_bytes());
+    hasher.update(index.to_be_bytes());
+    hex::encode(hasher.finalize())
+}
@@ -0,0 +1,182 @@
+use axum::{
+    extract::Request,
+    http::{header::RETRY_AFTER, HeaderValue, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tracing::{info, warn};
+
+use crate::networks::Network;
+
+/// Tunables for the circuit breaker around a Soroban RPC endpoint
+#[derive(Debug, Clone)]
+pub struct CircuitSettings {
+    /// Consecutive failed calls that open the circuit; `0` disables the breaker
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before a trial call is let through
+    pub open_secs: u64,
+}
+
+impl Default for CircuitSettings {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            open_secs: 30,
+        }
+    }
+}
+
+/// Returned instead of calling the RPC while the circuit is open
+#[derive(Debug, Clone, Copy)]
+pub struct CircuitOpen {
+    pub retry_after: Duration,
+}
+
+impl fmt::Display for CircuitOpen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Soroban RPC circuit open after repeated failures; retry in {} seconds",
+            retry_after_secs(self.retry_after)
+        )
+    }
+}
+
+impl std::error::Error for CircuitOpen {}
+
+#[derive(Debug, Clone, Copy)]
+enum Circuit {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    /// A single trial call is in flight
+    HalfOpen { since: Instant },
+}
+
+/// Fails RPC calls fast once the endpoint has failed repeatedly
+///
+/// After `failure_threshold` consecutive failures the circuit opens and
+/// calls are refused with [`CircuitOpen`] for `open_secs`. Then one trial
+/// call is let through: success closes the circuit, failure opens it again.
+/// Clones share state, so every client of one RPC endpoint trips together.
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    settings: CircuitSettings,
+    circuit: Arc<Mutex<Circuit>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(settings: CircuitSettings) -> Self {
+        Self {
+            settings,
+            circuit: Arc::new(Mutex::new(Circuit::Closed { failures: 0 })),
+        }
+    }
+
+    /// A closed breaker with the same settings, for another endpoint
+    pub fn fresh(&self) -> Self {
+        Self::new(self.settings.clone())
+    }
+
+    fn open_for(&self) -> Duration {
+        Duration::from_secs(self.settings.open_secs)
+    }
+
+    /// Let a call through, or refuse it while the circuit is open
+    pub fn admit(&self) -> Result<(), CircuitOpen> {
+        if self.settings.failure_threshold == 0 {
+            return Ok(());
+        }
+
+        let mut circuit = self.circuit.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        match *circuit {
+            Circuit::Closed { .. } => Ok(()),
+            Circuit::Open { until } if now < until => Err(CircuitOpen {
+                retry_after: until - now,
+            }),
+            // A trial that never reported back (e.g. its request was dropped)
+            // doesn't keep the circuit half-open forever
+            Circuit::HalfOpen { since } if now < since + self.open_for() => Err(CircuitOpen {
+                retry_after: Duration::from_secs(1),
+            }),
+            Circuit::Open { .. } | Circuit::HalfOpen { .. } => {
+                info!("Soroban RPC circuit half-open; letting a trial call through");
+                *circuit = Circuit::HalfOpen { since: now };
+                Ok(())
+            }
+        }
+    }
+
+    /// Record a call the RPC answered
+    pub fn record_success(&self) {
+        if self.settings.failure_threshold == 0 {
+            return;
+        }
+
+        let mut circuit = self.circuit.lock().unwrap_or_else(|e| e.into_inner());
+        if !matches!(*circuit, Circuit::Closed { .. }) {
+            info!("Soroban RPC recovered; circuit closed");
+        }
+        *circuit = Circuit::Closed { failures: 0 };
+    }
+
+    /// Record a call the RPC couldn't answer
+    pub fn record_failure(&self) {
+        if self.settings.failure_threshold == 0 {
+            return;
+        }
+
+        let mut circuit = self.circuit.lock().unwrap_or_else(|e| e.into_inner());
+        let until = Instant::now() + self.open_for();
+        match *circuit {
+            Circuit::Closed { failures } if failures + 1 < self.settings.failure_threshold => {
+                *circuit = Circuit::Closed {
+                    failures: failures + 1,
+                };
+            }
+            Circuit::Closed { .. } | Circuit::HalfOpen { .. } => {
+                warn!(
+                    "Soroban RPC failing; circuit open for {} seconds",
+                    self.settings.open_secs
+                );
+                *circuit = Circuit::Open { until };
+            }
+            Circuit::Open { .. } => {}
+        }
+    }
+
+    /// How long until calls are let through again, if the circuit is open
+    pub fn retry_after(&self) -> Option<Duration> {
+        let circuit = self.circuit.lock().unwrap_or_else(|e| e.into_inner());
+        match *circuit {
+            Circuit::Open { until } => until.checked_duration_since(Instant::now()),
+            Circuit::HalfOpen { .. } => Some(Duration::from_secs(1)),
+            Circuit::Closed { .. } => None,
+        }
+    }
+}
+
+/// Whole seconds to wait, rounded up so clients don't retry early
+fn retry_after_secs(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+/// Add `Retry-After` to 503 answers given while the request's RPC circuit is open
+pub async fn retry_after(request: Request, next: Next) -> Response {
+    let network = request.extensions().get::<Network>().cloned();
+    let mut response = next.run(request).await;
+
+    if response.status() == StatusCode::SERVICE_UNAVAILABLE {
+        if let Some(wait) = network.and_then(|network| network.client.circuit_retry_after()) {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(retry_after_secs(wait)));
+        }
+    }
+    response
+}
@@ -0,0 +1,220 @@
+use anyhow::Result;
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+use stellar_xdr::curr::TransactionEnvelope;
+
+use crate::{
+    cache::ResponseCache,
+    fees::FeeEstimate,
+    models::{CertId, Certificate, LegacyCertificate, MetadataHash, PreparedTransaction, StellarAddress},
+    signer::Signer,
+    soroban_client::{SorobanClient, TransactionOutcome},
+};
+
+/// Future returned by [`ContractClient`] calls
+pub type ClientFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// Operations on one deployment of the registry contract, as used by request handlers
+///
+/// [`SorobanClient`] talks to a Soroban RPC; [`crate::mock_client::MockContractClient`]
+/// keeps the registry in memory so handlers can be tested without a network.
+/// Errors carry the contract's messages, which [`crate::models::ErrorCode::classify`]
+/// maps to error codes.
+pub trait ContractClient: Send + Sync {
+    /// Initialize the contract with `admin_address` as admin
+    fn init<'a>(&'a self, admin_address: &'a StellarAddress) -> ClientFuture<'a, String>;
+
+    /// Issue a certificate, returning the transaction hash
+    fn issue_certificate<'a>(
+        &'a self,
+        cert_id: &'a CertId,
+        metadata_hash: &'a MetadataHash,
+        owner_address: &'a StellarAddress,
+    ) -> ClientFuture<'a, String>;
+
+    /// Issue several certificates in one transaction
+    fn issue_batch<'a>(&'a self, certificates: &'a [LegacyCertificate]) -> ClientFuture<'a, String>;
+
+    /// Whether a certificate matches `metadata_hash`
+    fn verify_certificate<'a>(
+        &'a self,
+        cert_id: &'a CertId,
+        metadata_hash: &'a MetadataHash,
+    ) -> ClientFuture<'a, bool>;
+
+    /// Owner, metadata hash and validity of a certificate
+    fn get_certificate_details<'a>(&'a self, cert_id: &'a CertId) -> ClientFuture<'a, Certificate>;
+
+    /// Collection a certificate was issued into, with the collection's brand
+    fn get_certificate_collection<'a>(
+        &'a self,
+        cert_id: &'a CertId,
+    ) -> ClientFuture<'a, Option<(u32, String)>>;
+
+    /// Lifecycle status: `Valid`, `Disputed`, `Suspended` or `Revoked`
+    fn get_certificate_status<'a>(&'a self, cert_id: &'a CertId) -> ClientFuture<'a, String>;
+
+    /// When a certificate's warranty ends (unix seconds), if it has one
+    fn get_warranty<'a>(&'a self, cert_id: &'a CertId) -> ClientFuture<'a, Option<u64>>;
+
+    /// Build an unsigned transfer for the current owner to sign
+    fn prepare_transfer<'a>(
+        &'a self,
+        cert_id: &'a CertId,
+        new_owner_address: &'a StellarAddress,
+    ) -> ClientFuture<'a, PreparedTransaction>;
+
+    /// Decode a client-signed transaction and check it only invokes this contract
+    fn decode_signed_transaction(&self, transaction_xdr: &str) -> Result<TransactionEnvelope>;
+
+    /// Submit a client-signed transaction, returning its hash
+    fn submit_signed_transaction<'a>(&'a self, transaction_xdr: &'a str) -> ClientFuture<'a, String>;
+
+    /// Revoke a certificate, returning the transaction hash
+    fn revoke_certificate<'a>(&'a self, cert_id: &'a CertId) -> ClientFuture<'a, String>;
+
+    fn certificate_exists<'a>(&'a self, cert_id: &'a CertId) -> ClientFuture<'a, bool>;
+
+    /// Wait until a submitted transaction succeeds or fails
+    ///
+    /// Fails with an error mentioning "not confirmed" once `timeout` has passed.
+    fn wait_for_transaction<'a>(
+        &'a self,
+        hash: &'a str,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> ClientFuture<'a, TransactionOutcome>;
+
+    /// Current fees and the cost of issuing a certificate
+    fn estimate_fees(&self) -> ClientFuture<'_, FeeEstimate>;
+
+    /// Hex hash of a v1 transaction envelope on this network
+    fn envelope_hash(&self, envelope: &TransactionEnvelope) -> Result<String>;
+
+    /// A transaction sent recently through this client, by hash
+    fn sent_transaction(&self, hash: &str) -> Option<TransactionEnvelope>;
+
+    /// Resubmit a signed transaction wrapped in a fee bump, returning the bump's hash
+    fn fee_bump(&self, inner: TransactionEnvelope, fee: Option<i64>) -> ClientFuture<'_, String>;
+
+    /// How long until calls are let through again, if the RPC is being avoided
+    fn circuit_retry_after(&self) -> Option<Duration> {
+        None
+    }
+
+    /// A client for another contract on the same network, signing with `signer`
+    /// and caching reads in `cache`
+    fn for_contract(
+        &self,
+        contract_id: &str,
+        signer: Option<Arc<dyn Signer>>,
+        cache: ResponseCache,
+    ) -> Arc<dyn ContractClient>;
+}
+
+impl ContractClient for SorobanClient {
+    fn init<'a>(&'a self, admin_address: &'a StellarAddress) -> ClientFuture<'a, String> {
+        Box::pin(SorobanClient::init(self, admin_address))
+    }
+
+    fn issue_certificate<'a>(
+        &'a self,
+        cert_id: &'a CertId,
+        metadata_hash: &'a MetadataHash,
+        owner_address: &'a StellarAddress,
+    ) -> ClientFuture<'a, String> {
+        Box::pin(SorobanClient::issue_certificate(self, cert_id, metadata_hash, owner_address))
+    }
+
+    fn issue_batch<'a>(&'a self, certificates: &'a [LegacyCertificate]) -> ClientFuture<'a, String> {
+        Box::pin(SorobanClient::issue_batch(self, certificates))
+    }
+
+    fn verify_certificate<'a>(
+        &'a self,
+        cert_id: &'a CertId,
+        metadata_hash: &'a MetadataHash,
+    ) -> ClientFuture<'a, bool> {
+        Box::pin(SorobanClient::verify_certificate(self, cert_id, metadata_hash))
+    }
+
+    fn get_certificate_details<'a>(&'a self, cert_id: &'a CertId) -> ClientFuture<'a, Certificate> {
+        Box::pin(SorobanClient::get_certificate_details(self, cert_id))
+    }
+
+    fn get_certificate_collection<'a>(
+        &'a self,
+        cert_id: &'a CertId,
+    ) -> ClientFuture<'a, Option<(u32, String)>> {
+        Box::pin(SorobanClient::get_certificate_collection(self, cert_id))
+    }
+
+    fn get_certificate_status<'a>(&'a self, cert_id: &'a CertId) -> ClientFuture<'a, String> {
+        Box::pin(SorobanClient::get_certificate_status(self, cert_id))
+    }
+
+    fn get_warranty<'a>(&'a self, cert_id: &'a CertId) -> ClientFuture<'a, Option<u64>> {
+        Box::pin(SorobanClient::get_warranty(self, cert_id))
+    }
+
+    fn prepare_transfer<'a>(
+        &'a self,
+        cert_id: &'a CertId,
+        new_owner_address: &'a StellarAddress,
+    ) -> ClientFuture<'a, PreparedTransaction> {
+        Box::pin(SorobanClient::prepare_transfer(self, cert_id, new_owner_address))
+    }
+
+    fn decode_signed_transaction(&self, transaction_xdr: &str) -> Result<TransactionEnvelope> {
+        SorobanClient::decode_signed_transaction(self, transaction_xdr)
+    }
+
+    fn submit_signed_transaction<'a>(&'a self, transaction_xdr: &'a str) -> ClientFuture<'a, String> {
+        Box::pin(SorobanClient::submit_signed_transaction(self, transaction_xdr))
+    }
+
+    fn revoke_certificate<'a>(&'a self, cert_id: &'a CertId) -> ClientFuture<'a, String> {
+        Box::pin(SorobanClient::revoke_certificate(self, cert_id))
+    }
+
+    fn certificate_exists<'a>(&'a self, cert_id: &'a CertId) -> ClientFuture<'a, bool> {
+        Box::pin(SorobanClient::certificate_exists(self, cert_id))
+    }
+
+    fn wait_for_transaction<'a>(
+        &'a self,
+        hash: &'a str,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> ClientFuture<'a, TransactionOutcome> {
+        Box::pin(SorobanClient::wait_for_transaction(self, hash, timeout, poll_interval))
+    }
+
+    fn estimate_fees(&self) -> ClientFuture<'_, FeeEstimate> {
+        Box::pin(SorobanClient::estimate_fees(self))
+    }
+
+    fn envelope_hash(&self, envelope: &TransactionEnvelope) -> Result<String> {
+        SorobanClient::envelope_hash(self, envelope)
+    }
+
+    fn sent_transaction(&self, hash: &str) -> Option<TransactionEnvelope> {
+        SorobanClient::sent_transaction(self, hash)
+    }
+
+    fn fee_bump(&self, inner: TransactionEnvelope, fee: Option<i64>) -> ClientFuture<'_, String> {
+        Box::pin(SorobanClient::fee_bump(self, inner, fee))
+    }
+
+    fn circuit_retry_after(&self) -> Option<Duration> {
+        SorobanClient::circuit_retry_after(self)
+    }
+
+    fn for_contract(
+        &self,
+        contract_id: &str,
+        signer: Option<Arc<dyn Signer>>,
+        cache: ResponseCache,
+    ) -> Arc<dyn ContractClient> {
+        Arc::new(SorobanClient::for_contract(self, contract_id, signer).with_cache(cache))
+    }
+}
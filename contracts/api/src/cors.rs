@@ -0,0 +1,55 @@
+use axum::http::{request::Parts, HeaderValue};
+use std::sync::{Arc, RwLock};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+/// Tunables for cross-origin requests from browsers
+#[derive(Debug, Clone, Default)]
+pub struct CorsSettings {
+    /// Origins allowed to call the API, e.g. `https://shop.example.com`;
+    /// any origin when empty
+    pub allowed_origins: Vec<String>,
+}
+
+/// Origins allowed by the CORS layer, replaceable while the server runs
+#[derive(Debug, Clone, Default)]
+pub struct CorsOrigins {
+    origins: Arc<RwLock<Vec<String>>>,
+}
+
+impl CorsOrigins {
+    pub fn new(settings: &CorsSettings) -> Self {
+        let origins = Self::default();
+        origins.set(settings);
+        origins
+    }
+
+    /// Allow the origins in `settings` from the next request on
+    pub fn set(&self, settings: &CorsSettings) {
+        let origins = settings
+            .allowed_origins
+            .iter()
+            .map(|origin| origin.trim_end_matches('/').to_string())
+            .collect();
+        *self.origins.write().unwrap_or_else(|e| e.into_inner()) = origins;
+    }
+
+    /// Whether a request from `origin` may read the response
+    pub fn allows(&self, origin: &str) -> bool {
+        let origins = self.origins.read().unwrap_or_else(|e| e.into_inner());
+        origins.is_empty() || origins.iter().any(|allowed| allowed == origin)
+    }
+
+    /// A CORS layer checking each request's origin against the current list
+    pub fn layer(&self) -> CorsLayer {
+        let origins = self.clone();
+        CorsLayer::new()
+            .allow_origin(AllowOrigin::predicate(
+                move |origin: &HeaderValue, _: &Parts| {
+                    origin.to_str().is_ok_and(|origin| origins.allows(origin))
+                },
+            ))
+            .allow_methods(Any)
+            .allow_headers(Any)
+            .expose_headers(Any)
+    }
+}
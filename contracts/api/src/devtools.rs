@@ -0,0 +1,96 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use stellar_strkey::{ed25519, Contract, Strkey};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::signer::{LocalSigner, Signer};
+
+/// Kinds of strkey the converter handles
+///
+/// - `account`: an Ed25519 public key (G...)
+/// - `secret`: an Ed25519 secret seed (S...)
+/// - `contract`: a contract ID (C...)
+pub const STRKEY_KINDS: &[&str] = &["account", "secret", "contract"];
+
+/// A new Ed25519 keypair, in strkey and hex form
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DevKeypair {
+    pub public_key: String,
+    pub secret_key: String,
+    pub public_key_hex: String,
+    pub secret_key_hex: String,
+}
+
+/// Generate a random keypair
+pub fn generate_keypair() -> Result<DevKeypair> {
+    let seed: [u8; 32] = rand::random();
+    let signer = LocalSigner::from_secret(&hex::encode(seed))?;
+
+    Ok(DevKeypair {
+        public_key: signer.address(),
+        secret_key: ed25519::PrivateKey(seed).to_string(),
+        public_key_hex: hex::encode(signer.public_key()),
+        secret_key_hex: hex::encode(seed),
+    })
+}
+
+/// Query parameters for converting between hex and strkey
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StrkeyConvertQuery {
+    /// A strkey (G..., S... or C...) or 64 hex characters
+    pub value: String,
+    /// One of [`STRKEY_KINDS`], for hex values; `account` when omitted
+    pub kind: Option<String>,
+}
+
+/// A key or contract ID in both encodings
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StrkeyConversion {
+    /// One of [`STRKEY_KINDS`]
+    pub kind: String,
+    pub strkey: String,
+    pub hex: String,
+}
+
+/// Decode a strkey to hex, or encode 32 hex-encoded bytes as a strkey of `kind`
+pub fn convert(value: &str, kind: Option<&str>) -> Result<StrkeyConversion> {
+    let value = value.trim();
+
+    if let Ok(bytes) = hex::decode(value) {
+        let bytes: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| anyhow!("Hex values must be 32 bytes (64 characters)"))?;
+        let kind = kind.unwrap_or("account");
+        let strkey = match kind {
+            "account" => ed25519::PublicKey(bytes).to_string(),
+            "secret" => ed25519::PrivateKey(bytes).to_string(),
+            "contract" => Contract(bytes).to_string(),
+            other => {
+                return Err(anyhow!(
+                    "Unknown strkey kind {}; expected one of: {}",
+                    other,
+                    STRKEY_KINDS.join(", ")
+                ))
+            }
+        };
+        return Ok(StrkeyConversion {
+            kind: kind.to_string(),
+            strkey,
+            hex: value.to_ascii_lowercase(),
+        });
+    }
+
+    let (kind, bytes) = match Strkey::from_string(value) {
+        Ok(Strkey::PublicKeyEd25519(key)) => ("account", key.0),
+        Ok(Strkey::PrivateKeyEd25519(key)) => ("secret", key.0),
+        Ok(Strkey::Contract(contract)) => ("contract", contract.0),
+        Ok(_) => return Err(anyhow!("Only account, secret and contract strkeys are supported")),
+        Err(_) => return Err(anyhow!("Value is neither a valid strkey nor 64 hex characters")),
+    };
+    Ok(StrkeyConversion {
+        kind: kind.to_string(),
+        strkey: value.to_string(),
+        hex: hex::encode(bytes),
+    })
+}
@@ -0,0 +1,192 @@
+use anyhow::{anyhow, Result};
+use axum::body::Body;
+use futures::stream;
+use tracing::error;
+
+use crate::{
+    indexer::{CertificateFilter, Indexer},
+    models::ExportedCertificate,
+};
+
+/// Certificates read from the index per database round trip
+const EXPORT_BATCH: u32 = 500;
+
+/// CSV columns, in order; `history` holds the events as a JSON array
+const CSV_COLUMNS: &[&str] = &[
+    "cert_id",
+    "owner",
+    "metadata_hash",
+    "status",
+    "disputed",
+    "brand",
+    "model",
+    "serial_number",
+    "material",
+    "manufacture_year",
+    "collection_id",
+    "issued_ledger",
+    "issued_at",
+    "updated_ledger",
+    "updated_at",
+    "history",
+];
+
+/// Serialization of a registry export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn parse(format: &str) -> Option<Self> {
+        match format.to_ascii_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Json => "application/json",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+
+    fn header(self) -> String {
+        match self {
+            Self::Csv => format!("{}\r\n", CSV_COLUMNS.join(",")),
+            Self::Json => "[".to_string(),
+        }
+    }
+
+    fn footer(self) -> String {
+        match self {
+            Self::Csv => String::new(),
+            Self::Json => "]".to_string(),
+        }
+    }
+
+    /// Serialize a batch; `first` is whether it opens the export
+    fn batch(self, certificates: &[ExportedCertificate], first: bool) -> Result<String> {
+        let mut out = String::new();
+        for (i, certificate) in certificates.iter().enumerate() {
+            match self {
+                Self::Csv => {
+                    out.push_str(&csv_row(certificate)?);
+                    out.push_str("\r\n");
+                }
+                Self::Json => {
+                    if !first || i > 0 {
+                        out.push(',');
+                    }
+                    out.push_str(&serde_json::to_string(certificate)?);
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Progress of a streaming export
+struct ExportState {
+    indexer: Indexer,
+    filter: CertificateFilter,
+    format: ExportFormat,
+    cursor: Option<String>,
+    started: bool,
+    done: bool,
+}
+
+/// Stream every certificate matching `filter`, with its history, in ID order
+///
+/// The index is read in batches as the client consumes the body, so exports
+/// of any size use bounded memory. A database error ends the stream early;
+/// clients detect it as a truncated body.
+pub fn export_body(indexer: Indexer, filter: CertificateFilter, format: ExportFormat) -> Body {
+    let state = ExportState {
+        indexer,
+        filter,
+        format,
+        cursor: None,
+        started: false,
+        done: false,
+    };
+
+    Body::from_stream(stream::unfold(state, |mut state| async move {
+        if state.done {
+            return None;
+        }
+        if !state.started {
+            state.started = true;
+            return Some((Ok(state.format.header()), state));
+        }
+
+        let certificates = match state
+            .indexer
+            .export_certificates(&state.filter, EXPORT_BATCH, state.cursor.as_deref())
+            .await
+        {
+            Ok(certificates) => certificates,
+            Err(e) => {
+                error!("Certificate export failed: {}", e);
+                state.done = true;
+                return Some((Err(e), state));
+            }
+        };
+        if certificates.is_empty() {
+            state.done = true;
+            return Some((Ok(state.format.footer()), state));
+        }
+
+        let chunk = state.format.batch(&certificates, state.cursor.is_none());
+        state.cursor = certificates.last().map(|c| c.cert_id.clone());
+        if chunk.is_err() {
+            state.done = true;
+        }
+        Some((chunk, state))
+    }))
+}
+
+fn csv_row(certificate: &ExportedCertificate) -> Result<String> {
+    let optional = |value: Option<String>| value.unwrap_or_default();
+    let history = serde_json::to_string(&certificate.history)
+        .map_err(|e| anyhow!("Failed to encode history of {}: {}", certificate.cert_id, e))?;
+
+    let fields = [
+        certificate.cert_id.clone(),
+        certificate.owner.clone(),
+        certificate.metadata_hash.clone(),
+        certificate.status.clone(),
+        certificate.disputed.to_string(),
+        optional(certificate.brand.clone()),
+        optional(certificate.model.clone()),
+        optional(certificate.serial_number.clone()),
+        optional(certificate.material.clone()),
+        optional(certificate.manufacture_year.map(|year| year.to_string())),
+        optional(certificate.collection_id.map(|id| id.to_string())),
+        certificate.issued_ledger.to_string(),
+        optional(certificate.issued_at.map(|at| at.to_rfc3339())),
+        certificate.updated_ledger.to_string(),
+        optional(certificate.updated_at.map(|at| at.to_rfc3339())),
+        history,
+    ];
+    Ok(fields.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(","))
+}
+
+/// Quote a field per RFC 4180 when it needs it
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
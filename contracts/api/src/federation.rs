@@ -0,0 +1,105 @@
+use anyhow::Result;
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use crate::{
+    contract_client::ContractClient,
+    models::{CertId, MetadataHash},
+    soroban_client::{HttpSettings, SorobanClient},
+};
+
+/// Name reported for certificates answered by this deployment's own contract
+pub const LOCAL_REGISTRY_NAME: &str = "local";
+
+/// A peer VeriLuxe registry deployed on another contract or network
+#[derive(Debug, Clone, Deserialize)]
+pub struct PeerRegistry {
+    pub name: String,
+    pub rpc_url: String,
+    pub network_passphrase: String,
+    pub contract_id: String,
+}
+
+/// Result of a federated verification
+#[derive(Debug, Clone)]
+pub struct FederatedMatch {
+    pub registry: String,
+    pub is_valid: bool,
+}
+
+/// Verifies certificates across the local registry and configured peers
+#[derive(Clone)]
+pub struct Federation {
+    peers: Vec<(String, SorobanClient)>,
+}
+
+impl Federation {
+    /// Build read-only clients for every configured peer registry
+    pub fn new(peers: &[PeerRegistry], http: &HttpSettings) -> Result<Self> {
+        let http_client = http.build_client()?;
+        let peers = peers
+            .iter()
+            .map(|peer| {
+                let client = SorobanClient::new_read_only(
+                    peer.rpc_url.clone(),
+                    peer.network_passphrase.clone(),
+                    peer.contract_id.clone(),
+                )
+                .with_http_client(http_client.clone());
+                (peer.name.clone(), client)
+            })
+            .collect();
+
+        Ok(Self { peers })
+    }
+
+    /// A federation with no peers, which only consults the local registry
+    pub fn empty() -> Self {
+        Self { peers: Vec::new() }
+    }
+
+    /// Names of the configured peer registries
+    pub fn peer_names(&self) -> Vec<String> {
+        self.peers.iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    /// Verify a certificate, asking the local registry first and then each peer in order
+    ///
+    /// Returns the first registry that knows the certificate, or `None` if no
+    /// registry has it. Peers that fail to answer are skipped.
+    pub async fn verify(
+        &self,
+        local: &dyn ContractClient,
+        cert_id: &CertId,
+        metadata_hash: &MetadataHash,
+    ) -> Result<Option<FederatedMatch>> {
+        info!("Federated verification for certificate: {}", cert_id);
+
+        if local.certificate_exists(cert_id).await? {
+            let is_valid = local.verify_certificate(cert_id, metadata_hash).await?;
+            return Ok(Some(FederatedMatch {
+                registry: LOCAL_REGISTRY_NAME.to_string(),
+                is_valid,
+            }));
+        }
+
+        for (name, client) in &self.peers {
+            match client.certificate_exists(cert_id).await {
+                Ok(true) => {
+                    let is_valid = client.verify_certificate(cert_id, metadata_hash).await?;
+                    return Ok(Some(FederatedMatch {
+                        registry: name.clone(),
+                        is_valid,
+                    }));
+                }
+                Ok(false) => continue,
+                Err(e) => {
+                    warn!("Peer registry {} failed to answer: {}", name, e);
+                    continue;
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,70 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// How the inclusion fee of each transaction is chosen
+///
+/// - `fixed` always bids `base_fee`
+/// - `surge` bids the 90th percentile of recent Soroban inclusion fees
+///   reported by `getFeeStats`, and never less than `base_fee`
+pub const FEE_STRATEGIES: &[&str] = &["fixed", "surge"];
+
+/// Tunables for transaction fees
+#[derive(Debug, Clone)]
+pub struct FeeSettings {
+    /// Inclusion fee per operation, in stroops
+    pub base_fee: u32,
+    /// Transactions whose total fee would exceed this are not submitted
+    pub max_fee: u32,
+    /// One of [`FEE_STRATEGIES`]
+    pub strategy: String,
+    /// Account paying for fee bumps; the admin account when empty
+    pub sponsor_secret_key: String,
+}
+
+impl Default for FeeSettings {
+    fn default() -> Self {
+        Self {
+            base_fee: 100,
+            max_fee: 10_000_000,
+            strategy: "fixed".to_string(),
+            sponsor_secret_key: String::new(),
+        }
+    }
+}
+
+/// Recent Soroban inclusion fees, from `getFeeStats`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InclusionFeeStats {
+    pub p50: u64,
+    pub p90: u64,
+    pub max: u64,
+}
+
+impl FeeSettings {
+    /// Inclusion fee to bid given the network's recent fees
+    pub fn inclusion_fee(&self, stats: Option<InclusionFeeStats>) -> u32 {
+        match (self.strategy.as_str(), stats) {
+            ("surge", Some(stats)) => u32::try_from(stats.p90)
+                .unwrap_or(u32::MAX)
+                .clamp(self.base_fee, self.max_fee.max(self.base_fee)),
+            _ => self.base_fee,
+        }
+    }
+}
+
+/// Current fees, and what issuing a certificate would cost
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FeeEstimate {
+    pub strategy: String,
+    /// Inclusion fee the API bids per operation right now, in stroops
+    pub inclusion_fee: u32,
+    pub network_inclusion_fee_p50: u64,
+    pub network_inclusion_fee_p90: u64,
+    pub network_inclusion_fee_max: u64,
+    /// Resource fee of an `issue_certificate` invocation, from simulation
+    pub issue_resource_fee: i64,
+    /// Inclusion plus resource fee of issuing a certificate
+    pub issue_total_fee: i64,
+    pub max_fee: u32,
+    pub latest_ledger: u32,
+}
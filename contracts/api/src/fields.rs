@@ -0,0 +1,53 @@
+use serde_json::Value;
+
+/// Fields requested with `?fields=`, e.g. `owner,status,issued_at`
+///
+/// Responses are trimmed after serialization, so the models stay the same and
+/// new fields are only sent to clients that ask for them by name or don't ask
+/// for a selection at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSelection {
+    fields: Vec<String>,
+}
+
+impl FieldSelection {
+    /// Parse a comma-separated list of names, each one of `available`
+    ///
+    /// `None` or an empty list selects every field.
+    pub fn parse(fields: Option<&str>, available: &[&str]) -> Result<Option<Self>, String> {
+        let fields: Vec<String> = fields
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+            .map(str::to_string)
+            .collect();
+        if fields.is_empty() {
+            return Ok(None);
+        }
+
+        let unknown: Vec<&str> = fields
+            .iter()
+            .map(String::as_str)
+            .filter(|field| !available.contains(field))
+            .collect();
+        if !unknown.is_empty() {
+            return Err(format!(
+                "Unknown fields: {}; available fields are {}",
+                unknown.join(", "),
+                available.join(", ")
+            ));
+        }
+
+        Ok(Some(Self { fields }))
+    }
+
+    /// Drop every member of `object` that wasn't selected, other than `keep`
+    pub fn trim(&self, object: &mut Value, keep: &[&str]) {
+        if let Value::Object(members) = object {
+            members.retain(|name, _| {
+                self.fields.iter().any(|field| field == name) || keep.contains(&name.as_str())
+            });
+        }
+    }
+}
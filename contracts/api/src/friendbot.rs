@@ -0,0 +1,64 @@
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use tracing::info;
+
+use crate::sandbox::SandboxSettings;
+
+/// Passphrase of the public test network
+pub const TESTNET_PASSPHRASE: &str = "Test SDF Network ; September 2015";
+
+pub const TESTNET_FRIENDBOT_URL: &str = "https://friendbot.stellar.org";
+
+/// Creates and funds accounts on networks whose lumens are free
+#[derive(Clone)]
+pub struct Friendbot {
+    url: String,
+    http_client: Client,
+}
+
+impl Friendbot {
+    pub fn new(url: String, http_client: Client) -> Self {
+        Self { url, http_client }
+    }
+
+    /// The friendbot of the network with `network_passphrase`, if it has one
+    ///
+    /// Only testnet and the sandbox's local network have one.
+    pub fn for_network(
+        network_passphrase: &str,
+        sandbox: &SandboxSettings,
+        http_client: Client,
+    ) -> Option<Self> {
+        if sandbox.enabled {
+            Some(Self::new(sandbox.friendbot_url.clone(), http_client))
+        } else if network_passphrase == TESTNET_PASSPHRASE {
+            Some(Self::new(TESTNET_FRIENDBOT_URL.to_string(), http_client))
+        } else {
+            None
+        }
+    }
+
+    /// Create and fund `address`, returning `false` if the account already existed
+    pub async fn fund(&self, address: &str) -> Result<bool> {
+        let response = self
+            .http_client
+            .get(&self.url)
+            .query(&[("addr", address)])
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to reach friendbot at {}: {}", self.url, e))?;
+
+        if response.status().is_success() {
+            info!("Friendbot funded account {}", address);
+            return Ok(true);
+        }
+
+        // Friendbot refuses to fund accounts that already exist
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if body.contains("already funded") || body.contains("createAccountAlreadyExist") {
+            return Ok(false);
+        }
+        Err(anyhow!("Friendbot answered {} for {}: {}", status, address, body))
+    }
+}
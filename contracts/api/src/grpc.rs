@@ -0,0 +1,419 @@
+use anyhow::{anyhow, Result};
+use std::{net::SocketAddr, time::Duration};
+use tonic::{
+    metadata::{MetadataMap, MetadataValue},
+    transport::{Identity, Server, ServerTlsConfig},
+    Code, Request, Response, Status,
+};
+use tracing::{error, info};
+use validator::ValidationError;
+
+use crate::{
+    auth::{Role, HOLDERS, ISSUERS, READERS},
+    contract_client::ContractClient,
+    handlers::AppState,
+    indexer::{CertificateFilter, CERTIFICATE_STATUSES},
+    models::{CertId, CertificateSummary, ErrorCode, MetadataHash, StellarAddress},
+    networks::{Network, NETWORK_HEADER},
+    pagination::{CertificateCursor, CertificateSort, CERTIFICATE_SORTS},
+    soroban_client::TransactionOutcome,
+    tls::TlsSettings,
+    validation::validate_issuable,
+};
+
+/// Code generated from `proto/veriluxe.proto`
+pub mod proto {
+    tonic::include_proto!("veriluxe.v1");
+}
+
+use proto::certificate_service_server::{CertificateService, CertificateServiceServer};
+
+/// Tunables for the gRPC server
+#[derive(Debug, Clone)]
+pub struct GrpcSettings {
+    pub enabled: bool,
+    /// Served on `API_HOST` at this port
+    pub port: u16,
+}
+
+impl Default for GrpcSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 50051,
+        }
+    }
+}
+
+/// Serve the gRPC API on `host`, over TLS when HTTPS is enabled
+pub async fn serve(
+    state: AppState,
+    host: &str,
+    settings: &GrpcSettings,
+    tls: &TlsSettings,
+) -> Result<()> {
+    let address: SocketAddr = format!("{}:{}", host, settings.port)
+        .parse()
+        .map_err(|_| anyhow!("API_HOST must be an IP address when gRPC is enabled"))?;
+
+    let mut server = Server::builder();
+    if tls.enabled {
+        let cert = tokio::fs::read(&tls.cert_path).await?;
+        let key = tokio::fs::read(&tls.key_path).await?;
+        server = server
+            .tls_config(ServerTlsConfig::new().identity(Identity::from_pem(cert, key)))
+            .map_err(|e| anyhow!("Failed to configure gRPC TLS: {}", e))?;
+    }
+
+    info!("gRPC server listening on {}", address);
+    server
+        .add_service(CertificateServiceServer::new(GrpcService { state }))
+        .serve(address)
+        .await
+        .map_err(|e| anyhow!("gRPC server stopped: {}", e))
+}
+
+/// [`CertificateService`] backed by the same state as the REST API
+pub struct GrpcService {
+    state: AppState,
+}
+
+impl GrpcService {
+    /// Check the call's bearer token grants one of `roles`
+    fn authorize(&self, metadata: &MetadataMap, roles: &[Role]) -> Result<(), Status> {
+        if !self.state.auth.is_enabled() {
+            return Ok(());
+        }
+
+        let token = metadata
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| Status::unauthenticated("Missing bearer token"))?;
+        let claims = self
+            .state
+            .auth
+            .verify(token)
+            .map_err(|e| Status::unauthenticated(e.to_string()))?;
+
+        if !claims.roles.iter().any(|role| roles.contains(role)) {
+            let allowed: Vec<&str> = roles.iter().map(Role::as_str).collect();
+            return Err(Status::permission_denied(format!(
+                "Requires one of the roles: {}",
+                allowed.join(", ")
+            )));
+        }
+        Ok(())
+    }
+
+    /// The network named by the call's `x-network` entry, or the default one
+    fn network(&self, metadata: &MetadataMap) -> Result<Network, Status> {
+        let name = metadata
+            .get(NETWORK_HEADER)
+            .map(|value| value.to_str().unwrap_or_default().trim());
+        self.state.networks.get(name).cloned().ok_or_else(|| {
+            Status::invalid_argument(format!(
+                "Unknown network {}; expected one of: {}",
+                name.unwrap_or_default(),
+                self.state.networks.names().join(", ")
+            ))
+        })
+    }
+
+    /// Reply for a submitted transaction, waiting for it if asked
+    async fn finish(
+        &self,
+        client: &dyn ContractClient,
+        tx_hash: String,
+        wait: bool,
+    ) -> Result<proto::TransactionReply, Status> {
+        if !wait {
+            return Ok(proto::TransactionReply {
+                transaction_hash: tx_hash,
+                status: "submitted".to_string(),
+                ledger: None,
+            });
+        }
+
+        let outcome = client
+            .wait_for_transaction(
+                &tx_hash,
+                Duration::from_secs(self.state.submit.wait_timeout_secs),
+                Duration::from_millis(self.state.submit.poll_interval_ms),
+            )
+            .await
+            .map_err(|e| failed("Transaction not confirmed", &e))?;
+
+        match outcome {
+            TransactionOutcome::Success { ledger, .. } => Ok(proto::TransactionReply {
+                transaction_hash: tx_hash,
+                status: "confirmed".to_string(),
+                ledger: Some(ledger),
+            }),
+            TransactionOutcome::Failed { ledger, summary } => Err(with_code(
+                Status::failed_precondition(summary.failure_message(&tx_hash, ledger)),
+                ErrorCode::TransactionFailed,
+            )),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl CertificateService for GrpcService {
+    async fn issue_certificate(
+        &self,
+        request: Request<proto::IssueCertificateRequest>,
+    ) -> Result<Response<proto::TransactionReply>, Status> {
+        self.authorize(request.metadata(), ISSUERS)?;
+        let network = self.network(request.metadata())?;
+        let request = request.into_inner();
+        info!("gRPC: issuing certificate {}", request.cert_id);
+
+        let cert_id = cert_id(&request.cert_id)?;
+        valid(validate_issuable(&cert_id))?;
+        let metadata_hash = metadata_hash(&request.metadata_hash)?;
+        let owner_address = account("owner_address", &request.owner_address)?;
+
+        let tx_hash = network
+            .client
+            .issue_certificate(&cert_id, &metadata_hash, &owner_address)
+            .await
+            .map_err(|e| failed("Failed to issue certificate", &e))?;
+        let reply = self.finish(network.client.as_ref(), tx_hash, request.wait).await?;
+        Ok(Response::new(reply))
+    }
+
+    async fn verify_certificate(
+        &self,
+        request: Request<proto::VerifyCertificateRequest>,
+    ) -> Result<Response<proto::VerifyCertificateReply>, Status> {
+        let network = self.network(request.metadata())?;
+        let request = request.into_inner();
+
+        let cert_id = cert_id(&request.cert_id)?;
+        let metadata_hash = metadata_hash(&request.metadata_hash)?;
+
+        let is_valid = network
+            .client
+            .verify_certificate(&cert_id, &metadata_hash)
+            .await
+            .map_err(|e| failed("Failed to verify certificate", &e))?;
+        Ok(Response::new(proto::VerifyCertificateReply {
+            cert_id: request.cert_id,
+            is_valid,
+        }))
+    }
+
+    async fn prepare_transfer(
+        &self,
+        request: Request<proto::PrepareTransferRequest>,
+    ) -> Result<Response<proto::PreparedTransaction>, Status> {
+        self.authorize(request.metadata(), HOLDERS)?;
+        let network = self.network(request.metadata())?;
+        let request = request.into_inner();
+        info!("gRPC: preparing transfer of {}", request.cert_id);
+
+        let cert_id = cert_id(&request.cert_id)?;
+        let new_owner_address = account("new_owner_address", &request.new_owner_address)?;
+
+        let prepared = network
+            .client
+            .prepare_transfer(&cert_id, &new_owner_address)
+            .await
+            .map_err(|e| failed("Failed to prepare transfer", &e))?;
+        Ok(Response::new(proto::PreparedTransaction {
+            transaction: prepared.transaction,
+            hash: prepared.hash,
+            source: prepared.source,
+            network_passphrase: prepared.network_passphrase,
+        }))
+    }
+
+    async fn submit_transaction(
+        &self,
+        request: Request<proto::SubmitTransactionRequest>,
+    ) -> Result<Response<proto::TransactionReply>, Status> {
+        self.authorize(request.metadata(), HOLDERS)?;
+        let network = self.network(request.metadata())?;
+        let request = request.into_inner();
+
+        required("transaction", &request.transaction)?;
+
+        let tx_hash = network
+            .client
+            .submit_signed_transaction(&request.transaction)
+            .await
+            .map_err(|e| failed("Failed to submit transaction", &e))?;
+        let reply = self.finish(network.client.as_ref(), tx_hash, request.wait).await?;
+        Ok(Response::new(reply))
+    }
+
+    async fn list_certificates(
+        &self,
+        request: Request<proto::ListCertificatesRequest>,
+    ) -> Result<Response<proto::ListCertificatesReply>, Status> {
+        self.authorize(request.metadata(), READERS)?;
+        let network = self.network(request.metadata())?;
+        let request = request.into_inner();
+
+        let limit = if request.limit == 0 {
+            50
+        } else {
+            request.limit
+        };
+        if !(1..=200).contains(&limit) {
+            return Err(Status::invalid_argument("Limit must be between 1 and 200"));
+        }
+        let optional = |value: String| (!value.is_empty()).then_some(value);
+        let sort = match optional(request.sort) {
+            None => CertificateSort::default(),
+            Some(sort) => CertificateSort::parse(&sort).ok_or_else(|| {
+                Status::invalid_argument(format!(
+                    "Sort must be one of: {}, optionally prefixed with -",
+                    CERTIFICATE_SORTS.join(", ")
+                ))
+            })?,
+        };
+        let cursor = match optional(request.cursor) {
+            None => None,
+            Some(token) => Some(CertificateCursor::decode(&token, sort).ok_or_else(|| {
+                Status::invalid_argument("Cursor must be a next_cursor returned for the same sort")
+            })?),
+        };
+        let status = optional(request.status);
+        if let Some(status) = &status {
+            if !CERTIFICATE_STATUSES.contains(&status.as_str()) {
+                return Err(Status::invalid_argument(format!(
+                    "Status must be one of: {}",
+                    CERTIFICATE_STATUSES.join(", ")
+                )));
+            }
+        }
+
+        // The indexer only follows the default network
+        let indexer = match &self.state.indexer {
+            Some(indexer) if network.is_default => indexer,
+            Some(_) => {
+                return Err(Status::invalid_argument(format!(
+                    "The certificate index doesn't cover network {}",
+                    network.name
+                )))
+            }
+            None => {
+                return Err(Status::unavailable(
+                    "Listing requires the indexer (INDEXER_ENABLED)",
+                ))
+            }
+        };
+
+        let filter = CertificateFilter {
+            owner: optional(request.owner),
+            status,
+            brand: optional(request.brand),
+            collection_id: request.collection_id,
+            ..CertificateFilter::default()
+        };
+        let page = indexer
+            .list_certificates(&filter, sort, limit, cursor.as_ref())
+            .await
+            .map_err(|e| failed("Failed to list certificates", &e))?;
+
+        Ok(Response::new(proto::ListCertificatesReply {
+            certificates: page.certificates.into_iter().map(summary).collect(),
+            next_cursor: page.next_cursor.unwrap_or_default(),
+        }))
+    }
+}
+
+fn summary(certificate: CertificateSummary) -> proto::CertificateSummary {
+    proto::CertificateSummary {
+        cert_id: certificate.cert_id,
+        owner: certificate.owner,
+        metadata_hash: certificate.metadata_hash,
+        status: certificate.status,
+        disputed: certificate.disputed,
+        brand: certificate.brand,
+        model: certificate.model,
+        issued_ledger: certificate.issued_ledger,
+        issued_at: certificate.issued_at.map(|at| at.timestamp()),
+        updated_ledger: certificate.updated_ledger,
+        updated_at: certificate.updated_at.map(|at| at.timestamp()),
+    }
+}
+
+fn required(field: &str, value: &str) -> Result<(), Status> {
+    if value.trim().is_empty() {
+        return Err(with_code(
+            Status::invalid_argument(format!("{} cannot be empty", field)),
+            ErrorCode::InvalidRequest,
+        ));
+    }
+    Ok(())
+}
+
+/// Apply one of the REST request models' validators
+fn valid(result: Result<(), ValidationError>) -> Result<(), Status> {
+    result.map_err(|e| {
+        let message = e.message.map(|m| m.to_string()).unwrap_or_else(|| e.code.to_string());
+        with_code(Status::invalid_argument(message), ErrorCode::InvalidRequest)
+    })
+}
+
+fn cert_id(cert_id: &str) -> Result<CertId, Status> {
+    CertId::parse(cert_id)
+        .map_err(|e| with_code(Status::invalid_argument(e), ErrorCode::InvalidCertId))
+}
+
+fn metadata_hash(hash: &str) -> Result<MetadataHash, Status> {
+    MetadataHash::parse(hash)
+        .map_err(|e| with_code(Status::invalid_argument(e), ErrorCode::InvalidRequest))
+}
+
+fn account(field: &str, address: &str) -> Result<StellarAddress, Status> {
+    StellarAddress::parse(address).map_err(|_| {
+        with_code(
+            Status::invalid_argument(format!("{} must be a Stellar public key (G...)", field)),
+            ErrorCode::InvalidAddress,
+        )
+    })
+}
+
+/// Map a Soroban client failure to a status, keeping the REST error code
+fn failed(context: &str, e: &anyhow::Error) -> Status {
+    error!("{}: {}", context, e);
+    let code = ErrorCode::classify(e);
+    let grpc_code = match code {
+        ErrorCode::NotFound | ErrorCode::CertNotFound => Code::NotFound,
+        ErrorCode::DuplicateCertId | ErrorCode::ReplayedRequest => Code::AlreadyExists,
+        ErrorCode::InvalidRequest
+        | ErrorCode::InvalidAddress
+        | ErrorCode::InvalidCertId
+        | ErrorCode::QrInvalid => Code::InvalidArgument,
+        ErrorCode::CertRevoked
+        | ErrorCode::CertLocked
+        | ErrorCode::ContractNotInitialized
+        | ErrorCode::ContractPaused
+        | ErrorCode::QrExpired
+        | ErrorCode::TransactionRejected
+        | ErrorCode::TransactionFailed => Code::FailedPrecondition,
+        ErrorCode::Unauthorized => Code::Unauthenticated,
+        ErrorCode::Forbidden => Code::PermissionDenied,
+        ErrorCode::QuotaExceeded => Code::ResourceExhausted,
+        ErrorCode::TransactionNotConfirmed => Code::DeadlineExceeded,
+        ErrorCode::RpcUnavailable | ErrorCode::ServiceUnavailable => Code::Unavailable,
+        ErrorCode::InternalError => Code::Internal,
+    };
+    with_code(Status::new(grpc_code, format!("{}: {}", context, e)), code)
+}
+
+/// Attach the REST API's error code as `x-error-code` metadata
+fn with_code(mut status: Status, code: ErrorCode) -> Status {
+    let code = serde_json::to_value(code)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default();
+    if let Ok(value) = MetadataValue::try_from(code) {
+        status.metadata_mut().insert("x-error-code", value);
+    }
+    status
+}
@@ -1,25 +1,240 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    Json,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
 };
-use tracing::{error, info};
+use serde_json::Value;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use stellar_strkey::ed25519;
+use tracing::{error, info, warn};
 use utoipa::{self, OpenApi};
 
 use crate::{
     models::{
-        ApiResponse, Certificate, ErrorResponse, ExistsResponse, InitRequest,
-        IssueCertificateRequest, TransactionResponse, TransferCertificateRequest,
+        ApiResponse, CertId, Certificate, ErrorCode, ErrorResponse, ExistsResponse, FieldError, InitRequest,
+        IssueCertificateRequest, TransactionResponse, CallSummary, EmittedEvent, PrepareTransferRequest,
         VerifyCertificateRequest, VerifyResponse, HealthResponse, CertificateResponse,
-        TransactionApiResponse, VerifyApiResponse, ExistsApiResponse,
+        TransactionApiResponse, VerifyApiResponse, ExistsApiResponse, FederatedVerifyQuery,
+        FederatedVerifyResponse, FederatedVerifyApiResponse, RentForecastApiResponse,
+        SubmitQuery, JobApiResponse, RegisterWebhookRequest, WebhookApiResponse,
+        WebhookListApiResponse, CertificateHistoryApiResponse, ReconciliationApiResponse, UpdateNotificationPreferencesRequest, NotificationPreferencesApiResponse, WebhookDeliveriesQuery, DeliveryListApiResponse, ListCertificatesQuery, CertificatePage, CertificateSummary,
+        CertificatePageApiResponse, SearchCertificatesQuery, ChallengeQuery, ChallengeResponse,
+        ChallengeTokenRequest, ChallengeTokenResponse, PreparedTransaction,
+        PreparedTransactionApiResponse, SubmitTransactionRequest, ReadinessApiResponse,
+        FundAccountRequest, FundedAccount, FundedAccountApiResponse, AccountInfoApiResponse,
+        DevKeypairApiResponse, StrkeyConversionApiResponse,
+        VerifyQrRequest, QrVerifyResponse, QrVerifyApiResponse, QrPayloadApiResponse,
+        PublicVerifyQuery, PublicVerifyResponse, PublicVerifyApiResponse, VerificationReason,
+        AuditQuery, AuditEntry, AuditPage, AuditPageApiResponse, AuditVerification,
+        AuditVerificationApiResponse, ExportQuery, ExportedCertificate, CertificateHistoryEvent,
+        ImportQuery, ImportReportApiResponse, LegacyCertificate, FeeBumpRequest, ApiKeyUsageApiResponse,
+        FeeEstimateApiResponse, ReloadApiResponse, CertificateQuery, CERTIFICATE_FIELDS,
+        CERTIFICATE_SUMMARY_FIELDS, MetadataHash, StellarAddress,
     },
-    soroban_client::SorobanClient,
+    audit::{AuditFilter, AuditLog, AUDIT_OUTCOMES},
+    export::{export_body, ExportFormat},
+    fields::FieldSelection,
+    auth::{Authenticator, Claims},
+    circuit::CircuitOpen,
+    contract_client::ContractClient,
+    cors::CorsOrigins,
+    devtools::{self, DevKeypair, StrkeyConversion, StrkeyConvertQuery},
+    federation::Federation,
+    notifications::{self, NotificationPreferences, Notifier},
+    reconcile::{Drift, ReconciliationReport, ReconciliationStatus, Reconciler},
+    reload::{ConfigReloader, ReloadReport},
+    replay::ReplayGuard,
+    server::ServerSettings,
+    validation::{LimitSettings, Valid},
+    friendbot::Friendbot,
+    fees::FeeEstimate,
+    health::{HealthCheck, HealthChecker, ReadinessReport},
+    i18n,
+    horizon::{AccountBalance, AccountInfo, AccountSigner, Horizon},
+    import::{ImportFormat, ImportManager, ImportReport, ImportRow, ImportRowStatus, ImportStatus},
+    indexer::{CertificateFilter, Indexer, CERTIFICATE_STATUSES},
+    jobs::{Job, JobOperation, JobQueue, JobStatus},
+    networks::{Network, Networks},
+    pagination::{AuditCursor, CertificateCursor, CertificateSort, CERTIFICATE_SORTS},
+    qr::{QrCodec, QrPayload},
+    rent::{RentForecast, RentManager},
+    sep10::WebAuth,
+    signer::{LocalSigner, Signer},
+    tenants::{Tenant, Tenants},
+    usage::{ApiKeyUsage, PeriodUsage},
+    soroban_client::{ContractError, SorobanClient, SubmitSettings, TransactionOutcome},
+    webhooks::{Delivery, DeliveryStatus, Webhook, WebhookDispatcher},
 };
 
 /// Application state containing the Soroban client
 #[derive(Clone)]
 pub struct AppState {
     pub soroban_client: SorobanClient,
+    pub networks: Networks,
+    pub tenants: Tenants,
+    pub federation: Federation,
+    pub rent_manager: RentManager,
+    pub health: HealthChecker,
+    pub submit: SubmitSettings,
+    pub job_queue: JobQueue,
+    pub imports: ImportManager,
+    pub webhooks: WebhookDispatcher,
+    pub indexer: Option<Indexer>,
+    pub reconciler: Option<Reconciler>,
+    pub audit: Option<AuditLog>,
+    pub notifier: Option<Notifier>,
+    pub auth: Authenticator,
+    pub web_auth: Option<WebAuth>,
+    pub qr: Option<QrCodec>,
+    pub friendbot: Option<Friendbot>,
+    /// Whether `/dev/keypairs` and `/dev/strkey/convert` are served
+    pub dev_tools: bool,
+    pub horizon: Option<Horizon>,
+    pub cors: CorsOrigins,
+    pub reloader: Option<ConfigReloader>,
+    pub replay: Option<ReplayGuard>,
+    pub limits: LimitSettings,
+    pub server: ServerSettings,
+}
+
+/// Whether writes go through the job queue
+///
+/// The queue submits to the default network, so writes to other networks are
+/// always sent directly.
+fn queue_writes(state: &AppState, network: &Network) -> bool {
+    network.is_default && state.job_queue.is_enabled()
+}
+
+/// Hand a write to the job queue and answer 202 with the new job
+async fn enqueue_job(
+    state: &AppState,
+    operation: JobOperation,
+    message: &str,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    match state.job_queue.enqueue(operation).await {
+        Ok(job) => Ok((
+            StatusCode::ACCEPTED,
+            Json(ApiResponse::success(job, message.to_string())),
+        )
+            .into_response()),
+        Err(e) => {
+            error!("Failed to queue job: {}", e);
+            Err((
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(ErrorResponse::new(format!("Failed to queue job: {}", e), 503)),
+            ))
+        }
+    }
+}
+
+/// Build the response for a submitted transaction, waiting for it if requested
+///
+/// `?wait=` overrides the configured default. A transaction that fails on-chain
+/// or isn't confirmed in time is reported as an error.
+async fn finish_submission(
+    state: &AppState,
+    client: &dyn ContractClient,
+    tx_hash: String,
+    options: &SubmitQuery,
+) -> Result<TransactionResponse, (StatusCode, Json<ErrorResponse>)> {
+    if !options.wait.unwrap_or(state.submit.wait_by_default) {
+        return Ok(TransactionResponse {
+            transaction_hash: tx_hash,
+            status: "submitted".to_string(),
+            ledger: None,
+            result: None,
+        });
+    }
+
+    let outcome = client
+        .wait_for_transaction(
+            &tx_hash,
+            Duration::from_secs(state.submit.wait_timeout_secs),
+            Duration::from_millis(state.submit.poll_interval_ms),
+        )
+        .await;
+
+    match outcome {
+        Ok(TransactionOutcome::Success { ledger, summary }) => Ok(TransactionResponse {
+            transaction_hash: tx_hash,
+            status: "confirmed".to_string(),
+            ledger: Some(ledger),
+            result: Some(summary),
+        }),
+        Ok(TransactionOutcome::Failed { ledger, summary }) => {
+            let message = summary.failure_message(&tx_hash, ledger);
+            error!("{}", message);
+            let code = summary
+                .error
+                .as_deref()
+                .and_then(ContractError::parse)
+                .map_or(ErrorCode::TransactionFailed, ErrorCode::from);
+            let status = code.status();
+            Err((
+                StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+                Json(ErrorResponse::new(message, status).with_code(code)),
+            ))
+        }
+        Err(e) if ErrorCode::classify(&e) == ErrorCode::TransactionNotConfirmed => Err((
+            StatusCode::GATEWAY_TIMEOUT,
+            Json(ErrorResponse::new(e.to_string(), 504)),
+        )),
+        Err(e) => {
+            error!("Failed to confirm transaction {}: {}", tx_hash, e);
+            Err(operation_failed(&format!("Failed to confirm transaction {}", tx_hash), &e))
+        }
+    }
+}
+
+/// An error response for a failed operation, tagged with the cause of `e`
+///
+/// Typed contract errors get their own status, e.g. 409 for a duplicate
+/// certificate ID; other failures are reported as 500.
+fn operation_failed(context: &str, e: &anyhow::Error) -> (StatusCode, Json<ErrorResponse>) {
+    // The RPC wasn't called at all; the caller should come back later
+    if e.is::<CircuitOpen>() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(format!("{}: {}", context, e), 503).with_code(ErrorCode::RpcUnavailable)),
+        );
+    }
+
+    let code = ErrorCode::classify(e);
+    let status = code.status();
+    (
+        StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+        Json(ErrorResponse::new(format!("{}: {}", context, e), status).with_code(code)),
+    )
+}
+
+/// Parse the certificate ID given in the path or query string
+fn parse_cert_id(cert_id: &str) -> Result<CertId, (StatusCode, Json<ErrorResponse>)> {
+    CertId::parse(cert_id).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::invalid_field("cert_id", e).with_code(ErrorCode::InvalidCertId)),
+        )
+    })
+}
+
+/// Parse the metadata hash given in query parameter `field`
+fn parse_metadata_hash(field: &str, hash: &str) -> Result<MetadataHash, (StatusCode, Json<ErrorResponse>)> {
+    MetadataHash::parse(hash)
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse::invalid_field(field, e))))
+}
+
+/// Parse the `G...` account address given in `field` of the path
+fn parse_address(field: &str, address: &str) -> Result<StellarAddress, (StatusCode, Json<ErrorResponse>)> {
+    StellarAddress::parse(address).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::invalid_field(
+                field,
+                format!("{} must be a Stellar public key (G...)", field),
+            ).with_code(ErrorCode::InvalidAddress)),
+        )
+    })
 }
 
 /// Initialize the contract with admin
@@ -29,36 +244,43 @@ pub struct AppState {
     request_body = InitRequest,
     responses(
         (status = 200, description = "Contract initialized successfully", body = TransactionApiResponse),
+        (status = 202, description = "Initialization queued", body = JobApiResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "Contract Management"
 )]
 pub async fn init_contract(
     State(state): State<AppState>,
-    Json(payload): Json<InitRequest>,
-) -> Result<Json<ApiResponse<TransactionResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    Extension(network): Extension<Network>,
+    Valid(payload): Valid<InitRequest>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
     info!("Initializing contract with admin: {}", payload.admin_address);
 
-    match state.soroban_client.init(&payload.admin_address).await {
+    if queue_writes(&state, &network) {
+        let operation = JobOperation::Init {
+            admin_address: payload.admin_address.clone(),
+        };
+        return enqueue_job(&state, operation, "Contract initialization queued").await;
+    }
+
+    match network.client.init(&payload.admin_address).await {
         Ok(tx_hash) => {
             let response = ApiResponse::success(
                 TransactionResponse {
                     transaction_hash: tx_hash,
                     status: "submitted".to_string(),
+                    ledger: None,
+                    result: None,
                 },
                 "Contract initialized successfully".to_string(),
             );
-            Ok(Json(response))
+            Ok(Json(response).into_response())
         }
         Err(e) => {
             error!("Failed to initialize contract: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse::internal_error(format!(
-                    "Failed to initialize contract: {}",
-                    e
-                ))),
-            ))
+            Err(operation_failed("Failed to initialize contract", &e))
         }
     }
 }
@@ -67,72 +289,465 @@ pub async fn init_contract(
 #[utoipa::path(
     post,
     path = "/certificates",
+    params(SubmitQuery),
     request_body = IssueCertificateRequest,
     responses(
         (status = 200, description = "Certificate issued successfully", body = TransactionApiResponse),
+        (status = 202, description = "Issuance queued", body = JobApiResponse),
         (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 409, description = "Certificate ID already issued", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "Certificate Management"
 )]
 pub async fn issue_certificate(
     State(state): State<AppState>,
-    Json(payload): Json<IssueCertificateRequest>,
-) -> Result<Json<ApiResponse<TransactionResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    Extension(network): Extension<Network>,
+    Query(options): Query<SubmitQuery>,
+    Valid(payload): Valid<IssueCertificateRequest>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
     info!("Issuing certificate: {}", payload.cert_id);
 
-    // Validate input
-    if payload.cert_id.is_empty() {
+    if queue_writes(&state, &network) {
+        let operation = JobOperation::Issue {
+            cert_id: payload.cert_id.clone(),
+            metadata_hash: payload.metadata_hash.clone(),
+            owner_address: payload.owner_address.clone(),
+        };
+        return enqueue_job(&state, operation, "Certificate issuance queued").await;
+    }
+
+    match network
+        .client
+        .issue_certificate(&payload.cert_id, &payload.metadata_hash, &payload.owner_address)
+        .await
+    {
+        Ok(tx_hash) => {
+            let transaction = finish_submission(&state, network.client.as_ref(), tx_hash, &options).await?;
+            let response = ApiResponse::success(
+                transaction,
+                "Certificate issued successfully".to_string(),
+            );
+            Ok(Json(response).into_response())
+        }
+        Err(e) => {
+            error!("Failed to issue certificate: {}", e);
+            Err(operation_failed("Failed to issue certificate", &e))
+        }
+    }
+}
+
+/// Validate a page size, defaulting to 50
+fn page_limit(limit: Option<u32>) -> Result<u32, (StatusCode, Json<ErrorResponse>)> {
+    let limit = limit.unwrap_or(50);
+    if !(1..=200).contains(&limit) {
         return Err((
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse::bad_request(
-                "Certificate ID cannot be empty".to_string(),
+                "Limit must be between 1 and 200".to_string(),
             )),
         ));
     }
+    Ok(limit)
+}
+
+/// Order and starting point requested by `sort` and `cursor`
+///
+/// A cursor is only valid for the sort it was issued under.
+fn certificate_paging(
+    sort: Option<&str>,
+    cursor: Option<&str>,
+) -> Result<(CertificateSort, Option<CertificateCursor>), (StatusCode, Json<ErrorResponse>)> {
+    let sort = match sort {
+        None => CertificateSort::default(),
+        Some(sort) => CertificateSort::parse(sort).ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::bad_request(format!(
+                    "Sort must be one of: {}, optionally prefixed with -",
+                    CERTIFICATE_SORTS.join(", ")
+                ))),
+            )
+        })?,
+    };
+
+    let cursor = match cursor {
+        None => None,
+        Some(token) => Some(CertificateCursor::decode(token, sort).ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::bad_request(
+                    "Cursor must be a next_cursor returned for the same sort".to_string(),
+                )),
+            )
+        })?),
+    };
+
+    Ok((sort, cursor))
+}
+
+/// Fields picked with `fields=`, out of those `available` in the response
+fn field_selection(
+    fields: Option<&str>,
+    available: &[&str],
+) -> Result<Option<FieldSelection>, (StatusCode, Json<ErrorResponse>)> {
+    FieldSelection::parse(fields, available)
+        .map_err(|message| (StatusCode::BAD_REQUEST, Json(ErrorResponse::bad_request(message))))
+}
+
+/// A page of certificates with each certificate trimmed to `fields`
+///
+/// The certificate ID is always kept so clients can tell the rows apart.
+fn certificate_page_json(page: &CertificatePage, fields: Option<&FieldSelection>) -> Value {
+    let mut value = serde_json::to_value(page).expect("certificate pages serialize to JSON");
+    if let (Some(fields), Some(certificates)) = (
+        fields,
+        value.get_mut("certificates").and_then(Value::as_array_mut),
+    ) {
+        for certificate in certificates {
+            fields.trim(certificate, &["cert_id"]);
+        }
+    }
+    value
+}
 
-    if payload.metadata_hash.is_empty() {
+/// The event indexer, or 503 when it isn't running
+///
+/// The indexer only follows the default network, so other networks get a 400.
+fn require_indexer<'a>(
+    state: &'a AppState,
+    network: &Network,
+) -> Result<&'a Indexer, (StatusCode, Json<ErrorResponse>)> {
+    if !network.is_default {
         return Err((
             StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::bad_request(
-                "Metadata hash cannot be empty".to_string(),
-            )),
+            Json(ErrorResponse::bad_request(format!(
+                "The certificate index doesn't cover network {}",
+                network.name
+            ))),
         ));
     }
 
-    if payload.owner_address.is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::bad_request(
-                "Owner address cannot be empty".to_string(),
+    state.indexer.as_ref().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(
+                "This endpoint requires the indexer (INDEXER_ENABLED)".to_string(),
+                503,
+            )),
+        )
+    })
+}
+
+/// List issued certificates, one page at a time
+#[utoipa::path(
+    get,
+    path = "/certificates",
+    params(ListCertificatesQuery),
+    responses(
+        (status = 200, description = "Certificates retrieved successfully", body = CertificatePageApiResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 503, description = "Certificate index not enabled", body = ErrorResponse)
+    ),
+    tag = "Certificate Management"
+)]
+pub async fn list_certificates(
+    State(state): State<AppState>,
+    Extension(network): Extension<Network>,
+    Query(query): Query<ListCertificatesQuery>,
+) -> Result<Json<ApiResponse<Value>>, (StatusCode, Json<ErrorResponse>)> {
+    info!(
+        "Listing certificates (sort {:?}, limit {:?}, cursor {:?})",
+        query.sort, query.limit, query.cursor
+    );
+
+    let limit = page_limit(query.limit)?;
+    let (sort, cursor) = certificate_paging(query.sort.as_deref(), query.cursor.as_deref())?;
+    let fields = field_selection(query.fields.as_deref(), CERTIFICATE_SUMMARY_FIELDS)?;
+    let indexer = require_indexer(&state, &network)?;
+
+    match indexer
+        .list_certificates(&CertificateFilter::default(), sort, limit, cursor.as_ref())
+        .await
+    {
+        Ok(page) => Ok(Json(ApiResponse::success(
+            certificate_page_json(&page, fields.as_ref()),
+            "Certificates retrieved successfully".to_string(),
+        ))),
+        Err(e) => {
+            error!("Failed to list certificates: {}", e);
+            Err(operation_failed("Failed to list certificates", &e))
+        }
+    }
+}
+
+/// Search certificates by owner, status, brand, collection, issuance date or free text
+#[utoipa::path(
+    get,
+    path = "/certificates/search",
+    params(SearchCertificatesQuery),
+    responses(
+        (status = 200, description = "Search completed", body = CertificatePageApiResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 503, description = "Certificate index not enabled", body = ErrorResponse)
+    ),
+    tag = "Certificate Management"
+)]
+pub async fn search_certificates(
+    State(state): State<AppState>,
+    Extension(network): Extension<Network>,
+    Query(query): Query<SearchCertificatesQuery>,
+) -> Result<Json<ApiResponse<Value>>, (StatusCode, Json<ErrorResponse>)> {
+    info!("Searching certificates: {:?}", query);
+
+    let limit = page_limit(query.limit)?;
+    let (sort, cursor) = certificate_paging(query.sort.as_deref(), query.cursor.as_deref())?;
+    let fields = field_selection(query.fields.as_deref(), CERTIFICATE_SUMMARY_FIELDS)?;
+
+    if let Some(status) = &query.status {
+        if !CERTIFICATE_STATUSES.contains(&status.as_str()) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::bad_request(format!(
+                    "Status must be one of: {}",
+                    CERTIFICATE_STATUSES.join(", ")
+                ))),
+            ));
+        }
+    }
+
+    if let (Some(from), Some(to)) = (query.issued_from, query.issued_to) {
+        if from >= to {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::bad_request(
+                    "issued_from must be before issued_to".to_string(),
+                )),
+            ));
+        }
+    }
+
+    let indexer = require_indexer(&state, &network)?;
+    let filter = CertificateFilter {
+        owner: query.owner,
+        status: query.status,
+        brand: query.brand,
+        collection_id: query.collection_id,
+        issued_from: query.issued_from,
+        issued_to: query.issued_to,
+        text: query.q.filter(|q| !q.trim().is_empty()),
+    };
+
+    match indexer
+        .list_certificates(&filter, sort, limit, cursor.as_ref())
+        .await
+    {
+        Ok(page) => Ok(Json(ApiResponse::success(
+            certificate_page_json(&page, fields.as_ref()),
+            "Search completed".to_string(),
+        ))),
+        Err(e) => {
+            error!("Failed to search certificates: {}", e);
+            Err(operation_failed("Failed to search certificates", &e))
+        }
+    }
+}
+
+/// Certificates currently held by the authenticated account
+#[utoipa::path(
+    get,
+    path = "/me/certificates",
+    params(ListCertificatesQuery),
+    responses(
+        (status = 200, description = "Certificates retrieved successfully", body = CertificatePageApiResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid owner token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 503, description = "Certificate index not enabled", body = ErrorResponse)
+    ),
+    tag = "Owners"
+)]
+pub async fn my_certificates(
+    State(state): State<AppState>,
+    Extension(network): Extension<Network>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<ListCertificatesQuery>,
+) -> Result<Json<ApiResponse<Value>>, (StatusCode, Json<ErrorResponse>)> {
+    info!("Listing certificates owned by {}", claims.sub);
+
+    let limit = page_limit(query.limit)?;
+    let (sort, cursor) = certificate_paging(query.sort.as_deref(), query.cursor.as_deref())?;
+    let fields = field_selection(query.fields.as_deref(), CERTIFICATE_SUMMARY_FIELDS)?;
+    let indexer = require_indexer(&state, &network)?;
+    let filter = CertificateFilter {
+        owner: Some(claims.sub),
+        ..Default::default()
+    };
+
+    match indexer
+        .list_certificates(&filter, sort, limit, cursor.as_ref())
+        .await
+    {
+        Ok(page) => Ok(Json(ApiResponse::success(
+            certificate_page_json(&page, fields.as_ref()),
+            "Certificates retrieved successfully".to_string(),
+        ))),
+        Err(e) => {
+            error!("Failed to list owned certificates: {}", e);
+            Err(operation_failed("Failed to list certificates", &e))
+        }
+    }
+}
+
+fn require_notifier(state: &AppState) -> Result<&Notifier, (StatusCode, Json<ErrorResponse>)> {
+    state.notifier.as_ref().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(
+                "Notifications are not enabled (NOTIFICATIONS_ENABLED)".to_string(),
+                503,
+            )),
+        )
+    })
+}
+
+/// Contact details and events the authenticated owner is notified about
+#[utoipa::path(
+    get,
+    path = "/me/notifications",
+    responses(
+        (status = 200, description = "Preferences retrieved successfully", body = NotificationPreferencesApiResponse),
+        (status = 401, description = "Missing or invalid owner token", body = ErrorResponse),
+        (status = 404, description = "No preferences registered", body = ErrorResponse),
+        (status = 503, description = "Notifications not enabled", body = ErrorResponse)
+    ),
+    tag = "Owners"
+)]
+pub async fn get_notification_preferences(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<NotificationPreferences>>, (StatusCode, Json<ErrorResponse>)> {
+    let notifier = require_notifier(&state)?;
+
+    match notifier.preferences(&claims.sub).await {
+        Ok(Some(preferences)) => Ok(Json(ApiResponse::success(
+            preferences,
+            "Preferences retrieved successfully".to_string(),
+        ))),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::not_found(
+                "No notification preferences registered".to_string(),
             )),
+        )),
+        Err(e) => {
+            error!("Failed to load notification preferences: {}", e);
+            Err(operation_failed("Failed to load notification preferences", &e))
+        }
+    }
+}
+
+/// Register the authenticated owner's contact details for notifications
+///
+/// Replaces any previous preferences. Owners are notified when their
+/// certificates are transferred, revoked or disputed.
+#[utoipa::path(
+    put,
+    path = "/me/notifications",
+    request_body = UpdateNotificationPreferencesRequest,
+    responses(
+        (status = 200, description = "Preferences saved successfully", body = NotificationPreferencesApiResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid owner token", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields", body = ErrorResponse),
+        (status = 503, description = "Notifications not enabled", body = ErrorResponse)
+    ),
+    tag = "Owners"
+)]
+pub async fn update_notification_preferences(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Valid(payload): Valid<UpdateNotificationPreferencesRequest>,
+) -> Result<Json<ApiResponse<NotificationPreferences>>, (StatusCode, Json<ErrorResponse>)> {
+    let notifier = require_notifier(&state)?;
+    info!("Updating notification preferences for {}", claims.sub);
+
+    let bad_request = |message: String| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::bad_request(message)),
+        )
+    };
+    if payload.email.is_none() && payload.phone.is_none() {
+        return Err(bad_request(
+            "Provide an email address or a phone number".to_string(),
         ));
     }
+    if payload.email.is_some() && !notifier.supports("email") {
+        return Err(bad_request("Email notifications are not configured".to_string()));
+    }
+    if payload.phone.is_some() && !notifier.supports("sms") {
+        return Err(bad_request("SMS notifications are not configured".to_string()));
+    }
 
-    match state
-        .soroban_client
-        .issue_certificate(&payload.cert_id, &payload.metadata_hash, &payload.owner_address)
+    let events = payload.events.unwrap_or_default();
+    if let Err(e) = notifications::validate_preferences(
+        payload.email.as_deref(),
+        payload.phone.as_deref(),
+        &events,
+    ) {
+        return Err(bad_request(e.to_string()));
+    }
+
+    match notifier
+        .save_preferences(&claims.sub, payload.email, payload.phone, events)
         .await
     {
-        Ok(tx_hash) => {
-            let response = ApiResponse::success(
-                TransactionResponse {
-                    transaction_hash: tx_hash,
-                    status: "submitted".to_string(),
-                },
-                "Certificate issued successfully".to_string(),
-            );
-            Ok(Json(response))
+        Ok(preferences) => Ok(Json(ApiResponse::success(
+            preferences,
+            "Preferences saved successfully".to_string(),
+        ))),
+        Err(e) => {
+            error!("Failed to save notification preferences: {}", e);
+            Err(operation_failed("Failed to save notification preferences", &e))
         }
+    }
+}
+
+/// Stop notifying the authenticated owner and forget their contact details
+#[utoipa::path(
+    delete,
+    path = "/me/notifications",
+    responses(
+        (status = 200, description = "Preferences removed successfully"),
+        (status = 401, description = "Missing or invalid owner token", body = ErrorResponse),
+        (status = 404, description = "No preferences registered", body = ErrorResponse),
+        (status = 503, description = "Notifications not enabled", body = ErrorResponse)
+    ),
+    tag = "Owners"
+)]
+pub async fn delete_notification_preferences(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<()>>, (StatusCode, Json<ErrorResponse>)> {
+    let notifier = require_notifier(&state)?;
+
+    match notifier.remove_preferences(&claims.sub).await {
+        Ok(true) => Ok(Json(ApiResponse::<()>::success_with_message(
+            "Preferences removed successfully".to_string(),
+        ))),
+        Ok(false) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::not_found(
+                "No notification preferences registered".to_string(),
+            )),
+        )),
         Err(e) => {
-            error!("Failed to issue certificate: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse::internal_error(format!(
-                    "Failed to issue certificate: {}",
-                    e
-                ))),
-            ))
+            error!("Failed to remove notification preferences: {}", e);
+            Err(operation_failed("Failed to remove notification preferences", &e))
         }
     }
 }
@@ -142,7 +757,8 @@ pub async fn issue_certificate(
     get,
     path = "/certificates/{id}",
     params(
-        ("id" = String, Path, description = "Certificate ID")
+        ("id" = String, Path, description = "Certificate ID"),
+        CertificateQuery
     ),
     responses(
         (status = 200, description = "Certificate details retrieved successfully", body = CertificateResponse),
@@ -154,21 +770,22 @@ pub async fn issue_certificate(
 )]
 pub async fn get_certificate(
     State(state): State<AppState>,
+    Extension(network): Extension<Network>,
     Path(cert_id): Path<String>,
-) -> Result<Json<ApiResponse<Certificate>>, (StatusCode, Json<ErrorResponse>)> {
+    Query(query): Query<CertificateQuery>,
+) -> Result<Json<ApiResponse<Value>>, (StatusCode, Json<ErrorResponse>)> {
     info!("Getting certificate details for: {}", cert_id);
 
-    if cert_id.is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::bad_request(
-                "Certificate ID cannot be empty".to_string(),
-            )),
-        ));
-    }
+    let cert_id = parse_cert_id(&cert_id)?;
+    let fields = field_selection(query.fields.as_deref(), CERTIFICATE_FIELDS)?;
 
-    match state.soroban_client.get_certificate_details(&cert_id).await {
+    match network.client.get_certificate_details(&cert_id).await {
         Ok(certificate) => {
+            let mut certificate =
+                serde_json::to_value(&certificate).expect("certificates serialize to JSON");
+            if let Some(fields) = &fields {
+                fields.trim(&mut certificate, &[]);
+            }
             let response = ApiResponse::success(
                 certificate,
                 "Certificate details retrieved successfully".to_string(),
@@ -177,28 +794,69 @@ pub async fn get_certificate(
         }
         Err(e) => {
             error!("Failed to get certificate details: {}", e);
-            if e.to_string().contains("not found") {
+            if ErrorCode::classify(&e) == ErrorCode::CertNotFound {
                 Err((
                     StatusCode::NOT_FOUND,
                     Json(ErrorResponse::not_found(format!(
                         "Certificate {} not found",
                         cert_id
-                    ))),
+                    )).with_code(ErrorCode::CertNotFound)),
                 ))
             } else {
-                Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse::internal_error(format!(
-                        "Failed to get certificate details: {}",
-                        e
-                    ))),
-                ))
+                Err(operation_failed("Failed to get certificate details", &e))
             }
         }
     }
 }
 
+/// Chronological lifecycle events of a certificate, for provenance display
+///
+/// Served from the event index, so events appear once the indexer has caught
+/// up with them.
+#[utoipa::path(
+    get,
+    path = "/certificates/{id}/history",
+    params(
+        ("id" = String, Path, description = "Certificate ID")
+    ),
+    responses(
+        (status = 200, description = "History retrieved successfully", body = CertificateHistoryApiResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 404, description = "Certificate not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 503, description = "Certificate index not enabled", body = ErrorResponse)
+    ),
+    tag = "Certificate Management"
+)]
+pub async fn get_certificate_history(
+    State(state): State<AppState>,
+    Extension(network): Extension<Network>,
+    Path(cert_id): Path<String>,
+) -> Result<Json<ApiResponse<Vec<CertificateHistoryEvent>>>, (StatusCode, Json<ErrorResponse>)> {
+    info!("Getting history of certificate: {}", cert_id);
+
+    let indexer = require_indexer(&state, &network)?;
+    match indexer.certificate_history(&cert_id).await {
+        Ok(history) if history.is_empty() => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::not_found(format!("Certificate {} not found", cert_id))
+                .with_code(ErrorCode::CertNotFound)),
+        )),
+        Ok(history) => Ok(Json(ApiResponse::success(
+            history,
+            "History retrieved successfully".to_string(),
+        ))),
+        Err(e) => {
+            error!("Failed to get certificate history: {}", e);
+            Err(operation_failed("Failed to get certificate history", &e))
+        }
+    }
+}
+
 /// Verify a certificate by ID and metadata hash
+///
+/// Besides `is_valid`, the result says why a certificate didn't verify
+/// and includes its brand and issuance date, as `/public/verify` does.
 #[utoipa::path(
     post,
     path = "/certificates/{id}/verify",
@@ -209,46 +867,37 @@ pub async fn get_certificate(
     responses(
         (status = 200, description = "Certificate verification completed", body = VerifyApiResponse),
         (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "Certificate Management"
 )]
 pub async fn verify_certificate(
     State(state): State<AppState>,
+    Extension(network): Extension<Network>,
+    tenant: Option<Extension<Tenant>>,
     Path(cert_id): Path<String>,
-    Json(payload): Json<VerifyCertificateRequest>,
+    Valid(payload): Valid<VerifyCertificateRequest>,
 ) -> Result<Json<ApiResponse<VerifyResponse>>, (StatusCode, Json<ErrorResponse>)> {
     info!("Verifying certificate: {}", cert_id);
 
-    if cert_id.is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::bad_request(
-                "Certificate ID cannot be empty".to_string(),
-            )),
-        ));
-    }
-
-    if payload.metadata_hash.is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::bad_request(
-                "Metadata hash cannot be empty".to_string(),
-            )),
-        ));
-    }
+    let cert_id = parse_cert_id(&cert_id)?;
 
-    match state
-        .soroban_client
-        .verify_certificate(&cert_id, &payload.metadata_hash)
-        .await
-    {
-        Ok(is_valid) => {
+    match public_verification(&state, &network, &cert_id, &payload.metadata_hash).await {
+        Ok(mut result) => {
+            if result.reason != VerificationReason::UnknownCertificate && result.brand.is_none() {
+                result.brand = tenant.map(|Extension(tenant)| tenant.brand);
+            }
+            let is_valid = result.is_valid;
             let response = ApiResponse::success(
                 VerifyResponse {
                     is_valid,
-                    cert_id: cert_id.clone(),
-                    metadata_hash: payload.metadata_hash.clone(),
+                    status: result.reason,
+                    reason: i18n::translate(result.reason.describe().to_string()),
+                    cert_id: result.cert_id,
+                    metadata_hash: payload.metadata_hash.to_string(),
+                    issued_at: result.issued_at,
+                    brand: result.brand,
                 },
                 if is_valid {
                     "Certificate verification successful".to_string()
@@ -260,111 +909,377 @@ pub async fn verify_certificate(
         }
         Err(e) => {
             error!("Failed to verify certificate: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse::internal_error(format!(
-                    "Failed to verify certificate: {}",
-                    e
-                ))),
-            ))
+            Err(operation_failed("Failed to verify certificate", &e))
         }
     }
 }
 
-/// Transfer certificate ownership
+/// Verify a certificate across the local registry and all federated peers
 #[utoipa::path(
-    post,
-    path = "/certificates/{id}/transfer",
-    params(
-        ("id" = String, Path, description = "Certificate ID")
-    ),
-    request_body = TransferCertificateRequest,
+    get,
+    path = "/verify",
+    params(FederatedVerifyQuery),
     responses(
-        (status = 200, description = "Certificate transferred successfully", body = TransactionApiResponse),
+        (status = 200, description = "Federated verification completed", body = FederatedVerifyApiResponse),
         (status = 400, description = "Bad request", body = ErrorResponse),
-        (status = 404, description = "Certificate not found", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
-    tag = "Certificate Management"
+    tag = "Federation"
 )]
-pub async fn transfer_certificate(
+pub async fn federated_verify(
     State(state): State<AppState>,
-    Path(cert_id): Path<String>,
-    Json(payload): Json<TransferCertificateRequest>,
-) -> Result<Json<ApiResponse<TransactionResponse>>, (StatusCode, Json<ErrorResponse>)> {
-    info!("Transferring certificate: {}", cert_id);
+    Extension(network): Extension<Network>,
+    Query(query): Query<FederatedVerifyQuery>,
+) -> Result<Json<ApiResponse<FederatedVerifyResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    info!("Federated verification for certificate: {}", query.cert_id);
 
-    if cert_id.is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::bad_request(
-                "Certificate ID cannot be empty".to_string(),
-            )),
-        ));
+    let cert_id = parse_cert_id(&query.cert_id)?;
+    let metadata_hash = parse_metadata_hash("metadata_hash", &query.metadata_hash)?;
+
+    match state
+        .federation
+        .verify(network.client.as_ref(), &cert_id, &metadata_hash)
+        .await
+    {
+        Ok(found) => {
+            let is_valid = found.as_ref().map(|m| m.is_valid).unwrap_or(false);
+            let message = match &found {
+                Some(m) if m.is_valid => format!("Certificate verified by registry {}", m.registry),
+                Some(m) => format!("Certificate verification failed in registry {}", m.registry),
+                None => "Certificate not found in any registry".to_string(),
+            };
+            let response = ApiResponse::success(
+                FederatedVerifyResponse {
+                    is_valid,
+                    cert_id: query.cert_id.clone(),
+                    metadata_hash: query.metadata_hash.clone(),
+                    registry: found.map(|m| m.registry),
+                },
+                message,
+            );
+            Ok(Json(response))
+        }
+        Err(e) => {
+            error!("Failed federated verification: {}", e);
+            Err(operation_failed("Failed federated verification", &e))
+        }
     }
+}
 
-    if payload.new_owner_address.is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::bad_request(
-                "New owner address cannot be empty".to_string(),
-            )),
-        ));
+/// Verify a certificate for a consumer-facing page
+///
+/// Like `/certificates/{id}/verify`, the result says why a certificate
+/// didn't verify and includes its brand and issuance date; its message is
+/// worded for the buyer.
+#[utoipa::path(
+    get,
+    path = "/public/verify",
+    params(PublicVerifyQuery),
+    responses(
+        (status = 200, description = "Verification completed", body = PublicVerifyApiResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Certificate Management"
+)]
+pub async fn public_verify(
+    State(state): State<AppState>,
+    Extension(network): Extension<Network>,
+    tenant: Option<Extension<Tenant>>,
+    Query(query): Query<PublicVerifyQuery>,
+) -> Result<Json<ApiResponse<PublicVerifyResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    info!("Public verification for certificate: {}", query.cert_id);
+
+    let cert_id = parse_cert_id(&query.cert_id)?;
+    let hash = parse_metadata_hash("hash", &query.hash)?;
+
+    match public_verification(&state, &network, &cert_id, &hash).await {
+        Ok(mut result) => {
+            // A tenant's certificates are all its own brand
+            if result.reason != VerificationReason::UnknownCertificate && result.brand.is_none() {
+                result.brand = tenant.map(|Extension(tenant)| tenant.brand);
+            }
+            let message = result.reason.describe().to_string();
+            Ok(Json(ApiResponse::success(result, message)))
+        }
+        Err(e) => {
+            error!("Failed public verification: {}", e);
+            Err(operation_failed("Failed to verify certificate", &e))
+        }
     }
+}
 
-    if payload.current_owner_secret_key.is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::bad_request(
-                "Current owner secret key cannot be empty".to_string(),
-            )),
-        ));
+/// Work out why a certificate does or doesn't verify against `hash`
+///
+/// Revocation is reported before a hash mismatch, and both before a
+/// suspension or an ended warranty.
+async fn public_verification(
+    state: &AppState,
+    network: &Network,
+    cert_id: &CertId,
+    hash: &MetadataHash,
+) -> anyhow::Result<PublicVerifyResponse> {
+    let client = &network.client;
+    let mut result = PublicVerifyResponse {
+        is_valid: false,
+        reason: VerificationReason::UnknownCertificate,
+        cert_id: cert_id.to_string(),
+        issued_at: None,
+        brand: None,
+    };
+    if !client.certificate_exists(cert_id).await? {
+        return Ok(result);
     }
 
-    match state
-        .soroban_client
-        .transfer_certificate(
-            &cert_id,
-            &payload.new_owner_address,
-            &payload.current_owner_secret_key,
+    let certificate = client.get_certificate_details(cert_id).await?;
+    let status = client.get_certificate_status(cert_id).await?;
+    result.reason = if !certificate.is_valid || status == "Revoked" {
+        VerificationReason::Revoked
+    } else if certificate.metadata_hash != hash.as_str() {
+        VerificationReason::HashMismatch
+    } else if status != "Valid" {
+        VerificationReason::Suspended
+    } else {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        match client.get_warranty(cert_id).await? {
+            Some(expires_at) if expires_at <= now => VerificationReason::Expired,
+            _ => VerificationReason::Valid,
+        }
+    };
+    result.is_valid = matches!(
+        result.reason,
+        VerificationReason::Valid | VerificationReason::Expired
+    );
+
+    // The issuance date is only recorded by the indexer; the chain has the brand too
+    if let Some(indexer) = state.indexer.as_ref().filter(|_| network.is_default) {
+        match indexer.get_certificate(cert_id).await {
+            Ok(Some(indexed)) => {
+                result.issued_at = indexed.issued_at;
+                result.brand = indexed.brand;
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Failed to read indexed certificate {}: {}", cert_id, e),
+        }
+    }
+    if result.brand.is_none() {
+        result.brand = client
+            .get_certificate_collection(cert_id)
+            .await?
+            .map(|(_, brand)| brand);
+    }
+
+    Ok(result)
+}
+
+/// Verify a certificate from the payload of a scanned VeriLuxe QR code
+///
+/// The payload's signature and expiry are checked before the certificate is
+/// verified on-chain against the metadata hash the code was minted for.
+#[utoipa::path(
+    post,
+    path = "/verify/qr",
+    request_body = VerifyQrRequest,
+    responses(
+        (status = 200, description = "QR code verification completed", body = QrVerifyApiResponse),
+        (status = 400, description = "Malformed, forged or expired QR payload", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields", body = ErrorResponse),
+        (status = 503, description = "No admin signer configured", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Certificate Management"
+)]
+pub async fn verify_qr(
+    State(state): State<AppState>,
+    Extension(network): Extension<Network>,
+    Valid(payload): Valid<VerifyQrRequest>,
+) -> Result<Json<ApiResponse<QrVerifyResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let codec = require_qr(&state)?;
+
+    let claims = codec.decode(&payload.payload).map_err(|e| {
+        warn!("Rejected QR payload: {}", e);
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::bad_request(e.to_string()).with_code(ErrorCode::classify(&e))),
         )
+    })?;
+    info!("Verifying QR code for certificate: {}", claims.cert_id);
+
+    // Claims are signed by this service, so only QR codes minted for
+    // certificates with malformed legacy identifiers fail to parse
+    let (cert_id, metadata_hash) = CertId::parse(&claims.cert_id)
+        .and_then(|cert_id| Ok((cert_id, MetadataHash::parse(&claims.metadata_hash)?)))
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::bad_request(format!("Invalid QR payload: {}", e))
+                    .with_code(ErrorCode::QrInvalid)),
+            )
+        })?;
+
+    match network
+        .client
+        .verify_certificate(&cert_id, &metadata_hash)
         .await
     {
-        Ok(tx_hash) => {
+        Ok(is_valid) => {
+            let message = if is_valid {
+                "Authentic VeriLuxe certificate".to_string()
+            } else {
+                "This certificate is no longer valid".to_string()
+            };
             let response = ApiResponse::success(
-                TransactionResponse {
-                    transaction_hash: tx_hash,
-                    status: "submitted".to_string(),
+                QrVerifyResponse {
+                    is_valid,
+                    cert_id: claims.cert_id,
+                    metadata_hash: claims.metadata_hash,
+                    expires_at: claims.expires_at,
                 },
-                "Certificate transferred successfully".to_string(),
+                message,
             );
             Ok(Json(response))
         }
         Err(e) => {
-            error!("Failed to transfer certificate: {}", e);
-            if e.to_string().contains("not found") {
-                Err((
+            error!("Failed to verify QR code: {}", e);
+            Err(operation_failed("Failed to verify certificate", &e))
+        }
+    }
+}
+
+/// Prepare a certificate transfer for the current owner to sign
+///
+/// Nothing is submitted: the owner signs the returned transaction with their
+/// wallet and sends it to `/transactions/submit`. The route's policy only
+/// lets owner session tokens prepare transfers of certificates they hold.
+#[utoipa::path(
+    post,
+    path = "/certificates/{id}/transfer/prepare",
+    params(
+        ("id" = String, Path, description = "Certificate ID")
+    ),
+    request_body = PrepareTransferRequest,
+    responses(
+        (status = 200, description = "Transfer prepared", body = PreparedTransactionApiResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 403, description = "Certificate not held by the signed-in owner", body = ErrorResponse),
+        (status = 404, description = "Certificate not found", body = ErrorResponse),
+        (status = 410, description = "Certificate revoked", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Certificate Management"
+)]
+pub async fn prepare_transfer(
+    State(state): State<AppState>,
+    Extension(network): Extension<Network>,
+    Path(cert_id): Path<String>,
+    Valid(payload): Valid<PrepareTransferRequest>,
+) -> Result<Json<ApiResponse<PreparedTransaction>>, (StatusCode, Json<ErrorResponse>)> {
+    info!("Preparing transfer of certificate: {}", cert_id);
+
+    let cert_id = parse_cert_id(&cert_id)?;
+
+    match network
+        .client
+        .prepare_transfer(&cert_id, &payload.new_owner_address)
+        .await
+    {
+        Ok(prepared) => Ok(Json(ApiResponse::success(
+            prepared,
+            "Transfer prepared; sign it and send it to /transactions/submit".to_string(),
+        ))),
+        Err(e) => {
+            error!("Failed to prepare transfer: {}", e);
+            match ErrorCode::classify(&e) {
+                ErrorCode::CertNotFound => Err((
                     StatusCode::NOT_FOUND,
                     Json(ErrorResponse::not_found(format!(
                         "Certificate {} not found",
                         cert_id
-                    ))),
-                ))
-            } else if e.to_string().contains("invalid certificate") {
+                    )).with_code(ErrorCode::CertNotFound)),
+                )),
+                ErrorCode::CertRevoked => Err((
+                    StatusCode::GONE,
+                    Json(ErrorResponse::new(
+                        "Cannot transfer invalid certificate".to_string(),
+                        410,
+                    ).with_code(ErrorCode::CertRevoked)),
+                )),
+                ErrorCode::InvalidAddress => Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse::bad_request(e.to_string()).with_code(ErrorCode::InvalidAddress)),
+                )),
+                _ => Err(operation_failed("Failed to prepare transfer", &e)),
+            }
+        }
+    }
+}
+
+/// Submit a transaction signed by the client
+///
+/// Accepts transactions returned by the prepare endpoints once signed; only
+/// invocations of this registry's contract are relayed.
+#[utoipa::path(
+    post,
+    path = "/transactions/submit",
+    params(SubmitQuery),
+    request_body = SubmitTransactionRequest,
+    responses(
+        (status = 200, description = "Transaction submitted successfully", body = TransactionApiResponse),
+        (status = 202, description = "Submission queued", body = JobApiResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Certificate Management"
+)]
+pub async fn submit_transaction(
+    State(state): State<AppState>,
+    Extension(network): Extension<Network>,
+    Query(options): Query<SubmitQuery>,
+    Valid(payload): Valid<SubmitTransactionRequest>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    info!("Submitting client-signed transaction");
+
+    if let Err(e) = network.client.decode_signed_transaction(&payload.transaction) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::bad_request(e.to_string())),
+        ));
+    }
+
+    if queue_writes(&state, &network) {
+        let operation = JobOperation::Submit {
+            transaction: payload.transaction,
+        };
+        return enqueue_job(&state, operation, "Transaction submission queued").await;
+    }
+
+    match network
+        .client
+        .submit_signed_transaction(&payload.transaction)
+        .await
+    {
+        Ok(tx_hash) => {
+            let transaction = finish_submission(&state, network.client.as_ref(), tx_hash, &options).await?;
+            let response = ApiResponse::success(
+                transaction,
+                "Transaction submitted successfully".to_string(),
+            );
+            Ok(Json(response).into_response())
+        }
+        Err(e) => {
+            error!("Failed to submit transaction: {}", e);
+            if ErrorCode::classify(&e) == ErrorCode::TransactionRejected {
                 Err((
                     StatusCode::BAD_REQUEST,
-                    Json(ErrorResponse::bad_request(
-                        "Cannot transfer invalid certificate".to_string(),
-                    )),
+                    Json(ErrorResponse::bad_request(e.to_string()).with_code(ErrorCode::TransactionRejected)),
                 ))
             } else {
-                Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse::internal_error(format!(
-                        "Failed to transfer certificate: {}",
-                        e
-                    ))),
-                ))
+                Err(operation_failed("Failed to submit transaction", &e))
             }
         }
     }
@@ -375,10 +1290,12 @@ pub async fn transfer_certificate(
     post,
     path = "/certificates/{id}/revoke",
     params(
-        ("id" = String, Path, description = "Certificate ID")
+        ("id" = String, Path, description = "Certificate ID"),
+        SubmitQuery
     ),
     responses(
         (status = 200, description = "Certificate revoked successfully", body = TransactionApiResponse),
+        (status = 202, description = "Revocation queued", body = JobApiResponse),
         (status = 400, description = "Bad request", body = ErrorResponse),
         (status = 404, description = "Certificate not found", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
@@ -387,48 +1304,42 @@ pub async fn transfer_certificate(
 )]
 pub async fn revoke_certificate(
     State(state): State<AppState>,
+    Extension(network): Extension<Network>,
     Path(cert_id): Path<String>,
-) -> Result<Json<ApiResponse<TransactionResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    Query(options): Query<SubmitQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
     info!("Revoking certificate: {}", cert_id);
 
-    if cert_id.is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::bad_request(
-                "Certificate ID cannot be empty".to_string(),
-            )),
-        ));
+    let cert_id = parse_cert_id(&cert_id)?;
+
+    if queue_writes(&state, &network) {
+        let operation = JobOperation::Revoke {
+            cert_id: cert_id.clone(),
+        };
+        return enqueue_job(&state, operation, "Certificate revocation queued").await;
     }
 
-    match state.soroban_client.revoke_certificate(&cert_id).await {
+    match network.client.revoke_certificate(&cert_id).await {
         Ok(tx_hash) => {
+            let transaction = finish_submission(&state, network.client.as_ref(), tx_hash, &options).await?;
             let response = ApiResponse::success(
-                TransactionResponse {
-                    transaction_hash: tx_hash,
-                    status: "submitted".to_string(),
-                },
+                transaction,
                 "Certificate revoked successfully".to_string(),
             );
-            Ok(Json(response))
+            Ok(Json(response).into_response())
         }
         Err(e) => {
             error!("Failed to revoke certificate: {}", e);
-            if e.to_string().contains("not found") {
+            if ErrorCode::classify(&e) == ErrorCode::CertNotFound {
                 Err((
                     StatusCode::NOT_FOUND,
                     Json(ErrorResponse::not_found(format!(
                         "Certificate {} not found",
                         cert_id
-                    ))),
+                    )).with_code(ErrorCode::CertNotFound)),
                 ))
             } else {
-                Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse::internal_error(format!(
-                        "Failed to revoke certificate: {}",
-                        e
-                    ))),
-                ))
+                Err(operation_failed("Failed to revoke certificate", &e))
             }
         }
     }
@@ -450,25 +1361,19 @@ pub async fn revoke_certificate(
 )]
 pub async fn check_certificate_exists(
     State(state): State<AppState>,
+    Extension(network): Extension<Network>,
     Path(cert_id): Path<String>,
 ) -> Result<Json<ApiResponse<ExistsResponse>>, (StatusCode, Json<ErrorResponse>)> {
     info!("Checking if certificate exists: {}", cert_id);
 
-    if cert_id.is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::bad_request(
-                "Certificate ID cannot be empty".to_string(),
-            )),
-        ));
-    }
+    let cert_id = parse_cert_id(&cert_id)?;
 
-    match state.soroban_client.certificate_exists(&cert_id).await {
+    match network.client.certificate_exists(&cert_id).await {
         Ok(exists) => {
             let response = ApiResponse::success(
                 ExistsResponse {
                     exists,
-                    cert_id: cert_id.clone(),
+                    cert_id: cert_id.to_string(),
                 },
                 if exists {
                     "Certificate exists".to_string()
@@ -480,67 +1385,1291 @@ pub async fn check_certificate_exists(
         }
         Err(e) => {
             error!("Failed to check certificate existence: {}", e);
+            Err(operation_failed("Failed to check certificate existence", &e))
+        }
+    }
+}
+
+/// Mint a signed QR payload for a certificate
+///
+/// The payload binds the certificate to its current metadata hash and expires
+/// after `QR_TTL_SECS`.
+#[utoipa::path(
+    post,
+    path = "/certificates/{id}/qr",
+    params(
+        ("id" = String, Path, description = "Certificate ID")
+    ),
+    responses(
+        (status = 200, description = "QR payload minted", body = QrPayloadApiResponse),
+        (status = 404, description = "Certificate not found", body = ErrorResponse),
+        (status = 503, description = "No admin signer configured", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Certificate Management"
+)]
+pub async fn mint_qr_code(
+    State(state): State<AppState>,
+    Extension(network): Extension<Network>,
+    Path(cert_id): Path<String>,
+) -> Result<Json<ApiResponse<QrPayload>>, (StatusCode, Json<ErrorResponse>)> {
+    info!("Minting QR payload for certificate: {}", cert_id);
+
+    let cert_id = parse_cert_id(&cert_id)?;
+    let codec = require_qr(&state)?;
+
+    let certificate = match network.client.get_certificate_details(&cert_id).await {
+        Ok(certificate) => certificate,
+        Err(e) if ErrorCode::classify(&e) == ErrorCode::CertNotFound => {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::not_found(format!(
+                    "Certificate {} not found",
+                    cert_id
+                )).with_code(ErrorCode::CertNotFound)),
+            ));
+        }
+        Err(e) => {
+            error!("Failed to get certificate details: {}", e);
+            return Err(operation_failed("Failed to get certificate details", &e));
+        }
+    };
+
+    match codec.mint(&cert_id, &certificate.metadata_hash).await {
+        Ok(payload) => Ok(Json(ApiResponse::success(
+            payload,
+            "QR payload minted successfully".to_string(),
+        ))),
+        Err(e) => {
+            error!("Failed to mint QR payload: {}", e);
+            Err(operation_failed("Failed to mint QR payload", &e))
+        }
+    }
+}
+
+fn require_qr(state: &AppState) -> Result<&QrCodec, (StatusCode, Json<ErrorResponse>)> {
+    state.qr.as_ref().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(
+                "QR codes require an admin signer".to_string(),
+                503,
+            )),
+        )
+    })
+}
+
+fn require_web_auth(state: &AppState) -> Result<&WebAuth, (StatusCode, Json<ErrorResponse>)> {
+    state.web_auth.as_ref().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(
+                "SEP-10 authentication is not enabled (SEP10_ENABLED)".to_string(),
+                503,
+            )),
+        )
+    })
+}
+
+/// Request a SEP-10 challenge transaction for an account
+///
+/// The response follows SEP-10 and is not wrapped in the usual envelope.
+#[utoipa::path(
+    get,
+    path = "/auth",
+    params(ChallengeQuery),
+    responses(
+        (status = 200, description = "Challenge issued", body = ChallengeResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 503, description = "SEP-10 authentication not enabled", body = ErrorResponse)
+    ),
+    tag = "Owners"
+)]
+pub async fn get_challenge(
+    State(state): State<AppState>,
+    Query(query): Query<ChallengeQuery>,
+) -> Result<Json<ChallengeResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let web_auth = require_web_auth(&state)?;
+    info!("Issuing SEP-10 challenge for {}", query.account);
+
+    match web_auth.challenge(&query.account).await {
+        Ok(transaction) => Ok(Json(ChallengeResponse {
+            transaction,
+            network_passphrase: web_auth.network_passphrase().to_string(),
+        })),
+        Err(e) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::bad_request(e.to_string())),
+        )),
+    }
+}
+
+/// Exchange a signed SEP-10 challenge for an owner session token
+///
+/// Sent with `X-Tenant`, the token is scoped to that tenant.
+#[utoipa::path(
+    post,
+    path = "/auth",
+    request_body = ChallengeTokenRequest,
+    responses(
+        (status = 200, description = "Challenge verified", body = ChallengeTokenResponse),
+        (status = 400, description = "Invalid or unsigned challenge", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields", body = ErrorResponse),
+        (status = 503, description = "SEP-10 authentication not enabled", body = ErrorResponse)
+    ),
+    tag = "Owners"
+)]
+pub async fn verify_challenge(
+    State(state): State<AppState>,
+    tenant: Option<Extension<Tenant>>,
+    Valid(request): Valid<ChallengeTokenRequest>,
+) -> Result<Json<ChallengeTokenResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let web_auth = require_web_auth(&state)?;
+    let tenant = tenant.as_ref().map(|Extension(tenant)| tenant.id.as_str());
+
+    match web_auth.verify(&request.transaction, tenant) {
+        Ok(token) => Ok(Json(ChallengeTokenResponse { token })),
+        Err(e) => {
+            warn!("Rejected SEP-10 challenge: {}", e);
             Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse::internal_error(format!(
-                    "Failed to check certificate existence: {}",
-                    e
-                ))),
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::bad_request(e.to_string())),
             ))
         }
     }
 }
 
-/// Health check endpoint
+/// Get the status of a queued write
 #[utoipa::path(
     get,
-    path = "/health",
+    path = "/jobs/{id}",
+    params(
+        ("id" = String, Path, description = "Job ID")
+    ),
     responses(
-        (status = 200, description = "API is healthy", body = HealthResponse)
+        (status = 200, description = "Job retrieved successfully", body = JobApiResponse),
+        (status = 404, description = "Job not found", body = ErrorResponse)
     ),
-    tag = "Health"
+    tag = "Jobs"
 )]
-pub async fn health_check() -> Json<ApiResponse<String>> {
+pub async fn get_job(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+) -> Result<Json<ApiResponse<Job>>, (StatusCode, Json<ErrorResponse>)> {
+    info!("Getting job: {}", job_id);
+
+    match state.job_queue.get(&job_id).await {
+        Some(job) => Ok(Json(ApiResponse::success(
+            job,
+            "Job retrieved successfully".to_string(),
+        ))),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::not_found(format!("Job {} not found", job_id))),
+        )),
+    }
+}
+
+/// Register a webhook for certificate lifecycle events
+#[utoipa::path(
+    post,
+    path = "/webhooks",
+    request_body = RegisterWebhookRequest,
+    responses(
+        (status = 201, description = "Webhook registered successfully", body = WebhookApiResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields", body = ErrorResponse)
+    ),
+    tag = "Webhooks"
+)]
+pub async fn register_webhook(
+    State(state): State<AppState>,
+    Valid(payload): Valid<RegisterWebhookRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<Webhook>>), (StatusCode, Json<ErrorResponse>)> {
+    info!("Registering webhook for: {}", payload.url);
+
+    if !payload.url.starts_with("http://") && !payload.url.starts_with("https://") {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::bad_request(
+                "Webhook URL must be an http(s) URL".to_string(),
+            )),
+        ));
+    }
+
+    match state
+        .webhooks
+        .register(payload.url, payload.events.unwrap_or_default(), payload.secret)
+        .await
+    {
+        Ok(webhook) => Ok((
+            StatusCode::CREATED,
+            Json(ApiResponse::success(
+                webhook,
+                "Webhook registered successfully".to_string(),
+            )),
+        )),
+        Err(e) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::bad_request(e.to_string())),
+        )),
+    }
+}
+
+/// List registered webhooks
+#[utoipa::path(
+    get,
+    path = "/webhooks",
+    responses(
+        (status = 200, description = "Webhooks retrieved successfully", body = WebhookListApiResponse)
+    ),
+    tag = "Webhooks"
+)]
+pub async fn list_webhooks(State(state): State<AppState>) -> Json<ApiResponse<Vec<Webhook>>> {
     Json(ApiResponse::success(
-        "healthy".to_string(),
-        "API is running".to_string(),
+        state.webhooks.list().await,
+        "Webhooks retrieved successfully".to_string(),
     ))
 }
 
+/// Remove a webhook
+#[utoipa::path(
+    delete,
+    path = "/webhooks/{id}",
+    params(
+        ("id" = String, Path, description = "Webhook ID")
+    ),
+    responses(
+        (status = 200, description = "Webhook removed successfully"),
+        (status = 404, description = "Webhook not found", body = ErrorResponse)
+    ),
+    tag = "Webhooks"
+)]
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    Path(webhook_id): Path<String>,
+) -> Result<Json<ApiResponse<()>>, (StatusCode, Json<ErrorResponse>)> {
+    info!("Removing webhook: {}", webhook_id);
+
+    if state.webhooks.remove(&webhook_id).await {
+        Ok(Json(ApiResponse::<()>::success_with_message(
+            "Webhook removed successfully".to_string(),
+        )))
+    } else {
+        Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::not_found(format!("Webhook {} not found", webhook_id))),
+        ))
+    }
+}
+
+/// List a webhook's recent deliveries, newest first
+///
+/// Deliveries that exhausted their retries are kept as `dead_letter` until the
+/// webhook is removed.
+#[utoipa::path(
+    get,
+    path = "/webhooks/{id}/deliveries",
+    params(
+        ("id" = String, Path, description = "Webhook ID"),
+        WebhookDeliveriesQuery
+    ),
+    responses(
+        (status = 200, description = "Deliveries retrieved successfully", body = DeliveryListApiResponse),
+        (status = 404, description = "Webhook not found", body = ErrorResponse)
+    ),
+    tag = "Webhooks"
+)]
+pub async fn list_webhook_deliveries(
+    State(state): State<AppState>,
+    Path(webhook_id): Path<String>,
+    Query(query): Query<WebhookDeliveriesQuery>,
+) -> Result<Json<ApiResponse<Vec<Delivery>>>, (StatusCode, Json<ErrorResponse>)> {
+    match state.webhooks.deliveries(&webhook_id, query.status).await {
+        Some(deliveries) => Ok(Json(ApiResponse::success(
+            deliveries,
+            "Deliveries retrieved successfully".to_string(),
+        ))),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::not_found(format!("Webhook {} not found", webhook_id))),
+        )),
+    }
+}
+
+/// Get the contract's storage TTL and rent funding projections
+#[utoipa::path(
+    get,
+    path = "/rent/forecast",
+    responses(
+        (status = 200, description = "Rent forecast retrieved successfully", body = RentForecastApiResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Operations"
+)]
+pub async fn rent_forecast(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<RentForecast>>, (StatusCode, Json<ErrorResponse>)> {
+    info!("Getting rent forecast");
+
+    match state.rent_manager.forecast().await {
+        Ok(forecast) => {
+            let message = if forecast.over_budget {
+                "Projected rent exceeds the configured budget".to_string()
+            } else {
+                "Rent forecast retrieved successfully".to_string()
+            };
+            Ok(Json(ApiResponse::success(forecast, message)))
+        }
+        Err(e) => {
+            error!("Failed to compute rent forecast: {}", e);
+            Err(operation_failed("Failed to compute rent forecast", &e))
+        }
+    }
+}
+
+/// Get current network fees and the simulated cost of issuing a certificate
+#[utoipa::path(
+    get,
+    path = "/fees/estimate",
+    responses(
+        (status = 200, description = "Fee estimate retrieved successfully", body = FeeEstimateApiResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Operations"
+)]
+pub async fn estimate_fees(
+    Extension(network): Extension<Network>,
+) -> Result<Json<ApiResponse<FeeEstimate>>, (StatusCode, Json<ErrorResponse>)> {
+    info!("Estimating fees");
+
+    match network.client.estimate_fees().await {
+        Ok(estimate) => Ok(Json(ApiResponse::success(
+            estimate,
+            "Fee estimate retrieved successfully".to_string(),
+        ))),
+        Err(e) => {
+            error!("Failed to estimate fees: {}", e);
+            Err(operation_failed("Failed to estimate fees", &e))
+        }
+    }
+}
+
+/// Resubmit a stuck transaction wrapped in a fee bump paid by the sponsor account
+///
+/// Transactions this API submitted recently are found by hash; others must
+/// be passed as signed XDR, and only invocations of this registry's contract
+/// are accepted.
+#[utoipa::path(
+    post,
+    path = "/transactions/{hash}/fee-bump",
+    params(
+        ("hash" = String, Path, description = "Hash of the transaction to bump"),
+        SubmitQuery
+    ),
+    request_body = FeeBumpRequest,
+    responses(
+        (status = 200, description = "Fee bump submitted successfully", body = TransactionApiResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 404, description = "Transaction not found", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Operations"
+)]
+pub async fn fee_bump_transaction(
+    State(state): State<AppState>,
+    Extension(network): Extension<Network>,
+    Path(hash): Path<String>,
+    Query(options): Query<SubmitQuery>,
+    Valid(payload): Valid<FeeBumpRequest>,
+) -> Result<Json<ApiResponse<TransactionResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    info!("Fee-bumping transaction {}", hash);
+
+    let hash = hash.to_ascii_lowercase();
+    let inner = match &payload.transaction {
+        Some(transaction) => network
+            .client
+            .decode_signed_transaction(transaction)
+            .and_then(|envelope| {
+                let envelope_hash = network.client.envelope_hash(&envelope)?;
+                if envelope_hash != hash {
+                    return Err(anyhow::anyhow!(
+                        "Invalid transaction: its hash is {}, not {}",
+                        envelope_hash,
+                        hash
+                    ));
+                }
+                Ok(envelope)
+            })
+            .map_err(|e| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse::invalid_field("transaction", e.to_string())),
+                )
+            })?,
+        None => network.client.sent_transaction(&hash).ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::not_found(format!(
+                    "Transaction {} wasn't submitted recently by this API; pass its signed XDR",
+                    hash
+                ))),
+            )
+        })?,
+    };
+
+    match network.client.fee_bump(inner, payload.fee).await {
+        Ok(tx_hash) => {
+            let transaction = finish_submission(&state, network.client.as_ref(), tx_hash, &options).await?;
+            Ok(Json(ApiResponse::success(
+                transaction,
+                "Fee bump submitted successfully".to_string(),
+            )))
+        }
+        Err(e) => {
+            error!("Failed to fee-bump transaction {}: {}", hash, e);
+            match ErrorCode::classify(&e) {
+                ErrorCode::InvalidRequest | ErrorCode::TransactionRejected => Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse::bad_request(e.to_string()).with_code(ErrorCode::classify(&e))),
+                )),
+                _ => Err(operation_failed("Failed to fee-bump transaction", &e)),
+            }
+        }
+    }
+}
+
+/// Look up an account on Horizon: whether it exists, its balances and signers
+///
+/// Lets clients check that an owner account exists and is funded before
+/// issuing to it or transferring to it. Accounts are read from the default
+/// network's Horizon.
+#[utoipa::path(
+    get,
+    path = "/accounts/{address}",
+    params(
+        ("address" = String, Path, description = "Stellar account address (G...)")
+    ),
+    responses(
+        (status = 200, description = "Account looked up; `exists` is false for unknown accounts", body = AccountInfoApiResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 502, description = "Horizon failed", body = ErrorResponse),
+        (status = 503, description = "Horizon not configured", body = ErrorResponse)
+    ),
+    tag = "Accounts"
+)]
+pub async fn get_account(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+) -> Result<Json<ApiResponse<AccountInfo>>, (StatusCode, Json<ErrorResponse>)> {
+    let address = parse_address("address", &address)?;
+
+    let horizon = state.horizon.as_ref().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(
+                "Account lookups require a Horizon server (HORIZON_URL)".to_string(),
+                503,
+            )),
+        )
+    })?;
+
+    match horizon.account(&address).await {
+        Ok(account) => {
+            let message = if account.exists {
+                "Account found".to_string()
+            } else {
+                "Account does not exist".to_string()
+            };
+            Ok(Json(ApiResponse::success(account, message)))
+        }
+        Err(e) => {
+            error!("Failed to look up account {}: {}", address, e);
+            Err((
+                StatusCode::BAD_GATEWAY,
+                Json(ErrorResponse::new(format!("Failed to look up account: {}", e), 502)),
+            ))
+        }
+    }
+}
+
+/// Refuse the keypair and strkey utilities unless explicitly enabled
+fn dev_tools(state: &AppState) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    if state.dev_tools {
+        return Ok(());
+    }
+    Err((
+        StatusCode::NOT_FOUND,
+        Json(ErrorResponse::not_found(
+            "Development utilities are only available in sandbox mode or with DEV_TOOLS_ENABLED".to_string(),
+        )),
+    ))
+}
+
+/// Friendbot, which only exists on the networks `/dev/fund` is served for
+fn dev_friendbot(state: &AppState) -> Result<&Friendbot, (StatusCode, Json<ErrorResponse>)> {
+    state.friendbot.as_ref().ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::not_found(
+                "Development endpoints are only available on testnet and in sandbox mode".to_string(),
+            )),
+        )
+    })
+}
+
+/// Generate an Ed25519 keypair
+#[utoipa::path(
+    post,
+    path = "/dev/keypairs",
+    responses(
+        (status = 200, description = "Keypair generated", body = DevKeypairApiResponse),
+        (status = 404, description = "Development utilities disabled", body = ErrorResponse)
+    ),
+    tag = "Development"
+)]
+pub async fn generate_keypair(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<DevKeypair>>, (StatusCode, Json<ErrorResponse>)> {
+    dev_tools(&state)?;
+
+    let keypair = devtools::generate_keypair()
+        .map_err(|e| operation_failed("Failed to generate keypair", &e))?;
+    Ok(Json(ApiResponse::success(keypair, "Keypair generated".to_string())))
+}
+
+/// Convert a key or contract ID between hex and strkey
+///
+/// Strkeys are decoded to hex; hex values are encoded as the strkey `kind`.
+#[utoipa::path(
+    get,
+    path = "/dev/strkey/convert",
+    params(StrkeyConvertQuery),
+    responses(
+        (status = 200, description = "Value converted", body = StrkeyConversionApiResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 404, description = "Development utilities disabled", body = ErrorResponse)
+    ),
+    tag = "Development"
+)]
+pub async fn convert_strkey(
+    State(state): State<AppState>,
+    Query(query): Query<StrkeyConvertQuery>,
+) -> Result<Json<ApiResponse<StrkeyConversion>>, (StatusCode, Json<ErrorResponse>)> {
+    dev_tools(&state)?;
+
+    let conversion = devtools::convert(&query.value, query.kind.as_deref()).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::invalid_field("value", e.to_string())),
+        )
+    })?;
+    Ok(Json(ApiResponse::success(conversion, "Value converted".to_string())))
+}
+
+/// Create and fund a development account through friendbot
+///
+/// Funds `address` on the default network, or a newly created keypair whose
+/// secret key is returned. Only available on testnet and in sandbox mode.
+#[utoipa::path(
+    post,
+    path = "/dev/fund",
+    request_body = FundAccountRequest,
+    responses(
+        (status = 200, description = "Account funded", body = FundedAccountApiResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 404, description = "Not available on this network", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields", body = ErrorResponse),
+        (status = 502, description = "Friendbot failed", body = ErrorResponse)
+    ),
+    tag = "Development"
+)]
+pub async fn fund_account(
+    State(state): State<AppState>,
+    Valid(payload): Valid<FundAccountRequest>,
+) -> Result<Json<ApiResponse<FundedAccount>>, (StatusCode, Json<ErrorResponse>)> {
+    let friendbot = dev_friendbot(&state)?;
+
+    let (address, secret_key) = match payload.address {
+        Some(address) => (address.to_string(), None),
+        None => {
+            let secret_key = ed25519::PrivateKey(rand::random()).to_string();
+            let signer = LocalSigner::from_secret(&secret_key)
+                .map_err(|e| operation_failed("Failed to create keypair", &e))?;
+            (signer.address(), Some(secret_key))
+        }
+    };
+    info!("Funding development account {}", address);
+
+    let created = friendbot.fund(&address).await.map_err(|e| {
+        error!("Failed to fund account {}: {}", address, e);
+        (
+            StatusCode::BAD_GATEWAY,
+            Json(ErrorResponse::new(format!("Failed to fund account: {}", e), 502)),
+        )
+    })?;
+    let balance = state
+        .soroban_client
+        .get_account_balance(&address)
+        .await
+        .map_err(|e| operation_failed("Failed to read the funded account", &e))?;
+
+    Ok(Json(ApiResponse::success(
+        FundedAccount {
+            address,
+            secret_key,
+            created,
+            balance,
+            network_passphrase: state.soroban_client.network_passphrase().to_string(),
+        },
+        if created {
+            "Account created and funded".to_string()
+        } else {
+            "Account already exists".to_string()
+        },
+    )))
+}
+
+/// Download the certificate registry with each certificate's history
+#[utoipa::path(
+    get,
+    path = "/admin/export",
+    params(ExportQuery),
+    responses(
+        (status = 200, description = "Certificates streamed as a JSON array or CSV", body = [ExportedCertificate]),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 503, description = "Certificate index not enabled", body = ErrorResponse)
+    ),
+    tag = "Operations"
+)]
+pub async fn export_registry(
+    State(state): State<AppState>,
+    Extension(network): Extension<Network>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    info!("Exporting certificates: {:?}", query);
+
+    let format = match query.format.as_deref() {
+        None => ExportFormat::Json,
+        Some(format) => ExportFormat::parse(format).ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::invalid_field(
+                    "format",
+                    "Format must be one of: csv, json".to_string(),
+                )),
+            )
+        })?,
+    };
+
+    if let (Some(from), Some(to)) = (query.issued_from, query.issued_to) {
+        if from >= to {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::bad_request(
+                    "issued_from must be before issued_to".to_string(),
+                )),
+            ));
+        }
+    }
+
+    let indexer = require_indexer(&state, &network)?;
+    let filter = CertificateFilter {
+        collection_id: query.collection_id,
+        issued_from: query.issued_from,
+        issued_to: query.issued_to,
+        ..CertificateFilter::default()
+    };
+
+    let filename = format!(
+        "certificates-{}.{}",
+        chrono::Utc::now().format("%Y%m%dT%H%M%SZ"),
+        format.extension()
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        export_body(indexer.clone(), filter, format),
+    )
+        .into_response())
+}
+
+/// Import a registry of legacy certificates from a CSV or JSON file
+///
+/// Rows are validated up front and the valid ones issued in batches in the
+/// background; poll `GET /admin/import/{id}` for progress and the per-row report.
+#[utoipa::path(
+    post,
+    path = "/admin/import",
+    params(ImportQuery),
+    request_body(
+        content = [LegacyCertificate],
+        description = "JSON array, or CSV with a `cert_id,metadata_hash,owner_address` header"
+    ),
+    responses(
+        (status = 202, description = "Import started", body = ImportReportApiResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse)
+    ),
+    tag = "Operations"
+)]
+pub async fn import_certificates(
+    State(state): State<AppState>,
+    Extension(network): Extension<Network>,
+    Query(query): Query<ImportQuery>,
+    body: String,
+) -> Result<(StatusCode, Json<ApiResponse<ImportReport>>), (StatusCode, Json<ErrorResponse>)> {
+    info!("Importing certificates ({:?})", query.format);
+
+    let format = match query.format.as_deref() {
+        None => ImportFormat::Json,
+        Some(format) => ImportFormat::parse(format).ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::invalid_field(
+                    "format",
+                    "Format must be one of: csv, json".to_string(),
+                )),
+            )
+        })?,
+    };
+
+    let certificates = format.read(&body).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::bad_request(format!("Failed to read import file: {}", e))),
+        )
+    })?;
+
+    if certificates.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::bad_request("Import file has no certificates".to_string())),
+        ));
+    }
+
+    let max_rows = state.imports.max_rows();
+    if certificates.len() > max_rows {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::bad_request(format!(
+                "Import file has {} certificates; at most {} are accepted",
+                certificates.len(),
+                max_rows
+            ))),
+        ));
+    }
+
+    let report = state.imports.start(network.client.clone(), certificates).await;
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(ApiResponse::success(report, "Import started".to_string())),
+    ))
+}
+
+/// Get the progress and per-row report of an import
+#[utoipa::path(
+    get,
+    path = "/admin/import/{id}",
+    params(
+        ("id" = String, Path, description = "Import ID")
+    ),
+    responses(
+        (status = 200, description = "Import retrieved successfully", body = ImportReportApiResponse),
+        (status = 404, description = "Import not found", body = ErrorResponse)
+    ),
+    tag = "Operations"
+)]
+pub async fn get_import(
+    State(state): State<AppState>,
+    Path(import_id): Path<String>,
+) -> Result<Json<ApiResponse<ImportReport>>, (StatusCode, Json<ErrorResponse>)> {
+    info!("Getting import: {}", import_id);
+
+    match state.imports.get(&import_id).await {
+        Some(report) => Ok(Json(ApiResponse::success(
+            report,
+            "Import retrieved successfully".to_string(),
+        ))),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::not_found(format!("Import {} not found", import_id))),
+        )),
+    }
+}
+
+fn require_audit(state: &AppState) -> Result<&AuditLog, (StatusCode, Json<ErrorResponse>)> {
+    state.audit.as_ref().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(
+                "The audit log is not enabled (AUDIT_ENABLED)".to_string(),
+                503,
+            )),
+        )
+    })
+}
+
+/// Latest comparison of the certificate index with on-chain state
+///
+/// While a run is in progress, its partial report is returned.
+#[utoipa::path(
+    get,
+    path = "/admin/reconciliation",
+    responses(
+        (status = 200, description = "Reconciliation report retrieved successfully", body = ReconciliationApiResponse),
+        (status = 404, description = "No reconciliation has run yet", body = ErrorResponse),
+        (status = 503, description = "Reconciliation not enabled", body = ErrorResponse)
+    ),
+    tag = "Operations"
+)]
+pub async fn get_reconciliation(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<ReconciliationReport>>, (StatusCode, Json<ErrorResponse>)> {
+    let reconciler = state.reconciler.as_ref().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(
+                "Reconciliation is not enabled (RECONCILIATION_ENABLED)".to_string(),
+                503,
+            )),
+        )
+    })?;
+
+    match reconciler.report().await {
+        Some(report) => Ok(Json(ApiResponse::success(
+            report,
+            "Reconciliation report retrieved successfully".to_string(),
+        ))),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::not_found(
+                "No reconciliation has run yet".to_string(),
+            )),
+        )),
+    }
+}
+
+/// Requests made with a tenant API key today and this month, against its quotas
+#[utoipa::path(
+    get,
+    path = "/admin/api-keys/{id}/usage",
+    params(
+        ("id" = String, Path, description = "API key ID")
+    ),
+    responses(
+        (status = 200, description = "API key usage retrieved successfully", body = ApiKeyUsageApiResponse),
+        (status = 404, description = "API key not found", body = ErrorResponse),
+        (status = 503, description = "Usage store unavailable", body = ErrorResponse)
+    ),
+    tag = "Operations"
+)]
+pub async fn get_api_key_usage(
+    State(state): State<AppState>,
+    Path(key_id): Path<String>,
+) -> Result<Json<ApiResponse<ApiKeyUsage>>, (StatusCode, Json<ErrorResponse>)> {
+    info!("Getting usage of API key: {}", key_id);
+
+    match state.tenants.usage(&key_id).await {
+        Ok(Some(usage)) => Ok(Json(ApiResponse::success(
+            usage,
+            "API key usage retrieved successfully".to_string(),
+        ))),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::not_found(format!("API key {} not found", key_id))),
+        )),
+        Err(e) => {
+            error!("Failed to read API key usage: {}", e);
+            Err((
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(ErrorResponse::new(format!("Failed to read API key usage: {}", e), 503)),
+            ))
+        }
+    }
+}
+
+/// List audited operations, newest first
+#[utoipa::path(
+    get,
+    path = "/admin/audit",
+    params(AuditQuery),
+    responses(
+        (status = 200, description = "Audit entries retrieved successfully", body = AuditPageApiResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 503, description = "Audit log not enabled", body = ErrorResponse)
+    ),
+    tag = "Audit"
+)]
+pub async fn list_audit(
+    State(state): State<AppState>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<ApiResponse<AuditPage>>, (StatusCode, Json<ErrorResponse>)> {
+    info!("Listing audit entries: {:?}", query);
+
+    let limit = page_limit(query.limit)?;
+    let cursor = match query.cursor.as_deref() {
+        None => None,
+        Some(token) => Some(AuditCursor::decode(token).ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::bad_request(
+                    "Cursor must be a next_cursor returned by this endpoint".to_string(),
+                )),
+            )
+        })?),
+    };
+
+    if let Some(outcome) = &query.outcome {
+        if !AUDIT_OUTCOMES.contains(&outcome.as_str()) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::bad_request(format!(
+                    "Outcome must be one of: {}",
+                    AUDIT_OUTCOMES.join(", ")
+                ))),
+            ));
+        }
+    }
+
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from >= to {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::bad_request("from must be before to".to_string())),
+            ));
+        }
+    }
+
+    let audit = require_audit(&state)?;
+    let filter = AuditFilter {
+        actor: query.actor,
+        action: query.action,
+        outcome: query.outcome,
+        from: query.from,
+        to: query.to,
+    };
+
+    match audit.list(&filter, limit, cursor).await {
+        Ok(page) => Ok(Json(ApiResponse::success(
+            page,
+            "Audit entries retrieved successfully".to_string(),
+        ))),
+        Err(e) => {
+            error!("Failed to list audit entries: {}", e);
+            Err(operation_failed("Failed to list audit entries", &e))
+        }
+    }
+}
+
+/// Recompute the audit log's hash chain to detect tampering
+#[utoipa::path(
+    get,
+    path = "/admin/audit/verify",
+    responses(
+        (status = 200, description = "Audit log checked", body = AuditVerificationApiResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 503, description = "Audit log not enabled", body = ErrorResponse)
+    ),
+    tag = "Audit"
+)]
+pub async fn verify_audit(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<AuditVerification>>, (StatusCode, Json<ErrorResponse>)> {
+    info!("Verifying audit log");
+
+    let audit = require_audit(&state)?;
+
+    match audit.verify().await {
+        Ok(verification) => {
+            let message = match verification.broken_at {
+                Some(id) => {
+                    warn!("Audit log hash chain broken at entry {}", id);
+                    format!("Audit log hash chain is broken at entry {}", id)
+                }
+                None => "Audit log is intact".to_string(),
+            };
+            Ok(Json(ApiResponse::success(verification, message)))
+        }
+        Err(e) => {
+            error!("Failed to verify audit log: {}", e);
+            Err(operation_failed("Failed to verify audit log", &e))
+        }
+    }
+}
+
+/// Reload the configuration and apply its tunable settings without restarting
+///
+/// Fee settings, CORS origins, configured webhook endpoints and webhook
+/// delivery tunables take effect immediately. Other changed settings are
+/// listed in `requires_restart` and keep their running values. Sending the
+/// process `SIGHUP` does the same.
+#[utoipa::path(
+    post,
+    path = "/admin/config/reload",
+    responses(
+        (status = 200, description = "Configuration reloaded", body = ReloadApiResponse),
+        (status = 500, description = "The configuration is invalid; the running settings are kept", body = ErrorResponse),
+        (status = 503, description = "Reloading not available", body = ErrorResponse)
+    ),
+    tag = "Operations"
+)]
+pub async fn reload_config(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<ReloadReport>>, (StatusCode, Json<ErrorResponse>)> {
+    info!("Reloading configuration");
+
+    let reloader = state.reloader.as_ref().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(
+                "Configuration reload is not available".to_string(),
+                503,
+            )),
+        )
+    })?;
+
+    match reloader.reload().await {
+        Ok(report) => Ok(Json(ApiResponse::success(
+            report,
+            "Configuration reloaded successfully".to_string(),
+        ))),
+        Err(e) => {
+            error!("Failed to reload configuration: {:#}", e);
+            Err(operation_failed("Failed to reload configuration", &e))
+        }
+    }
+}
+
+/// Health check endpoint
+#[utoipa::path(
+    get,
+    path = "/health",
+    responses(
+        (status = 200, description = "API is healthy", body = HealthResponse)
+    ),
+    tag = "Health"
+)]
+pub async fn health_check() -> Json<ApiResponse<String>> {
+    Json(ApiResponse::success(
+        "healthy".to_string(),
+        "API is running".to_string(),
+    ))
+}
+
+/// Liveness probe: the process is up and serving requests
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    responses(
+        (status = 200, description = "API is alive", body = HealthResponse)
+    ),
+    tag = "Health"
+)]
+pub async fn health_live() -> Json<ApiResponse<String>> {
+    Json(ApiResponse::success(
+        "alive".to_string(),
+        "API is running".to_string(),
+    ))
+}
+
+/// Readiness probe: the Soroban RPC and the contract are reachable
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    responses(
+        (status = 200, description = "API is ready", body = ReadinessApiResponse),
+        (status = 503, description = "A dependency check failed", body = ReadinessApiResponse)
+    ),
+    tag = "Health"
+)]
+pub async fn health_ready(
+    State(state): State<AppState>,
+) -> (StatusCode, Json<ApiResponse<ReadinessReport>>) {
+    let report = state.health.readiness().await;
+
+    if report.ready {
+        (
+            StatusCode::OK,
+            Json(ApiResponse::success(report, "API is ready".to_string())),
+        )
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse {
+                success: false,
+                data: Some(report),
+                message: "API is not ready".to_string(),
+            }),
+        )
+    }
+}
+
 #[derive(OpenApi)]
 #[openapi(
     paths(
         health_check,
+        health_live,
+        health_ready,
         init_contract,
         issue_certificate,
+        list_certificates,
+        search_certificates,
+        my_certificates,
+        get_notification_preferences,
+        update_notification_preferences,
+        delete_notification_preferences,
         get_certificate,
+        get_certificate_history,
         verify_certificate,
-        transfer_certificate,
+        federated_verify,
+        public_verify,
+        verify_qr,
+        prepare_transfer,
+        submit_transaction,
         revoke_certificate,
         check_certificate_exists,
+        mint_qr_code,
+        get_job,
+        get_challenge,
+        verify_challenge,
+        register_webhook,
+        list_webhooks,
+        delete_webhook,
+        list_webhook_deliveries,
+        rent_forecast,
+        estimate_fees,
+        fee_bump_transaction,
+        fund_account,
+        generate_keypair,
+        convert_strkey,
+        get_account,
+        export_registry,
+        import_certificates,
+        get_import,
+        get_reconciliation,
+        get_api_key_usage,
+        list_audit,
+        verify_audit,
+        reload_config,
     ),
     components(
         schemas(
             HealthResponse,
+            ReadinessApiResponse,
+            PublicVerifyApiResponse,
+            PublicVerifyResponse,
+            VerificationReason,
+            QrVerifyApiResponse,
+            QrVerifyResponse,
+            QrPayloadApiResponse,
+            QrPayload,
+            VerifyQrRequest,
+            ReadinessReport,
+            HealthCheck,
             CertificateResponse,
+            CertificatePageApiResponse,
+            CertificatePage,
+            CertificateSummary,
             TransactionApiResponse,
             VerifyApiResponse,
             ExistsApiResponse,
+            FederatedVerifyApiResponse,
+            RentForecastApiResponse,
+            CertificateHistoryApiResponse,
+            ReconciliationApiResponse,
+            ReconciliationReport,
+            ApiKeyUsageApiResponse,
+            ApiKeyUsage,
+            PeriodUsage,
+            ReloadApiResponse,
+            ReloadReport,
+            ReconciliationStatus,
+            Drift,
+            RentForecast,
+            FeeEstimateApiResponse,
+            FeeEstimate,
+            FeeBumpRequest,
+            FundAccountRequest,
+            FundedAccountApiResponse,
+            FundedAccount,
+            DevKeypairApiResponse,
+            DevKeypair,
+            StrkeyConversionApiResponse,
+            StrkeyConversion,
+            AccountInfoApiResponse,
+            AccountInfo,
+            AccountBalance,
+            AccountSigner,
+            ExportedCertificate,
+            CertificateHistoryEvent,
+            ImportReportApiResponse,
+            ImportReport,
+            ImportRow,
+            ImportRowStatus,
+            ImportStatus,
+            LegacyCertificate,
+            AuditPageApiResponse,
+            AuditPage,
+            AuditEntry,
+            AuditVerificationApiResponse,
+            AuditVerification,
+            JobApiResponse,
+            Job,
+            JobStatus,
+            WebhookApiResponse,
+            WebhookListApiResponse,
+            Webhook,
+            RegisterWebhookRequest,
+            UpdateNotificationPreferencesRequest,
+            NotificationPreferencesApiResponse,
+            NotificationPreferences,
+            DeliveryListApiResponse,
+            Delivery,
+            DeliveryStatus,
+            ChallengeResponse,
+            ChallengeTokenRequest,
+            ChallengeTokenResponse,
             Certificate,
+            CertId,
+            MetadataHash,
+            StellarAddress,
             InitRequest,
             IssueCertificateRequest,
             VerifyCertificateRequest,
-            TransferCertificateRequest,
+            PrepareTransferRequest,
+            SubmitTransactionRequest,
+            PreparedTransaction,
+            PreparedTransactionApiResponse,
             TransactionResponse,
+            CallSummary,
+            EmittedEvent,
             VerifyResponse,
+            FederatedVerifyResponse,
             ExistsResponse,
             ErrorResponse,
+            FieldError,
+            ErrorCode,
         )
     ),
     tags(
         (name = "Health", description = "Health check endpoints"),
         (name = "Contract Management", description = "Smart contract initialization"),
         (name = "Certificate Management", description = "Certificate CRUD operations"),
+        (name = "Federation", description = "Verification across peer registries"),
+        (name = "Jobs", description = "Status of queued contract writes"),
+        (name = "Webhooks", description = "Notifications for certificate lifecycle events"),
+        (name = "Owners", description = "SEP-10 sign-in and owner-scoped endpoints"),
+        (name = "Operations", description = "Contract storage, funding and fee operations"),
+        (name = "Audit", description = "Record of API-initiated operations"),
+        (name = "Accounts", description = "Stellar account lookups"),
+        (name = "Development", description = "Helpers for trying the API on test networks"),
     ),
     info(
         title = "VeriLuxe API",
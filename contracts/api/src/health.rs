@@ -0,0 +1,210 @@
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+use crate::{
+    models::ErrorCode,
+    soroban_client::{SorobanClient, CONTRACT_FUNCTIONS},
+};
+
+/// What to do when the startup check finds a problem
+///
+/// - `off` skips the check
+/// - `warn` logs each failed check and starts anyway
+/// - `strict` refuses to start
+pub const STARTUP_CHECK_MODES: &[&str] = &["off", "warn", "strict"];
+
+/// Tunables for the readiness probe
+#[derive(Debug, Clone)]
+pub struct HealthSettings {
+    /// Also require the contract to have an admin set
+    pub check_initialized: bool,
+    /// Limit on each dependency check
+    pub timeout_secs: u64,
+    /// One of [`STARTUP_CHECK_MODES`]
+    pub startup_check: String,
+}
+
+impl Default for HealthSettings {
+    fn default() -> Self {
+        Self {
+            check_initialized: true,
+            timeout_secs: 5,
+            startup_check: "warn".to_string(),
+        }
+    }
+}
+
+/// Outcome of one dependency check
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct HealthCheck {
+    pub name: String,
+    pub ok: bool,
+    pub detail: String,
+    pub latency_ms: u64,
+}
+
+/// Whether the API can serve requests, with the checks that decided it
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    pub checks: Vec<HealthCheck>,
+}
+
+/// Checks the dependencies the API needs to serve requests
+#[derive(Clone)]
+pub struct HealthChecker {
+    client: SorobanClient,
+    settings: HealthSettings,
+}
+
+impl HealthChecker {
+    pub fn new(client: SorobanClient, settings: HealthSettings) -> Self {
+        Self { client, settings }
+    }
+
+    /// Check the RPC, the contract and, if configured, its initialization
+    ///
+    /// Checks run in order and stop at the first failure, since later ones
+    /// depend on the earlier ones.
+    pub async fn readiness(&self) -> ReadinessReport {
+        let mut checks = vec![
+            self.check("rpc", async {
+                let ledger = self.client.get_latest_ledger().await?;
+                Ok(format!("Latest ledger {}", ledger))
+            })
+            .await,
+        ];
+
+        if checks.iter().all(|check| check.ok) {
+            checks.push(
+                self.check("contract", async {
+                    let live_until = self.client.get_contract_instance_live_until().await?;
+                    Ok(format!("Instance live until ledger {}", live_until))
+                })
+                .await,
+            );
+        }
+
+        if self.settings.check_initialized && checks.iter().all(|check| check.ok) {
+            checks.push(
+                self.check("initialized", async {
+                    let admin = self.client.get_admin().await
+                        .map_err(|e| anyhow!("Contract is not initialized: {}", e))?;
+                    Ok(format!("Admin {}", admin))
+                })
+                .await,
+            );
+        }
+
+        ReadinessReport {
+            ready: checks.iter().all(|check| check.ok),
+            checks,
+        }
+    }
+
+    /// Check the configured contract before serving requests
+    ///
+    /// Verifies that the contract exists on the network, that its interface
+    /// exports every function the API calls, and that its admin is the
+    /// configured signer. An uninitialized contract passes, since `/init`
+    /// can still set it up. Depending on `startup_check`, failures are logged
+    /// or returned as an error.
+    pub async fn startup(&self) -> Result<()> {
+        if self.settings.startup_check == "off" {
+            return Ok(());
+        }
+
+        let mut checks = vec![
+            self.check("contract", async {
+                let live_until = self.client.get_contract_instance_live_until().await?;
+                Ok(format!("Instance live until ledger {}", live_until))
+            })
+            .await,
+        ];
+
+        // Without the instance, the other checks can only fail the same way
+        if checks.iter().all(|check| check.ok) {
+            checks.push(
+                self.check("interface", async {
+                    let functions = self.client.contract_functions().await?;
+                    let missing: Vec<&str> = CONTRACT_FUNCTIONS
+                        .iter()
+                        .copied()
+                        .filter(|function| !functions.iter().any(|f| f == function))
+                        .collect();
+                    if !missing.is_empty() {
+                        return Err(anyhow!("Contract doesn't export {}", missing.join(", ")));
+                    }
+                    Ok(format!("Exports all {} functions the API calls", CONTRACT_FUNCTIONS.len()))
+                })
+                .await,
+            );
+
+            checks.push(
+                self.check("admin", async {
+                    let signer = self.client.admin_public_key()?;
+                    match self.client.get_admin().await {
+                        Ok(admin) if admin == signer => Ok(format!("Admin {}", admin)),
+                        Ok(admin) => Err(anyhow!(
+                            "Contract admin is {}, but the API signs as {}",
+                            admin,
+                            signer
+                        )),
+                        Err(e) if ErrorCode::classify(&e) == ErrorCode::ContractNotInitialized => {
+                            Ok("Contract not initialized yet; POST /init to set its admin".to_string())
+                        }
+                        Err(e) => Err(e),
+                    }
+                })
+                .await,
+            );
+        }
+
+        let failed: Vec<&HealthCheck> = checks.iter().filter(|check| !check.ok).collect();
+        if failed.is_empty() {
+            info!("Startup check passed");
+            return Ok(());
+        }
+
+        let summary = failed
+            .iter()
+            .map(|check| format!("{}: {}", check.name, check.detail))
+            .collect::<Vec<_>>()
+            .join("; ");
+        if self.settings.startup_check == "strict" {
+            return Err(anyhow!("Startup check failed: {}", summary));
+        }
+        warn!("Startup check failed, serving anyway: {}", summary);
+        Ok(())
+    }
+
+    async fn check(&self, name: &str, check: impl Future<Output = Result<String>>) -> HealthCheck {
+        let started = Instant::now();
+        let timeout = Duration::from_secs(self.settings.timeout_secs);
+        let result = match tokio::time::timeout(timeout, check).await {
+            Ok(result) => result,
+            Err(_) => Err(anyhow!("Timed out after {}s", self.settings.timeout_secs)),
+        };
+
+        let (ok, detail) = match result {
+            Ok(detail) => (true, detail),
+            Err(e) => {
+                warn!("Readiness check {} failed: {}", name, e);
+                (false, e.to_string())
+            }
+        };
+
+        HealthCheck {
+            name: name.to_string(),
+            ok,
+            detail,
+            latency_ms: started.elapsed().as_millis() as u64,
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use anyhow::{anyhow, Result};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{friendbot::TESTNET_PASSPHRASE, sandbox::SandboxSettings};
+
+/// Passphrase of the public Stellar network
+pub const MAINNET_PASSPHRASE: &str = "Public Global Stellar Network ; September 2015";
+
+/// Tunables for reading accounts from Horizon
+#[derive(Debug, Clone, Default)]
+pub struct HorizonSettings {
+    /// Horizon base URL; account lookups are disabled when empty
+    pub url: String,
+}
+
+impl HorizonSettings {
+    /// SDF's Horizon for testnet and mainnet, or the sandbox's local one
+    pub fn default_url(network_passphrase: &str, sandbox: &SandboxSettings) -> String {
+        if sandbox.enabled {
+            "http://localhost:8000".to_string()
+        } else if network_passphrase == TESTNET_PASSPHRASE {
+            "https://horizon-testnet.stellar.org".to_string()
+        } else if network_passphrase == MAINNET_PASSPHRASE {
+            "https://horizon.stellar.org".to_string()
+        } else {
+            String::new()
+        }
+    }
+}
+
+/// An account as seen by Horizon
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AccountInfo {
+    pub address: String,
+    /// Whether the account exists on the ledger; nothing else is set otherwise
+    pub exists: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<String>,
+    /// XLM balance, in lumens
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub native_balance: Option<String>,
+    pub balances: Vec<AccountBalance>,
+    pub signers: Vec<AccountSigner>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccountBalance {
+    /// `native`, `credit_alphanum4`, `credit_alphanum12` or `liquidity_pool_shares`
+    pub asset_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset_code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset_issuer: Option<String>,
+    pub balance: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccountSigner {
+    pub key: String,
+    pub weight: u32,
+    #[serde(rename = "type")]
+    pub signer_type: String,
+}
+
+/// Horizon's `/accounts/{id}` resource, reduced to the fields served
+#[derive(Deserialize)]
+struct HorizonAccount {
+    sequence: String,
+    #[serde(default)]
+    balances: Vec<AccountBalance>,
+    #[serde(default)]
+    signers: Vec<AccountSigner>,
+}
+
+/// Reads accounts from a Horizon server
+#[derive(Clone)]
+pub struct Horizon {
+    url: String,
+    http_client: Client,
+}
+
+impl Horizon {
+    /// A client for the configured Horizon, if any
+    pub fn new(settings: &HorizonSettings, http_client: Client) -> Option<Self> {
+        if settings.url.is_empty() {
+            return None;
+        }
+        Some(Self {
+            url: settings.url.trim_end_matches('/').to_string(),
+            http_client,
+        })
+    }
+
+    /// Look up an account, reporting a missing one rather than failing
+    pub async fn account(&self, address: &str) -> Result<AccountInfo> {
+        let response = self
+            .http_client
+            .get(&format!("{}/accounts/{}", self.url, address))
+            .send()
+            .await
+            .map_err(|e| anyhow!("Horizon request failed: {}", e))?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(AccountInfo {
+                address: address.to_string(),
+                exists: false,
+                sequence: None,
+                native_balance: None,
+                balances: Vec::new(),
+                signers: Vec::new(),
+            });
+        }
+        if !response.status().is_success() {
+            return Err(anyhow!("Horizon answered {} for account {}", response.status(), address));
+        }
+
+        let account: HorizonAccount = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Invalid Horizon account response: {}", e))?;
+        let native_balance = account
+            .balances
+            .iter()
+            .find(|balance| balance.asset_type == "native")
+            .map(|balance| balance.balance.clone());
+
+        Ok(AccountInfo {
+            address: address.to_string(),
+            exists: true,
+            sequence: Some(account.sequence),
+            native_balance,
+            balances: account.balances,
+            signers: account.signers,
+        })
+    }
+}
@@ -0,0 +1,422 @@
+use axum::{
+    extract::Request,
+    http::{
+        header::{ACCEPT_LANGUAGE, CONTENT_LANGUAGE, VARY},
+        HeaderValue,
+    },
+    middleware::Next,
+    response::Response,
+};
+
+tokio::task_local! {
+    /// Language negotiated for the request being handled
+    static LOCALE: Locale;
+}
+
+/// Languages messages are translated into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    En,
+    Fr,
+    It,
+    De,
+    Es,
+    Ja,
+    Zh,
+}
+
+impl Locale {
+    /// Every supported language, in the order of [`CATALOG`]'s columns
+    pub const ALL: [Locale; 7] = [
+        Locale::En,
+        Locale::Fr,
+        Locale::It,
+        Locale::De,
+        Locale::Es,
+        Locale::Ja,
+        Locale::Zh,
+    ];
+
+    /// The language's BCP 47 primary subtag
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Fr => "fr",
+            Locale::It => "it",
+            Locale::De => "de",
+            Locale::Es => "es",
+            Locale::Ja => "ja",
+            Locale::Zh => "zh",
+        }
+    }
+
+    /// The supported language of a tag such as `fr-CH` or `zh-Hans`
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.split('-').next().unwrap_or_default();
+        Self::ALL
+            .into_iter()
+            .find(|locale| locale.tag().eq_ignore_ascii_case(primary))
+    }
+
+    /// The most preferred supported language of an `Accept-Language` header
+    ///
+    /// Languages are ranked by their `q` weight, ties keeping header order.
+    /// English is used when nothing listed is supported.
+    pub fn negotiate(accept_language: &str) -> Self {
+        let mut ranges: Vec<(&str, f32)> = accept_language
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';').map(str::trim);
+                let tag = parts.next().filter(|tag| !tag.is_empty())?;
+                let quality = parts
+                    .find_map(|param| param.strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.parse::<f32>().ok())?;
+                Some((tag, quality))
+            })
+            .filter(|(_, quality)| *quality > 0.0)
+            .collect();
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        ranges
+            .into_iter()
+            .find_map(|(tag, _)| Self::from_tag(tag))
+            .unwrap_or(Locale::En)
+    }
+
+    /// The language of the request being handled; English outside of one
+    pub fn current() -> Self {
+        LOCALE.try_with(|locale| *locale).unwrap_or(Locale::En)
+    }
+}
+
+/// Translated user-facing messages, one row per message: English, French,
+/// Italian, German, Spanish, Japanese, then Chinese
+///
+/// `{}` stands for a value, such as a registry name, that is carried over
+/// from the English message unchanged. Messages not listed stay in English.
+const CATALOG: &[[&str; 7]] = &[
+    [
+        "Authentic VeriLuxe certificate",
+        "Certificat VeriLuxe authentique",
+        "Certificato VeriLuxe autentico",
+        "Echtes VeriLuxe-Zertifikat",
+        "Certificado VeriLuxe auténtico",
+        "正規のVeriLuxe証明書です",
+        "真实的VeriLuxe证书",
+    ],
+    [
+        "Authentic VeriLuxe certificate; its warranty has ended",
+        "Certificat VeriLuxe authentique ; sa garantie a expiré",
+        "Certificato VeriLuxe autentico; la garanzia è scaduta",
+        "Echtes VeriLuxe-Zertifikat; die Garantie ist abgelaufen",
+        "Certificado VeriLuxe auténtico; su garantía ha vencido",
+        "正規のVeriLuxe証明書です（保証期間は終了しています）",
+        "真实的VeriLuxe证书；保修期已结束",
+    ],
+    [
+        "This certificate has been revoked",
+        "Ce certificat a été révoqué",
+        "Questo certificato è stato revocato",
+        "Dieses Zertifikat wurde widerrufen",
+        "Este certificado ha sido revocado",
+        "この証明書は取り消されています",
+        "此证书已被撤销",
+    ],
+    [
+        "This certificate is under review",
+        "Ce certificat est en cours d'examen",
+        "Questo certificato è in fase di verifica",
+        "Dieses Zertifikat wird derzeit geprüft",
+        "Este certificado está en revisión",
+        "この証明書は審査中です",
+        "此证书正在审核中",
+    ],
+    [
+        "This item does not match its certificate",
+        "Cet article ne correspond pas à son certificat",
+        "Questo articolo non corrisponde al suo certificato",
+        "Dieser Artikel stimmt nicht mit seinem Zertifikat überein",
+        "Este artículo no coincide con su certificado",
+        "この商品は証明書と一致しません",
+        "此物品与其证书不符",
+    ],
+    [
+        "No VeriLuxe certificate has this ID",
+        "Aucun certificat VeriLuxe ne porte cet identifiant",
+        "Nessun certificato VeriLuxe ha questo ID",
+        "Kein VeriLuxe-Zertifikat hat diese ID",
+        "Ningún certificado VeriLuxe tiene este ID",
+        "このIDのVeriLuxe証明書はありません",
+        "没有使用此ID的VeriLuxe证书",
+    ],
+    [
+        "This certificate is no longer valid",
+        "Ce certificat n'est plus valide",
+        "Questo certificato non è più valido",
+        "Dieses Zertifikat ist nicht mehr gültig",
+        "Este certificado ya no es válido",
+        "この証明書は現在無効です",
+        "此证书已失效",
+    ],
+    [
+        "Certificate verification successful",
+        "Vérification du certificat réussie",
+        "Verifica del certificato riuscita",
+        "Zertifikatsprüfung erfolgreich",
+        "Verificación del certificado correcta",
+        "証明書の検証に成功しました",
+        "证书验证成功",
+    ],
+    [
+        "Certificate verification failed",
+        "Échec de la vérification du certificat",
+        "Verifica del certificato non riuscita",
+        "Zertifikatsprüfung fehlgeschlagen",
+        "La verificación del certificado ha fallado",
+        "証明書の検証に失敗しました",
+        "证书验证失败",
+    ],
+    [
+        "Certificate verified by registry {}",
+        "Certificat vérifié par le registre {}",
+        "Certificato verificato dal registro {}",
+        "Zertifikat von Register {} bestätigt",
+        "Certificado verificado por el registro {}",
+        "レジストリ{}で証明書が確認されました",
+        "证书已由登记处{}验证",
+    ],
+    [
+        "Certificate verification failed in registry {}",
+        "Échec de la vérification du certificat dans le registre {}",
+        "Verifica del certificato non riuscita nel registro {}",
+        "Zertifikatsprüfung in Register {} fehlgeschlagen",
+        "La verificación del certificado ha fallado en el registro {}",
+        "レジストリ{}で証明書の検証に失敗しました",
+        "证书在登记处{}验证失败",
+    ],
+    [
+        "Certificate not found in any registry",
+        "Certificat introuvable dans les registres",
+        "Certificato non trovato in alcun registro",
+        "Zertifikat in keinem Register gefunden",
+        "Certificado no encontrado en ningún registro",
+        "どのレジストリにも証明書が見つかりません",
+        "在任何登记处均未找到该证书",
+    ],
+    [
+        "Certificate details retrieved successfully",
+        "Détails du certificat récupérés",
+        "Dettagli del certificato recuperati",
+        "Zertifikatsdetails abgerufen",
+        "Detalles del certificado obtenidos",
+        "証明書の詳細を取得しました",
+        "已获取证书详情",
+    ],
+    [
+        "Certificates retrieved successfully",
+        "Certificats récupérés",
+        "Certificati recuperati",
+        "Zertifikate abgerufen",
+        "Certificados obtenidos",
+        "証明書を取得しました",
+        "已获取证书",
+    ],
+    [
+        "History retrieved successfully",
+        "Historique récupéré",
+        "Cronologia recuperata",
+        "Verlauf abgerufen",
+        "Historial obtenido",
+        "履歴を取得しました",
+        "已获取历史记录",
+    ],
+    [
+        "Certificate exists",
+        "Le certificat existe",
+        "Il certificato esiste",
+        "Das Zertifikat existiert",
+        "El certificado existe",
+        "証明書が存在します",
+        "证书存在",
+    ],
+    [
+        "Certificate does not exist",
+        "Le certificat n'existe pas",
+        "Il certificato non esiste",
+        "Das Zertifikat existiert nicht",
+        "El certificado no existe",
+        "証明書は存在しません",
+        "证书不存在",
+    ],
+    [
+        "Transfer prepared; sign it and send it to /transactions/submit",
+        "Transfert préparé ; signez-le et envoyez-le à /transactions/submit",
+        "Trasferimento preparato; firmalo e invialo a /transactions/submit",
+        "Übertragung vorbereitet; signieren Sie sie und senden Sie sie an /transactions/submit",
+        "Transferencia preparada; fírmela y envíela a /transactions/submit",
+        "譲渡を準備しました。署名して /transactions/submit に送信してください",
+        "转让已准备好；请签名后发送至 /transactions/submit",
+    ],
+    [
+        "Transaction submitted successfully",
+        "Transaction soumise",
+        "Transazione inviata",
+        "Transaktion übermittelt",
+        "Transacción enviada",
+        "トランザクションを送信しました",
+        "交易已提交",
+    ],
+    [
+        "Preferences saved successfully",
+        "Préférences enregistrées",
+        "Preferenze salvate",
+        "Einstellungen gespeichert",
+        "Preferencias guardadas",
+        "設定を保存しました",
+        "偏好设置已保存",
+    ],
+    [
+        "Preferences retrieved successfully",
+        "Préférences récupérées",
+        "Preferenze recuperate",
+        "Einstellungen abgerufen",
+        "Preferencias obtenidas",
+        "設定を取得しました",
+        "已获取偏好设置",
+    ],
+    [
+        "Preferences removed successfully",
+        "Préférences supprimées",
+        "Preferenze rimosse",
+        "Einstellungen entfernt",
+        "Preferencias eliminadas",
+        "設定を削除しました",
+        "偏好设置已删除",
+    ],
+    [
+        "No notification preferences registered",
+        "Aucune préférence de notification enregistrée",
+        "Nessuna preferenza di notifica registrata",
+        "Keine Benachrichtigungseinstellungen hinterlegt",
+        "No hay preferencias de notificación registradas",
+        "通知設定は登録されていません",
+        "未登记通知偏好设置",
+    ],
+    [
+        "Provide an email address or a phone number",
+        "Indiquez une adresse e-mail ou un numéro de téléphone",
+        "Indica un indirizzo email o un numero di telefono",
+        "Geben Sie eine E-Mail-Adresse oder Telefonnummer an",
+        "Indique una dirección de correo electrónico o un número de teléfono",
+        "メールアドレスまたは電話番号を入力してください",
+        "请提供电子邮件地址或电话号码",
+    ],
+    [
+        "Certificate ID cannot be empty",
+        "L'identifiant du certificat est obligatoire",
+        "L'ID del certificato è obbligatorio",
+        "Die Zertifikats-ID darf nicht leer sein",
+        "El ID del certificado no puede estar vacío",
+        "証明書IDを入力してください",
+        "证书ID不能为空",
+    ],
+    [
+        "Metadata hash cannot be empty",
+        "L'empreinte des métadonnées est obligatoire",
+        "L'hash dei metadati è obbligatorio",
+        "Der Metadaten-Hash darf nicht leer sein",
+        "El hash de metadatos no puede estar vacío",
+        "メタデータハッシュを入力してください",
+        "元数据哈希不能为空",
+    ],
+    [
+        "Invalid QR payload: signature does not match",
+        "Code QR invalide : la signature ne correspond pas",
+        "Codice QR non valido: la firma non corrisponde",
+        "Ungültiger QR-Code: Die Signatur stimmt nicht überein",
+        "Código QR no válido: la firma no coincide",
+        "無効なQRコードです：署名が一致しません",
+        "二维码无效：签名不匹配",
+    ],
+    [
+        "Invalid QR payload: not a VeriLuxe code",
+        "Code QR invalide : ce n'est pas un code VeriLuxe",
+        "Codice QR non valido: non è un codice VeriLuxe",
+        "Ungültiger QR-Code: kein VeriLuxe-Code",
+        "Código QR no válido: no es un código VeriLuxe",
+        "無効なQRコードです：VeriLuxeのコードではありません",
+        "二维码无效：不是VeriLuxe二维码",
+    ],
+    [
+        "QR payload expired at {}",
+        "Ce code QR a expiré ({})",
+        "Questo codice QR è scaduto ({})",
+        "Dieser QR-Code ist abgelaufen ({})",
+        "Este código QR ha caducado ({})",
+        "このQRコードは有効期限切れです（{}）",
+        "此二维码已过期（{}）",
+    ],
+];
+
+/// `message` in the language of the request being handled, when translated
+pub fn translate(message: String) -> String {
+    let locale = Locale::current();
+    if locale == Locale::En {
+        return message;
+    }
+
+    let column = locale as usize;
+    CATALOG
+        .iter()
+        .find_map(|row| {
+            let values = match_template(row[0], &message)?;
+            Some(fill_template(row[column], &values))
+        })
+        .unwrap_or(message)
+}
+
+/// The values standing in for each `{}` of `template`, if `message` fits it
+fn match_template<'a>(template: &str, message: &'a str) -> Option<Vec<&'a str>> {
+    let mut parts = template.split("{}");
+    let mut rest = message.strip_prefix(parts.next().unwrap_or_default())?;
+    let mut values = Vec::new();
+    let mut parts = parts.peekable();
+    while let Some(part) = parts.next() {
+        let end = if parts.peek().is_none() {
+            rest.len().checked_sub(part.len()).filter(|&end| rest[end..] == *part)?
+        } else {
+            rest.find(part)?
+        };
+        values.push(&rest[..end]);
+        rest = &rest[end + part.len()..];
+    }
+    rest.is_empty().then_some(values)
+}
+
+fn fill_template(template: &str, values: &[&str]) -> String {
+    let mut parts = template.split("{}");
+    let mut filled = parts.next().unwrap_or_default().to_string();
+    for (part, value) in parts.zip(values.iter().chain(std::iter::repeat(&""))) {
+        filled.push_str(value);
+        filled.push_str(part);
+    }
+    filled
+}
+
+/// Answer in the language the client prefers, per `Accept-Language`
+///
+/// Messages built while the request is handled are translated, and the
+/// response names its language in `Content-Language`.
+pub async fn localize(request: Request, next: Next) -> Response {
+    let locale = request
+        .headers()
+        .get(ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .map_or(Locale::En, Locale::negotiate);
+
+    let mut response = LOCALE.scope(locale, next.run(request)).await;
+    let headers = response.headers_mut();
+    headers.insert(CONTENT_LANGUAGE, HeaderValue::from_static(locale.tag()));
+    headers.append(VARY, HeaderValue::from_static("accept-language"));
+    response
+}
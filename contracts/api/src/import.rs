@@ -0,0 +1,438 @@
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::RwLock;
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+use crate::{
+    contract_client::ContractClient,
+    export::csv_field,
+    models::{CertId, LegacyCertificate, MetadataHash, StellarAddress},
+    soroban_client::{SubmitSettings, TransactionOutcome},
+};
+
+/// Tunables for bulk certificate imports
+#[derive(Debug, Clone)]
+pub struct ImportSettings {
+    /// Certificates issued per transaction; must not exceed the contract's `max_batch_size`
+    pub batch_size: usize,
+    /// Largest file accepted, in rows
+    pub max_rows: usize,
+}
+
+impl Default for ImportSettings {
+    fn default() -> Self {
+        Self {
+            batch_size: 20,
+            max_rows: 10_000,
+        }
+    }
+}
+
+/// Format of an uploaded import file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Csv,
+    Json,
+}
+
+impl ImportFormat {
+    pub fn parse(format: &str) -> Option<Self> {
+        match format.to_ascii_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    /// Read the certificates out of an uploaded file
+    ///
+    /// JSON files are an array of objects; CSV files start with a header
+    /// naming the `cert_id`, `metadata_hash` and `owner_address` (or `owner`)
+    /// columns, in any order. Other columns are ignored.
+    pub fn read(self, body: &str) -> Result<Vec<LegacyCertificate>> {
+        let body = body.trim_start_matches('\u{feff}');
+        match self {
+            Self::Json => serde_json::from_str(body).map_err(|e| anyhow!("Invalid JSON: {}", e)),
+            Self::Csv => read_csv(body),
+        }
+    }
+}
+
+/// What happened to one row of an import
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportRowStatus {
+    /// Waiting for its batch
+    Pending,
+    /// Rejected by validation; never submitted
+    Invalid,
+    /// The certificate already exists on-chain
+    Skipped,
+    Issued,
+    /// Its batch was rejected or didn't confirm
+    Failed,
+}
+
+impl ImportRowStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Invalid => "invalid",
+            Self::Skipped => "skipped",
+            Self::Issued => "issued",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// Result for one row of an import
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ImportRow {
+    /// 1-based position among the file's certificates
+    pub row: usize,
+    pub cert_id: String,
+    pub status: ImportRowStatus,
+    pub transaction_hash: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Lifecycle of an import
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStatus {
+    Running,
+    Completed,
+}
+
+/// Progress and per-row report of an import
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ImportReport {
+    pub id: String,
+    pub status: ImportStatus,
+    pub total: usize,
+    pub pending: usize,
+    pub issued: usize,
+    pub skipped: usize,
+    pub invalid: usize,
+    pub failed: usize,
+    pub rows: Vec<ImportRow>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+impl ImportReport {
+    /// The per-row results as CSV, with the transaction hash of each issued row
+    pub fn results_csv(&self) -> String {
+        let mut csv = String::from("row,cert_id,status,transaction_hash,error\n");
+        for row in &self.rows {
+            let fields = [
+                row.row.to_string(),
+                row.cert_id.clone(),
+                row.status.as_str().to_string(),
+                row.transaction_hash.clone().unwrap_or_default(),
+                row.error.clone().unwrap_or_default(),
+            ];
+            csv.push_str(&fields.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    fn tally(&mut self) {
+        let count = |status| self.rows.iter().filter(|row| row.status == status).count();
+        self.pending = count(ImportRowStatus::Pending);
+        self.issued = count(ImportRowStatus::Issued);
+        self.skipped = count(ImportRowStatus::Skipped);
+        self.invalid = count(ImportRowStatus::Invalid);
+        self.failed = count(ImportRowStatus::Failed);
+        self.updated_at = now_secs();
+    }
+}
+
+/// Migrates registries of legacy certificates onto the contract
+///
+/// Every row is validated up front, then the valid ones are issued in
+/// atomic `issue_batch` transactions in the background. Certificates that
+/// already exist are skipped, so a failed import can be re-run with the
+/// same file. Reports are kept in memory and lost when the process restarts.
+#[derive(Clone)]
+pub struct ImportManager {
+    settings: ImportSettings,
+    submit: SubmitSettings,
+    imports: Arc<RwLock<HashMap<String, ImportReport>>>,
+}
+
+impl ImportManager {
+    pub fn new(settings: ImportSettings, submit: SubmitSettings) -> Self {
+        Self {
+            settings,
+            submit,
+            imports: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub fn max_rows(&self) -> usize {
+        self.settings.max_rows
+    }
+
+    /// Validate the certificates and start issuing the valid ones through `client`
+    pub async fn start(
+        &self,
+        client: Arc<dyn ContractClient>,
+        certificates: Vec<LegacyCertificate>,
+    ) -> ImportReport {
+        let mut seen_ids = HashSet::new();
+        let mut seen_hashes = HashSet::new();
+        let rows = certificates
+            .iter()
+            .enumerate()
+            .map(|(i, certificate)| {
+                let error = validate(certificate, &mut seen_ids, &mut seen_hashes).err();
+                ImportRow {
+                    row: i + 1,
+                    cert_id: certificate.cert_id.clone(),
+                    status: if error.is_some() {
+                        ImportRowStatus::Invalid
+                    } else {
+                        ImportRowStatus::Pending
+                    },
+                    transaction_hash: None,
+                    error,
+                }
+            })
+            .collect();
+
+        let now = now_secs();
+        let mut report = ImportReport {
+            id: uuid::Uuid::new_v4().to_string(),
+            status: ImportStatus::Running,
+            total: certificates.len(),
+            pending: 0,
+            issued: 0,
+            skipped: 0,
+            invalid: 0,
+            failed: 0,
+            rows,
+            created_at: now,
+            updated_at: now,
+        };
+        report.tally();
+        info!(
+            "Starting import {} of {} certificates ({} invalid)",
+            report.id, report.total, report.invalid
+        );
+
+        self.imports.write().await.insert(report.id.clone(), report.clone());
+        let manager = self.clone();
+        let id = report.id.clone();
+        tokio::spawn(async move { manager.run(&id, client, certificates).await });
+        report
+    }
+
+    /// Look up an import by ID
+    pub async fn get(&self, id: &str) -> Option<ImportReport> {
+        self.imports.read().await.get(id).cloned()
+    }
+
+    async fn run(&self, id: &str, client: Arc<dyn ContractClient>, certificates: Vec<LegacyCertificate>) {
+        let pending: Vec<usize> = match self.get(id).await {
+            Some(report) => report
+                .rows
+                .iter()
+                .filter(|row| row.status == ImportRowStatus::Pending)
+                .map(|row| row.row - 1)
+                .collect(),
+            None => return,
+        };
+
+        // Skip what's already on-chain so a batch isn't rejected for one row
+        let mut to_issue = Vec::new();
+        for index in pending {
+            // Rows were validated on upload, so their IDs parse
+            let exists = match CertId::parse(&certificates[index].cert_id) {
+                Ok(cert_id) => client.certificate_exists(&cert_id).await,
+                Err(e) => Err(anyhow!("{}", e)),
+            };
+            match exists {
+                Ok(false) => to_issue.push(index),
+                Ok(true) => {
+                    let error = "Certificate already exists";
+                    self.set_rows(id, &[index], ImportRowStatus::Skipped, None, Some(error))
+                        .await
+                }
+                Err(e) => {
+                    let error = format!("Failed to check certificate: {}", e);
+                    self.set_rows(id, &[index], ImportRowStatus::Failed, None, Some(&error))
+                        .await
+                }
+            }
+        }
+
+        for batch in to_issue.chunks(self.settings.batch_size.max(1)) {
+            let entries: Vec<LegacyCertificate> =
+                batch.iter().map(|&index| certificates[index].clone()).collect();
+            match self.issue(client.as_ref(), &entries).await {
+                Ok(tx_hash) => {
+                    self.set_rows(id, batch, ImportRowStatus::Issued, Some(&tx_hash), None)
+                        .await
+                }
+                Err(e) => {
+                    warn!("Import {} batch failed: {}", id, e);
+                    self.set_rows(id, batch, ImportRowStatus::Failed, None, Some(&e.to_string()))
+                        .await
+                }
+            }
+        }
+
+        if let Some(report) = self.imports.write().await.get_mut(id) {
+            report.status = ImportStatus::Completed;
+            report.tally();
+            info!(
+                "Import {} completed: {} issued, {} skipped, {} invalid, {} failed",
+                id, report.issued, report.skipped, report.invalid, report.failed
+            );
+        }
+    }
+
+    /// Submit one batch and wait for it to land
+    async fn issue(&self, client: &dyn ContractClient, entries: &[LegacyCertificate]) -> Result<String> {
+        let tx_hash = client.issue_batch(entries).await?;
+        let outcome = client
+            .wait_for_transaction(
+                &tx_hash,
+                Duration::from_secs(self.submit.wait_timeout_secs),
+                Duration::from_millis(self.submit.poll_interval_ms),
+            )
+            .await
+            .map_err(|e| anyhow!("Transaction {} not confirmed: {}", tx_hash, e))?;
+
+        match outcome {
+            TransactionOutcome::Success { .. } => Ok(tx_hash),
+            TransactionOutcome::Failed { ledger, summary } => {
+                let message = summary.failure_message(&tx_hash, ledger);
+                error!("Import failed: {}", message);
+                Err(anyhow!("{}", message))
+            }
+        }
+    }
+
+    async fn set_rows(
+        &self,
+        id: &str,
+        indexes: &[usize],
+        status: ImportRowStatus,
+        tx_hash: Option<&str>,
+        error: Option<&str>,
+    ) {
+        if let Some(report) = self.imports.write().await.get_mut(id) {
+            for &index in indexes {
+                let row = &mut report.rows[index];
+                row.status = status;
+                row.transaction_hash = tx_hash.map(str::to_string);
+                row.error = error.map(str::to_string);
+            }
+            report.tally();
+        }
+    }
+}
+
+/// Check a row against the contract's issuance rules and the rows before it
+fn validate(
+    certificate: &LegacyCertificate,
+    seen_ids: &mut HashSet<String>,
+    seen_hashes: &mut HashSet<String>,
+) -> Result<(), String> {
+    let cert_id = &certificate.cert_id;
+    if !CertId::parse(cert_id).map_or(false, |id| id.is_issuable()) {
+        return Err("cert_id must be 3-64 characters from [A-Za-z0-9._-]".to_string());
+    }
+    MetadataHash::parse(&certificate.metadata_hash)?;
+    if StellarAddress::parse(&certificate.owner_address).is_err() {
+        return Err("owner_address must be a Stellar public key (G...)".to_string());
+    }
+    if !seen_ids.insert(cert_id.clone()) {
+        return Err("Duplicate cert_id in file".to_string());
+    }
+    if !seen_hashes.insert(certificate.metadata_hash.clone()) {
+        return Err("Duplicate metadata_hash in file".to_string());
+    }
+    Ok(())
+}
+
+fn read_csv(body: &str) -> Result<Vec<LegacyCertificate>> {
+    let mut records = csv_records(body)?.into_iter();
+    let header = records.next().ok_or_else(|| anyhow!("CSV file is empty"))?;
+    let column = |names: &[&str]| {
+        header
+            .iter()
+            .position(|name| names.contains(&name.trim()))
+            .ok_or_else(|| anyhow!("CSV header is missing the {} column", names[0]))
+    };
+    let cert_id = column(&["cert_id"])?;
+    let metadata_hash = column(&["metadata_hash"])?;
+    let owner_address = column(&["owner_address", "owner"])?;
+
+    let field = |record: &[String], index: usize| {
+        record.get(index).map(|f| f.trim().to_string()).unwrap_or_default()
+    };
+    Ok(records
+        .map(|record| LegacyCertificate {
+            cert_id: field(&record, cert_id),
+            metadata_hash: field(&record, metadata_hash),
+            owner_address: field(&record, owner_address),
+        })
+        .collect())
+}
+
+/// Split RFC 4180 CSV into records, skipping blank lines
+fn csv_records(body: &str) -> Result<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = body.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => record.push(std::mem::take(&mut field)),
+            (false, '\r') => {}
+            (false, '\n') => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                } else {
+                    record.clear();
+                }
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(anyhow!("CSV file ends inside a quoted field"));
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.is_empty()) {
+        records.push(record);
+    }
+    Ok(records)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,554 @@
+use anyhow::{anyhow, Result};
+use sqlx::{
+    postgres::{PgConnection, PgPool, PgPoolOptions, PgRow},
+    types::Json,
+    FromRow, Row,
+};
+use chrono::{DateTime, Utc};
+use std::{collections::HashMap, time::Duration};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    models::{
+        CertId, CertificateHistoryEvent, CertificatePage, CertificateSummary, ContractEvent,
+        ExportedCertificate,
+    },
+    pagination::{CertificateCursor, CertificateSort},
+    soroban_client::SorobanClient,
+};
+
+/// Columns selected into a `CertificateSummary`
+const CERTIFICATE_COLUMNS: &str = "cert_id, owner, metadata_hash, status, disputed, brand, \
+    model, issued_ledger, issued_at, updated_ledger, updated_at";
+
+/// Conditions applied by a `CertificateFilter`
+///
+/// `$1` is the cursor's certificate ID and `$2` the page size; the filter's
+/// fields are bound from `$3` to `$9`.
+const CERTIFICATE_FILTER: &str = "($3::text IS NULL OR owner = $3)
+               AND ($4::text IS NULL OR status = $4)
+               AND ($5::text IS NULL OR lower(brand) = lower($5))
+               AND ($6::bigint IS NULL OR collection_id = $6)
+               AND ($7::timestamptz IS NULL OR issued_at >= $7)
+               AND ($8::timestamptz IS NULL OR issued_at < $8)
+               AND ($9::text IS NULL OR search_text @@ plainto_tsquery('simple', $9))";
+
+/// Statuses a certificate can have in the index
+pub const CERTIFICATE_STATUSES: &[&str] = &["active", "revoked", "burned"];
+
+/// Tunables for the chain event indexer
+#[derive(Debug, Clone)]
+pub struct IndexerSettings {
+    pub enabled: bool,
+    pub database_url: String,
+    pub max_connections: u32,
+    pub poll_interval_secs: u64,
+    /// Ledger to start from on first run; 0 starts at the latest ledger
+    pub start_ledger: u32,
+    pub page_limit: u32,
+}
+
+impl Default for IndexerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            database_url: String::new(),
+            max_connections: 5,
+            poll_interval_secs: 5,
+            start_ledger: 0,
+            page_limit: 100,
+        }
+    }
+}
+
+/// Optional filters applied when listing certificates
+#[derive(Debug, Clone, Default)]
+pub struct CertificateFilter {
+    pub owner: Option<String>,
+    pub status: Option<String>,
+    pub brand: Option<String>,
+    pub collection_id: Option<u32>,
+    pub issued_from: Option<DateTime<Utc>>,
+    pub issued_to: Option<DateTime<Utc>>,
+    pub text: Option<String>,
+}
+
+/// Background task mirroring contract events into Postgres
+///
+/// Certificates, their event history and transfer records are kept in the
+/// database together with the `getEvents` cursor, so the indexer picks up where
+/// it left off after a restart.
+#[derive(Clone)]
+pub struct Indexer {
+    client: SorobanClient,
+    settings: IndexerSettings,
+    pool: PgPool,
+}
+
+impl Indexer {
+    /// Connect to the database and apply migrations, if enabled
+    pub async fn connect(client: SorobanClient, settings: IndexerSettings) -> Result<Option<Self>> {
+        if !settings.enabled {
+            info!("Indexer disabled");
+            return Ok(None);
+        }
+        if settings.database_url.is_empty() {
+            return Err(anyhow!("DATABASE_URL is required when the indexer is enabled"));
+        }
+
+        let pool = PgPoolOptions::new()
+            .max_connections(settings.max_connections)
+            .connect(&settings.database_url)
+            .await
+            .map_err(|e| anyhow!("Failed to connect to database: {}", e))?;
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .map_err(|e| anyhow!("Failed to run database migrations: {}", e))?;
+
+        Ok(Some(Self {
+            client,
+            settings,
+            pool,
+        }))
+    }
+
+    /// Start the polling loop
+    pub fn spawn(&self) -> JoinHandle<()> {
+        let indexer = self.clone();
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(indexer.settings.poll_interval_secs));
+            loop {
+                interval.tick().await;
+                // Drain full pages straight away so a backlog is caught up quickly
+                loop {
+                    match indexer.sync().await {
+                        Ok(count) if count >= indexer.settings.page_limit as usize => continue,
+                        Ok(_) => break,
+                        Err(e) => {
+                            error!("Indexer sync failed: {}", e);
+                            break;
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    /// Page through indexed certificates matching `filter` in `sort` order,
+    /// starting after `cursor`
+    pub async fn list_certificates(
+        &self,
+        filter: &CertificateFilter,
+        sort: CertificateSort,
+        limit: u32,
+        cursor: Option<&CertificateCursor>,
+    ) -> Result<CertificatePage> {
+        // The sort key is bound to $10 only when the sort has one
+        let sql = format!(
+            "SELECT {}, {} AS sort_key FROM certificates
+             WHERE {} AND {}
+             ORDER BY {}
+             LIMIT $2",
+            CERTIFICATE_COLUMNS,
+            sort.key().unwrap_or("NULL::bigint"),
+            sort.after("$10"),
+            CERTIFICATE_FILTER,
+            sort.order_by()
+        );
+
+        // Fetch one extra row to learn whether another page follows
+        let mut query = sqlx::query(&sql)
+            .bind(cursor.map(|cursor| cursor.cert_id.as_str()))
+            .bind(i64::from(limit) + 1)
+            .bind(filter.owner.as_deref())
+            .bind(filter.status.as_deref())
+            .bind(filter.brand.as_deref())
+            .bind(filter.collection_id.map(i64::from))
+            .bind(filter.issued_from)
+            .bind(filter.issued_to)
+            .bind(filter.text.as_deref());
+        if sort.key().is_some() {
+            query = query.bind(cursor.and_then(|cursor| cursor.key));
+        }
+        let mut rows = query.fetch_all(&self.pool).await?;
+
+        let next_cursor = if rows.len() > limit as usize {
+            rows.truncate(limit as usize);
+            match rows.last() {
+                Some(row) => {
+                    let key: Option<i64> = row.try_get("sort_key")?;
+                    let cert_id: String = row.try_get("cert_id")?;
+                    Some(CertificateCursor::new(sort, key, &cert_id).encode())
+                }
+                None => None,
+            }
+        } else {
+            None
+        };
+
+        let certificates = rows
+            .iter()
+            .map(CertificateSummary::from_row)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(CertificatePage {
+            certificates,
+            next_cursor,
+        })
+    }
+
+    /// Read up to `limit` certificates matching `filter` after `cursor`, in ID
+    /// order, each with its event history
+    pub async fn export_certificates(
+        &self,
+        filter: &CertificateFilter,
+        limit: u32,
+        cursor: Option<&str>,
+    ) -> Result<Vec<ExportedCertificate>> {
+        let sql = format!(
+            "SELECT {}, serial_number, material, manufacture_year, collection_id
+             FROM certificates
+             WHERE ($1::text IS NULL OR cert_id > $1) AND {}
+             ORDER BY cert_id
+             LIMIT $2",
+            CERTIFICATE_COLUMNS, CERTIFICATE_FILTER
+        );
+
+        let mut certificates = sqlx::query_as::<_, ExportedCertificate>(&sql)
+            .bind(cursor)
+            .bind(i64::from(limit))
+            .bind(filter.owner.as_deref())
+            .bind(filter.status.as_deref())
+            .bind(filter.brand.as_deref())
+            .bind(filter.collection_id.map(i64::from))
+            .bind(filter.issued_from)
+            .bind(filter.issued_to)
+            .bind(filter.text.as_deref())
+            .fetch_all(&self.pool)
+            .await?;
+        if certificates.is_empty() {
+            return Ok(certificates);
+        }
+
+        let cert_ids: Vec<String> = certificates.iter().map(|c| c.cert_id.clone()).collect();
+        let rows = sqlx::query(
+            "SELECT cert_id, event, ledger, ledger_closed_at, transaction_hash, data
+             FROM certificate_events
+             WHERE cert_id = ANY($1)
+             ORDER BY ledger, id",
+        )
+        .bind(&cert_ids[..])
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut history: HashMap<String, Vec<CertificateHistoryEvent>> = HashMap::new();
+        for row in rows {
+            history
+                .entry(row.try_get("cert_id")?)
+                .or_default()
+                .push(history_event(&row)?);
+        }
+        for certificate in &mut certificates {
+            certificate.history = history.remove(&certificate.cert_id).unwrap_or_default();
+        }
+
+        Ok(certificates)
+    }
+
+    /// Look up a single indexed certificate
+    pub async fn get_certificate(&self, cert_id: &str) -> Result<Option<CertificateSummary>> {
+        let sql = format!("SELECT {} FROM certificates WHERE cert_id = $1", CERTIFICATE_COLUMNS);
+        Ok(sqlx::query_as::<_, CertificateSummary>(&sql)
+            .bind(cert_id)
+            .fetch_optional(&self.pool)
+            .await?)
+    }
+
+    /// Every event recorded for a certificate, oldest first
+    ///
+    /// Empty if the certificate was never indexed.
+    pub async fn certificate_history(&self, cert_id: &str) -> Result<Vec<CertificateHistoryEvent>> {
+        let rows = sqlx::query(
+            "SELECT event, ledger, ledger_closed_at, transaction_hash, data
+             FROM certificate_events
+             WHERE cert_id = $1
+             ORDER BY ledger, id",
+        )
+        .bind(cert_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(history_event).collect()
+    }
+
+    /// Overwrite an indexed certificate's status, and its owner and metadata
+    /// hash when given, with what the chain holds
+    pub async fn repair_certificate(
+        &self,
+        cert_id: &str,
+        status: &str,
+        owner: Option<&str>,
+        metadata_hash: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE certificates
+             SET status = $2, owner = COALESCE($3, owner),
+                 metadata_hash = COALESCE($4, metadata_hash)
+             WHERE cert_id = $1",
+        )
+        .bind(cert_id)
+        .bind(status)
+        .bind(owner)
+        .bind(metadata_hash)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Index one page of events, returning how many were stored
+    async fn sync(&self) -> Result<usize> {
+        let (cursor, ledger) = self.load_cursor().await?;
+        let start_ledger = match ledger {
+            Some(ledger) => ledger,
+            None if self.settings.start_ledger > 0 => self.settings.start_ledger,
+            None => self.client.get_latest_ledger().await?,
+        };
+
+        let page = self
+            .client
+            .get_events(start_ledger, cursor.as_deref(), self.settings.page_limit)
+            .await?;
+
+        let mut tx = self.pool.begin().await?;
+        let mut indexed = 0;
+        for event in &page.events {
+            if store_event(&self.client, &mut tx, event).await? {
+                indexed += 1;
+            }
+        }
+
+        let next_ledger = start_ledger.max(page.latest_ledger);
+        sqlx::query(
+            "INSERT INTO indexer_state (id, cursor, ledger) VALUES (1, $1, $2)
+             ON CONFLICT (id) DO UPDATE SET cursor = EXCLUDED.cursor, ledger = EXCLUDED.ledger",
+        )
+        .bind(page.cursor.or(cursor))
+        .bind(i64::from(next_ledger))
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        if indexed > 0 {
+            info!("Indexed {} contract events up to ledger {}", indexed, next_ledger);
+        } else {
+            debug!("No new contract events up to ledger {}", next_ledger);
+        }
+        Ok(page.events.len())
+    }
+
+    /// Saved `getEvents` cursor and the ledger it was taken at
+    async fn load_cursor(&self) -> Result<(Option<String>, Option<u32>)> {
+        let row = sqlx::query("SELECT cursor, ledger FROM indexer_state WHERE id = 1")
+            .fetch_optional(&self.pool)
+            .await?;
+
+        match row {
+            Some(row) => {
+                let cursor: Option<String> = row.try_get("cursor")?;
+                let ledger: i64 = row.try_get("ledger")?;
+                Ok((cursor, Some(ledger as u32)))
+            }
+            None => Ok((None, None)),
+        }
+    }
+}
+
+fn history_event(row: &PgRow) -> Result<CertificateHistoryEvent> {
+    let ledger: i64 = row.try_get("ledger")?;
+    let data: Json<serde_json::Value> = row.try_get("data")?;
+    Ok(CertificateHistoryEvent {
+        event: row.try_get("event")?,
+        ledger: u32::try_from(ledger)?,
+        ledger_closed_at: row.try_get("ledger_closed_at")?,
+        transaction_hash: row.try_get("transaction_hash")?,
+        data: data.0,
+    })
+}
+
+/// Record an event and apply it to the certificate it concerns
+///
+/// Returns `false` if the event was already indexed.
+async fn store_event(
+    client: &SorobanClient,
+    conn: &mut PgConnection,
+    event: &ContractEvent,
+) -> Result<bool> {
+    let inserted = sqlx::query(
+        "INSERT INTO certificate_events
+             (id, cert_id, event, ledger, ledger_closed_at, transaction_hash, data)
+         VALUES ($1, $2, $3, $4, NULLIF($5, '')::timestamptz, $6, $7)
+         ON CONFLICT (id) DO NOTHING",
+    )
+    .bind(&event.id)
+    .bind(&event.cert_id)
+    .bind(&event.event)
+    .bind(i64::from(event.ledger))
+    .bind(&event.ledger_closed_at)
+    .bind(&event.transaction_hash)
+    .bind(Json(&event.data))
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    if inserted == 0 {
+        return Ok(false);
+    }
+
+    let field = |name: &str| event.data.get(name).and_then(|v| v.as_str()).unwrap_or_default();
+    let ledger = i64::from(event.ledger);
+
+    match event.event.as_str() {
+        "issued" => {
+            sqlx::query(
+                "INSERT INTO certificates
+                     (cert_id, owner, metadata_hash, status, disputed,
+                      issued_ledger, issued_at, updated_ledger, updated_at)
+                 VALUES ($1, $2, $3, 'active', FALSE, $4, NULLIF($5, '')::timestamptz, $4,
+                         NULLIF($5, '')::timestamptz)
+                 ON CONFLICT (cert_id) DO UPDATE SET
+                     owner = EXCLUDED.owner,
+                     metadata_hash = EXCLUDED.metadata_hash,
+                     status = 'active',
+                     disputed = FALSE,
+                     issued_ledger = EXCLUDED.issued_ledger,
+                     issued_at = EXCLUDED.issued_at,
+                     updated_ledger = EXCLUDED.updated_ledger,
+                     updated_at = EXCLUDED.updated_at,
+                     brand = NULL,
+                     model = NULL,
+                     serial_number = NULL,
+                     material = NULL,
+                     manufacture_year = NULL,
+                     collection_id = NULL",
+            )
+            .bind(&event.cert_id)
+            .bind(field("owner"))
+            .bind(field("metadata_hash"))
+            .bind(ledger)
+            .bind(&event.ledger_closed_at)
+            .execute(&mut *conn)
+            .await?;
+
+            // Collection membership is fixed at issuance but isn't part of the event
+            let collection = match CertId::parse(&event.cert_id) {
+                Ok(cert_id) => client.get_certificate_collection(&cert_id).await,
+                Err(e) => Err(anyhow!("{}", e)),
+            };
+            match collection {
+                Ok(Some((collection_id, brand))) => {
+                    sqlx::query(
+                        "UPDATE certificates
+                         SET collection_id = $2, brand = COALESCE(brand, $3)
+                         WHERE cert_id = $1",
+                    )
+                    .bind(&event.cert_id)
+                    .bind(i64::from(collection_id))
+                    .bind(brand)
+                    .execute(&mut *conn)
+                    .await?;
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Failed to look up collection of {}: {}", event.cert_id, e),
+            }
+        }
+        "attrs" => {
+            sqlx::query(
+                "UPDATE certificates
+                 SET brand = $2, model = $3, serial_number = $4, material = $5,
+                     manufacture_year = $6, updated_ledger = $7,
+                     updated_at = NULLIF($8, '')::timestamptz
+                 WHERE cert_id = $1",
+            )
+            .bind(&event.cert_id)
+            .bind(field("brand"))
+            .bind(field("model"))
+            .bind(field("serial_number"))
+            .bind(field("material"))
+            .bind(
+                event.data.get("manufacture_year")
+                    .and_then(|v| v.as_i64())
+                    .map(|year| year as i32),
+            )
+            .bind(ledger)
+            .bind(&event.ledger_closed_at)
+            .execute(&mut *conn)
+            .await?;
+        }
+        "transfer" => {
+            sqlx::query(
+                "INSERT INTO transfers
+                     (event_id, cert_id, from_owner, to_owner, ledger, ledger_closed_at, transaction_hash)
+                 VALUES ($1, $2, $3, $4, $5, NULLIF($6, '')::timestamptz, $7)",
+            )
+            .bind(&event.id)
+            .bind(&event.cert_id)
+            .bind(field("from"))
+            .bind(field("to"))
+            .bind(ledger)
+            .bind(&event.ledger_closed_at)
+            .bind(&event.transaction_hash)
+            .execute(&mut *conn)
+            .await?;
+
+            update_certificate(
+                conn,
+                event,
+                "owner = $4, last_transfer_ledger = $2",
+                Some(field("to")),
+            )
+            .await?;
+        }
+        "revoked" => update_certificate(conn, event, "status = 'revoked'", None).await?,
+        "burned" => update_certificate(conn, event, "status = 'burned'", None).await?,
+        "disputed" => update_certificate(conn, event, "disputed = TRUE", None).await?,
+        other => warn!("Indexed unrecognised contract event {}", other),
+    }
+
+    Ok(true)
+}
+
+/// Apply `assignment` to an indexed certificate and bump its update ledger
+async fn update_certificate(
+    conn: &mut PgConnection,
+    event: &ContractEvent,
+    assignment: &str,
+    value: Option<&str>,
+) -> Result<()> {
+    let sql = format!(
+        "UPDATE certificates
+         SET {}, updated_ledger = $2, updated_at = NULLIF($3, '')::timestamptz
+         WHERE cert_id = $1",
+        assignment
+    );
+
+    let mut query = sqlx::query(&sql)
+        .bind(&event.cert_id)
+        .bind(i64::from(event.ledger))
+        .bind(&event.ledger_closed_at);
+    if let Some(value) = value {
+        query = query.bind(value);
+    }
+
+    let updated = query.execute(&mut *conn).await?.rows_affected();
+    if updated == 0 {
+        warn!(
+            "{} event {} for certificate {} that hasn't been indexed",
+            event.event, event.id, event.cert_id
+        );
+    }
+    Ok(())
+}
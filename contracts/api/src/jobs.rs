@@ -0,0 +1,279 @@
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+    sync::{mpsc, Mutex, RwLock},
+    task::JoinHandle,
+};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+use crate::{
+    models::{CertId, MetadataHash, StellarAddress},
+    soroban_client::{SorobanClient, SubmitSettings, TransactionOutcome},
+};
+
+/// Tunables for the background submission queue
+#[derive(Debug, Clone)]
+pub struct JobSettings {
+    pub enabled: bool,
+    pub capacity: usize,
+    pub max_attempts: u32,
+    pub retry_delay_ms: u64,
+}
+
+impl Default for JobSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            capacity: 1000,
+            max_attempts: 3,
+            retry_delay_ms: 2000,
+        }
+    }
+}
+
+/// A contract write waiting to be submitted
+#[derive(Debug, Clone)]
+pub enum JobOperation {
+    Init {
+        admin_address: StellarAddress,
+    },
+    Issue {
+        cert_id: CertId,
+        metadata_hash: MetadataHash,
+        owner_address: StellarAddress,
+    },
+    /// A transaction already signed by the client, e.g. a prepared transfer
+    Submit {
+        transaction: String,
+    },
+    Revoke {
+        cert_id: CertId,
+    },
+}
+
+impl JobOperation {
+    fn kind(&self) -> &'static str {
+        match self {
+            JobOperation::Init { .. } => "init",
+            JobOperation::Issue { .. } => "issue",
+            JobOperation::Submit { .. } => "submit",
+            JobOperation::Revoke { .. } => "revoke",
+        }
+    }
+
+    fn cert_id(&self) -> Option<String> {
+        match self {
+            JobOperation::Init { .. } | JobOperation::Submit { .. } => None,
+            JobOperation::Issue { cert_id, .. } | JobOperation::Revoke { cert_id } => {
+                Some(cert_id.to_string())
+            }
+        }
+    }
+}
+
+/// Lifecycle of a queued job
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Submitting,
+    Submitted,
+    Confirmed,
+    Failed,
+}
+
+/// Public view of a queued job
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Job {
+    pub id: String,
+    pub operation: String,
+    pub cert_id: Option<String>,
+    pub status: JobStatus,
+    pub attempts: u32,
+    pub transaction_hash: Option<String>,
+    pub ledger: Option<u32>,
+    pub error: Option<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+/// Background queue that signs, submits and confirms contract writes
+///
+/// Jobs are kept in memory, so their status is lost when the process restarts.
+#[derive(Clone)]
+pub struct JobQueue {
+    client: SorobanClient,
+    settings: JobSettings,
+    submit: SubmitSettings,
+    jobs: Arc<RwLock<HashMap<String, Job>>>,
+    sender: mpsc::Sender<(String, JobOperation)>,
+    receiver: Arc<Mutex<Option<mpsc::Receiver<(String, JobOperation)>>>>,
+}
+
+impl JobQueue {
+    pub fn new(client: SorobanClient, settings: JobSettings, submit: SubmitSettings) -> Self {
+        let (sender, receiver) = mpsc::channel(settings.capacity);
+        Self {
+            client,
+            settings,
+            submit,
+            jobs: Arc::new(RwLock::new(HashMap::new())),
+            sender,
+            receiver: Arc::new(Mutex::new(Some(receiver))),
+        }
+    }
+
+    /// Whether write endpoints should enqueue instead of submitting inline
+    pub fn is_enabled(&self) -> bool {
+        self.settings.enabled
+    }
+
+    /// Start the worker draining the queue, if enabled
+    pub async fn spawn(&self) -> Option<JoinHandle<()>> {
+        if !self.settings.enabled {
+            info!("Job queue disabled");
+            return None;
+        }
+
+        let mut receiver = self.receiver.lock().await.take()?;
+        let queue = self.clone();
+        Some(tokio::spawn(async move {
+            while let Some((id, operation)) = receiver.recv().await {
+                queue.process(&id, operation).await;
+            }
+        }))
+    }
+
+    /// Record a new job and hand it to the worker
+    pub async fn enqueue(&self, operation: JobOperation) -> Result<Job> {
+        let now = now_secs();
+        let job = Job {
+            id: uuid::Uuid::new_v4().to_string(),
+            operation: operation.kind().to_string(),
+            cert_id: operation.cert_id(),
+            status: JobStatus::Queued,
+            attempts: 0,
+            transaction_hash: None,
+            ledger: None,
+            error: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        self.jobs.write().await.insert(job.id.clone(), job.clone());
+        if let Err(e) = self.sender.send((job.id.clone(), operation)).await {
+            self.jobs.write().await.remove(&job.id);
+            return Err(anyhow!("Job queue is not accepting jobs: {}", e));
+        }
+
+        info!("Queued {} job {}", job.operation, job.id);
+        Ok(job)
+    }
+
+    /// Look up a job by ID
+    pub async fn get(&self, id: &str) -> Option<Job> {
+        self.jobs.read().await.get(id).cloned()
+    }
+
+    /// Submit a job, retrying failed submissions, then wait for its outcome
+    async fn process(&self, id: &str, operation: JobOperation) {
+        let mut attempt = 0;
+        let tx_hash = loop {
+            attempt += 1;
+            self.update(id, |job| {
+                job.status = JobStatus::Submitting;
+                job.attempts = attempt;
+            })
+            .await;
+
+            match self.submit(&operation).await {
+                Ok(tx_hash) => break tx_hash,
+                Err(e) if attempt < self.settings.max_attempts => {
+                    warn!("Job {} attempt {} failed, retrying: {}", id, attempt, e);
+                    self.update(id, |job| job.error = Some(e.to_string())).await;
+                    let delay = self.settings.retry_delay_ms * u64::from(attempt);
+                    tokio::time::sleep(Duration::from_millis(delay)).await;
+                }
+                Err(e) => {
+                    error!("Job {} failed after {} attempts: {}", id, attempt, e);
+                    self.update(id, |job| {
+                        job.status = JobStatus::Failed;
+                        job.error = Some(e.to_string());
+                    })
+                    .await;
+                    return;
+                }
+            }
+        };
+
+        self.update(id, |job| {
+            job.status = JobStatus::Submitted;
+            job.transaction_hash = Some(tx_hash.clone());
+            job.error = None;
+        })
+        .await;
+
+        let outcome = self
+            .client
+            .wait_for_transaction(
+                &tx_hash,
+                Duration::from_secs(self.submit.wait_timeout_secs),
+                Duration::from_millis(self.submit.poll_interval_ms),
+            )
+            .await;
+
+        self.update(id, |job| match outcome {
+            Ok(TransactionOutcome::Success { ledger, .. }) => {
+                job.status = JobStatus::Confirmed;
+                job.ledger = Some(ledger);
+            }
+            Ok(TransactionOutcome::Failed { ledger, summary }) => {
+                job.status = JobStatus::Failed;
+                job.ledger = Some(ledger);
+                job.error = Some(summary.failure_message(&tx_hash, ledger));
+            }
+            // Leave the job as submitted; the transaction may still land later
+            Err(e) => job.error = Some(e.to_string()),
+        })
+        .await;
+    }
+
+    async fn submit(&self, operation: &JobOperation) -> Result<String> {
+        match operation {
+            JobOperation::Init { admin_address } => self.client.init(admin_address).await,
+            JobOperation::Issue {
+                cert_id,
+                metadata_hash,
+                owner_address,
+            } => {
+                self.client
+                    .issue_certificate(cert_id, metadata_hash, owner_address)
+                    .await
+            }
+            JobOperation::Submit { transaction } => {
+                self.client.submit_signed_transaction(transaction).await
+            }
+            JobOperation::Revoke { cert_id } => self.client.revoke_certificate(cert_id).await,
+        }
+    }
+
+    async fn update(&self, id: &str, change: impl FnOnce(&mut Job)) {
+        if let Some(job) = self.jobs.write().await.get_mut(id) {
+            change(job);
+            job.updated_at = now_secs();
+        }
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,360 @@
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::time::{Duration, Instant};
+use stellar_strkey::ed25519;
+use tokio::sync::RwLock;
+
+use crate::signer::{verify_signature, SignFuture, Signer, SignerSettings};
+
+/// GCE/GKE metadata endpoint handing out access tokens for the attached service account
+const GCP_METADATA_TOKEN_URL: &str =
+    "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+
+/// Refresh metadata tokens this long before they expire
+const GCP_TOKEN_MARGIN: Duration = Duration::from_secs(60);
+
+/// Signs with an Ed25519 key held in AWS KMS
+///
+/// Requests are authenticated with Signature Version 4 using the standard
+/// `AWS_ACCESS_KEY_ID`/`AWS_SECRET_ACCESS_KEY`/`AWS_SESSION_TOKEN` credentials.
+/// The key must have the `ECC_NIST_EDWARDS25519` spec.
+pub struct AwsKmsSigner {
+    key_id: String,
+    region: String,
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: String,
+    public_key: [u8; 32],
+    http_client: Client,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AwsSignResponse {
+    signature: String,
+}
+
+impl AwsKmsSigner {
+    pub fn new(settings: &SignerSettings) -> Result<Self> {
+        if settings.kms_key_id.is_empty() {
+            return Err(anyhow!("SIGNER_KMS_KEY_ID is required for the aws-kms signer"));
+        }
+        if settings.aws_region.is_empty() {
+            return Err(anyhow!("AWS_REGION is required for the aws-kms signer"));
+        }
+        if settings.aws_access_key_id.is_empty() || settings.aws_secret_access_key.is_empty() {
+            return Err(anyhow!(
+                "AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY are required for the aws-kms signer"
+            ));
+        }
+
+        Ok(Self {
+            key_id: settings.kms_key_id.clone(),
+            region: settings.aws_region.clone(),
+            access_key_id: settings.aws_access_key_id.clone(),
+            secret_access_key: settings.aws_secret_access_key.clone(),
+            session_token: settings.aws_session_token.clone(),
+            public_key: signer_public_key(settings)?,
+            http_client: http_client(settings)?,
+        })
+    }
+
+    async fn request_signature(&self, hash: &[u8; 32]) -> Result<[u8; 64]> {
+        let host = format!("kms.{}.amazonaws.com", self.region);
+        let body = json!({
+            "KeyId": self.key_id,
+            "Message": BASE64.encode(hash),
+            "MessageType": "RAW",
+            "SigningAlgorithm": "ED25519_SHA_512",
+        })
+        .to_string();
+
+        let amz_date = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let mut headers = vec![
+            ("content-type", "application/x-amz-json-1.1".to_string()),
+            ("host", host.clone()),
+            ("x-amz-date", amz_date.clone()),
+            ("x-amz-target", "TrentService.Sign".to_string()),
+        ];
+        if !self.session_token.is_empty() {
+            headers.push(("x-amz-security-token", self.session_token.clone()));
+        }
+        headers.sort();
+
+        let authorization = sigv4_authorization(
+            &SigV4Request {
+                method: "POST",
+                path: "/",
+                query: "",
+                headers: &headers,
+                body: body.as_bytes(),
+            },
+            &SigV4Credentials {
+                access_key_id: &self.access_key_id,
+                secret_access_key: &self.secret_access_key,
+                region: &self.region,
+                service: "kms",
+            },
+            &amz_date,
+        )?;
+
+        let mut request = self
+            .http_client
+            .post(&format!("https://{}/", host))
+            .header("Authorization", authorization);
+        for (name, value) in headers.iter().filter(|(name, _)| *name != "host") {
+            request = request.header(name, value);
+        }
+
+        let response = request
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| anyhow!("AWS KMS request failed: {}", e))?;
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let detail = response.text().await.unwrap_or_default();
+            return Err(anyhow!("AWS KMS responded with {}: {}", status, detail));
+        }
+        let response: AwsSignResponse = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Invalid AWS KMS response: {}", e))?;
+
+        decode_signature(&self.public_key, hash, &response.signature)
+    }
+}
+
+impl Signer for AwsKmsSigner {
+    fn public_key(&self) -> [u8; 32] {
+        self.public_key
+    }
+
+    fn sign<'a>(&'a self, hash: &'a [u8; 32]) -> SignFuture<'a> {
+        Box::pin(self.request_signature(hash))
+    }
+}
+
+/// Signs with an Ed25519 key version held in Google Cloud KMS
+///
+/// `SIGNER_KMS_KEY_ID` is the full key version name
+/// (`projects/.../cryptoKeyVersions/N`) of an `EC_SIGN_ED25519` key. A static
+/// `SIGNER_REMOTE_TOKEN` is used as the access token when set; otherwise
+/// tokens are fetched from the metadata server of the instance.
+pub struct GcpKmsSigner {
+    key_version: String,
+    static_token: String,
+    public_key: [u8; 32],
+    http_client: Client,
+    token: RwLock<Option<(String, Instant)>>,
+}
+
+#[derive(Deserialize)]
+struct GcpSignResponse {
+    signature: String,
+}
+
+#[derive(Deserialize)]
+struct GcpTokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+impl GcpKmsSigner {
+    pub fn new(settings: &SignerSettings) -> Result<Self> {
+        if settings.kms_key_id.is_empty() {
+            return Err(anyhow!("SIGNER_KMS_KEY_ID is required for the gcp-kms signer"));
+        }
+
+        Ok(Self {
+            key_version: settings.kms_key_id.clone(),
+            static_token: settings.remote_token.clone(),
+            public_key: signer_public_key(settings)?,
+            http_client: http_client(settings)?,
+            token: RwLock::new(None),
+        })
+    }
+
+    async fn request_signature(&self, hash: &[u8; 32]) -> Result<[u8; 64]> {
+        let token = self.access_token().await?;
+        let url = format!(
+            "https://cloudkms.googleapis.com/v1/{}:asymmetricSign",
+            self.key_version
+        );
+
+        let response = self
+            .http_client
+            .post(&url)
+            .bearer_auth(&token)
+            .json(&json!({ "data": BASE64.encode(hash) }))
+            .send()
+            .await
+            .map_err(|e| anyhow!("Cloud KMS request failed: {}", e))?;
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let detail = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Cloud KMS responded with {}: {}", status, detail));
+        }
+        let response: GcpSignResponse = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Invalid Cloud KMS response: {}", e))?;
+
+        decode_signature(&self.public_key, hash, &response.signature)
+    }
+
+    /// The configured token, or a cached metadata server token
+    async fn access_token(&self) -> Result<String> {
+        if !self.static_token.is_empty() {
+            return Ok(self.static_token.clone());
+        }
+
+        if let Some((token, expires_at)) = &*self.token.read().await {
+            if Instant::now() + GCP_TOKEN_MARGIN < *expires_at {
+                return Ok(token.clone());
+            }
+        }
+
+        let response = self
+            .http_client
+            .get(GCP_METADATA_TOKEN_URL)
+            .header("Metadata-Flavor", "Google")
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to reach the GCP metadata server: {}", e))?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "GCP metadata server responded with {}",
+                response.status().as_u16()
+            ));
+        }
+        let response: GcpTokenResponse = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Invalid GCP metadata token response: {}", e))?;
+
+        let expires_at = Instant::now() + Duration::from_secs(response.expires_in);
+        *self.token.write().await = Some((response.access_token.clone(), expires_at));
+        Ok(response.access_token)
+    }
+}
+
+impl Signer for GcpKmsSigner {
+    fn public_key(&self) -> [u8; 32] {
+        self.public_key
+    }
+
+    fn sign<'a>(&'a self, hash: &'a [u8; 32]) -> SignFuture<'a> {
+        Box::pin(self.request_signature(hash))
+    }
+}
+
+/// The request parts covered by a Signature Version 4 signature
+pub struct SigV4Request<'a> {
+    pub method: &'a str,
+    pub path: &'a str,
+    pub query: &'a str,
+    /// Lower-case header names, sorted, including `host` and `x-amz-date`
+    pub headers: &'a [(&'a str, String)],
+    pub body: &'a [u8],
+}
+
+pub struct SigV4Credentials<'a> {
+    pub access_key_id: &'a str,
+    pub secret_access_key: &'a str,
+    pub region: &'a str,
+    pub service: &'a str,
+}
+
+/// `Authorization` header value for an AWS Signature Version 4 request
+pub fn sigv4_authorization(
+    request: &SigV4Request,
+    credentials: &SigV4Credentials,
+    amz_date: &str,
+) -> Result<String> {
+    let date = amz_date
+        .get(..8)
+        .ok_or_else(|| anyhow!("Invalid x-amz-date: {}", amz_date))?;
+
+    let canonical_headers: String = request
+        .headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+        .collect();
+    let signed_headers = request
+        .headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        request.method,
+        request.path,
+        request.query,
+        canonical_headers,
+        signed_headers,
+        hex::encode(Sha256::digest(request.body)),
+    );
+
+    let scope = format!(
+        "{}/{}/{}/aws4_request",
+        date, credentials.region, credentials.service
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes())),
+    );
+
+    let secret = format!("AWS4{}", credentials.secret_access_key);
+    let key = hmac_sha256(secret.as_bytes(), date.as_bytes())?;
+    let key = hmac_sha256(&key, credentials.region.as_bytes())?;
+    let key = hmac_sha256(&key, credentials.service.as_bytes())?;
+    let key = hmac_sha256(&key, b"aws4_request")?;
+    let signature = hex::encode(hmac_sha256(&key, string_to_sign.as_bytes())?);
+
+    Ok(format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        credentials.access_key_id, scope, signed_headers, signature
+    ))
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key)
+        .map_err(|e| anyhow!("Invalid HMAC key: {}", e))?;
+    mac.update(data);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+/// Decode a base64 signature returned by a KMS and check it against the public key
+fn decode_signature(public_key: &[u8; 32], hash: &[u8; 32], signature: &str) -> Result<[u8; 64]> {
+    let signature: [u8; 64] = BASE64
+        .decode(signature)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| anyhow!("KMS returned a malformed Ed25519 signature"))?;
+    verify_signature(public_key, hash, &signature)?;
+    Ok(signature)
+}
+
+/// The G... account a KMS key signs for, from `SIGNER_PUBLIC_KEY`
+fn signer_public_key(settings: &SignerSettings) -> Result<[u8; 32]> {
+    ed25519::PublicKey::from_string(&settings.public_key)
+        .map(|key| key.0)
+        .map_err(|_| anyhow!("SIGNER_PUBLIC_KEY must be the G... account of the KMS key"))
+}
+
+fn http_client(settings: &SignerSettings) -> Result<Client> {
+    Client::builder()
+        .timeout(Duration::from_secs(settings.timeout_secs))
+        .build()
+        .map_err(|e| anyhow!("Failed to build signer HTTP client: {}", e))
+}
@@ -1,5 +1,48 @@
+pub mod audit;
+pub mod auth;
+pub mod cache;
+pub mod channels;
+pub mod circuit;
 pub mod config;
+pub mod contract_client;
+pub mod cors;
+pub mod devtools;
+pub mod export;
+pub mod federation;
+pub mod fields;
+pub mod fees;
+pub mod friendbot;
+pub mod grpc;
+pub mod health;
+pub mod horizon;
+pub mod i18n;
 pub mod handlers;
+pub mod import;
+pub mod indexer;
+pub mod jobs;
+pub mod kms;
+pub mod mock_client;
 pub mod models;
+pub mod networks;
+pub mod notifications;
+pub mod pagination;
+pub mod policy;
+pub mod qr;
+pub mod reconcile;
+pub mod reload;
+pub mod replay;
+pub mod rent;
 pub mod routes;
-pub mod soroban_client;
\ No newline at end of file
+pub mod sandbox;
+pub mod secrets;
+pub mod server;
+pub mod sep10;
+pub mod sep5;
+pub mod signer;
+pub mod soroban_client;
+pub mod telemetry;
+pub mod tenants;
+pub mod tls;
+pub mod usage;
+pub mod validation;
+pub mod webhooks;
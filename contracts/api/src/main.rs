@@ -1,17 +1,105 @@
+mod audit;
+mod auth;
+mod cache;
+mod channels;
+mod circuit;
 mod config;
+mod contract_client;
+mod cors;
+mod devtools;
+mod export;
+mod federation;
+mod fields;
+mod fees;
+mod friendbot;
+mod grpc;
+mod health;
+mod horizon;
+mod i18n;
 mod handlers;
+mod import;
+mod indexer;
+mod jobs;
+mod kms;
 mod models;
+mod networks;
+mod notifications;
+mod pagination;
+mod policy;
+mod qr;
+mod reconcile;
+mod reload;
+mod replay;
+mod rent;
 mod routes;
+mod sandbox;
+mod secrets;
+mod server;
+mod sep10;
+mod sep5;
+mod signer;
 mod soroban_client;
+mod telemetry;
+mod tenants;
+mod tls;
+mod usage;
+mod validation;
+mod webhooks;
 
 use anyhow::Result;
-use config::Config;
+use audit::AuditLog;
+use clap::Parser;
+use auth::Authenticator;
+use cache::ResponseCache;
+use channels::ChannelPool;
+use config::{render_settings, Config, ConfigOptions};
+use cors::CorsOrigins;
+use federation::Federation;
+use friendbot::Friendbot;
+use health::HealthChecker;
+use horizon::Horizon;
+use import::ImportManager;
 use handlers::AppState;
+use indexer::Indexer;
+use notifications::Notifier;
+use reconcile::Reconciler;
+use reload::ConfigReloader;
+use replay::ReplayGuard;
+use jobs::JobQueue;
+use networks::Networks;
+use qr::QrCodec;
+use rent::RentManager;
 use routes::create_router;
+use secrets::SecretStore;
+use sep10::WebAuth;
+use signer::{load_signer, LocalSigner, Signer};
+use std::{path::PathBuf, sync::Arc};
 use soroban_client::SorobanClient;
-use tokio::net::TcpListener;
-use tracing::{info, level_filters::LevelFilter};
-use tracing_subscriber::{EnvFilter, FmtSubscriber};
+use telemetry::{init_tracing, shutdown_tracing};
+use tenants::Tenants;
+use tracing::info;
+use webhooks::WebhookDispatcher;
+
+/// VeriLuxe certificate API
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Cli {
+    /// Run against a local standalone network, deploying the contract if needed
+    #[arg(long)]
+    sandbox: bool,
+
+    /// TOML settings file [default: config.toml, if present]
+    #[arg(long, env = "CONFIG_FILE")]
+    config: Option<PathBuf>,
+
+    /// Profile of the settings file to apply, e.g. `testnet` or `mainnet`
+    #[arg(long, env = "CONFIG_PROFILE")]
+    profile: Option<String>,
+
+    /// Print the resolved settings, with secrets redacted, and exit
+    #[arg(long)]
+    print_config: bool,
+}
 
 /// Opens the Swagger UI URL in the default browser
 fn open_browser(url: &str) -> Result<()> {
@@ -41,40 +129,232 @@ fn open_browser(url: &str) -> Result<()> {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize tracing
-    let subscriber = FmtSubscriber::builder()
-        .with_env_filter(
-            EnvFilter::builder()
-                .with_default_directive(LevelFilter::INFO.into())
-                .from_env_lossy(),
-        )
-        .finish();
+    let cli = Cli::parse();
 
-    tracing::subscriber::set_global_default(subscriber)
-        .expect("Failed to set subscriber");
+    // Load configuration, with secrets fetched from the secrets provider when one is configured
+    let mut options = ConfigOptions {
+        file: cli.config,
+        profile: cli.profile,
+        sandbox: cli.sandbox,
+        contract_optional: false,
+        secrets: SecretStore::default(),
+    };
+    options.secrets = SecretStore::connect(Config::secrets_settings(&options)?).await?;
+    let (mut config, settings) = Config::load(&options)?;
+    if cli.print_config {
+        print!("{}", render_settings(&settings));
+        return Ok(());
+    }
 
-    // Load configuration
-    let config = Config::from_env()?;
+    // Initialize tracing, exporting spans when an OTLP endpoint is configured
+    init_tracing(&config.telemetry)?;
     info!("Loaded configuration successfully");
+    if options.secrets.provider() != "none" {
+        info!("Read secrets from the {} secrets provider", options.secrets.provider());
+    }
+
+    // Load the admin signer
+    let signer = load_signer(&config.signer)?;
+    info!("Using {} signer for admin account {}", config.signer.kind, signer.address());
+
+    // Sandbox mode funds the admin and deploys the contract on a local network
+    if config.sandbox.enabled {
+        info!("Sandbox mode: using local network at {}", config.sandbox.rpc_url);
+        config.fashion_auth_contract_id = sandbox::prepare(
+            &config.sandbox,
+            &config.fashion_auth_contract_id,
+            signer.clone(),
+            config.http.build_client()?,
+        )
+        .await?;
+    }
+
+    // Cache certificate reads, shared with other instances when Redis is configured
+    let cache = ResponseCache::connect(config.cache.clone(), &config.fashion_auth_contract_id).await?;
+
+    // Fee bumps are paid by the sponsor account when one is configured
+    let sponsor: Option<Arc<dyn Signer>> = if config.fees.sponsor_secret_key.is_empty() {
+        None
+    } else {
+        Some(Arc::new(LocalSigner::from_secret(&config.fees.sponsor_secret_key)?))
+    };
 
     // Initialize Soroban client
-    let soroban_client = SorobanClient::new(
+    let mut soroban_client = SorobanClient::new(
         config.soroban_rpc_url.clone(),
         config.soroban_network_passphrase.clone(),
         config.fashion_auth_contract_id.clone(),
-        config.admin_secret_key.clone(),
-    )?;
+        signer,
+    )
+    .with_cache(cache)
+    .with_http_client(config.http.build_client()?)
+    .with_retry(config.retry.clone())
+    .with_circuit_breaker(config.circuit.clone())
+    .with_fees(config.fees.clone(), sponsor);
+    if let Some(channels) = ChannelPool::load(&config.channels)? {
+        soroban_client = soroban_client.with_channels(channels);
+    }
     info!("Initialized Soroban client");
 
+    // Additional network profiles selectable per request with X-Network
+    let networks = Networks::connect(soroban_client.clone(), &config.networks, &config.cache).await?;
+    info!("Serving networks: {}", networks.names().join(", "));
+
+    // Brands served as tenants, each with its own contract and issuer key
+    let tenants = Tenants::connect(
+        &config.tenants,
+        &networks,
+        &config.signer,
+        &options.secrets,
+        &config.cache,
+    )
+    .await?;
+    if !tenants.is_empty() {
+        info!("Serving {} tenants", tenants.len());
+    }
+
+    // Initialize peer registries for federated verification
+    let federation = Federation::new(&config.federation_peers, &config.http)?;
+    info!("Configured {} peer registries", federation.peer_names().len());
+
+    // Dependency checks behind /health/ready, and a check of the contract before serving
+    let health = HealthChecker::new(soroban_client.clone(), config.health.clone());
+    health.startup().await?;
+
+    // Start the rent funding manager
+    let rent_manager = RentManager::new(soroban_client.clone(), config.rent.clone());
+    rent_manager.spawn();
+
+    // Start the background submission queue
+    let job_queue = JobQueue::new(soroban_client.clone(), config.jobs.clone(), config.submit.clone());
+    job_queue.spawn().await;
+
+    // Bulk imports of legacy certificates
+    let imports = ImportManager::new(config.import.clone(), config.submit.clone());
+
+    // Start delivering lifecycle events to registered webhooks
+    let webhooks = WebhookDispatcher::new(soroban_client.clone(), config.webhooks.clone());
+    webhooks.spawn();
+
+    // Start mirroring contract events into the database
+    let indexer = Indexer::connect(soroban_client.clone(), config.indexer.clone()).await?;
+    if let Some(indexer) = &indexer {
+        indexer.spawn();
+    }
+
+    // Periodically check the index against the chain
+    let reconciler = Reconciler::new(Arc::new(soroban_client.clone()), indexer.as_ref(), config.reconcile.clone())?;
+    if let Some(reconciler) = &reconciler {
+        reconciler.spawn();
+    }
+
+    // Append-only record of API-initiated operations
+    let audit = AuditLog::connect(&config.audit).await?;
+
+    // Email and SMS notifications for certificate owners
+    let notifier = Notifier::connect(
+        soroban_client.clone(),
+        config.notifications.clone(),
+        config.http.build_client()?,
+    )
+    .await?;
+    if let Some(notifier) = &notifier {
+        notifier.spawn();
+    }
+
+    // Bearer-token authentication for protected routes
+    let auth = Authenticator::new(&config.auth)?;
+    if !config.auth.enabled {
+        info!("Authentication disabled; all routes are open");
+    }
+
+    // SEP-10 sign-in for certificate owners
+    let web_auth = WebAuth::new(
+        config.sep10.clone(),
+        config.soroban_network_passphrase.clone(),
+        soroban_client.signer(),
+        auth.clone(),
+    )?;
+
+    // Signed QR payloads for consumer verification
+    let qr = QrCodec::new(soroban_client.signer(), config.qr.clone());
+
+    // Account funding for developers, on networks with a friendbot
+    let friendbot = Friendbot::for_network(
+        &config.soroban_network_passphrase,
+        &config.sandbox,
+        config.http.build_client()?,
+    );
+
+    // Account lookups on the default network's Horizon
+    let horizon = Horizon::new(&config.horizon, config.http.build_client()?);
+
+    // Origins allowed to call the API from a browser
+    let cors = CorsOrigins::new(&config.cors);
+
+    // Apply changes to tunable settings on SIGHUP and POST /admin/config/reload
+    let reloader = ConfigReloader::new(
+        options,
+        settings,
+        soroban_client.clone(),
+        webhooks.clone(),
+        cors.clone(),
+    );
+    #[cfg(unix)]
+    reloader.spawn_on_hangup()?;
+    reloader.spawn_secret_refresh();
+
+    // Refuse replayed transfer and revoke requests, sharing nonces through Redis when configured
+    let replay =
+        ReplayGuard::connect(&config.replay, &config.cache.redis_url, &config.fashion_auth_contract_id)
+            .await?;
+
     // Create application state
-    let app_state = AppState { soroban_client };
+    let app_state = AppState {
+        soroban_client,
+        networks,
+        tenants,
+        federation,
+        rent_manager,
+        health,
+        submit: config.submit.clone(),
+        job_queue,
+        imports,
+        webhooks,
+        indexer,
+        reconciler,
+        audit,
+        notifier,
+        auth,
+        web_auth,
+        qr,
+        friendbot,
+        dev_tools: config.sandbox.dev_tools_enabled(),
+        horizon,
+        cors,
+        reloader: Some(reloader),
+        replay,
+        limits: config.limits.clone(),
+        server: config.server.clone(),
+    };
+
+    // gRPC API for partner integrations, sharing the REST API's state
+    if config.grpc.enabled {
+        let state = app_state.clone();
+        let host = config.api_host.clone();
+        let (grpc, tls) = (config.grpc.clone(), config.tls.clone());
+        tokio::spawn(async move {
+            if let Err(e) = grpc::serve(state, &host, &grpc, &tls).await {
+                tracing::error!("{}", e);
+            }
+        });
+    }
 
     // Create router
     let app = create_router(app_state);
 
-    // Create listener
-    let listener = TcpListener::bind(&config.api_address()).await?;
-    let server_url = format!("http://{}", config.api_address());
+    let scheme = if config.tls.enabled { "https" } else { "http" };
+    let server_url = format!("{}://{}", scheme, config.api_address());
     let swagger_url = format!("{}/swagger-ui", server_url);
     
     info!("API server listening on {}", config.api_address());
@@ -86,7 +366,8 @@ async fn main() -> Result<()> {
     }
 
     // Start server
-    axum::serve(listener, app).await?;
+    tls::serve(app, &config.api_address(), &config.tls, &config.server).await?;
 
+    shutdown_tracing();
     Ok(())
 }
\ No newline at end of file
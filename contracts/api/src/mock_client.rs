@@ -0,0 +1,343 @@
+use anyhow::{anyhow, Result};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+use stellar_xdr::curr::TransactionEnvelope;
+
+use crate::{
+    cache::ResponseCache,
+    contract_client::{ClientFuture, ContractClient},
+    fees::{FeeEstimate, FeeSettings},
+    models::{
+        CallSummary, CertId, Certificate, LegacyCertificate, MetadataHash, PreparedTransaction,
+        StellarAddress,
+    },
+    signer::Signer,
+    soroban_client::{ContractError, TransactionOutcome},
+};
+
+/// Ledger the mock registry starts at; every transaction closes one more
+const FIRST_LEDGER: u32 = 1000;
+
+/// A certificate held by the mock registry
+#[derive(Debug, Clone)]
+struct MockCertificate {
+    details: Certificate,
+    status: String,
+    collection: Option<(u32, String)>,
+    warranty: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    admin: Option<String>,
+    certificates: HashMap<String, MockCertificate>,
+    /// Ledger each submitted transaction was included in, by hash
+    transactions: HashMap<String, u32>,
+    /// Message every call fails with, as if the contract or RPC had returned it
+    failure: Option<String>,
+}
+
+/// In-memory stand-in for the registry contract, for tests
+///
+/// Writes take effect immediately and get sequential transaction hashes, and
+/// every transaction is confirmed in its own ledger. Errors use the contract's
+/// panic messages, so they map to the same error codes as on chain. Flows
+/// that need real transaction XDR (transfers, client-signed submissions and
+/// fee bumps) are refused as invalid transactions. Clones share the registry.
+#[derive(Clone, Default)]
+pub struct MockContractClient {
+    registry: Arc<Mutex<Registry>>,
+}
+
+impl MockContractClient {
+    /// An uninitialized registry with no certificates
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry already initialized with `admin_address` as admin
+    pub fn initialized(admin_address: &str) -> Self {
+        let client = Self::new();
+        client.registry().admin = Some(admin_address.to_string());
+        client
+    }
+
+    /// Add a valid certificate without a transaction
+    pub fn with_certificate(self, cert_id: &str, metadata_hash: &str, owner_address: &str) -> Self {
+        self.registry()
+            .certificates
+            .insert(cert_id.to_string(), new_certificate(metadata_hash, owner_address));
+        self
+    }
+
+    /// Set a certificate's lifecycle status, e.g. `Suspended`
+    pub fn set_status(&self, cert_id: &str, status: &str) {
+        if let Some(certificate) = self.registry().certificates.get_mut(cert_id) {
+            certificate.status = status.to_string();
+        }
+    }
+
+    /// Set when a certificate's warranty ends (unix seconds)
+    pub fn set_warranty(&self, cert_id: &str, expires_at: u64) {
+        if let Some(certificate) = self.registry().certificates.get_mut(cert_id) {
+            certificate.warranty = Some(expires_at);
+        }
+    }
+
+    /// Place a certificate in a collection of `brand`
+    pub fn set_collection(&self, cert_id: &str, collection_id: u32, brand: &str) {
+        if let Some(certificate) = self.registry().certificates.get_mut(cert_id) {
+            certificate.collection = Some((collection_id, brand.to_string()));
+        }
+    }
+
+    /// Fail every call with `message` until [`MockContractClient::recover`] is called
+    pub fn fail_with(&self, message: &str) {
+        self.registry().failure = Some(message.to_string());
+    }
+
+    pub fn recover(&self) {
+        self.registry().failure = None;
+    }
+
+    fn registry(&self) -> MutexGuard<'_, Registry> {
+        self.registry.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The registry, unless calls are set to fail
+    fn checked(&self) -> Result<MutexGuard<'_, Registry>> {
+        let registry = self.registry();
+        match &registry.failure {
+            Some(message) => Err(anyhow!("{}", message)),
+            None => Ok(registry),
+        }
+    }
+
+    /// The registry, unless it is uninitialized or calls are set to fail
+    fn initialized_registry(&self) -> Result<MutexGuard<'_, Registry>> {
+        let registry = self.checked()?;
+        if registry.admin.is_none() {
+            return Err(anyhow!("Contract not initialized"));
+        }
+        Ok(registry)
+    }
+
+    fn certificate(&self, cert_id: &str) -> Result<MockCertificate> {
+        self.checked()?
+            .certificates
+            .get(cert_id)
+            .cloned()
+            .ok_or_else(|| ContractError::CertificateNotFound.into())
+    }
+}
+
+impl Registry {
+    /// Record a transaction in the next ledger and return its hash
+    fn transact(&mut self) -> String {
+        let ledger = FIRST_LEDGER + self.transactions.len() as u32 + 1;
+        let hash = format!("{:064x}", ledger);
+        self.transactions.insert(hash.clone(), ledger);
+        hash
+    }
+}
+
+fn new_certificate(metadata_hash: &str, owner_address: &str) -> MockCertificate {
+    MockCertificate {
+        details: Certificate {
+            owner: owner_address.to_string(),
+            metadata_hash: metadata_hash.to_string(),
+            is_valid: true,
+        },
+        status: "Valid".to_string(),
+        collection: None,
+        warranty: None,
+    }
+}
+
+fn unsupported_transaction<T>() -> Result<T> {
+    Err(anyhow!("Invalid transaction: the mock client doesn't build or decode transactions"))
+}
+
+impl ContractClient for MockContractClient {
+    fn init<'a>(&'a self, admin_address: &'a StellarAddress) -> ClientFuture<'a, String> {
+        Box::pin(async move {
+            let mut registry = self.checked()?;
+            if registry.admin.is_some() {
+                return Err(anyhow!("Contract already initialized"));
+            }
+            registry.admin = Some(admin_address.to_string());
+            Ok(registry.transact())
+        })
+    }
+
+    fn issue_certificate<'a>(
+        &'a self,
+        cert_id: &'a CertId,
+        metadata_hash: &'a MetadataHash,
+        owner_address: &'a StellarAddress,
+    ) -> ClientFuture<'a, String> {
+        Box::pin(async move {
+            let mut registry = self.initialized_registry()?;
+            if registry.certificates.contains_key(cert_id.as_str()) {
+                return Err(ContractError::CertificateExists.into());
+            }
+            registry
+                .certificates
+                .insert(cert_id.to_string(), new_certificate(metadata_hash, owner_address));
+            Ok(registry.transact())
+        })
+    }
+
+    fn issue_batch<'a>(&'a self, certificates: &'a [LegacyCertificate]) -> ClientFuture<'a, String> {
+        Box::pin(async move {
+            if certificates.is_empty() {
+                return Err(anyhow!("Batch cannot be empty"));
+            }
+
+            // The batch is atomic, so check every entry before issuing any
+            let mut registry = self.initialized_registry()?;
+            for certificate in certificates {
+                if registry.certificates.contains_key(&certificate.cert_id) {
+                    return Err(ContractError::CertificateExists.into());
+                }
+            }
+            for certificate in certificates {
+                registry.certificates.insert(
+                    certificate.cert_id.clone(),
+                    new_certificate(&certificate.metadata_hash, &certificate.owner_address),
+                );
+            }
+            Ok(registry.transact())
+        })
+    }
+
+    fn verify_certificate<'a>(
+        &'a self,
+        cert_id: &'a CertId,
+        metadata_hash: &'a MetadataHash,
+    ) -> ClientFuture<'a, bool> {
+        Box::pin(async move {
+            let certificate = self.certificate(cert_id)?;
+            Ok(certificate.details.is_valid && certificate.details.metadata_hash == metadata_hash.as_str())
+        })
+    }
+
+    fn get_certificate_details<'a>(&'a self, cert_id: &'a CertId) -> ClientFuture<'a, Certificate> {
+        Box::pin(async move { self.certificate(cert_id).map(|certificate| certificate.details) })
+    }
+
+    fn get_certificate_collection<'a>(
+        &'a self,
+        cert_id: &'a CertId,
+    ) -> ClientFuture<'a, Option<(u32, String)>> {
+        Box::pin(async move { Ok(self.certificate(cert_id)?.collection) })
+    }
+
+    fn get_certificate_status<'a>(&'a self, cert_id: &'a CertId) -> ClientFuture<'a, String> {
+        Box::pin(async move { Ok(self.certificate(cert_id)?.status) })
+    }
+
+    fn get_warranty<'a>(&'a self, cert_id: &'a CertId) -> ClientFuture<'a, Option<u64>> {
+        Box::pin(async move { Ok(self.certificate(cert_id)?.warranty) })
+    }
+
+    fn prepare_transfer<'a>(
+        &'a self,
+        cert_id: &'a CertId,
+        _new_owner_address: &'a StellarAddress,
+    ) -> ClientFuture<'a, PreparedTransaction> {
+        Box::pin(async move {
+            self.certificate(cert_id)?;
+            unsupported_transaction()
+        })
+    }
+
+    fn decode_signed_transaction(&self, _transaction_xdr: &str) -> Result<TransactionEnvelope> {
+        unsupported_transaction()
+    }
+
+    fn submit_signed_transaction<'a>(&'a self, _transaction_xdr: &'a str) -> ClientFuture<'a, String> {
+        Box::pin(async move { unsupported_transaction() })
+    }
+
+    fn revoke_certificate<'a>(&'a self, cert_id: &'a CertId) -> ClientFuture<'a, String> {
+        Box::pin(async move {
+            let mut registry = self.initialized_registry()?;
+            let certificate = registry
+                .certificates
+                .get_mut(cert_id.as_str())
+                .ok_or(ContractError::CertificateNotFound)?;
+            certificate.details.is_valid = false;
+            certificate.status = "Revoked".to_string();
+            Ok(registry.transact())
+        })
+    }
+
+    fn certificate_exists<'a>(&'a self, cert_id: &'a CertId) -> ClientFuture<'a, bool> {
+        Box::pin(async move { Ok(self.checked()?.certificates.contains_key(cert_id.as_str())) })
+    }
+
+    fn wait_for_transaction<'a>(
+        &'a self,
+        hash: &'a str,
+        timeout: Duration,
+        _poll_interval: Duration,
+    ) -> ClientFuture<'a, TransactionOutcome> {
+        Box::pin(async move {
+            match self.checked()?.transactions.get(hash) {
+                Some(&ledger) => Ok(TransactionOutcome::Success {
+                    ledger,
+                    summary: CallSummary::default(),
+                }),
+                None => Err(anyhow!(
+                    "Transaction {} not confirmed within {} seconds",
+                    hash,
+                    timeout.as_secs()
+                )),
+            }
+        })
+    }
+
+    fn estimate_fees(&self) -> ClientFuture<'_, FeeEstimate> {
+        Box::pin(async move {
+            let registry = self.checked()?;
+            let fees = FeeSettings::default();
+            Ok(FeeEstimate {
+                strategy: fees.strategy,
+                inclusion_fee: fees.base_fee,
+                network_inclusion_fee_p50: u64::from(fees.base_fee),
+                network_inclusion_fee_p90: u64::from(fees.base_fee),
+                network_inclusion_fee_max: u64::from(fees.base_fee),
+                issue_resource_fee: 0,
+                issue_total_fee: i64::from(fees.base_fee),
+                max_fee: fees.max_fee,
+                latest_ledger: FIRST_LEDGER + registry.transactions.len() as u32,
+            })
+        })
+    }
+
+    fn envelope_hash(&self, _envelope: &TransactionEnvelope) -> Result<String> {
+        unsupported_transaction()
+    }
+
+    fn sent_transaction(&self, _hash: &str) -> Option<TransactionEnvelope> {
+        None
+    }
+
+    fn fee_bump(&self, _inner: TransactionEnvelope, _fee: Option<i64>) -> ClientFuture<'_, String> {
+        Box::pin(async move { unsupported_transaction() })
+    }
+
+    /// The same registry; the mock holds a single contract
+    fn for_contract(
+        &self,
+        _contract_id: &str,
+        _signer: Option<Arc<dyn Signer>>,
+        _cache: ResponseCache,
+    ) -> Arc<dyn ContractClient> {
+        Arc::new(self.clone())
+    }
+}
@@ -1,5 +1,187 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use std::{fmt, ops::Deref, str::FromStr};
+use stellar_strkey::ed25519;
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::{
+    devtools::{DevKeypair, StrkeyConversion},
+    health::ReadinessReport,
+    horizon::AccountInfo,
+    i18n,
+    import::ImportReport,
+    fees::FeeEstimate,
+    jobs::Job,
+    notifications::NotificationPreferences,
+    qr::QrPayload,
+    reconcile::ReconciliationReport,
+    reload::ReloadReport,
+    rent::RentForecast,
+    soroban_client::ContractError,
+    usage::ApiKeyUsage,
+    validation::validate_issuable,
+    webhooks::{Delivery, DeliveryStatus, Webhook},
+};
+
+/// Longest certificate ID accepted anywhere
+///
+/// Issuance allows 64 characters, but certificates issued before namespaces
+/// may have longer IDs and must still be found.
+const MAX_CERT_ID_LEN: usize = 256;
+
+/// Characters allowed in an issued certificate ID besides ASCII letters and digits
+const CERT_ID_PUNCTUATION: &[char] = &['.', '_', '-'];
+
+/// Digits of the base58btc alphabet used by CIDv0
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Implement the conversions shared by the identifier newtypes
+///
+/// Each type is built only by its `parse`, which deserialization and
+/// `FromStr` go through, and reads as a `&str` everywhere else.
+macro_rules! identifier {
+    ($name:ident) => {
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = String;
+
+            fn from_str(value: &str) -> Result<Self, String> {
+                Self::parse(value)
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = String;
+
+            fn try_from(value: String) -> Result<Self, String> {
+                Self::parse(&value)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> String {
+                value.0
+            }
+        }
+    };
+}
+
+/// A certificate ID that can be sent to the contract
+///
+/// Any ID up to 256 characters without whitespace or control characters is
+/// accepted, so existing certificates can always be looked up; the stricter
+/// rule for issuing new ones is [`CertId::is_issuable`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(try_from = "String", into = "String")]
+pub struct CertId(String);
+
+impl CertId {
+    pub fn parse(cert_id: &str) -> Result<Self, String> {
+        if cert_id.is_empty() {
+            return Err("Certificate ID cannot be empty".to_string());
+        }
+        if cert_id.len() > MAX_CERT_ID_LEN
+            || cert_id.chars().any(|c| c.is_whitespace() || c.is_control())
+        {
+            return Err(format!(
+                "Certificate ID must be at most {} characters, without whitespace",
+                MAX_CERT_ID_LEN
+            ));
+        }
+        Ok(Self(cert_id.to_string()))
+    }
+
+    /// Whether the contract issues certificates under this ID: 3-64
+    /// characters from `[A-Za-z0-9._-]`
+    pub fn is_issuable(&self) -> bool {
+        (3..=64).contains(&self.0.len())
+            && self
+                .0
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || CERT_ID_PUNCTUATION.contains(&c))
+    }
+}
+
+identifier!(CertId);
+
+/// A certificate's metadata hash
+///
+/// Either the IPFS CID of the metadata document, CIDv0 (`Qm...`, base58btc)
+/// or CIDv1 in the default base32 encoding (`bafy...`), or the hex sha256
+/// digest of the document, which the contract's `verify_metadata` checks
+/// documents against.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(try_from = "String", into = "String")]
+pub struct MetadataHash(String);
+
+impl MetadataHash {
+    pub fn parse(hash: &str) -> Result<Self, String> {
+        if hash.is_empty() {
+            return Err("Metadata hash cannot be empty".to_string());
+        }
+
+        let cid_v0 = hash.len() == 46
+            && hash.starts_with("Qm")
+            && hash.chars().all(|c| BASE58_ALPHABET.contains(c));
+        let cid_v1 = (59..=128).contains(&hash.len())
+            && hash.starts_with('b')
+            && hash[1..]
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || ('2'..='7').contains(&c));
+        let sha256 = hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit());
+        if !cid_v0 && !cid_v1 && !sha256 {
+            return Err(
+                "Metadata hash must be an IPFS CID (Qm... or b...) or a hex sha256 digest"
+                    .to_string(),
+            );
+        }
+        Ok(Self(hash.to_string()))
+    }
+}
+
+identifier!(MetadataHash);
+
+/// A Stellar account address (`G...`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(try_from = "String", into = "String")]
+pub struct StellarAddress(String);
+
+impl StellarAddress {
+    pub fn parse(address: &str) -> Result<Self, String> {
+        if address.is_empty() {
+            return Err("Address cannot be empty".to_string());
+        }
+        if ed25519::PublicKey::from_string(address).is_err() {
+            return Err("Address must be a Stellar public key (G...)".to_string());
+        }
+        Ok(Self(address.to_string()))
+    }
+}
+
+identifier!(StellarAddress);
+
+/// Fields of a `Certificate` that can be picked with `fields=`
+pub const CERTIFICATE_FIELDS: &[&str] = &["owner", "metadata_hash", "is_valid"];
 
 /// Certificate data structure matching the smart contract
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -10,30 +192,268 @@ pub struct Certificate {
 }
 
 /// Request body for initializing the contract
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct InitRequest {
-    pub admin_address: String,
+    pub admin_address: StellarAddress,
 }
 
 /// Request body for issuing a certificate
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct IssueCertificateRequest {
+    /// 3-64 characters from `[A-Za-z0-9._-]`
+    #[validate(custom = "validate_issuable")]
+    pub cert_id: CertId,
+    /// IPFS CID or hex sha256 digest of the certificate's metadata
+    pub metadata_hash: MetadataHash,
+    pub owner_address: StellarAddress,
+}
+
+/// One certificate of a legacy registry being imported
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct LegacyCertificate {
     pub cert_id: String,
     pub metadata_hash: String,
     pub owner_address: String,
 }
 
 /// Request body for verifying a certificate
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct VerifyCertificateRequest {
+    pub metadata_hash: MetadataHash,
+}
+
+/// Request body for verifying a scanned QR code
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct VerifyQrRequest {
+    /// Payload read from the QR code, as printed
+    #[validate(length(max = 2048, message = "payload must be at most 2048 characters"))]
+    pub payload: String,
+}
+
+/// Query parameters for federated verification across registries
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FederatedVerifyQuery {
+    pub cert_id: String,
     pub metadata_hash: String,
 }
 
-/// Request body for transferring a certificate
-#[derive(Debug, Deserialize, ToSchema)]
-pub struct TransferCertificateRequest {
-    pub new_owner_address: String,
-    pub current_owner_secret_key: String,
+/// Query parameters for public consumer verification
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PublicVerifyQuery {
+    pub cert_id: String,
+    /// Metadata hash printed with the item
+    pub hash: String,
+}
+
+/// Query parameters for reading one certificate
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CertificateQuery {
+    /// Comma-separated fields to return, e.g. `owner,is_valid`; all when omitted
+    pub fields: Option<String>,
+}
+
+/// Query parameters for listing certificates
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListCertificatesQuery {
+    /// `cert_id` (default), `issued_at` or `last_transfer`; prefix with `-`
+    /// for descending order
+    pub sort: Option<String>,
+    /// Maximum number of certificates to return (1-200, default 50)
+    pub limit: Option<u32>,
+    /// `next_cursor` from the previous page, listed with the same `sort`
+    pub cursor: Option<String>,
+    /// Comma-separated fields to return for each certificate, e.g.
+    /// `owner,status,issued_at`; `cert_id` is always included
+    pub fields: Option<String>,
+}
+
+/// Query parameters for searching indexed certificates
+///
+/// All filters are optional and combined with AND.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchCertificatesQuery {
+    /// Current owner address
+    pub owner: Option<String>,
+    /// `active`, `revoked` or `burned`
+    pub status: Option<String>,
+    /// Item or collection brand (case-insensitive)
+    pub brand: Option<String>,
+    pub collection_id: Option<u32>,
+    /// Issued at or after this time (RFC 3339)
+    pub issued_from: Option<DateTime<Utc>>,
+    /// Issued before this time (RFC 3339)
+    pub issued_to: Option<DateTime<Utc>>,
+    /// Free text matched against the ID, metadata hash and item attributes
+    pub q: Option<String>,
+    /// `cert_id` (default), `issued_at` or `last_transfer`; prefix with `-`
+    /// for descending order
+    pub sort: Option<String>,
+    /// Maximum number of certificates to return (1-200, default 50)
+    pub limit: Option<u32>,
+    /// `next_cursor` from the previous page, listed with the same `sort`
+    pub cursor: Option<String>,
+    /// Comma-separated fields to return for each certificate, e.g.
+    /// `owner,status,issued_at`; `cert_id` is always included
+    pub fields: Option<String>,
+}
+
+/// Query parameters for exporting the certificate registry
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportQuery {
+    /// `json` (default) or `csv`
+    pub format: Option<String>,
+    pub collection_id: Option<u32>,
+    /// Issued at or after this time (RFC 3339)
+    pub issued_from: Option<DateTime<Utc>>,
+    /// Issued before this time (RFC 3339)
+    pub issued_to: Option<DateTime<Utc>>,
+}
+
+/// Query parameters for importing legacy certificates
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImportQuery {
+    /// `json` (default) or `csv`
+    pub format: Option<String>,
+}
+
+/// Query parameters for reading the audit log
+///
+/// All filters are optional and combined with AND.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditQuery {
+    /// Token subject, `tenant:<id>` or `anonymous`
+    pub actor: Option<String>,
+    /// Method and route, e.g. `POST /certificates/:id/revoke`
+    pub action: Option<String>,
+    /// `success` or `failure`
+    pub outcome: Option<String>,
+    /// Recorded at or after this time (RFC 3339)
+    pub from: Option<DateTime<Utc>>,
+    /// Recorded before this time (RFC 3339)
+    pub to: Option<DateTime<Utc>>,
+    /// Maximum number of entries to return (1-200, default 50)
+    pub limit: Option<u32>,
+    /// `next_cursor` from the previous page
+    pub cursor: Option<String>,
+}
+
+/// Query parameters for requesting a SEP-10 challenge
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ChallengeQuery {
+    /// Stellar account (G...) to authenticate
+    pub account: String,
+}
+
+/// Request body for exchanging a signed SEP-10 challenge for a token
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct ChallengeTokenRequest {
+    /// Challenge envelope XDR, co-signed by the account
+    #[validate(length(max = 16384, message = "transaction must be at most 16384 characters"))]
+    pub transaction: String,
+}
+
+/// Query parameters accepted by endpoints that submit transactions
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SubmitQuery {
+    /// Wait for the transaction to be confirmed before responding
+    pub wait: Option<bool>,
+}
+
+/// Request body for registering a webhook
+///
+/// `events` limits deliveries to the given lifecycle events; all events are
+/// delivered when it is omitted.
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct RegisterWebhookRequest {
+    #[validate(length(max = 2048, message = "url must be at most 2048 characters"))]
+    pub url: String,
+    #[validate(length(max = 32, message = "events must list at most 32 events"))]
+    pub events: Option<Vec<String>>,
+    /// Key for signing deliveries, at least 16 characters; generated when omitted
+    #[validate(length(max = 256, message = "secret must be at most 256 characters"))]
+    pub secret: Option<String>,
+}
+
+/// Request body for registering an owner's notification preferences
+///
+/// At least one of `email` and `phone` is required. `events` limits
+/// notifications to the given events; all events are notified when it is
+/// omitted.
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct UpdateNotificationPreferencesRequest {
+    #[validate(length(max = 254, message = "email must be at most 254 characters"))]
+    pub email: Option<String>,
+    /// Mobile number in E.164 format, e.g. `+14155550100`
+    #[validate(length(max = 16, message = "phone must be at most 16 characters"))]
+    pub phone: Option<String>,
+    #[validate(length(max = 32, message = "events must list at most 32 events"))]
+    pub events: Option<Vec<String>>,
+}
+
+/// Query parameters for listing a webhook's deliveries
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WebhookDeliveriesQuery {
+    /// Only deliveries in this state, e.g. `dead_letter`
+    pub status: Option<DeliveryStatus>,
+}
+
+/// Request body for preparing a certificate transfer
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct PrepareTransferRequest {
+    pub new_owner_address: StellarAddress,
+}
+
+/// Request body for submitting a client-signed transaction
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct SubmitTransactionRequest {
+    /// Signed transaction envelope XDR, base64-encoded
+    #[validate(length(max = 32768, message = "transaction must be at most 32768 characters"))]
+    pub transaction: String,
+}
+
+/// Request body for funding a development account
+#[derive(Debug, Default, Deserialize, ToSchema, Validate)]
+pub struct FundAccountRequest {
+    /// Account to fund; a new keypair is created when omitted
+    #[serde(default)]
+    pub address: Option<StellarAddress>,
+}
+
+/// An account funded by friendbot
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FundedAccount {
+    pub address: String,
+    /// Secret key (S...) of a keypair created for the request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret_key: Option<String>,
+    /// Whether the account was created now, rather than already existing
+    pub created: bool,
+    /// Native balance, in stroops
+    pub balance: i64,
+    pub network_passphrase: String,
+}
+
+/// Request body for fee-bumping a stuck transaction
+#[derive(Debug, Default, Deserialize, ToSchema, Validate)]
+pub struct FeeBumpRequest {
+    /// Signed envelope XDR of the transaction; optional for transactions this API submitted
+    #[serde(default)]
+    #[validate(length(max = 32768, message = "transaction must be at most 32768 characters"))]
+    pub transaction: Option<String>,
+    /// Total fee of the fee bump, in stroops; ten times the original fee when omitted
+    #[serde(default)]
+    pub fee: Option<i64>,
 }
 
 /// Response for successful operations
@@ -52,6 +472,13 @@ pub struct HealthResponse {
     pub message: String,
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReadinessApiResponse {
+    pub success: bool,
+    pub data: Option<ReadinessReport>,
+    pub message: String,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct CertificateResponse {
     pub success: bool,
@@ -73,6 +500,174 @@ pub struct VerifyApiResponse {
     pub message: String,
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QrVerifyApiResponse {
+    pub success: bool,
+    pub data: Option<QrVerifyResponse>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PublicVerifyApiResponse {
+    pub success: bool,
+    pub data: Option<PublicVerifyResponse>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QrPayloadApiResponse {
+    pub success: bool,
+    pub data: Option<QrPayload>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FederatedVerifyApiResponse {
+    pub success: bool,
+    pub data: Option<FederatedVerifyResponse>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RentForecastApiResponse {
+    pub success: bool,
+    pub data: Option<RentForecast>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FeeEstimateApiResponse {
+    pub success: bool,
+    pub data: Option<FeeEstimate>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobApiResponse {
+    pub success: bool,
+    pub data: Option<Job>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookApiResponse {
+    pub success: bool,
+    pub data: Option<Webhook>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookListApiResponse {
+    pub success: bool,
+    pub data: Option<Vec<Webhook>>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotificationPreferencesApiResponse {
+    pub success: bool,
+    pub data: Option<NotificationPreferences>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CertificateHistoryApiResponse {
+    pub success: bool,
+    pub data: Option<Vec<CertificateHistoryEvent>>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReconciliationApiResponse {
+    pub success: bool,
+    pub data: Option<ReconciliationReport>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiKeyUsageApiResponse {
+    pub success: bool,
+    pub data: Option<ApiKeyUsage>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeliveryListApiResponse {
+    pub success: bool,
+    pub data: Option<Vec<Delivery>>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportReportApiResponse {
+    pub success: bool,
+    pub data: Option<ImportReport>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditPageApiResponse {
+    pub success: bool,
+    pub data: Option<AuditPage>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditVerificationApiResponse {
+    pub success: bool,
+    pub data: Option<AuditVerification>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReloadApiResponse {
+    pub success: bool,
+    pub data: Option<ReloadReport>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CertificatePageApiResponse {
+    pub success: bool,
+    pub data: Option<CertificatePage>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PreparedTransactionApiResponse {
+    pub success: bool,
+    pub data: Option<PreparedTransaction>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FundedAccountApiResponse {
+    pub success: bool,
+    pub data: Option<FundedAccount>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DevKeypairApiResponse {
+    pub success: bool,
+    pub data: Option<DevKeypair>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StrkeyConversionApiResponse {
+    pub success: bool,
+    pub data: Option<StrkeyConversion>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AccountInfoApiResponse {
+    pub success: bool,
+    pub data: Option<AccountInfo>,
+    pub message: String,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ExistsApiResponse {
     pub success: bool,
@@ -83,9 +678,80 @@ pub struct ExistsApiResponse {
 /// Response for verification operations
 #[derive(Debug, Serialize, ToSchema)]
 pub struct VerifyResponse {
+    /// Whether the item is authentic: `true` for `valid` and `expired`
+    pub is_valid: bool,
+    /// `valid`, or why the certificate doesn't verify
+    pub status: VerificationReason,
+    /// `status` explained, for buyers and support staff
+    pub reason: String,
+    pub cert_id: String,
+    pub metadata_hash: String,
+    /// Known when the event indexer is enabled
+    pub issued_at: Option<DateTime<Utc>>,
+    pub brand: Option<String>,
+}
+
+/// Result of verifying a scanned QR code
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QrVerifyResponse {
+    pub is_valid: bool,
+    pub cert_id: String,
+    pub metadata_hash: String,
+    /// When the scanned code stops being accepted
+    pub expires_at: u64,
+}
+
+/// Why a public verification came out the way it did
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationReason {
+    /// Authentic and in good standing
+    Valid,
+    /// Revoked by the admin
+    Revoked,
+    /// Authentic, but its warranty period has ended
+    Expired,
+    /// Frozen by an open dispute or a deactivated issuer
+    Suspended,
+    /// The hash doesn't match the certificate's metadata
+    HashMismatch,
+    /// No certificate has this ID
+    UnknownCertificate,
+}
+
+impl VerificationReason {
+    /// What the outcome means for the person holding the item
+    pub fn describe(&self) -> &'static str {
+        match self {
+            VerificationReason::Valid => "Authentic VeriLuxe certificate",
+            VerificationReason::Expired => "Authentic VeriLuxe certificate; its warranty has ended",
+            VerificationReason::Revoked => "This certificate has been revoked",
+            VerificationReason::Suspended => "This certificate is under review",
+            VerificationReason::HashMismatch => "This item does not match its certificate",
+            VerificationReason::UnknownCertificate => "No VeriLuxe certificate has this ID",
+        }
+    }
+}
+
+/// Consumer-facing verification result
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PublicVerifyResponse {
+    /// Whether the item is authentic: `true` for `valid` and `expired`
+    pub is_valid: bool,
+    pub reason: VerificationReason,
+    pub cert_id: String,
+    /// Known when the event indexer is enabled
+    pub issued_at: Option<DateTime<Utc>>,
+    pub brand: Option<String>,
+}
+
+/// Response for federated verification, naming the registry that answered
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FederatedVerifyResponse {
     pub is_valid: bool,
     pub cert_id: String,
     pub metadata_hash: String,
+    pub registry: Option<String>,
 }
 
 /// Response for certificate existence check
@@ -96,10 +762,366 @@ pub struct ExistsResponse {
 }
 
 /// Response for transaction operations
+///
+/// `status` is `submitted` when the API didn't wait for the network, or
+/// `confirmed` once the transaction succeeded in `ledger`, in which case
+/// `result` summarises the contract call.
 #[derive(Debug, Serialize, ToSchema)]
 pub struct TransactionResponse {
     pub transaction_hash: String,
     pub status: String,
+    pub ledger: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<CallSummary>,
+}
+
+/// What a completed transaction's receipt says about its contract call
+///
+/// Decoded from the `getTransaction` result and result meta; fields the RPC
+/// didn't return are left empty.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct CallSummary {
+    /// Transaction result code, e.g. `TxSuccess` or `TxFailed`
+    pub result_code: Option<String>,
+    /// Result of the invocation, e.g. `Success`, `Trapped` or `ResourceLimitExceeded`
+    pub operation_result: Option<String>,
+    pub fee_charged: Option<i64>,
+    /// The contract function's return value, on success
+    #[schema(value_type = Option<Object>)]
+    pub return_value: Option<serde_json::Value>,
+    /// Events emitted by the contract
+    pub events: Vec<EmittedEvent>,
+    /// Diagnostic events recorded by the RPC, on failure
+    pub diagnostic_events: Vec<EmittedEvent>,
+    /// Contract error or panic message recovered from the diagnostic events
+    pub error: Option<String>,
+}
+
+impl CallSummary {
+    /// "Transaction <hash> failed in ledger <n>", with the reason when known
+    pub fn failure_message(&self, transaction_hash: &str, ledger: u32) -> String {
+        let reason = self
+            .error
+            .as_deref()
+            .or(self.operation_result.as_deref())
+            .or(self.result_code.as_deref());
+        match reason {
+            Some(reason) => format!(
+                "Transaction {} failed in ledger {}: {}",
+                transaction_hash, ledger, reason
+            ),
+            None => format!("Transaction {} failed in ledger {}", transaction_hash, ledger),
+        }
+    }
+}
+
+/// A contract event from a transaction's receipt, decoded to JSON
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct EmittedEvent {
+    /// C... strkey of the emitting contract, if any
+    pub contract_id: Option<String>,
+    #[schema(value_type = Vec<Object>)]
+    pub topics: Vec<serde_json::Value>,
+    #[schema(value_type = Object)]
+    pub data: serde_json::Value,
+}
+
+/// SEP-10 challenge for the client to sign
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChallengeResponse {
+    pub transaction: String,
+    pub network_passphrase: String,
+}
+
+/// Session token issued for a verified SEP-10 challenge
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChallengeTokenResponse {
+    pub token: String,
+}
+
+/// Unsigned transaction for a client to sign and pass to `/transactions/submit`
+///
+/// `transaction` is the base64 envelope XDR, already simulated so it carries
+/// its fee, resources and authorization; `hash` is what `source` must sign.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PreparedTransaction {
+    pub transaction: String,
+    pub hash: String,
+    pub source: String,
+    pub network_passphrase: String,
+}
+
+/// Fields of a `CertificateSummary` that can be picked with `fields=`
+pub const CERTIFICATE_SUMMARY_FIELDS: &[&str] = &[
+    "cert_id",
+    "owner",
+    "metadata_hash",
+    "status",
+    "disputed",
+    "brand",
+    "model",
+    "issued_ledger",
+    "issued_at",
+    "updated_ledger",
+    "updated_at",
+];
+
+/// Indexed view of a certificate, as rebuilt from contract events
+///
+/// `status` is `active`, `revoked` or `burned`.
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct CertificateSummary {
+    pub cert_id: String,
+    pub owner: String,
+    pub metadata_hash: String,
+    pub status: String,
+    pub disputed: bool,
+    pub brand: Option<String>,
+    pub model: Option<String>,
+    #[sqlx(try_from = "i64")]
+    pub issued_ledger: u32,
+    pub issued_at: Option<DateTime<Utc>>,
+    #[sqlx(try_from = "i64")]
+    pub updated_ledger: u32,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// One page of certificates; pass `next_cursor` to fetch the next one
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CertificatePage {
+    pub certificates: Vec<CertificateSummary>,
+    /// Opaque token for the following page, `null` on the last one
+    pub next_cursor: Option<String>,
+}
+
+/// A certificate as exported, with every event recorded for it
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct ExportedCertificate {
+    pub cert_id: String,
+    pub owner: String,
+    pub metadata_hash: String,
+    pub status: String,
+    pub disputed: bool,
+    pub brand: Option<String>,
+    pub model: Option<String>,
+    pub serial_number: Option<String>,
+    pub material: Option<String>,
+    pub manufacture_year: Option<i32>,
+    pub collection_id: Option<i64>,
+    #[sqlx(try_from = "i64")]
+    pub issued_ledger: u32,
+    pub issued_at: Option<DateTime<Utc>>,
+    #[sqlx(try_from = "i64")]
+    pub updated_ledger: u32,
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Lifecycle events in ledger order
+    #[sqlx(skip)]
+    pub history: Vec<CertificateHistoryEvent>,
+}
+
+/// One lifecycle event in a certificate's history
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CertificateHistoryEvent {
+    /// `issued`, `transfer`, `attrs` (item attributes set), `revoked`,
+    /// `disputed` or `burned`
+    pub event: String,
+    pub ledger: u32,
+    pub ledger_closed_at: Option<DateTime<Utc>>,
+    pub transaction_hash: String,
+    #[schema(value_type = Object)]
+    pub data: serde_json::Value,
+}
+
+/// An operation recorded in the audit log
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub occurred_at: DateTime<Utc>,
+    /// Token subject, `tenant:<id>` or `anonymous`
+    pub actor: String,
+    pub network: String,
+    /// Method and route, e.g. `POST /certificates`
+    pub action: String,
+    pub path: String,
+    /// SHA-256 of the request body, hex
+    pub payload_hash: String,
+    #[sqlx(try_from = "i32")]
+    pub status: u16,
+    /// `success` for 2xx responses, `failure` otherwise
+    pub outcome: String,
+    pub transaction_hash: Option<String>,
+    /// Set when the operation was queued
+    pub job_id: Option<String>,
+    pub prev_hash: String,
+    pub entry_hash: String,
+}
+
+/// One page of audit entries, newest first; pass `next_cursor` to fetch the next one
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditPage {
+    pub entries: Vec<AuditEntry>,
+    pub next_cursor: Option<String>,
+}
+
+/// Result of recomputing the audit log's hash chain
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditVerification {
+    pub valid: bool,
+    /// Entries found intact
+    pub checked: u64,
+    /// First entry whose hash doesn't match, if any
+    pub broken_at: Option<i64>,
+}
+
+/// A certificate lifecycle event emitted by the contract
+///
+/// `event` is one of `issued`, `transfer`, `revoked`, `burned`, `disputed` or
+/// `attrs`, and `data` holds the event's named fields (e.g. `from` and `to`).
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ContractEvent {
+    pub id: String,
+    pub event: String,
+    pub cert_id: String,
+    pub ledger: u32,
+    pub ledger_closed_at: String,
+    pub transaction_hash: String,
+    #[schema(value_type = Object)]
+    pub data: serde_json::Value,
+}
+
+/// Stable, machine-readable cause of an error
+///
+/// Unlike `error`, which is meant for people and may change wording, these
+/// values are part of the API contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    InvalidRequest,
+    InvalidAddress,
+    InvalidCertId,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    CertNotFound,
+    CertRevoked,
+    /// The certificate is suspended, locked or disputed
+    CertLocked,
+    DuplicateCertId,
+    ContractNotInitialized,
+    ContractPaused,
+    /// A scanned QR payload is malformed or its signature doesn't match
+    QrInvalid,
+    QrExpired,
+    /// A request's nonce was already used or its timestamp is too old
+    ReplayedRequest,
+    /// An API key has used up its daily or monthly quota
+    QuotaExceeded,
+    TransactionRejected,
+    TransactionFailed,
+    TransactionNotConfirmed,
+    RpcUnavailable,
+    ServiceUnavailable,
+    InternalError,
+}
+
+impl ErrorCode {
+    /// Default code for a response with HTTP status `status`
+    pub fn for_status(status: u16) -> Self {
+        match status {
+            400 | 413 | 415 | 422 => ErrorCode::InvalidRequest,
+            401 => ErrorCode::Unauthorized,
+            403 => ErrorCode::Forbidden,
+            404 => ErrorCode::NotFound,
+            429 => ErrorCode::QuotaExceeded,
+            503 => ErrorCode::ServiceUnavailable,
+            504 => ErrorCode::TransactionNotConfirmed,
+            _ => ErrorCode::InternalError,
+        }
+    }
+
+    /// HTTP status for a failed operation with this code
+    ///
+    /// Codes without a more precise status are reported as 500.
+    pub fn status(&self) -> u16 {
+        match self {
+            ErrorCode::InvalidRequest | ErrorCode::InvalidAddress | ErrorCode::InvalidCertId => 400,
+            ErrorCode::Forbidden => 403,
+            ErrorCode::NotFound | ErrorCode::CertNotFound => 404,
+            ErrorCode::DuplicateCertId | ErrorCode::ReplayedRequest => 409,
+            ErrorCode::CertRevoked => 410,
+            ErrorCode::QuotaExceeded => 429,
+            _ => 500,
+        }
+    }
+
+    /// Classify a failure reported by the Soroban client
+    ///
+    /// Typed contract errors are mapped by their code. Failures the contract
+    /// still reports by panicking surface as text in the RPC's simulation
+    /// error, so this is the one place that knows their messages.
+    pub fn classify(error: &anyhow::Error) -> Self {
+        if let Some(contract_error) = ContractError::find(error) {
+            return contract_error.into();
+        }
+
+        let message = error.to_string();
+        let has = |needle: &str| message.contains(needle);
+
+        if has("Certificate ID was burned") {
+            ErrorCode::DuplicateCertId
+        } else if has("Certificate ID cannot be empty") {
+            ErrorCode::InvalidCertId
+        } else if has("invalid certificate") {
+            ErrorCode::CertRevoked
+        } else if has("Certificate is suspended")
+            || has("Certificate is locked")
+            || has("Certificate is disputed")
+        {
+            ErrorCode::CertLocked
+        } else if has("Contract not initialized") {
+            ErrorCode::ContractNotInitialized
+        } else if has("Contract is paused") {
+            ErrorCode::ContractPaused
+        } else if has("Invalid Stellar address") || has("held by a contract") {
+            ErrorCode::InvalidAddress
+        } else if has("Invalid QR payload") {
+            ErrorCode::QrInvalid
+        } else if has("QR payload expired") {
+            ErrorCode::QrExpired
+        } else if has("Invalid transaction") {
+            ErrorCode::InvalidRequest
+        } else if has("Missing required role") || has("Error(Auth")
+        {
+            ErrorCode::Forbidden
+        } else if has("not confirmed") {
+            ErrorCode::TransactionNotConfirmed
+        } else if has("rejected") {
+            ErrorCode::TransactionRejected
+        } else if has("HTTP request failed")
+            || has("RPC error")
+            || has("Failed to parse JSON response")
+            || has("try again later")
+            || has("circuit open")
+        {
+            ErrorCode::RpcUnavailable
+        } else {
+            ErrorCode::InternalError
+        }
+    }
+}
+
+impl From<ContractError> for ErrorCode {
+    fn from(error: ContractError) -> Self {
+        match error {
+            ContractError::InvalidCertIdLength | ContractError::InvalidCertIdChar => {
+                ErrorCode::InvalidCertId
+            }
+            ContractError::CertificateNotFound => ErrorCode::CertNotFound,
+            ContractError::CertificateExists => ErrorCode::DuplicateCertId,
+            ContractError::NotAuthorized => ErrorCode::Forbidden,
+            ContractError::CertificateRevoked => ErrorCode::CertRevoked,
+        }
+    }
 }
 
 /// Error response structure
@@ -108,6 +1130,22 @@ pub struct ErrorResponse {
     pub success: bool,
     pub error: String,
     pub code: u16,
+    pub error_code: ErrorCode,
+    /// Request field the error refers to, for validation errors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// Every field that failed validation, for 422 responses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Vec<FieldError>>,
+}
+
+/// A request field that broke one of its model's validation rules
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    /// Rule that failed, e.g. `length`, `cert_id` or `cid`
+    pub code: String,
+    pub message: String,
 }
 
 impl<T> ApiResponse<T> {
@@ -115,7 +1153,7 @@ impl<T> ApiResponse<T> {
         Self {
             success: true,
             data: Some(data),
-            message,
+            message: i18n::translate(message),
         }
     }
 
@@ -123,7 +1161,7 @@ impl<T> ApiResponse<T> {
         ApiResponse {
             success: true,
             data: Some(()),
-            message,
+            message: i18n::translate(message),
         }
     }
 
@@ -131,7 +1169,7 @@ impl<T> ApiResponse<T> {
         ApiResponse {
             success: false,
             data: None,
-            message,
+            message: i18n::translate(message),
         }
     }
 }
@@ -140,8 +1178,41 @@ impl ErrorResponse {
     pub fn new(error: String, code: u16) -> Self {
         Self {
             success: false,
-            error,
+            error: i18n::translate(error),
             code,
+            error_code: ErrorCode::for_status(code),
+            field: None,
+            errors: None,
+        }
+    }
+
+    /// Replace the code derived from the HTTP status with a more precise one
+    pub fn with_code(mut self, error_code: ErrorCode) -> Self {
+        self.error_code = error_code;
+        self
+    }
+
+    /// A 400 naming the request field that failed validation
+    pub fn invalid_field(field: &str, error: String) -> Self {
+        Self {
+            field: Some(field.to_string()),
+            ..Self::bad_request(error)
+        }
+    }
+
+    /// A 422 listing every field that failed validation
+    ///
+    /// `field` names the first of them, for clients reading a single field.
+    pub fn invalid_fields(mut errors: Vec<FieldError>) -> Self {
+        for error in &mut errors {
+            error.message = i18n::translate(std::mem::take(&mut error.message));
+        }
+        let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+        let error = messages.join("; ");
+        Self {
+            field: errors.first().map(|e| e.field.clone()),
+            errors: Some(errors),
+            ..Self::new(error, 422)
         }
     }
 
@@ -149,6 +1220,14 @@ impl ErrorResponse {
         Self::new(error, 400)
     }
 
+    pub fn unauthorized(error: String) -> Self {
+        Self::new(error, 401)
+    }
+
+    pub fn forbidden(error: String) -> Self {
+        Self::new(error, 403)
+    }
+
     pub fn not_found(error: String) -> Self {
         Self::new(error, 404)
     }
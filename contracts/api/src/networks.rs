@@ -0,0 +1,152 @@
+use anyhow::{anyhow, Result};
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+    Json,
+};
+use serde::Deserialize;
+use std::{collections::HashMap, sync::Arc};
+use tracing::info;
+
+use crate::{
+    cache::{CacheSettings, ResponseCache},
+    contract_client::ContractClient,
+    models::ErrorResponse,
+    soroban_client::SorobanClient,
+};
+
+/// Header selecting the network profile a request is served from
+pub const NETWORK_HEADER: &str = "x-network";
+
+/// A registry deployment the API can serve alongside its default one
+#[derive(Debug, Clone, Deserialize)]
+pub struct NetworkProfile {
+    pub name: String,
+    pub rpc_url: String,
+    pub network_passphrase: String,
+    pub contract_id: String,
+}
+
+/// Tunables for serving several networks from one instance
+#[derive(Debug, Clone)]
+pub struct NetworkSettings {
+    /// Name of the default profile, built from the `SOROBAN_*` settings
+    pub default_name: String,
+    /// Additional profiles selectable with `X-Network`
+    pub profiles: Vec<NetworkProfile>,
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            default_name: "default".to_string(),
+            profiles: Vec::new(),
+        }
+    }
+}
+
+/// The network profile a request is served from
+///
+/// Inserted into request extensions by [`select_network`].
+#[derive(Clone)]
+pub struct Network {
+    pub name: String,
+    pub client: Arc<dyn ContractClient>,
+    /// Background services (job queue, indexer, webhooks, rent) only run here
+    pub is_default: bool,
+}
+
+/// The named network profiles served by this instance
+#[derive(Clone)]
+pub struct Networks {
+    default: Network,
+    others: Arc<HashMap<String, Network>>,
+}
+
+impl Networks {
+    /// Serve only the default network
+    pub fn single(name: &str, client: Arc<dyn ContractClient>) -> Self {
+        Self {
+            default: Network {
+                name: name.to_string(),
+                client,
+                is_default: true,
+            },
+            others: Arc::new(HashMap::new()),
+        }
+    }
+
+    /// Build clients for every additional profile
+    ///
+    /// They share the default client's signer, and each gets its own response
+    /// cache namespaced by its contract ID.
+    pub async fn connect(
+        default: SorobanClient,
+        settings: &NetworkSettings,
+        cache: &CacheSettings,
+    ) -> Result<Self> {
+        let mut others = HashMap::new();
+        for profile in &settings.profiles {
+            if profile.name == settings.default_name || others.contains_key(&profile.name) {
+                return Err(anyhow!("Duplicate network profile name: {}", profile.name));
+            }
+
+            let cache = ResponseCache::connect(cache.clone(), &profile.contract_id).await?;
+            let network = Network {
+                name: profile.name.clone(),
+                client: Arc::new(default.for_network(profile).with_cache(cache)),
+                is_default: false,
+            };
+            info!("Serving network {} (contract {})", profile.name, profile.contract_id);
+            others.insert(profile.name.clone(), network);
+        }
+
+        let mut networks = Self::single(&settings.default_name, Arc::new(default));
+        networks.others = Arc::new(others);
+        Ok(networks)
+    }
+
+    /// The network named `name`, or the default one when no name is given
+    pub fn get(&self, name: Option<&str>) -> Option<&Network> {
+        match name {
+            None => Some(&self.default),
+            Some(name) if name == self.default.name => Some(&self.default),
+            Some(name) => self.others.get(name),
+        }
+    }
+
+    /// Names of every served network, the default one first
+    pub fn names(&self) -> Vec<String> {
+        let mut others: Vec<String> = self.others.keys().cloned().collect();
+        others.sort();
+        std::iter::once(self.default.name.clone()).chain(others).collect()
+    }
+}
+
+/// Resolve the request's `X-Network` header into a [`Network`] extension
+pub async fn select_network(
+    State(networks): State<Networks>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let name = request
+        .headers()
+        .get(NETWORK_HEADER)
+        .map(|value| value.to_str().unwrap_or_default().trim());
+
+    let network = networks.get(name).cloned().ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::bad_request(format!(
+                "Unknown network {}; expected one of: {}",
+                name.unwrap_or_default(),
+                networks.names().join(", ")
+            ))),
+        )
+    })?;
+
+    request.extensions_mut().insert(network);
+    Ok(next.run(request).await)
+}
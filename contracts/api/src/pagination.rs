@@ -0,0 +1,152 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD as BASE64URL, Engine};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// Values accepted by `sort=`; prefix one with `-` for descending order
+pub const CERTIFICATE_SORTS: &[&str] = &["cert_id", "issued_at", "last_transfer"];
+
+/// Field certificates are ordered by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortField {
+    #[default]
+    CertId,
+    IssuedAt,
+    LastTransfer,
+}
+
+/// Order certificates are listed in
+///
+/// Every order ends with the certificate ID, so rows that share a sort key
+/// still come back in the same sequence on every request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CertificateSort {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+impl CertificateSort {
+    /// Parse a `sort=` value such as `issued_at` or `-last_transfer`
+    pub fn parse(sort: &str) -> Option<Self> {
+        let (descending, field) = match sort.strip_prefix('-') {
+            Some(field) => (true, field),
+            None => (false, sort),
+        };
+        let field = match field {
+            "cert_id" => SortField::CertId,
+            "issued_at" => SortField::IssuedAt,
+            "last_transfer" => SortField::LastTransfer,
+            _ => return None,
+        };
+        Some(Self { field, descending })
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match (self.field, self.descending) {
+            (SortField::CertId, false) => "cert_id",
+            (SortField::CertId, true) => "-cert_id",
+            (SortField::IssuedAt, false) => "issued_at",
+            (SortField::IssuedAt, true) => "-issued_at",
+            (SortField::LastTransfer, false) => "last_transfer",
+            (SortField::LastTransfer, true) => "-last_transfer",
+        }
+    }
+
+    /// Column expression ordered on before the certificate ID, if any
+    ///
+    /// Issuance is ordered by ledger rather than close time: ledgers close in
+    /// sequence and, unlike `issued_at`, the ledger is never missing.
+    /// Certificates that were never transferred sort as if transferred at
+    /// ledger 0.
+    pub fn key(self) -> Option<&'static str> {
+        match self.field {
+            SortField::CertId => None,
+            SortField::IssuedAt => Some("issued_ledger"),
+            SortField::LastTransfer => Some("COALESCE(last_transfer_ledger, 0)"),
+        }
+    }
+
+    /// `ORDER BY` clause for this sort
+    pub fn order_by(self) -> String {
+        let direction = if self.descending { "DESC" } else { "ASC" };
+        match self.key() {
+            Some(key) => format!("{} {}, cert_id {}", key, direction, direction),
+            None => format!("cert_id {}", direction),
+        }
+    }
+
+    /// Condition selecting the rows after a cursor
+    ///
+    /// The cursor's certificate ID is bound to `$1` and its sort key to
+    /// `key_param`; a NULL ID selects every row.
+    pub fn after(self, key_param: &str) -> String {
+        let comparison = if self.descending { "<" } else { ">" };
+        match self.key() {
+            Some(key) => format!(
+                "($1::text IS NULL OR ({}, cert_id) {} ({}::bigint, $1))",
+                key, comparison, key_param
+            ),
+            None => format!("($1::text IS NULL OR cert_id {} $1)", comparison),
+        }
+    }
+}
+
+/// Position after the last certificate of a page
+///
+/// Cursors are keyed on the last row's sort key and ID rather than an offset,
+/// so certificates issued or transferred while a client pages through the
+/// registry never shift rows between pages or repeat them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CertificateCursor {
+    /// `sort=` value of the listing the cursor came from
+    pub sort: String,
+    pub key: Option<i64>,
+    pub cert_id: String,
+}
+
+impl CertificateCursor {
+    pub fn new(sort: CertificateSort, key: Option<i64>, cert_id: &str) -> Self {
+        Self {
+            sort: sort.as_str().to_string(),
+            key: key.filter(|_| sort.key().is_some()),
+            cert_id: cert_id.to_string(),
+        }
+    }
+
+    /// Opaque token handed to clients as `next_cursor`
+    pub fn encode(&self) -> String {
+        encode_cursor(self)
+    }
+
+    /// Read a token produced by `encode` for a listing with the same `sort`
+    pub fn decode(token: &str, sort: CertificateSort) -> Option<Self> {
+        let cursor: Self = decode_cursor(token)?;
+        let keyed = sort.key().is_some();
+        (cursor.sort == sort.as_str() && cursor.key.is_some() == keyed).then_some(cursor)
+    }
+}
+
+/// Position after the last audit entry of a page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditCursor {
+    /// ID of the last entry returned; the next page starts before it
+    pub before: i64,
+}
+
+impl AuditCursor {
+    pub fn encode(&self) -> String {
+        encode_cursor(self)
+    }
+
+    pub fn decode(token: &str) -> Option<Self> {
+        decode_cursor(token)
+    }
+}
+
+fn encode_cursor<T: Serialize>(cursor: &T) -> String {
+    let json = serde_json::to_vec(cursor).expect("cursor serializes to JSON");
+    BASE64URL.encode(json)
+}
+
+fn decode_cursor<T: DeserializeOwned>(token: &str) -> Option<T> {
+    let json = BASE64URL.decode(token.trim()).ok()?;
+    serde_json::from_slice(&json).ok()
+}
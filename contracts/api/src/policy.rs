@@ -0,0 +1,185 @@
+use axum::{
+    extract::{Path, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+    Json,
+};
+use std::collections::HashMap;
+use tracing::{error, warn};
+
+use crate::{
+    auth::{bearer_claims, Authenticator, Claims, Role},
+    models::{CertId, ErrorCode, ErrorResponse},
+    networks::Network,
+    tenants::Tenant,
+};
+
+/// Check a route makes beyond the caller's roles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ownership {
+    /// The certificate named by the route's `:id` must be held by the token's
+    /// subject
+    Certificate,
+}
+
+/// What a route requires of its caller, evaluated by [`enforce`]
+///
+/// Routes declare their policy where they're mounted, so handlers can assume
+/// the caller is allowed in and only deal with the request itself.
+#[derive(Clone)]
+pub struct Policy {
+    auth: Authenticator,
+    roles: &'static [Role],
+    /// Check the token even when authentication is disabled
+    token_required: bool,
+    ownership: Option<Ownership>,
+}
+
+impl Policy {
+    pub fn new(auth: Authenticator, roles: &'static [Role], token_required: bool) -> Self {
+        Self {
+            auth,
+            roles,
+            token_required,
+            ownership: None,
+        }
+    }
+
+    /// Also require `ownership` of callers whose only role is `owner`
+    ///
+    /// Staff roles act on any certificate; owner session tokens only on
+    /// their own.
+    pub fn with_ownership(mut self, ownership: Ownership) -> Self {
+        self.ownership = Some(ownership);
+        self
+    }
+
+    /// Whether `claims` grant one of the policy's roles
+    fn admits(&self, claims: &Claims) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+        if claims.roles.iter().any(|role| self.roles.contains(role)) {
+            return Ok(());
+        }
+
+        let allowed: Vec<&str> = self.roles.iter().map(Role::as_str).collect();
+        Err((
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::forbidden(format!(
+                "Requires one of the roles: {}",
+                allowed.join(", ")
+            ))),
+        ))
+    }
+
+    /// Ownership check `claims` are subject to, if any
+    fn ownership_for(&self, claims: &Claims) -> Option<Ownership> {
+        let owner_only = claims.roles.iter().all(|role| *role == Role::Owner);
+        self.ownership.filter(|_| owner_only)
+    }
+}
+
+/// Reject requests that don't satisfy the route's [`Policy`]
+///
+/// Answers 401 for a missing or invalid token and 403 for a valid token
+/// lacking the role or, on routes checking ownership, the certificate, and
+/// for a token scoped to another tenant than the request's. The verified
+/// claims are added to the request extensions. Requests authenticated with a
+/// tenant API key are checked against the claims it was resolved to instead.
+pub async fn enforce(
+    State(policy): State<Policy>,
+    params: Option<Path<HashMap<String, String>>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    if !policy.auth.is_enabled() && !policy.token_required {
+        return Ok(next.run(request).await);
+    }
+
+    // Tenant API keys are checked before routing and leave their claims behind
+    let claims = match request.extensions().get::<Claims>() {
+        Some(claims) => claims.clone(),
+        None => bearer_claims(&policy.auth, &request)?,
+    };
+    let tenant = request.extensions().get::<Tenant>().map(|tenant| tenant.id.as_str());
+    check_tenant_scope(&claims, tenant)?;
+    policy.admits(&claims)?;
+
+    if let Some(Ownership::Certificate) = policy.ownership_for(&claims) {
+        let id = params
+            .as_ref()
+            .and_then(|Path(params)| params.get("id"))
+            .map(String::as_str)
+            .unwrap_or_default();
+        let cert_id = CertId::parse(id).map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::invalid_field("cert_id", e).with_code(ErrorCode::InvalidCertId)),
+            )
+        })?;
+        let network = request.extensions().get::<Network>().cloned().ok_or_else(|| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::internal_error("No network selected".to_string())),
+            )
+        })?;
+        check_certificate_owner(&network, &cert_id, &claims).await?;
+    }
+
+    request.extensions_mut().insert(claims);
+    Ok(next.run(request).await)
+}
+
+/// Refuse tokens scoped to another tenant than the request's
+///
+/// Platform tokens only act on the platform's registry and a tenant's tokens
+/// only on that tenant's contract, whatever `X-Tenant` says.
+pub fn check_tenant_scope(claims: &Claims, tenant: Option<&str>) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    if claims.tenant.as_deref() == tenant {
+        return Ok(());
+    }
+
+    warn!("{} refused access to tenant {:?} outside its scope {:?}", claims.sub, tenant, claims.tenant);
+    let message = match tenant {
+        Some(tenant) => format!("Token is not valid for tenant {}", tenant),
+        None => "Tenant tokens must be used with their tenant".to_string(),
+    };
+    Err((StatusCode::FORBIDDEN, Json(ErrorResponse::forbidden(message))))
+}
+
+/// Refuse unless `cert_id` is currently held by the token's subject
+async fn check_certificate_owner(
+    network: &Network,
+    cert_id: &CertId,
+    claims: &Claims,
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    let certificate = network
+        .client
+        .get_certificate_details(cert_id)
+        .await
+        .map_err(|e| {
+            let code = ErrorCode::classify(&e);
+            if code != ErrorCode::CertNotFound {
+                error!("Failed to look up owner of {}: {}", cert_id, e);
+            }
+            let status = code.status();
+            (
+                StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+                Json(
+                    ErrorResponse::new(format!("Failed to check owner of {}: {}", cert_id, e), status)
+                        .with_code(code),
+                ),
+            )
+        })?;
+
+    if certificate.owner != claims.sub {
+        warn!("{} refused access to certificate {} held by another account", claims.sub, cert_id);
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::forbidden(format!(
+                "Certificate {} is not held by {}",
+                cert_id, claims.sub
+            ))),
+        ));
+    }
+    Ok(())
+}
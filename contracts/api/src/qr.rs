@@ -0,0 +1,129 @@
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD as BASE64URL, Engine};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use utoipa::ToSchema;
+
+use crate::signer::{verify_signature, Signer};
+
+/// Version tag starting every payload
+const PAYLOAD_PREFIX: &str = "VLX1";
+
+/// Tunables for QR verification payloads
+#[derive(Debug, Clone)]
+pub struct QrSettings {
+    /// How long a minted payload stays valid
+    pub ttl_secs: u64,
+}
+
+impl Default for QrSettings {
+    fn default() -> Self {
+        Self {
+            ttl_secs: 365 * 24 * 3600,
+        }
+    }
+}
+
+/// What a QR payload vouches for
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct QrClaims {
+    pub cert_id: String,
+    pub metadata_hash: String,
+    pub issued_at: u64,
+    pub expires_at: u64,
+}
+
+/// A signed payload ready to be rendered as a QR code
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct QrPayload {
+    pub payload: String,
+    pub cert_id: String,
+    pub expires_at: u64,
+}
+
+/// Mints and checks the payloads printed as VeriLuxe QR codes
+///
+/// A payload is `VLX1.<claims>.<signature>`: the claims are base64url JSON
+/// and the signature is the admin signer's Ed25519 signature of the SHA-256
+/// of `VLX1.<claims>`, also base64url. Scanning apps can check it offline
+/// with the admin's public key before asking the API.
+#[derive(Clone)]
+pub struct QrCodec {
+    signer: Arc<dyn Signer>,
+    settings: QrSettings,
+}
+
+impl QrCodec {
+    /// Build the codec when an admin signer is available
+    pub fn new(signer: Option<Arc<dyn Signer>>, settings: QrSettings) -> Option<Self> {
+        signer.map(|signer| Self { signer, settings })
+    }
+
+    /// Sign a payload binding `cert_id` to `metadata_hash`
+    pub async fn mint(&self, cert_id: &str, metadata_hash: &str) -> Result<QrPayload> {
+        let issued_at = now_secs();
+        let claims = QrClaims {
+            cert_id: cert_id.to_string(),
+            metadata_hash: metadata_hash.to_string(),
+            issued_at,
+            expires_at: issued_at + self.settings.ttl_secs,
+        };
+
+        let claims_json = serde_json::to_vec(&claims)
+            .map_err(|e| anyhow!("Failed to encode QR claims: {}", e))?;
+        let signed = format!("{}.{}", PAYLOAD_PREFIX, BASE64URL.encode(claims_json));
+        let hash: [u8; 32] = Sha256::digest(signed.as_bytes()).into();
+        let signature = self.signer.sign(&hash).await?;
+
+        Ok(QrPayload {
+            payload: format!("{}.{}", signed, BASE64URL.encode(signature)),
+            cert_id: claims.cert_id,
+            expires_at: claims.expires_at,
+        })
+    }
+
+    /// Check a scanned payload's signature and expiry, returning its claims
+    ///
+    /// Errors start with "Invalid QR payload" or "QR payload expired".
+    pub fn decode(&self, payload: &str) -> Result<QrClaims> {
+        let payload = payload.trim();
+        let (signed, signature) = payload
+            .rsplit_once('.')
+            .ok_or_else(|| anyhow!("Invalid QR payload: missing signature"))?;
+        let claims = signed
+            .strip_prefix(PAYLOAD_PREFIX)
+            .and_then(|rest| rest.strip_prefix('.'))
+            .ok_or_else(|| anyhow!("Invalid QR payload: not a VeriLuxe code"))?;
+
+        let signature: [u8; 64] = BASE64URL
+            .decode(signature)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| anyhow!("Invalid QR payload: malformed signature"))?;
+        let hash: [u8; 32] = Sha256::digest(signed.as_bytes()).into();
+        verify_signature(&self.signer.public_key(), &hash, &signature)
+            .map_err(|_| anyhow!("Invalid QR payload: signature does not match"))?;
+
+        let claims: QrClaims = BASE64URL
+            .decode(claims)
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .ok_or_else(|| anyhow!("Invalid QR payload: malformed claims"))?;
+
+        if claims.expires_at < now_secs() {
+            return Err(anyhow!("QR payload expired at {}", claims.expires_at));
+        }
+        Ok(claims)
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,311 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::{sync::Arc, time::Duration};
+use tokio::{sync::RwLock, task::JoinHandle};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+use crate::{
+    contract_client::ContractClient,
+    indexer::{CertificateFilter, Indexer},
+    models::{CertId, CertificateSummary},
+    pagination::{CertificateCursor, CertificateSort},
+};
+
+/// Most drift entries kept in a report; the counts cover all of them
+const MAX_REPORTED_DRIFT: usize = 500;
+
+/// Tunables for reconciling the index with the chain
+#[derive(Debug, Clone)]
+pub struct ReconcileSettings {
+    pub enabled: bool,
+    pub interval_secs: u64,
+    /// Certificates read from the index per page
+    pub batch_size: u32,
+    /// Rewrite drifted rows from the chain, rather than only reporting them
+    pub repair: bool,
+}
+
+impl Default for ReconcileSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 3600,
+            batch_size: 100,
+            repair: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReconciliationStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// A field whose indexed value differs from the chain
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Drift {
+    pub cert_id: String,
+    /// `owner`, `metadata_hash` or `status`
+    pub field: String,
+    pub indexed: String,
+    pub on_chain: String,
+    /// Whether the index was rewritten with the chain's value
+    pub repaired: bool,
+}
+
+/// Outcome of the latest reconciliation run
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReconciliationReport {
+    pub status: ReconciliationStatus,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Indexed certificates compared with the chain so far
+    pub checked: u64,
+    /// Certificates with at least one drifted field
+    pub drifted: u64,
+    pub repaired: u64,
+    /// Certificates that couldn't be read from the chain or repaired
+    pub errors: u64,
+    /// Drifted fields, up to 500
+    pub drift: Vec<Drift>,
+    /// Why the run stopped, if it failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// What the chain holds for an indexed certificate
+struct ChainCertificate {
+    owner: String,
+    metadata_hash: String,
+    /// Index status: `active`, `revoked` or `burned`
+    status: &'static str,
+}
+
+/// Background task comparing the event index against on-chain state
+///
+/// Indexed certificates are re-read from the contract page by page. Owners,
+/// metadata hashes and statuses that differ, e.g. because an event was missed
+/// or the RPC served a stale ledger, are reported and, with `repair`, copied
+/// from the chain. Certificates that were never indexed can't be found this
+/// way. Only the latest report is kept, in memory.
+#[derive(Clone)]
+pub struct Reconciler {
+    client: Arc<dyn ContractClient>,
+    indexer: Indexer,
+    settings: ReconcileSettings,
+    report: Arc<RwLock<Option<ReconciliationReport>>>,
+}
+
+impl Reconciler {
+    /// A reconciler for the indexer's database, if enabled
+    pub fn new(
+        client: Arc<dyn ContractClient>,
+        indexer: Option<&Indexer>,
+        settings: ReconcileSettings,
+    ) -> Result<Option<Self>> {
+        if !settings.enabled {
+            info!("Reconciliation disabled");
+            return Ok(None);
+        }
+        let indexer = indexer
+            .ok_or_else(|| anyhow!("Reconciliation requires the indexer (INDEXER_ENABLED)"))?;
+
+        Ok(Some(Self {
+            client,
+            indexer: indexer.clone(),
+            settings,
+            report: Arc::new(RwLock::new(None)),
+        }))
+    }
+
+    /// Reconcile every `interval_secs`, starting one interval after startup
+    pub fn spawn(&self) -> JoinHandle<()> {
+        let reconciler = self.clone();
+        tokio::spawn(async move {
+            let period = Duration::from_secs(reconciler.settings.interval_secs);
+            let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            loop {
+                interval.tick().await;
+                reconciler.run().await;
+            }
+        })
+    }
+
+    /// The latest report, or the one being built
+    pub async fn report(&self) -> Option<ReconciliationReport> {
+        self.report.read().await.clone()
+    }
+
+    /// Reconcile the whole index now, replacing the latest report
+    pub async fn run(&self) {
+        info!("Reconciling the certificate index with the chain");
+        *self.report.write().await = Some(ReconciliationReport {
+            status: ReconciliationStatus::Running,
+            started_at: Utc::now(),
+            finished_at: None,
+            checked: 0,
+            drifted: 0,
+            repaired: 0,
+            errors: 0,
+            drift: Vec::new(),
+            error: None,
+        });
+
+        let result = self.reconcile_all().await;
+
+        let mut report = self.report.write().await;
+        if let Some(report) = report.as_mut() {
+            report.finished_at = Some(Utc::now());
+            match result {
+                Ok(()) => {
+                    report.status = ReconciliationStatus::Completed;
+                    info!(
+                        "Reconciliation checked {} certificates: {} drifted, {} repaired, {} errors",
+                        report.checked, report.drifted, report.repaired, report.errors
+                    );
+                }
+                Err(e) => {
+                    error!("Reconciliation failed: {}", e);
+                    report.status = ReconciliationStatus::Failed;
+                    report.error = Some(e.to_string());
+                }
+            }
+        }
+    }
+
+    async fn reconcile_all(&self) -> Result<()> {
+        let filter = CertificateFilter::default();
+        let sort = CertificateSort::default();
+        let mut cursor = None;
+        loop {
+            let page = self
+                .indexer
+                .list_certificates(&filter, sort, self.settings.batch_size, cursor.as_ref())
+                .await?;
+
+            for certificate in &page.certificates {
+                self.reconcile(certificate).await;
+            }
+
+            // Pages are in ID order, so the last ID is all the cursor needs
+            match (&page.next_cursor, page.certificates.last()) {
+                (Some(_), Some(last)) => {
+                    cursor = Some(CertificateCursor::new(sort, None, &last.cert_id))
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// Compare one certificate and record the outcome in the report
+    async fn reconcile(&self, indexed: &CertificateSummary) {
+        let drift = match self.chain_certificate(&indexed.cert_id).await {
+            Ok(on_chain) => compare(indexed, &on_chain).map(|drift| (drift, on_chain)),
+            Err(e) => {
+                warn!("Failed to read certificate {} from the chain: {}", indexed.cert_id, e);
+                self.record(|report| report.errors += 1).await;
+                return;
+            }
+        };
+
+        let Some((mut drift, on_chain)) = drift else {
+            self.record(|report| report.checked += 1).await;
+            return;
+        };
+        warn!(
+            "Certificate {} drifted from the chain: {}",
+            indexed.cert_id,
+            drift.iter().map(|d| d.field.as_str()).collect::<Vec<_>>().join(", ")
+        );
+
+        let mut repaired = false;
+        let mut failed = false;
+        if self.settings.repair {
+            let live = on_chain.status != "burned";
+            match self
+                .indexer
+                .repair_certificate(
+                    &indexed.cert_id,
+                    on_chain.status,
+                    live.then_some(on_chain.owner.as_str()),
+                    live.then_some(on_chain.metadata_hash.as_str()),
+                )
+                .await
+            {
+                Ok(()) => {
+                    repaired = true;
+                    drift.iter_mut().for_each(|d| d.repaired = true);
+                }
+                Err(e) => {
+                    error!("Failed to repair indexed certificate {}: {}", indexed.cert_id, e);
+                    failed = true;
+                }
+            }
+        }
+
+        self.record(|report| {
+            report.checked += 1;
+            report.drifted += 1;
+            report.repaired += u64::from(repaired);
+            report.errors += u64::from(failed);
+            let room = MAX_REPORTED_DRIFT.saturating_sub(report.drift.len());
+            report.drift.extend(drift.into_iter().take(room));
+        })
+        .await;
+    }
+
+    async fn chain_certificate(&self, cert_id: &str) -> Result<ChainCertificate> {
+        let cert_id = CertId::parse(cert_id).map_err(|e| anyhow!("{}", e))?;
+        if !self.client.certificate_exists(&cert_id).await? {
+            return Ok(ChainCertificate {
+                owner: String::new(),
+                metadata_hash: String::new(),
+                status: "burned",
+            });
+        }
+
+        let certificate = self.client.get_certificate_details(&cert_id).await?;
+        Ok(ChainCertificate {
+            owner: certificate.owner,
+            metadata_hash: certificate.metadata_hash,
+            status: if certificate.is_valid { "active" } else { "revoked" },
+        })
+    }
+
+    async fn record(&self, change: impl FnOnce(&mut ReconciliationReport)) {
+        if let Some(report) = self.report.write().await.as_mut() {
+            change(report);
+        }
+    }
+}
+
+/// Fields of `indexed` that differ from the chain, if any
+///
+/// Burned certificates no longer have an owner or hash on chain, so only
+/// their status is compared.
+fn compare(indexed: &CertificateSummary, on_chain: &ChainCertificate) -> Option<Vec<Drift>> {
+    let mut fields = vec![("status", indexed.status.as_str(), on_chain.status)];
+    if on_chain.status != "burned" {
+        fields.push(("owner", &indexed.owner, &on_chain.owner));
+        fields.push(("metadata_hash", &indexed.metadata_hash, &on_chain.metadata_hash));
+    }
+
+    let drift: Vec<Drift> = fields
+        .into_iter()
+        .filter(|(_, indexed_value, chain_value)| indexed_value != chain_value)
+        .map(|(field, indexed_value, chain_value)| Drift {
+            cert_id: indexed.cert_id.clone(),
+            field: field.to_string(),
+            indexed: indexed_value.to_string(),
+            on_chain: chain_value.to_string(),
+            repaired: false,
+        })
+        .collect();
+
+    (!drift.is_empty()).then_some(drift)
+}
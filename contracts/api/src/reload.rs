@@ -0,0 +1,168 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::{sync::Mutex, task::JoinHandle};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+use crate::{
+    config::{Config, ConfigOptions, ResolvedSetting},
+    cors::CorsOrigins,
+    soroban_client::SorobanClient,
+    webhooks::WebhookDispatcher,
+};
+
+/// Settings a reload applies; changes to any other only take effect on restart
+pub const RELOADABLE_SETTINGS: &[&str] = &[
+    "CORS_ALLOWED_ORIGINS",
+    "FEE_BASE",
+    "FEE_MAX",
+    "FEE_STRATEGY",
+    "WEBHOOK_ENDPOINTS",
+    "WEBHOOK_MAX_ATTEMPTS",
+    "WEBHOOK_RETRY_DELAY_MS",
+    "WEBHOOK_MAX_RETRY_DELAY_MS",
+    "WEBHOOK_TIMEOUT_SECS",
+];
+
+/// Outcome of a configuration reload
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReloadReport {
+    pub reloaded_at: DateTime<Utc>,
+    /// Changed settings now in effect
+    pub applied: Vec<String>,
+    /// Changed settings that are ignored until the API restarts
+    pub requires_restart: Vec<String>,
+}
+
+/// Re-reads the configuration and applies its tunable settings in place
+///
+/// Reloads go through the same layering and validation as startup, so an
+/// invalid settings file is refused and the running settings stay in effect.
+/// Nothing is restarted: requests and transactions in flight finish with the
+/// settings they started with, and the next ones use the new values.
+#[derive(Clone)]
+pub struct ConfigReloader {
+    options: ConfigOptions,
+    client: SorobanClient,
+    webhooks: WebhookDispatcher,
+    cors: CorsOrigins,
+    /// Settings in effect; also keeps reloads from running concurrently
+    settings: Arc<Mutex<Vec<ResolvedSetting>>>,
+}
+
+impl ConfigReloader {
+    /// A reloader for settings loaded with `options`, starting from `settings`
+    pub fn new(
+        options: ConfigOptions,
+        settings: Vec<ResolvedSetting>,
+        client: SorobanClient,
+        webhooks: WebhookDispatcher,
+        cors: CorsOrigins,
+    ) -> Self {
+        Self {
+            options,
+            client,
+            webhooks,
+            cors,
+            settings: Arc::new(Mutex::new(settings)),
+        }
+    }
+
+    /// Reload the configuration and apply the reloadable settings
+    pub async fn reload(&self) -> Result<ReloadReport> {
+        let mut current = self.settings.lock().await;
+        let (config, reloaded) = Config::load(&self.options)?;
+
+        let (applied, requires_restart): (Vec<String>, Vec<String>) = reloaded
+            .iter()
+            .filter(|setting| {
+                current
+                    .iter()
+                    .find(|running| running.name == setting.name)
+                    .map_or(true, |running| running.value != setting.value)
+            })
+            .map(|setting| setting.name.clone())
+            .partition(|name| RELOADABLE_SETTINGS.contains(&name.as_str()));
+
+        self.client.set_fee_settings(config.fees);
+        self.webhooks.apply_settings(config.webhooks).await;
+        self.cors.set(&config.cors);
+
+        // Restart-only settings keep their running values, so they're
+        // reported again on every reload until the API restarts
+        for setting in reloaded {
+            if !RELOADABLE_SETTINGS.contains(&setting.name.as_str()) {
+                continue;
+            }
+            match current.iter_mut().find(|running| running.name == setting.name) {
+                Some(running) => *running = setting,
+                None => current.push(setting),
+            }
+        }
+
+        if applied.is_empty() {
+            info!("Reloaded configuration, no reloadable settings changed");
+        } else {
+            info!("Reloaded configuration, applied: {}", applied.join(", "));
+        }
+        if !requires_restart.is_empty() {
+            warn!(
+                "Changed settings only take effect after a restart: {}",
+                requires_restart.join(", ")
+            );
+        }
+
+        Ok(ReloadReport {
+            reloaded_at: Utc::now(),
+            applied,
+            requires_restart,
+        })
+    }
+
+    /// Fetch the secrets provider's secret periodically, reloading when it changed
+    ///
+    /// Changed secrets are applied like any other setting: reloadable ones
+    /// such as `WEBHOOK_ENDPOINTS` at once, the rest on the next restart.
+    pub fn spawn_secret_refresh(&self) -> Option<JoinHandle<()>> {
+        let secrets = self.options.secrets.clone();
+        let interval = secrets.refresh_interval()?;
+        let reloader = self.clone();
+        Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // The first tick completes at once, and the secret was just fetched
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                match secrets.refresh().await {
+                    Ok(false) => {}
+                    Ok(true) => {
+                        info!("Secret changed in the {} secrets provider, reloading configuration", secrets.provider());
+                        if let Err(e) = reloader.reload().await {
+                            error!("Failed to reload configuration, keeping the running settings: {:#}", e);
+                        }
+                    }
+                    Err(e) => warn!("Failed to refresh secrets, keeping the current values: {:#}", e),
+                }
+            }
+        }))
+    }
+
+    /// Reload whenever the process receives SIGHUP
+    #[cfg(unix)]
+    pub fn spawn_on_hangup(&self) -> Result<JoinHandle<()>> {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangups = signal(SignalKind::hangup())?;
+        let reloader = self.clone();
+        Ok(tokio::spawn(async move {
+            while hangups.recv().await.is_some() {
+                info!("Received SIGHUP, reloading configuration");
+                if let Err(e) = reloader.reload().await {
+                    error!("Failed to reload configuration, keeping the running settings: {:#}", e);
+                }
+            }
+        }))
+    }
+}
@@ -0,0 +1,163 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{sync::RwLock, task::JoinHandle};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+use crate::soroban_client::SorobanClient;
+
+/// Approximate number of ledgers closed in 30 days (one ledger every ~5 seconds)
+pub const LEDGERS_PER_MONTH: u32 = 518_400;
+
+/// Tunables for the rent funding manager
+#[derive(Debug, Clone)]
+pub struct RentSettings {
+    pub enabled: bool,
+    pub check_interval_secs: u64,
+    pub ttl_threshold_ledgers: u32,
+    pub extend_to_ledgers: u32,
+    pub stroops_per_ledger: i64,
+    pub monthly_budget_stroops: i64,
+}
+
+impl Default for RentSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            check_interval_secs: 3600,
+            ttl_threshold_ledgers: 120_960,
+            extend_to_ledgers: LEDGERS_PER_MONTH,
+            stroops_per_ledger: 10,
+            monthly_budget_stroops: 100_000_000,
+        }
+    }
+}
+
+/// Snapshot of the contract's TTL and projected rent spending
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RentForecast {
+    pub latest_ledger: u32,
+    pub instance_live_until_ledger: u32,
+    pub ledgers_remaining: u32,
+    pub funding_account: String,
+    pub funding_balance_stroops: i64,
+    pub projected_monthly_rent_stroops: i64,
+    pub monthly_budget_stroops: i64,
+    pub over_budget: bool,
+    pub months_of_funding_left: Option<f64>,
+    pub last_extension_tx: Option<String>,
+    pub generated_at: u64,
+}
+
+/// Background service keeping the contract's storage alive and funded
+#[derive(Clone)]
+pub struct RentManager {
+    client: SorobanClient,
+    settings: RentSettings,
+    latest: Arc<RwLock<Option<RentForecast>>>,
+}
+
+impl RentManager {
+    pub fn new(client: SorobanClient, settings: RentSettings) -> Self {
+        Self {
+            client,
+            settings,
+            latest: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Start the periodic monitoring loop, if enabled
+    pub fn spawn(&self) -> Option<JoinHandle<()>> {
+        if !self.settings.enabled {
+            info!("Rent manager disabled");
+            return None;
+        }
+
+        let manager = self.clone();
+        Some(tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(manager.settings.check_interval_secs));
+            loop {
+                interval.tick().await;
+                if let Err(e) = manager.run_once().await {
+                    error!("Rent manager check failed: {}", e);
+                }
+            }
+        }))
+    }
+
+    /// Most recent forecast, computing a fresh one if none has been produced yet
+    pub async fn forecast(&self) -> Result<RentForecast> {
+        if let Some(forecast) = self.latest.read().await.clone() {
+            return Ok(forecast);
+        }
+        self.compute_forecast(None).await
+    }
+
+    /// Check TTLs, extend them when close to expiry, and refresh the forecast
+    pub async fn run_once(&self) -> Result<RentForecast> {
+        let latest_ledger = self.client.get_latest_ledger().await?;
+        let live_until = self.client.get_contract_instance_live_until().await?;
+        let remaining = live_until.saturating_sub(latest_ledger);
+
+        let mut extension_tx = None;
+        if remaining < self.settings.ttl_threshold_ledgers {
+            info!(
+                "Contract TTL has {} ledgers left (threshold {}), extending",
+                remaining, self.settings.ttl_threshold_ledgers
+            );
+            extension_tx = Some(
+                self.client
+                    .extend_contract_ttl(self.settings.extend_to_ledgers)
+                    .await?,
+            );
+        }
+
+        let forecast = self.compute_forecast(extension_tx).await?;
+        if forecast.over_budget {
+            warn!(
+                "Projected monthly rent {} stroops exceeds budget of {} stroops",
+                forecast.projected_monthly_rent_stroops, forecast.monthly_budget_stroops
+            );
+        }
+
+        *self.latest.write().await = Some(forecast.clone());
+        Ok(forecast)
+    }
+
+    async fn compute_forecast(&self, last_extension_tx: Option<String>) -> Result<RentForecast> {
+        let latest_ledger = self.client.get_latest_ledger().await?;
+        let live_until = self.client.get_contract_instance_live_until().await?;
+        let funding_account = self.client.admin_public_key()?;
+        let funding_balance = self.client.get_account_balance(&funding_account).await?;
+
+        let projected_monthly =
+            self.settings.stroops_per_ledger * i64::from(LEDGERS_PER_MONTH);
+        let months_left = if projected_monthly > 0 {
+            Some(funding_balance as f64 / projected_monthly as f64)
+        } else {
+            None
+        };
+
+        Ok(RentForecast {
+            latest_ledger,
+            instance_live_until_ledger: live_until,
+            ledgers_remaining: live_until.saturating_sub(latest_ledger),
+            funding_account,
+            funding_balance_stroops: funding_balance,
+            projected_monthly_rent_stroops: projected_monthly,
+            monthly_budget_stroops: self.settings.monthly_budget_stroops,
+            over_budget: projected_monthly > self.settings.monthly_budget_stroops,
+            months_of_funding_left: months_left,
+            last_extension_tx,
+            generated_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        })
+    }
+}
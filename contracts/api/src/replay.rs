@@ -0,0 +1,326 @@
+use anyhow::{anyhow, Result};
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    middleware::Next,
+    response::Response,
+    Json,
+};
+use hmac::{Hmac, Mac};
+use redis::aio::ConnectionManager;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::{
+    models::{ErrorCode, ErrorResponse},
+    tenants::API_KEY_HEADER,
+};
+
+/// Header carrying a value the client never sends twice, e.g. a UUID
+pub const NONCE_HEADER: &str = "x-request-nonce";
+
+/// Header carrying the time the client made the request, in Unix seconds
+pub const TIMESTAMP_HEADER: &str = "x-request-timestamp";
+
+/// Header carrying the hex HMAC-SHA256 of the request, keyed with the caller's
+/// bearer token or API key; see [`signing_payload`]
+pub const SIGNATURE_HEADER: &str = "x-request-signature";
+
+/// Tunables for rejecting replayed write requests
+#[derive(Debug, Clone)]
+pub struct ReplaySettings {
+    pub enabled: bool,
+    /// How far a request's timestamp may be from the server's clock
+    pub window_secs: u64,
+    /// Nonces remembered in memory before new requests are refused with 503
+    pub max_nonces: usize,
+    /// Larger signed request bodies are refused with 413
+    pub max_body_bytes: usize,
+}
+
+impl Default for ReplaySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_secs: 300,
+            max_nonces: 100_000,
+            max_body_bytes: 64 * 1024,
+        }
+    }
+}
+
+/// Why a request was refused by a [`ReplayGuard`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Refusal {
+    /// The timestamp is outside the window
+    Expired,
+    /// The nonce was already used
+    Replayed,
+    /// The nonce store couldn't be reached, or is full
+    Unavailable,
+}
+
+/// Remembers the nonces of recent sensitive requests
+///
+/// A request is only accepted while its timestamp is within the window of the
+/// server's clock, and its nonce is remembered for as long as that could be,
+/// so a captured request can neither be sent again straight away nor later
+/// on. Nonces are kept in memory, up to `max_nonces` at a time, or in Redis
+/// when `REDIS_URL` is set so that every instance behind a load balancer sees
+/// them.
+#[derive(Clone)]
+pub struct ReplayGuard {
+    window: Duration,
+    max_nonces: usize,
+    max_body_bytes: usize,
+    backend: Backend,
+}
+
+#[derive(Clone)]
+enum Backend {
+    Memory(Arc<Mutex<HashMap<String, Instant>>>),
+    Redis { conn: ConnectionManager, prefix: String },
+}
+
+impl ReplayGuard {
+    /// Set up replay protection, if enabled
+    ///
+    /// `namespace` separates the nonces of registries sharing a Redis server.
+    pub async fn connect(
+        settings: &ReplaySettings,
+        redis_url: &str,
+        namespace: &str,
+    ) -> Result<Option<Self>> {
+        if !settings.enabled {
+            info!("Replay protection disabled");
+            return Ok(None);
+        }
+
+        let backend = if redis_url.is_empty() {
+            Backend::Memory(Arc::new(Mutex::new(HashMap::new())))
+        } else {
+            let conn = redis::Client::open(redis_url)
+                .map_err(|e| anyhow!("Invalid REDIS_URL: {}", e))?
+                .get_connection_manager()
+                .await
+                .map_err(|e| anyhow!("Failed to connect to Redis: {}", e))?;
+            Backend::Redis {
+                conn,
+                prefix: format!("veriluxe:{}:nonce", namespace),
+            }
+        };
+        info!("Rejecting replayed write requests outside a {}s window", settings.window_secs);
+
+        Ok(Some(Self {
+            window: Duration::from_secs(settings.window_secs),
+            max_nonces: settings.max_nonces,
+            max_body_bytes: settings.max_body_bytes,
+            backend,
+        }))
+    }
+
+    /// Accept a request made at `timestamp` with `nonce`, or say why it's refused
+    pub async fn check(&self, nonce: &str, timestamp: u64) -> Result<(), Refusal> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        if now.abs_diff(timestamp) > self.window.as_secs() {
+            return Err(Refusal::Expired);
+        }
+
+        // Timestamps up to a window ahead are accepted, so a nonce has to be
+        // remembered for two windows to outlive every copy of its request
+        let ttl = self.window * 2;
+        let first_use = match &self.backend {
+            Backend::Memory(seen) => {
+                let mut seen = seen.lock().await;
+                seen.retain(|_, first_seen| first_seen.elapsed() <= ttl);
+                // Forgetting a live nonce would let its request through again,
+                // so a full store refuses new ones instead
+                if seen.len() >= self.max_nonces && !seen.contains_key(nonce) {
+                    warn!("Nonce store is full with {} nonces", seen.len());
+                    return Err(Refusal::Unavailable);
+                }
+                seen.insert(nonce.to_string(), Instant::now()).is_none()
+            }
+            Backend::Redis { conn, prefix } => {
+                let stored: Option<String> = redis::cmd("SET")
+                    .arg(format!("{}:{}", prefix, nonce))
+                    .arg(timestamp)
+                    .arg("NX")
+                    .arg("EX")
+                    .arg(ttl.as_secs().max(1))
+                    .query_async(&mut conn.clone())
+                    .await
+                    .map_err(|e| {
+                        warn!("Nonce store unavailable: {}", e);
+                        Refusal::Unavailable
+                    })?;
+                stored.is_some()
+            }
+        };
+
+        if first_use {
+            Ok(())
+        } else {
+            Err(Refusal::Replayed)
+        }
+    }
+}
+
+/// Refuse requests without a fresh timestamp, an unused nonce and a signature
+///
+/// The signature binds the nonce and timestamp to the request's method, path
+/// and body, so they can't be lifted onto another request. Answers 400 when
+/// the headers are missing or malformed, 401 when the signature doesn't
+/// match, 409 with `REPLAYED_REQUEST` when the timestamp is stale or the
+/// nonce was seen before, and 503 when the nonce store can't be reached or is
+/// full. Requests pass unchecked when replay protection is disabled.
+pub async fn reject_replays(
+    State(guard): State<Option<ReplayGuard>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let Some(guard) = guard else {
+        return Ok(next.run(request).await);
+    };
+
+    let (nonce, timestamp, signature) = replay_headers(request.headers()).ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::bad_request(format!(
+                "Requires an {} header of 16-128 letters, digits, - or _, an {} header \
+                 with the request time in Unix seconds, and an {} header with the hex \
+                 HMAC-SHA256 of the request",
+                NONCE_HEADER, TIMESTAMP_HEADER, SIGNATURE_HEADER
+            ))),
+        )
+    })?;
+    let secret = signing_secret(request.headers()).ok_or_else(|| {
+        (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse::unauthorized(
+                "Signed requests need a bearer token or API key to sign with".to_string(),
+            )),
+        )
+    })?;
+
+    let (parts, body) = request.into_parts();
+    let body = to_bytes(body, guard.max_body_bytes).await.map_err(|_| {
+        (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            Json(ErrorResponse::new(
+                format!("Request body exceeds {} bytes", guard.max_body_bytes),
+                413,
+            )),
+        )
+    })?;
+    let path = parts.uri.path_and_query().map(|path| path.as_str()).unwrap_or("/");
+    let payload = signing_payload(parts.method.as_str(), path, &body, &nonce, timestamp);
+    if !signature_matches(&secret, &payload, &signature) {
+        warn!("Refused {} {}: request signature doesn't match", parts.method, parts.uri.path());
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse::unauthorized(format!(
+                "{} doesn't match the request",
+                SIGNATURE_HEADER
+            ))),
+        ));
+    }
+    let request = Request::from_parts(parts, Body::from(body));
+
+    if let Err(refusal) = guard.check(&nonce, timestamp).await {
+        let message = match refusal {
+            Refusal::Expired => format!(
+                "Request timestamp is more than {}s from the server's clock",
+                guard.window.as_secs()
+            ),
+            Refusal::Replayed => "Request nonce has already been used".to_string(),
+            Refusal::Unavailable => {
+                return Err((
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Json(ErrorResponse::new(
+                        "Replay protection is unavailable, try again later".to_string(),
+                        503,
+                    )),
+                ))
+            }
+        };
+        warn!("Refused {} {}: {}", request.method(), request.uri().path(), message);
+        return Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse::new(message, 409).with_code(ErrorCode::ReplayedRequest)),
+        ));
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// What a request's signature covers, one field per line
+///
+/// `METHOD`, the path with its query string, the hex SHA-256 of the body, the
+/// nonce and the timestamp.
+pub fn signing_payload(method: &str, path: &str, body: &[u8], nonce: &str, timestamp: u64) -> String {
+    format!(
+        "{}\n{}\n{}\n{}\n{}",
+        method,
+        path,
+        hex::encode(Sha256::digest(body)),
+        nonce,
+        timestamp
+    )
+}
+
+/// The credential a request is signed with: its bearer token, or else its API key
+fn signing_secret(headers: &HeaderMap) -> Option<String> {
+    let bearer = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let api_key = headers
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok());
+    bearer
+        .or(api_key)
+        .map(str::trim)
+        .filter(|secret| !secret.is_empty())
+        .map(str::to_string)
+}
+
+/// Compare a hex signature against the HMAC of `payload` in constant time
+fn signature_matches(secret: &str, payload: &str, signature: &str) -> bool {
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(payload.as_bytes());
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// The request's nonce, timestamp and signature, if all are present and well-formed
+fn replay_headers(headers: &HeaderMap) -> Option<(String, u64, String)> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+
+    let nonce = header(NONCE_HEADER)?.trim();
+    let well_formed = (16..=128).contains(&nonce.len())
+        && nonce
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !well_formed {
+        return None;
+    }
+
+    let timestamp = header(TIMESTAMP_HEADER)?.trim().parse().ok()?;
+    let signature = header(SIGNATURE_HEADER)?.trim();
+    Some((nonce.to_string(), timestamp, signature.to_string()))
+}
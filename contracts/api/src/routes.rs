@@ -1,41 +1,204 @@
 use axum::{
-    routing::{get, post},
+    extract::DefaultBodyLimit,
+    middleware::{from_fn, from_fn_with_state},
+    routing::{delete, get, post},
     Router,
 };
-use tower_http::cors::CorsLayer;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
 use crate::{
+    audit::{record_audit, AuditGuard},
+    auth::{ADMIN, HOLDERS, ISSUERS, OWNERS, READERS},
+    circuit::retry_after,
     handlers::{
-        check_certificate_exists, get_certificate, health_check, init_contract, issue_certificate,
-        revoke_certificate, transfer_certificate, verify_certificate, AppState, ApiDoc,
+        check_certificate_exists, delete_webhook, estimate_fees, export_registry, fee_bump_transaction, fund_account, generate_keypair, convert_strkey, get_account, get_import, import_certificates, federated_verify, get_certificate, get_certificate_history, public_verify,
+        get_api_key_usage, get_challenge, get_job, get_reconciliation, reload_config, health_check, list_audit, verify_audit, health_live, health_ready, init_contract, issue_certificate,
+        list_certificates, list_webhooks, list_webhook_deliveries, mint_qr_code, my_certificates, get_notification_preferences, update_notification_preferences, delete_notification_preferences, prepare_transfer, register_webhook,
+        rent_forecast, revoke_certificate, search_certificates, submit_transaction,
+        verify_certificate, verify_challenge, verify_qr, AppState, ApiDoc,
     },
+    i18n::localize,
+    networks::select_network,
+    policy::{enforce, Ownership, Policy},
+    replay::reject_replays,
+    telemetry::trace_request,
+    tenants::select_tenant,
 };
 
 /// Create the application router with all endpoints
+///
+/// Verification and certificate lookups are public; every other route
+/// declares a [`Policy`] naming the roles its bearer token must grant. Owner
+/// routes always require a SEP-10 session token, even with authentication
+/// disabled, and owner tokens may only prepare transfers of certificates they
+/// hold. Transfers and revocations must carry a fresh nonce and timestamp
+/// when replay protection is enabled. Mutating routes are recorded in the
+/// audit log, including refused attempts. Request bodies are limited to
+/// `MAX_BODY_BYTES`, except import files, limited to `IMPORT_MAX_BYTES`.
+/// Large responses are compressed for clients accepting gzip or brotli.
 pub fn create_router(state: AppState) -> Router {
+    let policy = |policy: Policy| from_fn_with_state(policy, enforce);
+    let admin = || policy(state.auth.require(ADMIN));
+    let issuers = || policy(state.auth.require(ISSUERS));
+    let readers = || policy(state.auth.require(READERS));
+    let holders = || policy(state.auth.require(HOLDERS));
+    let owners = || policy(state.auth.require_token(OWNERS));
+    let certificate_holders =
+        || policy(state.auth.require(HOLDERS).with_ownership(Ownership::Certificate));
+    let fresh = || from_fn_with_state(state.replay.clone(), reject_replays);
+    let audited = || {
+        from_fn_with_state(
+            AuditGuard::new(state.audit.clone(), state.auth.clone()),
+            record_audit,
+        )
+    };
+
     Router::new()
         // Health check
         .route("/health", get(health_check))
+        .route("/health/live", get(health_live))
+        .route("/health/ready", get(health_ready))
         
         // Contract initialization
-        .route("/init", post(init_contract))
+        .route("/init", post(init_contract).route_layer(admin()).route_layer(audited()))
         
         // Certificate management
-        .route("/certificates", post(issue_certificate))
+        .route(
+            "/certificates",
+            post(issue_certificate)
+                .route_layer(issuers())
+                .route_layer(audited())
+                .merge(get(list_certificates).route_layer(readers())),
+        )
+        .route("/certificates/search", get(search_certificates).route_layer(readers()))
         .route("/certificates/:id", get(get_certificate))
+        .route("/certificates/:id/history", get(get_certificate_history))
         .route("/certificates/:id/verify", post(verify_certificate))
-        .route("/certificates/:id/transfer", post(transfer_certificate))
-        .route("/certificates/:id/revoke", post(revoke_certificate))
+        .route(
+            "/certificates/:id/transfer/prepare",
+            post(prepare_transfer)
+                .route_layer(fresh())
+                .route_layer(certificate_holders()),
+        )
+        .route(
+            "/certificates/:id/revoke",
+            post(revoke_certificate)
+                .route_layer(fresh())
+                .route_layer(admin())
+                .route_layer(audited()),
+        )
         .route("/certificates/:id/exists", get(check_certificate_exists))
+        .route("/certificates/:id/qr", post(mint_qr_code).route_layer(issuers()))
+        
+        // Client-signed transactions
+        .route(
+            "/transactions/submit",
+            post(submit_transaction)
+                .route_layer(fresh())
+                .route_layer(holders())
+                .route_layer(audited()),
+        )
+        
+        // SEP-10 sign-in and owner-scoped routes
+        .route("/auth", get(get_challenge).post(verify_challenge))
+        .route("/me/certificates", get(my_certificates).route_layer(owners()))
+        .route(
+            "/me/notifications",
+            get(get_notification_preferences)
+                .put(update_notification_preferences)
+                .delete(delete_notification_preferences)
+                .route_layer(owners()),
+        )
+        
+        // Queued writes
+        .route("/jobs/:id", get(get_job).route_layer(readers()))
+        
+        // Lifecycle event webhooks
+        .route(
+            "/webhooks",
+            post(register_webhook)
+                .route_layer(admin())
+                .route_layer(audited())
+                .merge(get(list_webhooks).route_layer(admin())),
+        )
+        .route(
+            "/webhooks/:id",
+            delete(delete_webhook).route_layer(admin()).route_layer(audited()),
+        )
+        .route(
+            "/webhooks/:id/deliveries",
+            get(list_webhook_deliveries).route_layer(admin()),
+        )
+        
+        // Stellar accounts, to check owners before issuing or transferring
+        .route("/accounts/:address", get(get_account))
+        
+        // Federated verification across peer registries
+        .route("/verify", get(federated_verify))
+
+        // Consumer-facing verification
+        .route("/verify/qr", post(verify_qr))
+        .route("/public/verify", get(public_verify))
+        
+        // Operations
+        .route("/rent/forecast", get(rent_forecast).route_layer(admin()))
+        .route("/fees/estimate", get(estimate_fees).route_layer(readers()))
+        .route(
+            "/transactions/:hash/fee-bump",
+            post(fee_bump_transaction).route_layer(admin()).route_layer(audited()),
+        )
+        .route("/admin/export", get(export_registry).route_layer(admin()))
+        .route(
+            "/admin/import",
+            post(import_certificates)
+                .route_layer(admin())
+                .route_layer(audited())
+                .layer(DefaultBodyLimit::max(state.limits.max_import_bytes)),
+        )
+        .route("/admin/import/:id", get(get_import).route_layer(admin()))
+        .route("/admin/reconciliation", get(get_reconciliation).route_layer(admin()))
+        .route("/admin/api-keys/:id/usage", get(get_api_key_usage).route_layer(admin()))
+        .route("/admin/audit", get(list_audit).route_layer(admin()))
+        .route("/admin/audit/verify", get(verify_audit).route_layer(admin()))
+        .route(
+            "/admin/config/reload",
+            post(reload_config).route_layer(admin()).route_layer(audited()),
+        )
+
+        // Development helpers: funding on testnet and in sandbox mode, the
+        // utilities in sandbox mode or with DEV_TOOLS_ENABLED
+        .route("/dev/fund", post(fund_account))
+        .route("/dev/keypairs", post(generate_keypair))
+        .route("/dev/strkey/convert", get(convert_strkey))
         
         // Swagger UI
         .merge(SwaggerUi::new("/swagger-ui")
             .url("/api-docs/openapi.json", ApiDoc::openapi()))
         
-        // Add CORS middleware
-        .layer(CorsLayer::permissive())
+        // Refuse oversized bodies before they're buffered
+        .layer(DefaultBodyLimit::max(state.limits.max_body_bytes))
+
+        // Tell clients when to come back while the RPC circuit is open
+        .layer(from_fn(retry_after))
+
+        // Scope the request to the tenant named by X-Tenant or X-API-Key
+        .layer(from_fn_with_state((state.tenants.clone(), state.auth.clone()), select_tenant))
+
+        // Pick the network profile named by X-Network
+        .layer(from_fn_with_state(state.networks.clone(), select_network))
+
+        // Answer cross-origin requests from the allowed origins, as currently configured
+        .layer(state.cors.layer())
+
+        // Translate messages into the language named by Accept-Language
+        .layer(from_fn(localize))
+
+        // Compress large responses, such as lists and exports
+        .layer(state.server.compression_layer())
+
+        // Trace every request
+        .layer(from_fn(trace_request))
         
         // Add application state
         .with_state(state)
@@ -0,0 +1,109 @@
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use std::{sync::Arc, time::Duration};
+use tracing::info;
+
+use crate::{
+    friendbot::Friendbot,
+    models::StellarAddress,
+    signer::Signer,
+    soroban_client::{SorobanClient, TransactionOutcome},
+};
+
+/// How long sandbox setup waits for each of its transactions
+const SETUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Tunables for running against a local standalone network
+///
+/// The defaults match the `stellar/quickstart` image started with
+/// `--local --enable-soroban-rpc`.
+#[derive(Debug, Clone)]
+pub struct SandboxSettings {
+    /// Set with `SANDBOX=true` or the `--sandbox` flag
+    pub enabled: bool,
+    pub rpc_url: String,
+    pub network_passphrase: String,
+    /// Funds the admin account on startup
+    pub friendbot_url: String,
+    /// Contract deployed on startup, built with `stellar contract build`
+    pub wasm_path: String,
+    /// Serve `/dev/keypairs` and `/dev/strkey/convert` outside sandbox mode
+    pub dev_tools: bool,
+}
+
+impl Default for SandboxSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rpc_url: "http://localhost:8000/soroban/rpc".to_string(),
+            network_passphrase: "Standalone Network ; February 2017".to_string(),
+            friendbot_url: "http://localhost:8000/friendbot".to_string(),
+            wasm_path: "../contracts/target/wasm32-unknown-unknown/release/fashion_auth_contract.wasm"
+                .to_string(),
+            dev_tools: false,
+        }
+    }
+}
+
+impl SandboxSettings {
+    /// Whether the `/dev` utilities that don't need friendbot are served
+    pub fn dev_tools_enabled(&self) -> bool {
+        self.enabled || self.dev_tools
+    }
+}
+
+/// Fund the admin account, then deploy and initialize the contract unless `contract_id` is set
+///
+/// Returns the ID of the contract to serve.
+pub async fn prepare(
+    settings: &SandboxSettings,
+    contract_id: &str,
+    signer: Arc<dyn Signer>,
+    http_client: Client,
+) -> Result<String> {
+    let admin = StellarAddress::parse(&signer.address()).map_err(|e| anyhow!("{}", e))?;
+    let friendbot = Friendbot::new(settings.friendbot_url.clone(), http_client.clone());
+    if !friendbot.fund(&admin).await? {
+        info!("Sandbox admin account {} already exists", admin);
+    }
+
+    if !contract_id.is_empty() {
+        info!("Sandbox serving existing contract {}", contract_id);
+        return Ok(contract_id.to_string());
+    }
+
+    let wasm = std::fs::read(&settings.wasm_path).map_err(|e| {
+        anyhow!(
+            "Failed to read contract wasm {} (build it with `stellar contract build`): {}",
+            settings.wasm_path,
+            e
+        )
+    })?;
+
+    let client = SorobanClient::new(
+        settings.rpc_url.clone(),
+        settings.network_passphrase.clone(),
+        String::new(),
+        signer.clone(),
+    )
+    .with_http_client(http_client);
+
+    let contract_id = client.deploy_contract(&wasm, rand::random(), SETUP_TIMEOUT).await?;
+    info!("Sandbox deployed contract {}", contract_id);
+
+    let client = client.for_contract(&contract_id, Some(signer));
+    let hash = client.init(&admin).await?;
+    match client
+        .wait_for_transaction(&hash, SETUP_TIMEOUT, Duration::from_secs(1))
+        .await?
+    {
+        TransactionOutcome::Success { .. } => {
+            info!("Sandbox contract initialized with admin {}", admin);
+            Ok(contract_id)
+        }
+        TransactionOutcome::Failed { ledger, summary } => Err(anyhow!(
+            "Sandbox contract initialization failed: {}",
+            summary.failure_message(&hash, ledger)
+        )),
+    }
+}
@@ -0,0 +1,320 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use crate::kms::{sigv4_authorization, SigV4Credentials, SigV4Request};
+
+/// Providers `SECRETS_PROVIDER` accepts
+pub const SECRETS_PROVIDERS: &[&str] = &["none", "vault", "aws-secrets-manager"];
+
+/// Where settings holding secrets are fetched from at startup
+#[derive(Debug, Clone)]
+pub struct SecretsSettings {
+    /// One of [`SECRETS_PROVIDERS`]
+    pub provider: String,
+    /// Fetch the secret again this often, applying what changed; never when 0
+    pub refresh_secs: u64,
+    pub timeout_secs: u64,
+    pub vault_addr: String,
+    pub vault_token: String,
+    /// Vault Enterprise namespace, if any
+    pub vault_namespace: String,
+    /// Mount of the KV version 2 secrets engine
+    pub vault_mount: String,
+    /// Path of the secret within the mount
+    pub vault_path: String,
+    /// Name or ARN of the secret in AWS Secrets Manager
+    pub aws_secret_id: String,
+    pub aws_region: String,
+    pub aws_access_key_id: String,
+    pub aws_secret_access_key: String,
+    pub aws_session_token: String,
+}
+
+impl Default for SecretsSettings {
+    fn default() -> Self {
+        Self {
+            provider: "none".to_string(),
+            refresh_secs: 0,
+            timeout_secs: 10,
+            vault_addr: String::new(),
+            vault_token: String::new(),
+            vault_namespace: String::new(),
+            vault_mount: "secret".to_string(),
+            vault_path: String::new(),
+            aws_secret_id: String::new(),
+            aws_region: String::new(),
+            aws_access_key_id: String::new(),
+            aws_secret_access_key: String::new(),
+            aws_session_token: String::new(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct VaultResponse {
+    data: VaultData,
+}
+
+#[derive(Deserialize)]
+struct VaultData {
+    data: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AwsSecretResponse {
+    secret_string: Option<String>,
+}
+
+/// Setting values fetched from a secrets provider
+///
+/// The secret is a JSON object keyed by setting name, such as
+/// `{"ADMIN_SECRET_KEY": "S...", "JWT_SECRET": "..."}`, so any setting can be
+/// kept there instead of in `.env`. Clones share the values, so a refresh is
+/// seen by every configuration loaded afterwards.
+#[derive(Clone, Default)]
+pub struct SecretStore {
+    settings: SecretsSettings,
+    http_client: Option<Client>,
+    values: Arc<RwLock<HashMap<String, String>>>,
+}
+
+impl fmt::Debug for SecretStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<String> = self.values.read().unwrap().keys().cloned().collect();
+        names.sort();
+        f.debug_struct("SecretStore")
+            .field("provider", &self.settings.provider)
+            .field("names", &names)
+            .finish()
+    }
+}
+
+impl SecretStore {
+    /// Fetch the secret from the configured provider; an empty store without one
+    pub async fn connect(settings: SecretsSettings) -> Result<Self> {
+        match settings.provider.as_str() {
+            "none" => return Ok(Self { settings, ..Self::default() }),
+            "vault" => {
+                if settings.vault_addr.is_empty() || settings.vault_token.is_empty() {
+                    return Err(anyhow!(
+                        "VAULT_ADDR and VAULT_TOKEN are required for the vault secrets provider"
+                    ));
+                }
+                if settings.vault_path.is_empty() {
+                    return Err(anyhow!(
+                        "SECRETS_VAULT_PATH is required for the vault secrets provider"
+                    ));
+                }
+            }
+            "aws-secrets-manager" => {
+                if settings.aws_secret_id.is_empty() || settings.aws_region.is_empty() {
+                    return Err(anyhow!(
+                        "SECRETS_AWS_SECRET_ID and AWS_REGION are required for the aws-secrets-manager secrets provider"
+                    ));
+                }
+                if settings.aws_access_key_id.is_empty() || settings.aws_secret_access_key.is_empty() {
+                    return Err(anyhow!(
+                        "AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY are required for the aws-secrets-manager secrets provider"
+                    ));
+                }
+            }
+            other => {
+                return Err(anyhow!(
+                    "SECRETS_PROVIDER is {:?}; expected one of: {}",
+                    other,
+                    SECRETS_PROVIDERS.join(", ")
+                ))
+            }
+        }
+
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(settings.timeout_secs))
+            .build()
+            .map_err(|e| anyhow!("Failed to build secrets HTTP client: {}", e))?;
+        let store = Self {
+            settings,
+            http_client: Some(http_client),
+            values: Arc::default(),
+        };
+
+        let values = store.fetch().await?;
+        *store.values.write().unwrap() = values;
+        Ok(store)
+    }
+
+    /// The configured provider, `none` when secrets come from the environment
+    pub fn provider(&self) -> &str {
+        &self.settings.provider
+    }
+
+    /// The value the secret holds for setting `name`
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.values.read().unwrap().get(name).cloned()
+    }
+
+    /// How often to fetch the secret again, when it should be
+    pub fn refresh_interval(&self) -> Option<Duration> {
+        (self.http_client.is_some() && self.settings.refresh_secs > 0)
+            .then(|| Duration::from_secs(self.settings.refresh_secs))
+    }
+
+    /// Fetch the secret again, returning whether any value changed
+    ///
+    /// A failed fetch keeps the values already held.
+    pub async fn refresh(&self) -> Result<bool> {
+        if self.http_client.is_none() {
+            return Ok(false);
+        }
+        let values = self.fetch().await?;
+        let mut current = self.values.write().unwrap();
+        if *current == values {
+            return Ok(false);
+        }
+        *current = values;
+        Ok(true)
+    }
+
+    async fn fetch(&self) -> Result<HashMap<String, String>> {
+        let secret = match self.settings.provider.as_str() {
+            "vault" => self.fetch_vault().await?,
+            "aws-secrets-manager" => self.fetch_aws().await?,
+            _ => return Ok(HashMap::new()),
+        };
+        setting_values(secret)
+    }
+
+    /// Read the secret from Vault's KV version 2 engine
+    async fn fetch_vault(&self) -> Result<Value> {
+        let url = format!(
+            "{}/v1/{}/data/{}",
+            self.settings.vault_addr.trim_end_matches('/'),
+            self.settings.vault_mount.trim_matches('/'),
+            self.settings.vault_path.trim_matches('/')
+        );
+        let mut request = self
+            .client()
+            .get(&url)
+            .header("X-Vault-Token", &self.settings.vault_token);
+        if !self.settings.vault_namespace.is_empty() {
+            request = request.header("X-Vault-Namespace", &self.settings.vault_namespace);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow!("Vault request failed: {}", e))?;
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let detail = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Vault responded with {}: {}", status, detail));
+        }
+        let response: VaultResponse = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Invalid Vault response: {}", e))?;
+        Ok(response.data.data)
+    }
+
+    /// Read the secret's current version from AWS Secrets Manager
+    async fn fetch_aws(&self) -> Result<Value> {
+        let settings = &self.settings;
+        let host = format!("secretsmanager.{}.amazonaws.com", settings.aws_region);
+        let body = json!({ "SecretId": settings.aws_secret_id }).to_string();
+
+        let amz_date = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let mut headers = vec![
+            ("content-type", "application/x-amz-json-1.1".to_string()),
+            ("host", host.clone()),
+            ("x-amz-date", amz_date.clone()),
+            ("x-amz-target", "secretsmanager.GetSecretValue".to_string()),
+        ];
+        if !settings.aws_session_token.is_empty() {
+            headers.push(("x-amz-security-token", settings.aws_session_token.clone()));
+        }
+        headers.sort();
+
+        let authorization = sigv4_authorization(
+            &SigV4Request {
+                method: "POST",
+                path: "/",
+                query: "",
+                headers: &headers,
+                body: body.as_bytes(),
+            },
+            &SigV4Credentials {
+                access_key_id: &settings.aws_access_key_id,
+                secret_access_key: &settings.aws_secret_access_key,
+                region: &settings.aws_region,
+                service: "secretsmanager",
+            },
+            &amz_date,
+        )?;
+
+        let mut request = self
+            .client()
+            .post(&format!("https://{}/", host))
+            .header("Authorization", authorization);
+        for (name, value) in headers.iter().filter(|(name, _)| *name != "host") {
+            request = request.header(name, value);
+        }
+
+        let response = request
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| anyhow!("AWS Secrets Manager request failed: {}", e))?;
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let detail = response.text().await.unwrap_or_default();
+            return Err(anyhow!("AWS Secrets Manager responded with {}: {}", status, detail));
+        }
+        let response: AwsSecretResponse = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Invalid AWS Secrets Manager response: {}", e))?;
+
+        let secret = response
+            .secret_string
+            .ok_or_else(|| anyhow!("Secret {} has no string value", settings.aws_secret_id))?;
+        serde_json::from_str(&secret)
+            .map_err(|e| anyhow!("Secret {} is not JSON: {}", settings.aws_secret_id, e))
+    }
+
+    fn client(&self) -> &Client {
+        self.http_client
+            .as_ref()
+            .expect("secrets are only fetched with a provider configured")
+    }
+}
+
+/// Setting values of a secret, keyed by their uppercase name
+///
+/// As in the settings file, arrays and objects are passed on as JSON, so a
+/// secret can hold `TENANTS` or `WEBHOOK_ENDPOINTS` whole.
+fn setting_values(secret: Value) -> Result<HashMap<String, String>> {
+    let Value::Object(entries) = secret else {
+        return Err(anyhow!("The secret must be a JSON object of setting names to values"));
+    };
+
+    Ok(entries
+        .into_iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(name, value)| {
+            let value = match value {
+                Value::String(s) => s,
+                other => other.to_string(),
+            };
+            (name.to_uppercase(), value)
+        })
+        .collect())
+}
@@ -0,0 +1,280 @@
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use ed25519_dalek::{PublicKey, Signature as DalekSignature, Verifier};
+use rand::RngCore;
+use stellar_strkey::ed25519;
+use stellar_xdr::curr::{
+    DataValue, DecoratedSignature, Limits, ManageDataOp, Memo, MuxedAccount, Operation,
+    OperationBody, Preconditions, ReadXdr, SequenceNumber, String64, TimeBounds, TimePoint,
+    Transaction, TransactionEnvelope, TransactionExt, TransactionV1Envelope, Uint256, WriteXdr,
+};
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    auth::{Authenticator, Role},
+    signer::{LocalSigner, Signer},
+    soroban_client::{sign_hash, transaction_hash},
+};
+
+/// Name of the operation carrying the domain of the signing server
+const WEB_AUTH_DOMAIN_KEY: &str = "web_auth_domain";
+
+/// Random bytes in a challenge nonce, base64-encoded to fit a 64 byte data value
+const NONCE_LENGTH: usize = 48;
+
+/// Tunables for SEP-10 web authentication
+#[derive(Debug, Clone)]
+pub struct Sep10Settings {
+    pub enabled: bool,
+    pub home_domain: String,
+    /// Domain serving the challenge endpoint; defaults to `home_domain`
+    pub web_auth_domain: String,
+    /// Secret key signing challenges; defaults to the admin signer
+    pub signing_key: String,
+    pub challenge_timeout_secs: u64,
+    pub session_ttl_secs: u64,
+}
+
+impl Default for Sep10Settings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            home_domain: "localhost".to_string(),
+            web_auth_domain: String::new(),
+            signing_key: String::new(),
+            challenge_timeout_secs: 900,
+            session_ttl_secs: 86400,
+        }
+    }
+}
+
+/// Issues and verifies SEP-10 challenge transactions
+///
+/// A client proves control of an account by co-signing a challenge built and
+/// signed by the server. In exchange it receives an owner session token
+/// scoped to that account.
+#[derive(Clone)]
+pub struct WebAuth {
+    settings: Sep10Settings,
+    network_passphrase: String,
+    server_key: [u8; 32],
+    server: Arc<dyn Signer>,
+    auth: Authenticator,
+}
+
+impl WebAuth {
+    /// Build the service when enabled, using the admin signer unless a key is configured
+    pub fn new(
+        settings: Sep10Settings,
+        network_passphrase: String,
+        admin_signer: Option<Arc<dyn Signer>>,
+        auth: Authenticator,
+    ) -> Result<Option<Self>> {
+        if !settings.enabled {
+            return Ok(None);
+        }
+        if !auth.can_issue() {
+            return Err(anyhow!("JWT_SECRET is required when SEP-10 authentication is enabled"));
+        }
+
+        let server: Arc<dyn Signer> = if settings.signing_key.is_empty() {
+            admin_signer.ok_or_else(|| anyhow!("SEP10_SIGNING_KEY is required without an admin signer"))?
+        } else {
+            Arc::new(LocalSigner::from_secret(&settings.signing_key)?)
+        };
+        let server_key = server.public_key();
+
+        let mut settings = settings;
+        if settings.web_auth_domain.is_empty() {
+            settings.web_auth_domain = settings.home_domain.clone();
+        }
+
+        Ok(Some(Self {
+            settings,
+            network_passphrase,
+            server_key,
+            server,
+            auth,
+        }))
+    }
+
+    /// Passphrase of the network challenges are signed for
+    pub fn network_passphrase(&self) -> &str {
+        &self.network_passphrase
+    }
+
+    /// Build a server-signed challenge for `account`, as base64 envelope XDR
+    pub async fn challenge(&self, account: &str) -> Result<String> {
+        let client = ed25519::PublicKey::from_string(account)
+            .map_err(|_| anyhow!("Invalid Stellar account: {}", account))?;
+
+        let mut nonce = [0u8; NONCE_LENGTH];
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let now = now_secs();
+        let operations = vec![
+            manage_data(
+                client.0,
+                &format!("{} auth", self.settings.home_domain),
+                BASE64.encode(nonce).as_bytes(),
+            )?,
+            manage_data(
+                self.server_key,
+                WEB_AUTH_DOMAIN_KEY,
+                self.settings.web_auth_domain.as_bytes(),
+            )?,
+        ];
+
+        let transaction = Transaction {
+            source_account: MuxedAccount::Ed25519(Uint256(self.server_key)),
+            fee: 100 * operations.len() as u32,
+            seq_num: SequenceNumber(0),
+            cond: Preconditions::Time(TimeBounds {
+                min_time: TimePoint(now),
+                max_time: TimePoint(now + self.settings.challenge_timeout_secs),
+            }),
+            memo: Memo::None,
+            operations: operations
+                .try_into()
+                .map_err(|_| anyhow!("Too many operations"))?,
+            ext: TransactionExt::V0,
+        };
+
+        let hash = transaction_hash(&self.network_passphrase, &transaction)?;
+        let signature = sign_hash(self.server.as_ref(), &hash).await?;
+
+        let envelope = TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx: transaction,
+            signatures: vec![signature]
+                .try_into()
+                .map_err(|_| anyhow!("Too many signatures"))?,
+        });
+
+        let envelope_xdr = envelope
+            .to_xdr(Limits::none())
+            .map_err(|e| anyhow!("Failed to encode challenge: {}", e))?;
+
+        Ok(BASE64.encode(envelope_xdr))
+    }
+
+    /// Check a challenge signed by the client and issue an owner token for its account
+    ///
+    /// The token is scoped to `tenant`, the one the challenge was verified for.
+    pub fn verify(&self, transaction_xdr: &str, tenant: Option<&str>) -> Result<String> {
+        let bytes = BASE64.decode(transaction_xdr.trim())
+            .map_err(|_| anyhow!("Challenge transaction is not valid base64"))?;
+        let envelope = TransactionEnvelope::from_xdr(bytes, Limits::none())
+            .map_err(|_| anyhow!("Invalid challenge transaction XDR"))?;
+        let TransactionEnvelope::Tx(envelope) = envelope else {
+            return Err(anyhow!("Challenge must be a v1 transaction envelope"));
+        };
+        let transaction = &envelope.tx;
+
+        if transaction.source_account != MuxedAccount::Ed25519(Uint256(self.server_key)) {
+            return Err(anyhow!("Challenge was not issued by this server"));
+        }
+        if transaction.seq_num.0 != 0 {
+            return Err(anyhow!("Challenge sequence number must be 0"));
+        }
+
+        let now = now_secs();
+        match &transaction.cond {
+            Preconditions::Time(bounds)
+                if bounds.min_time.0 <= now && (bounds.max_time.0 == 0 || now <= bounds.max_time.0) => {}
+            Preconditions::Time(_) => return Err(anyhow!("Challenge has expired")),
+            _ => return Err(anyhow!("Challenge must have time bounds")),
+        }
+
+        let client = self.check_operations(transaction)?;
+
+        let hash = transaction_hash(&self.network_passphrase, transaction)?;
+        for (signer, name) in [(self.server_key, "server"), (client, "client")] {
+            if !is_signed_by(&envelope.signatures, &signer, &hash) {
+                return Err(anyhow!("Challenge is missing a valid {} signature", name));
+            }
+        }
+
+        let account = ed25519::PublicKey(client).to_string();
+        self.auth.issue(&account, &[Role::Owner], self.settings.session_ttl_secs, tenant)
+    }
+
+    /// Validate the challenge operations, returning the client account
+    fn check_operations(&self, transaction: &Transaction) -> Result<[u8; 32]> {
+        let expected_name = format!("{} auth", self.settings.home_domain);
+        let mut client = None;
+
+        for (index, operation) in transaction.operations.iter().enumerate() {
+            let OperationBody::ManageData(data) = &operation.body else {
+                return Err(anyhow!("Challenge operations must be manage_data"));
+            };
+            let Some(MuxedAccount::Ed25519(Uint256(source))) = &operation.source_account else {
+                return Err(anyhow!("Challenge operations must have an account source"));
+            };
+            let name = std::str::from_utf8(data.data_name.as_slice())
+                .map_err(|_| anyhow!("Invalid challenge operation name"))?;
+
+            if index == 0 {
+                if name != expected_name {
+                    return Err(anyhow!("Challenge is not for {}", self.settings.home_domain));
+                }
+                client = Some(*source);
+                continue;
+            }
+
+            if *source != self.server_key {
+                return Err(anyhow!("Only the first challenge operation may name the client"));
+            }
+            if name == WEB_AUTH_DOMAIN_KEY {
+                let value = data.data_value.as_ref().map(|v| v.as_slice()).unwrap_or_default();
+                if value != self.settings.web_auth_domain.as_bytes() {
+                    return Err(anyhow!("Challenge web_auth_domain does not match"));
+                }
+            }
+        }
+
+        client.ok_or_else(|| anyhow!("Challenge has no operations"))
+    }
+}
+
+/// A manage_data operation sourced from `account`
+fn manage_data(account: [u8; 32], name: &str, value: &[u8]) -> Result<Operation> {
+    Ok(Operation {
+        source_account: Some(MuxedAccount::Ed25519(Uint256(account))),
+        body: OperationBody::ManageData(ManageDataOp {
+            data_name: String64(
+                name.as_bytes()
+                    .to_vec()
+                    .try_into()
+                    .map_err(|_| anyhow!("Data name too long: {}", name))?,
+            ),
+            data_value: Some(DataValue(
+                value.to_vec()
+                    .try_into()
+                    .map_err(|_| anyhow!("Data value too long"))?,
+            )),
+        }),
+    })
+}
+
+/// Whether any of the signatures is a valid signature of `hash` by `account`
+fn is_signed_by(signatures: &[DecoratedSignature], account: &[u8; 32], hash: &[u8; 32]) -> bool {
+    let Ok(public_key) = PublicKey::from_bytes(account) else {
+        return false;
+    };
+
+    signatures
+        .iter()
+        .filter(|signature| signature.hint.0 == account[28..])
+        .filter_map(|signature| DalekSignature::try_from(signature.signature.0.as_slice()).ok())
+        .any(|signature| public_key.verify(hash, &signature).is_ok())
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
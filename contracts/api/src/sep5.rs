@@ -0,0 +1,57 @@
+use anyhow::{anyhow, Result};
+use bip39::Mnemonic;
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+use stellar_strkey::ed25519;
+
+/// BIP-44 coin type registered for Stellar
+const STELLAR_COIN_TYPE: u32 = 148;
+
+/// Set on the index of a hardened derivation step, the only kind Ed25519 allows
+const HARDENED: u32 = 0x8000_0000;
+
+/// Whether `secret` is a BIP-39 mnemonic rather than a single key
+pub fn is_mnemonic(secret: &str) -> bool {
+    let words = secret.split_whitespace().count();
+    (12..=24).contains(&words) && words % 3 == 0
+}
+
+/// Derive the S... secret of an account from a BIP-39 mnemonic
+///
+/// Follows SEP-5: the mnemonic and optional `passphrase` give a BIP-39 seed,
+/// from which the key at `m/44'/148'/{account}'` is derived with SLIP-10.
+/// Account 0 is the one wallets show first.
+pub fn derive_secret(mnemonic: &str, passphrase: &str, account: u32) -> Result<String> {
+    if account >= HARDENED {
+        return Err(anyhow!("Account index must be below {}", HARDENED));
+    }
+
+    let words = mnemonic.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    let mnemonic = Mnemonic::parse_normalized(&words)
+        .map_err(|e| anyhow!("Invalid mnemonic: {}", e))?;
+    let seed = mnemonic.to_seed_normalized(passphrase);
+
+    let (mut key, mut chain_code) = hmac_sha512(b"ed25519 seed", &seed);
+    for index in [44, STELLAR_COIN_TYPE, account] {
+        let mut data = Vec::with_capacity(37);
+        data.push(0);
+        data.extend_from_slice(&key);
+        data.extend_from_slice(&(index | HARDENED).to_be_bytes());
+        (key, chain_code) = hmac_sha512(&chain_code, &data);
+    }
+
+    Ok(ed25519::PrivateKey(key).to_string())
+}
+
+/// HMAC-SHA512 of `data`, split into the child key and chain code
+fn hmac_sha512(key: &[u8], data: &[u8]) -> ([u8; 32], [u8; 32]) {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    let digest = mac.finalize().into_bytes();
+
+    let mut child_key = [0u8; 32];
+    let mut chain_code = [0u8; 32];
+    child_key.copy_from_slice(&digest[..32]);
+    chain_code.copy_from_slice(&digest[32..]);
+    (child_key, chain_code)
+}
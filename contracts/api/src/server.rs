@@ -0,0 +1,69 @@
+use hyper_util::{
+    rt::{TokioExecutor, TokioTimer},
+    server::conn::auto::Builder,
+};
+use std::time::Duration;
+use tower_http::compression::{
+    predicate::{NotForContentType, SizeAbove},
+    CompressionLayer, Predicate,
+};
+
+/// Tunables for the HTTP server and its responses
+#[derive(Debug, Clone)]
+pub struct ServerSettings {
+    /// Compress responses with gzip or brotli for clients accepting them
+    pub compression: bool,
+    /// Smaller responses are sent uncompressed
+    pub compression_min_bytes: u16,
+    /// Keep HTTP/1.1 connections open between requests
+    pub keep_alive: bool,
+    /// Ping idle HTTP/2 connections this often; never when 0
+    pub http2_keep_alive_interval_secs: u64,
+    /// Close HTTP/2 connections whose ping isn't answered in time
+    pub http2_keep_alive_timeout_secs: u64,
+    /// Requests served at once on each HTTP/2 connection
+    pub http2_max_concurrent_streams: u32,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            compression: true,
+            compression_min_bytes: 1024,
+            keep_alive: true,
+            http2_keep_alive_interval_secs: 0,
+            http2_keep_alive_timeout_secs: 20,
+            http2_max_concurrent_streams: 200,
+        }
+    }
+}
+
+impl ServerSettings {
+    /// Compression of large responses, leaving images and event streams alone
+    pub fn compression_layer(&self) -> CompressionLayer<impl Predicate> {
+        CompressionLayer::new()
+            .gzip(self.compression)
+            .br(self.compression)
+            .compress_when(
+                SizeAbove::new(self.compression_min_bytes)
+                    .and(NotForContentType::GRPC)
+                    .and(NotForContentType::IMAGES)
+                    .and(NotForContentType::SSE),
+            )
+    }
+
+    /// Apply the connection settings to the server's HTTP/1 and HTTP/2 builder
+    pub fn configure(&self, builder: &mut Builder<TokioExecutor>) {
+        builder.http1().keep_alive(self.keep_alive);
+
+        let mut http2 = builder.http2();
+        http2
+            .timer(TokioTimer::new())
+            .max_concurrent_streams(self.http2_max_concurrent_streams);
+        if self.http2_keep_alive_interval_secs > 0 {
+            http2
+                .keep_alive_interval(Duration::from_secs(self.http2_keep_alive_interval_secs))
+                .keep_alive_timeout(Duration::from_secs(self.http2_keep_alive_timeout_secs));
+        }
+    }
+}
@@ -0,0 +1,321 @@
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chacha20poly1305::{aead::Aead, ChaCha20Poly1305, KeyInit, Nonce};
+use ed25519_dalek::{Keypair, PublicKey, Signature as DalekSignature, Signer as _, Verifier};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+use stellar_strkey::ed25519;
+
+use crate::{
+    kms::{AwsKmsSigner, GcpKmsSigner},
+    sep5,
+    soroban_client::keypair_from_secret,
+};
+
+/// Signers that can be selected with `SIGNER`
+pub const SIGNER_KINDS: &[&str] = &["local", "keystore", "remote", "aws-kms", "gcp-kms"];
+
+/// Future returned by [`Signer::sign`]
+pub type SignFuture<'a> = Pin<Box<dyn Future<Output = Result<[u8; 64]>> + Send + 'a>>;
+
+/// Signs transaction hashes on behalf of one Stellar account
+///
+/// Implementations may hold the key in memory or hand the hash to another
+/// process, so signing is asynchronous.
+pub trait Signer: Send + Sync {
+    /// Raw Ed25519 public key of the signing account
+    fn public_key(&self) -> [u8; 32];
+
+    /// Sign a 32-byte transaction hash
+    fn sign<'a>(&'a self, hash: &'a [u8; 32]) -> SignFuture<'a>;
+
+    /// The signing account as a G... strkey
+    fn address(&self) -> String {
+        ed25519::PublicKey(self.public_key()).to_string()
+    }
+}
+
+/// How the admin key is loaded
+#[derive(Debug, Clone)]
+pub struct SignerSettings {
+    /// One of [`SIGNER_KINDS`]
+    pub kind: String,
+    /// Hex or S... secret, or BIP-39 mnemonic, for the `local` signer
+    pub secret_key: String,
+    pub keystore_path: String,
+    pub keystore_password: String,
+    /// BIP-39 passphrase of a mnemonic secret, if it has one
+    pub mnemonic_passphrase: String,
+    /// SEP-5 account derived from a mnemonic secret
+    pub account_index: u32,
+    pub remote_url: String,
+    /// Bearer token sent to the remote signer or Cloud KMS, if any
+    pub remote_token: String,
+    /// G... account the remote or KMS signer signs for
+    pub public_key: String,
+    pub timeout_secs: u64,
+    /// AWS key ID/ARN or GCP key version name
+    pub kms_key_id: String,
+    pub aws_region: String,
+    pub aws_access_key_id: String,
+    pub aws_secret_access_key: String,
+    pub aws_session_token: String,
+}
+
+impl Default for SignerSettings {
+    fn default() -> Self {
+        Self {
+            kind: "local".to_string(),
+            secret_key: String::new(),
+            keystore_path: String::new(),
+            keystore_password: String::new(),
+            mnemonic_passphrase: String::new(),
+            account_index: 0,
+            remote_url: String::new(),
+            remote_token: String::new(),
+            public_key: String::new(),
+            timeout_secs: 10,
+            kms_key_id: String::new(),
+            aws_region: String::new(),
+            aws_access_key_id: String::new(),
+            aws_secret_access_key: String::new(),
+            aws_session_token: String::new(),
+        }
+    }
+}
+
+/// Build the signer selected by `settings`
+pub fn load_signer(settings: &SignerSettings) -> Result<Arc<dyn Signer>> {
+    match settings.kind.as_str() {
+        "local" => {
+            if settings.secret_key.is_empty() {
+                return Err(anyhow!("ADMIN_SECRET_KEY is required for the local signer"));
+            }
+            Ok(Arc::new(LocalSigner::from_configured(&settings.secret_key, settings)?))
+        }
+        "keystore" => {
+            if settings.keystore_path.is_empty() {
+                return Err(anyhow!("SIGNER_KEYSTORE_PATH is required for the keystore signer"));
+            }
+            Ok(Arc::new(LocalSigner::from_keystore(settings)?))
+        }
+        "remote" => Ok(Arc::new(RemoteSigner::new(settings)?)),
+        "aws-kms" => Ok(Arc::new(AwsKmsSigner::new(settings)?)),
+        "gcp-kms" => Ok(Arc::new(GcpKmsSigner::new(settings)?)),
+        other => Err(anyhow!(
+            "Unknown signer {}; expected one of: {}",
+            other,
+            SIGNER_KINDS.join(", ")
+        )),
+    }
+}
+
+/// Signs with a secret key held in memory
+pub struct LocalSigner {
+    keypair: Keypair,
+}
+
+impl LocalSigner {
+    /// Use a hex or Stellar (S...) secret key, or the first SEP-5 account of
+    /// a BIP-39 mnemonic
+    pub fn from_secret(secret_key: &str) -> Result<Self> {
+        Ok(Self {
+            keypair: keypair_from_secret(secret_key)?,
+        })
+    }
+
+    /// Use account `account` of a BIP-39 mnemonic, derived as in SEP-5
+    pub fn from_mnemonic(mnemonic: &str, passphrase: &str, account: u32) -> Result<Self> {
+        Self::from_secret(&sep5::derive_secret(mnemonic, passphrase, account)?)
+    }
+
+    /// Use a secret or mnemonic, deriving the account the settings select
+    fn from_configured(secret: &str, settings: &SignerSettings) -> Result<Self> {
+        if sep5::is_mnemonic(secret) {
+            Self::from_mnemonic(secret, &settings.mnemonic_passphrase, settings.account_index)
+        } else {
+            Self::from_secret(secret)
+        }
+    }
+
+    /// Decrypt the secret key or mnemonic from the settings' keystore file
+    pub fn from_keystore(settings: &SignerSettings) -> Result<Self> {
+        let path = &settings.keystore_path;
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read keystore {}: {}", path, e))?;
+        let keystore: Keystore = serde_json::from_str(&raw)
+            .map_err(|e| anyhow!("Invalid keystore {}: {}", path, e))?;
+
+        let secret = keystore.decrypt(&settings.keystore_password)?;
+        let signer = Self::from_configured(&secret, settings)?;
+        if signer.address() != keystore.public_key {
+            return Err(anyhow!("Keystore {} does not hold the key for {}", path, keystore.public_key));
+        }
+        Ok(signer)
+    }
+}
+
+impl Signer for LocalSigner {
+    fn public_key(&self) -> [u8; 32] {
+        self.keypair.public.to_bytes()
+    }
+
+    fn sign<'a>(&'a self, hash: &'a [u8; 32]) -> SignFuture<'a> {
+        Box::pin(async move { Ok(self.keypair.sign(hash).to_bytes()) })
+    }
+}
+
+/// Encrypted secret key file
+///
+/// The key is derived from the password with scrypt and the S... secret, or
+/// a mnemonic, is sealed with ChaCha20-Poly1305. Binary fields are
+/// base64-encoded.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Keystore {
+    pub version: u32,
+    pub public_key: String,
+    pub kdf: KeystoreKdf,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+/// scrypt parameters of a [`Keystore`]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeystoreKdf {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+    pub salt: String,
+}
+
+impl Keystore {
+    /// Recover the secret key, failing if the password is wrong
+    pub fn decrypt(&self, password: &str) -> Result<String> {
+        if self.version != 1 {
+            return Err(anyhow!("Unsupported keystore version {}", self.version));
+        }
+
+        let salt = BASE64.decode(&self.kdf.salt)
+            .map_err(|_| anyhow!("Invalid keystore salt"))?;
+        let nonce = BASE64.decode(&self.nonce)
+            .map_err(|_| anyhow!("Invalid keystore nonce"))?;
+        let ciphertext = BASE64.decode(&self.ciphertext)
+            .map_err(|_| anyhow!("Invalid keystore ciphertext"))?;
+        if nonce.len() != 12 {
+            return Err(anyhow!("Keystore nonce must be 12 bytes"));
+        }
+
+        let params = scrypt::Params::new(self.kdf.log_n, self.kdf.r, self.kdf.p, 32)
+            .map_err(|e| anyhow!("Invalid keystore scrypt parameters: {}", e))?;
+        let mut key = [0u8; 32];
+        scrypt::scrypt(password.as_bytes(), &salt, &params, &mut key)
+            .map_err(|e| anyhow!("Failed to derive keystore key: {}", e))?;
+
+        let cipher = ChaCha20Poly1305::new_from_slice(&key)
+            .map_err(|e| anyhow!("Invalid keystore key: {}", e))?;
+        let secret = cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| anyhow!("Wrong keystore password or corrupted keystore"))?;
+
+        String::from_utf8(secret).map_err(|_| anyhow!("Keystore does not hold a secret key"))
+    }
+}
+
+/// Asks a generic HTTP signing service for signatures
+///
+/// Each hash is POSTed as `{"public_key", "hash"}` (hex) and the service
+/// answers `{"signature"}` (hex). Signatures are checked against the
+/// configured public key before use.
+pub struct RemoteSigner {
+    url: String,
+    token: String,
+    public_key: [u8; 32],
+    http_client: Client,
+}
+
+#[derive(Serialize)]
+struct RemoteSignRequest<'a> {
+    public_key: &'a str,
+    hash: String,
+}
+
+#[derive(Deserialize)]
+struct RemoteSignResponse {
+    signature: String,
+}
+
+impl RemoteSigner {
+    pub fn new(settings: &SignerSettings) -> Result<Self> {
+        if settings.remote_url.is_empty() {
+            return Err(anyhow!("SIGNER_REMOTE_URL is required for the remote signer"));
+        }
+        let public_key = ed25519::PublicKey::from_string(&settings.public_key)
+            .map_err(|_| anyhow!("SIGNER_PUBLIC_KEY must be the G... account of the remote signer"))?;
+
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(settings.timeout_secs))
+            .build()
+            .map_err(|e| anyhow!("Failed to build signer HTTP client: {}", e))?;
+
+        Ok(Self {
+            url: settings.remote_url.clone(),
+            token: settings.remote_token.clone(),
+            public_key: public_key.0,
+            http_client,
+        })
+    }
+
+    async fn request_signature(&self, hash: &[u8; 32]) -> Result<[u8; 64]> {
+        let address = self.address();
+        let mut request = self.http_client.post(&self.url).json(&RemoteSignRequest {
+            public_key: &address,
+            hash: hex::encode(hash),
+        });
+        if !self.token.is_empty() {
+            request = request.bearer_auth(&self.token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow!("Remote signer request failed: {}", e))?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Remote signer responded with {}", response.status().as_u16()));
+        }
+        let response: RemoteSignResponse = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Invalid remote signer response: {}", e))?;
+
+        let signature: [u8; 64] = hex::decode(&response.signature)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| anyhow!("Remote signer returned a malformed signature"))?;
+        verify_signature(&self.public_key, hash, &signature)?;
+
+        Ok(signature)
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn public_key(&self) -> [u8; 32] {
+        self.public_key
+    }
+
+    fn sign<'a>(&'a self, hash: &'a [u8; 32]) -> SignFuture<'a> {
+        Box::pin(self.request_signature(hash))
+    }
+}
+
+/// Check an Ed25519 signature, so a misconfigured signer is caught before submission
+pub fn verify_signature(public_key: &[u8; 32], message: &[u8], signature: &[u8; 64]) -> Result<()> {
+    let public_key = PublicKey::from_bytes(public_key)
+        .map_err(|_| anyhow!("Invalid signer public key"))?;
+    let signature = DalekSignature::from_bytes(signature)
+        .map_err(|_| anyhow!("Invalid signature"))?;
+
+    public_key
+        .verify(message, &signature)
+        .map_err(|_| anyhow!("Signature does not match the signer's public key"))
+}
@@ -1,11 +1,298 @@
 use anyhow::{anyhow, Result};
-use ed25519_dalek::{Keypair, SECRET_KEY_LENGTH};
-use reqwest::Client;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, SECRET_KEY_LENGTH};
+use rand::Rng;
+use reqwest::{Client, Proxy, StatusCode};
 use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
 use stellar_strkey::ed25519;
-use tracing::{debug, info, warn};
+use stellar_xdr::curr::{
+    AccountEntry, AccountId, ContractDataDurability, ContractEvent as XdrContractEvent, ContractEventBody, DiagnosticEvent, ContractExecutable, ContractIdPreimage,
+    ContractIdPreimageFromAddress, CreateContractArgs, DecoratedSignature, ExtendFootprintTtlOp, ExtensionPoint, FeeBumpTransaction, FeeBumpTransactionEnvelope,
+    FeeBumpTransactionExt, FeeBumpTransactionInnerTx, Hash, HashIdPreimage, HashIdPreimageContractId,
+    HashIdPreimageSorobanAuthorization,
+    HostFunction, InnerTransactionResultResult, InvokeContractArgs,
+    InvokeHostFunctionOp, LedgerEntryData, LedgerFootprint, LedgerKey, LedgerKeyAccount,
+    LedgerKeyContractCode, LedgerKeyContractData, Limited, Limits, Memo, MuxedAccount, Operation,
+    OperationBody, OperationResult, OperationResultTr, Preconditions, PublicKey as XdrPublicKey, ReadXdr, ScAddress, ScBytes, ScError, ScMap, ScMapEntry, ScSpecEntry,
+    ScString, ScSymbol, ScVal, ScVec, SequenceNumber, Signature, SignatureHint, SorobanAddressCredentials,
+    SorobanAuthorizationEntry, SorobanAuthorizedInvocation, SorobanCredentials,
+    SorobanResources, SorobanTransactionData, Transaction, TransactionEnvelope, TransactionExt,
+    TransactionMeta, TransactionResult, TransactionResultResult,
+    TransactionSignaturePayload, TransactionSignaturePayloadTaggedTransaction,
+    TransactionV1Envelope, Uint256, WriteXdr,
+};
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant},
+};
+use tracing::{debug, info, instrument, warn, Span};
 
-use crate::models::Certificate;
+use crate::{
+    cache::ResponseCache,
+    channels::ChannelPool,
+    circuit::{CircuitBreaker, CircuitSettings},
+    fees::{FeeEstimate, FeeSettings, InclusionFeeStats},
+    models::{
+        CallSummary, CertId, Certificate, ContractEvent, EmittedEvent, LegacyCertificate,
+        MetadataHash, PreparedTransaction, StellarAddress,
+    },
+    networks::NetworkProfile,
+    sep5,
+    signer::Signer,
+};
+
+/// Sent transactions whose envelopes are kept for fee bumps
+const SENT_TRANSACTIONS_KEPT: usize = 1000;
+
+/// Ledgers an authorization signed for a channel transaction stays valid (about 5 minutes)
+const AUTH_VALIDITY_LEDGERS: u32 = 60;
+
+/// Contract functions this client calls
+pub const CONTRACT_FUNCTIONS: &[&str] = &[
+    "init",
+    "issue_certificate",
+    "issue_batch",
+    "verify",
+    "get_certificate_details",
+    "get_cert_collection",
+    "get_collection",
+    "get_status",
+    "get_warranty",
+    "transfer",
+    "revoke",
+    "certificate_exists",
+    "get_admin",
+];
+
+/// Tunables for waiting on submitted transactions
+#[derive(Debug, Clone)]
+pub struct SubmitSettings {
+    pub wait_by_default: bool,
+    pub wait_timeout_secs: u64,
+    pub poll_interval_ms: u64,
+}
+
+impl Default for SubmitSettings {
+    fn default() -> Self {
+        Self {
+            wait_by_default: false,
+            wait_timeout_secs: 30,
+            poll_interval_ms: 1000,
+        }
+    }
+}
+
+/// Tunables for the HTTP client reaching Soroban RPC endpoints
+#[derive(Debug, Clone)]
+pub struct HttpSettings {
+    pub connect_timeout_secs: u64,
+    /// Limit on a whole RPC request, from connecting to reading the response
+    pub timeout_secs: u64,
+    /// Idle connections kept open per RPC host
+    pub pool_max_idle_per_host: usize,
+    pub pool_idle_timeout_secs: u64,
+    /// HTTP(S) proxy for RPC requests; none when empty
+    pub proxy_url: String,
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            connect_timeout_secs: 5,
+            timeout_secs: 30,
+            pool_max_idle_per_host: 32,
+            pool_idle_timeout_secs: 90,
+            proxy_url: String::new(),
+        }
+    }
+}
+
+impl HttpSettings {
+    /// Build a client with these settings, to be shared by every RPC client
+    pub fn build_client(&self) -> Result<Client> {
+        let mut builder = Client::builder()
+            .connect_timeout(Duration::from_secs(self.connect_timeout_secs))
+            .timeout(Duration::from_secs(self.timeout_secs))
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .pool_idle_timeout(Some(Duration::from_secs(self.pool_idle_timeout_secs)));
+        if !self.proxy_url.is_empty() {
+            let proxy = Proxy::all(&self.proxy_url)
+                .map_err(|e| anyhow!("Invalid RPC_PROXY_URL: {}", e))?;
+            builder = builder.proxy(proxy);
+        }
+
+        builder
+            .build()
+            .map_err(|e| anyhow!("Failed to build HTTP client: {}", e))
+    }
+}
+
+/// How RPC calls are retried when the RPC is briefly unavailable
+#[derive(Debug, Clone)]
+pub struct RetrySettings {
+    /// Attempts per call, including the first; `1` disables retries
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetrySettings {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 200,
+            max_backoff_ms: 5000,
+        }
+    }
+}
+
+impl RetrySettings {
+    /// Delay before retrying after the `attempt`th failure
+    ///
+    /// The cap doubles with each attempt and the delay is drawn uniformly
+    /// below it ("full jitter"), so clients failing together don't retry together.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let cap = self
+            .initial_backoff_ms
+            .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+            .min(self.max_backoff_ms);
+        Duration::from_millis(rand::thread_rng().gen_range(0..=cap))
+    }
+}
+
+/// Whether a failed RPC request may be sent again
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Retry {
+    /// The RPC answered; sending the same request again gets the same answer
+    Never,
+    /// The request wasn't processed (connection refused, 429, 503)
+    Always,
+    /// The request may have been processed (timeout, other 5xx)
+    IfIdempotent,
+}
+
+/// A failed RPC request and whether it's worth retrying
+struct RpcFailure {
+    error: anyhow::Error,
+    retry: Retry,
+}
+
+impl RpcFailure {
+    fn permanent(error: anyhow::Error) -> Self {
+        Self { error, retry: Retry::Never }
+    }
+}
+
+/// Final state of a submitted transaction, with what its receipt says about the call
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransactionOutcome {
+    Success { ledger: u32, summary: CallSummary },
+    Failed { ledger: u32, summary: CallSummary },
+}
+
+/// A typed error returned by the contract, mirroring its `Error` enum
+///
+/// The client attaches these to the errors it returns when a simulation or
+/// transaction fails with `Error(Contract, #n)`; see [`ContractError::find`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContractError {
+    InvalidCertIdLength,
+    InvalidCertIdChar,
+    CertificateNotFound,
+    CertificateExists,
+    NotAuthorized,
+    CertificateRevoked,
+}
+
+impl ContractError {
+    /// The error with contract code `code`, if the API knows it
+    pub fn from_code(code: u32) -> Option<Self> {
+        match code {
+            1 => Some(Self::InvalidCertIdLength),
+            2 => Some(Self::InvalidCertIdChar),
+            3 => Some(Self::CertificateNotFound),
+            4 => Some(Self::CertificateExists),
+            5 => Some(Self::NotAuthorized),
+            6 => Some(Self::CertificateRevoked),
+            _ => None,
+        }
+    }
+
+    pub fn code(self) -> u32 {
+        match self {
+            Self::InvalidCertIdLength => 1,
+            Self::InvalidCertIdChar => 2,
+            Self::CertificateNotFound => 3,
+            Self::CertificateExists => 4,
+            Self::NotAuthorized => 5,
+            Self::CertificateRevoked => 6,
+        }
+    }
+
+    /// The first known `Error(Contract, #n)` in an RPC or host error message
+    pub fn parse(message: &str) -> Option<Self> {
+        const MARKER: &str = "Error(Contract, #";
+        message.match_indices(MARKER).find_map(|(start, _)| {
+            let digits: String = message[start + MARKER.len()..]
+                .chars()
+                .take_while(char::is_ascii_digit)
+                .collect();
+            digits.parse().ok().and_then(Self::from_code)
+        })
+    }
+
+    /// The contract error behind `error`, whether attached by the client or
+    /// only mentioned in a message that wrapped it
+    pub fn find(error: &anyhow::Error) -> Option<Self> {
+        error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<Self>().copied())
+            .or_else(|| Self::parse(&format!("{:#}", error)))
+    }
+}
+
+impl fmt::Display for ContractError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            Self::InvalidCertIdLength => "Certificate ID must be 3-64 characters",
+            Self::InvalidCertIdChar => "Certificate ID may only contain [A-Za-z0-9._-]",
+            Self::CertificateNotFound => "Certificate not found",
+            Self::CertificateExists => "Certificate already exists",
+            Self::NotAuthorized => "Not authorized for this certificate",
+            Self::CertificateRevoked => "Certificate is revoked",
+        };
+        write!(f, "{} (Error(Contract, #{}))", description, self.code())
+    }
+}
+
+impl std::error::Error for ContractError {}
+
+/// `message`, carrying the contract error named in `detail` if there is one
+fn contract_failure(message: String, detail: &str) -> anyhow::Error {
+    match ContractError::parse(detail) {
+        Some(error) => anyhow::Error::new(error).context(message),
+        None => anyhow!("{}", message),
+    }
+}
+
+/// A batch of contract events and the cursor to resume from
+#[derive(Debug, Clone)]
+pub struct EventPage {
+    pub events: Vec<ContractEvent>,
+    pub cursor: Option<String>,
+    pub latest_ledger: u32,
+}
+
+/// What `simulateTransaction` tells us about a prepared invocation
+struct Simulation {
+    transaction_data: SorobanTransactionData,
+    min_resource_fee: i64,
+    auth: Vec<SorobanAuthorizationEntry>,
+    result: Option<ScVal>,
+}
 
 /// Simplified Soroban client for contract interactions
 #[derive(Clone)]
@@ -13,207 +300,1766 @@ pub struct SorobanClient {
     rpc_url: String,
     network_passphrase: String,
     contract_id: String,
-    admin_secret_key: String, // Store as string instead of Keypair
+    signer: Option<Arc<dyn Signer>>,
     http_client: Client,
+    cache: ResponseCache,
+    retry: RetrySettings,
+    breaker: CircuitBreaker,
+    channels: Option<ChannelPool>,
+    /// Shared with clients derived from this one, and replaced on reload
+    fees: Arc<RwLock<FeeSettings>>,
+    /// Pays for fee bumps instead of the admin account
+    sponsor: Option<Arc<dyn Signer>>,
+    /// Envelopes of recently sent transactions, newest last
+    sent: Arc<Mutex<VecDeque<(String, TransactionV1Envelope)>>>,
 }
 
 impl SorobanClient {
-    /// Create a new Soroban client
+    /// Create a new Soroban client that signs admin transactions with `signer`
     pub fn new(
         rpc_url: String,
         network_passphrase: String,
         contract_id: String,
-        admin_secret_key: String,
-    ) -> Result<Self> {
-        // Validate the admin secret key format
-        if admin_secret_key.len() == 64 {
-            // Hex format - validate it's valid hex
-            hex::decode(&admin_secret_key)
-                .map_err(|_| anyhow!("Invalid admin secret key hex format"))?;
-        } else if admin_secret_key.starts_with('S') {
-            // Stellar secret key format - validate it's valid
-            ed25519::PrivateKey::from_string(&admin_secret_key)
-                .map_err(|_| anyhow!("Invalid Stellar secret key format"))?;
-        } else {
-            return Err(anyhow!("Invalid secret key format. Use hex or Stellar format"));
-        };
-
-        Ok(Self {
+        signer: Arc<dyn Signer>,
+    ) -> Self {
+        Self {
             rpc_url,
             network_passphrase,
             contract_id,
-            admin_secret_key,
+            signer: Some(signer),
             http_client: Client::new(),
-        })
+            cache: ResponseCache::disabled(),
+            retry: RetrySettings::default(),
+            breaker: CircuitBreaker::new(CircuitSettings::default()),
+            channels: None,
+            fees: Arc::default(),
+            sponsor: None,
+            sent: Arc::default(),
+        }
     }
 
-    /// Create a keypair from the stored secret key
-    fn _create_keypair(&self) -> Result<Keypair> {
-        let secret_bytes = if self.admin_secret_key.len() == 64 {
-            // Hex format
-            hex::decode(&self.admin_secret_key)
-                .map_err(|_| anyhow!("Invalid secret key hex format"))?
-        } else if self.admin_secret_key.starts_with('S') {
-            // Stellar secret key format
-            ed25519::PrivateKey::from_string(&self.admin_secret_key)
-                .map_err(|_| anyhow!("Invalid Stellar secret key format"))?
-                .0.to_vec()
-        } else {
-            return Err(anyhow!("Invalid secret key format"));
-        };
-        
-        if secret_bytes.len() != SECRET_KEY_LENGTH {
-            return Err(anyhow!("Secret key must be 32 bytes"));
+    /// Create a client that can only read contract state (no admin key)
+    pub fn new_read_only(rpc_url: String, network_passphrase: String, contract_id: String) -> Self {
+        Self {
+            rpc_url,
+            network_passphrase,
+            contract_id,
+            signer: None,
+            http_client: Client::new(),
+            cache: ResponseCache::disabled(),
+            retry: RetrySettings::default(),
+            breaker: CircuitBreaker::new(CircuitSettings::default()),
+            channels: None,
+            fees: Arc::default(),
+            sponsor: None,
+            sent: Arc::default(),
         }
+    }
 
-        let mut secret_array = [0u8; SECRET_KEY_LENGTH];
-        secret_array.copy_from_slice(&secret_bytes);
-        
-        Keypair::from_bytes(&secret_array)
-            .map_err(|_| anyhow!("Failed to create keypair from secret key"))
+    /// Serve certificate reads from `cache`, invalidating it on writes made through this client
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = cache;
+        self
     }
 
-    /// Initialize the contract with admin - simplified version
-    pub async fn init(&self, admin_address: &str) -> Result<String> {
+    /// Send RPC requests through `http_client`, e.g. one built from [`HttpSettings`]
+    pub fn with_http_client(mut self, http_client: Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Retry RPC calls that fail for transient reasons according to `retry`
+    pub fn with_retry(mut self, retry: RetrySettings) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Fail RPC calls fast after repeated failures, according to `settings`
+    pub fn with_circuit_breaker(mut self, settings: CircuitSettings) -> Self {
+        self.breaker = CircuitBreaker::new(settings);
+        self
+    }
+
+    /// Source contract invocations from `channels` instead of the admin account
+    pub fn with_channels(mut self, channels: ChannelPool) -> Self {
+        self.channels = Some(channels);
+        self
+    }
+
+    /// Choose fees according to `fees`, paying for fee bumps from `sponsor` when given
+    pub fn with_fees(mut self, fees: FeeSettings, sponsor: Option<Arc<dyn Signer>>) -> Self {
+        self.fees = Arc::new(RwLock::new(fees));
+        self.sponsor = sponsor;
+        self
+    }
+
+    /// The fee settings in effect
+    pub fn fee_settings(&self) -> FeeSettings {
+        self.fees.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replace the fee settings of this client and the clients derived from it
+    ///
+    /// Transactions already being prepared keep the fees they were given.
+    pub fn set_fee_settings(&self, fees: FeeSettings) {
+        *self.fees.write().unwrap_or_else(|e| e.into_inner()) = fees;
+    }
+
+    /// How long until RPC calls are let through again, if the circuit breaker is open
+    pub fn circuit_retry_after(&self) -> Option<Duration> {
+        self.breaker.retry_after()
+    }
+
+    /// A client for another network profile, sharing this client's signer
+    ///
+    /// The new client doesn't cache reads until given its own cache, has its
+    /// own circuit breaker, and uses neither channel accounts nor the fee
+    /// bump sponsor.
+    pub fn for_network(&self, profile: &NetworkProfile) -> Self {
+        Self {
+            rpc_url: profile.rpc_url.clone(),
+            network_passphrase: profile.network_passphrase.clone(),
+            contract_id: profile.contract_id.clone(),
+            signer: self.signer.clone(),
+            http_client: self.http_client.clone(),
+            cache: ResponseCache::disabled(),
+            retry: self.retry.clone(),
+            breaker: self.breaker.fresh(),
+            channels: None,
+            fees: self.fees.clone(),
+            sponsor: None,
+            sent: Arc::default(),
+        }
+    }
+
+    /// A client for another contract on the same network, signing with `signer`
+    ///
+    /// Invocations still go through this client's channel accounts, if any,
+    /// and fee bumps are paid by the same sponsor.
+    pub fn for_contract(&self, contract_id: &str, signer: Option<Arc<dyn Signer>>) -> Self {
+        Self {
+            rpc_url: self.rpc_url.clone(),
+            network_passphrase: self.network_passphrase.clone(),
+            contract_id: contract_id.to_string(),
+            signer,
+            http_client: self.http_client.clone(),
+            cache: ResponseCache::disabled(),
+            retry: self.retry.clone(),
+            breaker: self.breaker.clone(),
+            channels: self.channels.clone(),
+            fees: self.fees.clone(),
+            sponsor: self.sponsor.clone(),
+            sent: self.sent.clone(),
+        }
+    }
+
+    /// Passphrase of the network this client submits to
+    pub fn network_passphrase(&self) -> &str {
+        &self.network_passphrase
+    }
+
+    /// Signer for the admin account, if this client has one
+    pub fn signer(&self) -> Option<Arc<dyn Signer>> {
+        self.signer.clone()
+    }
+
+    fn admin_signer(&self) -> Result<&dyn Signer> {
+        self.signer
+            .as_deref()
+            .ok_or_else(|| anyhow!("No admin signer configured"))
+    }
+
+    /// Initialize the contract with admin
+    ///
+    /// The admin address must be the account of the configured admin key, since
+    /// the contract requires the admin's authorization and the transaction is
+    /// signed with that key.
+    pub async fn init(&self, admin_address: &StellarAddress) -> Result<String> {
         info!("Initializing contract with admin: {}", admin_address);
-        
-        // For now, return a mock response
-        // In a real implementation, you would call the Soroban CLI or use proper XDR encoding
-        warn!("Using mock implementation - contract initialization not fully implemented");
-        
-        let mock_tx_hash = format!("mock_init_tx_{}", uuid::Uuid::new_v4());
-        Ok(mock_tx_hash)
+
+        let signer = self.admin_signer()?;
+        self.invoke(signer, "init", vec![address_val(admin_address)?]).await
     }
 
-    /// Issue a new certificate - simplified version
+    /// Issue a new certificate
     pub async fn issue_certificate(
         &self,
-        cert_id: &str,
-        metadata_hash: &str,
-        owner_address: &str,
+        cert_id: &CertId,
+        metadata_hash: &MetadataHash,
+        owner_address: &StellarAddress,
     ) -> Result<String> {
         info!("Issuing certificate: {} for owner: {}", cert_id, owner_address);
-        
-        // Validate inputs
-        if cert_id.is_empty() || metadata_hash.is_empty() || owner_address.is_empty() {
-            return Err(anyhow!("All parameters are required"));
+
+        let signer = self.admin_signer()?;
+        let args = vec![
+            string_val(cert_id)?,
+            string_val(metadata_hash)?,
+            address_val(owner_address)?,
+        ];
+        self.cache.invalidate(cert_id).await;
+        self.invoke(signer, "issue_certificate", args).await
+    }
+
+    /// Issue several certificates atomically through the contract's `issue_batch`
+    pub async fn issue_batch(&self, certificates: &[LegacyCertificate]) -> Result<String> {
+        info!("Issuing a batch of {} certificates", certificates.len());
+
+        if certificates.is_empty() {
+            return Err(anyhow!("Batch cannot be empty"));
+        }
+
+        let signer = self.admin_signer()?;
+        let mut entries = Vec::with_capacity(certificates.len());
+        for certificate in certificates {
+            // Struct fields are encoded as a map sorted by field name
+            let fields = vec![
+                struct_field("cert_id", string_val(&certificate.cert_id)?)?,
+                struct_field("metadata_hash", string_val(&certificate.metadata_hash)?)?,
+                struct_field("owner", address_val(&certificate.owner_address)?)?,
+            ];
+            entries.push(ScVal::Map(Some(ScMap(
+                fields.try_into().map_err(|_| anyhow!("Too many struct fields"))?,
+            ))));
         }
-        
-        // For now, return a mock response
-        warn!("Using mock implementation - certificate issuance not fully implemented");
-        
-        let mock_tx_hash = format!("mock_issue_tx_{}", uuid::Uuid::new_v4());
-        Ok(mock_tx_hash)
+        let entries = ScVal::Vec(Some(ScVec(
+            entries.try_into().map_err(|_| anyhow!("Batch too large"))?,
+        )));
+
+        for certificate in certificates {
+            self.cache.invalidate(&certificate.cert_id).await;
+        }
+        self.invoke(signer, "issue_batch", vec![entries]).await
     }
 
-    /// Verify a certificate - simplified version
+    /// Verify a certificate against a metadata hash
     pub async fn verify_certificate(
         &self,
-        cert_id: &str,
-        metadata_hash: &str,
+        cert_id: &CertId,
+        metadata_hash: &MetadataHash,
     ) -> Result<bool> {
         info!("Verifying certificate: {}", cert_id);
-        
-        // For demo purposes, return true if cert_id is not empty
-        // In real implementation, this would query the contract
-        warn!("Using mock implementation - certificate verification not fully implemented");
-        
-        Ok(!cert_id.is_empty() && !metadata_hash.is_empty())
+
+        let field = format!("verify:{}", metadata_hash);
+        if let Some(is_valid) = self.cache.get(cert_id, &field).await {
+            return Ok(is_valid);
+        }
+
+        let args = vec![string_val(cert_id)?, string_val(metadata_hash)?];
+        let is_valid = match self.read("verify", args).await? {
+            ScVal::Bool(is_valid) => is_valid,
+            other => return Err(anyhow!("Unexpected verify result: {:?}", other)),
+        };
+        self.cache.put(cert_id, &field, &is_valid).await;
+        Ok(is_valid)
     }
 
-    /// Get certificate details - simplified version
-    pub async fn get_certificate_details(&self, cert_id: &str) -> Result<Certificate> {
+    /// Get certificate details
+    pub async fn get_certificate_details(&self, cert_id: &CertId) -> Result<Certificate> {
         info!("Getting certificate details for: {}", cert_id);
-        
-        if cert_id.is_empty() {
-            return Err(anyhow!("Certificate ID cannot be empty"));
-        }
-        
-        // For demo purposes, return a mock certificate
-        warn!("Using mock implementation - certificate details not fully implemented");
-        
-        Ok(Certificate {
-            owner: "GXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX".to_string(),
-            metadata_hash: "QmMockHash123456789".to_string(),
-            is_valid: true,
-        })
+
+        if let Some(certificate) = self.cache.get(cert_id, "details").await {
+            return Ok(certificate);
+        }
+
+        // Unknown IDs fail with ContractError::CertificateNotFound
+        let details = self.read("get_certificate_details", vec![string_val(cert_id)?]).await?;
+
+        let fields = match &details {
+            ScVal::Map(Some(fields)) => fields,
+            other => return Err(anyhow!("Unexpected certificate details: {:?}", other)),
+        };
+
+        let certificate = Certificate {
+            owner: match map_field(fields, "owner")? {
+                ScVal::Address(owner) => address_to_string(owner),
+                other => return Err(anyhow!("Unexpected owner value: {:?}", other)),
+            },
+            metadata_hash: match map_field(fields, "metadata_hash")? {
+                ScVal::String(hash) => hash.to_utf8_string()
+                    .map_err(|_| anyhow!("Metadata hash is not valid UTF-8"))?,
+                other => return Err(anyhow!("Unexpected metadata_hash value: {:?}", other)),
+            },
+            is_valid: match map_field(fields, "is_valid")? {
+                ScVal::Bool(is_valid) => *is_valid,
+                other => return Err(anyhow!("Unexpected is_valid value: {:?}", other)),
+            },
+        };
+        self.cache.put(cert_id, "details", &certificate).await;
+        Ok(certificate)
+    }
+
+    /// Get the collection a certificate was issued into, with the collection's brand
+    pub async fn get_certificate_collection(&self, cert_id: &CertId) -> Result<Option<(u32, String)>> {
+        let collection_id = match self.read("get_cert_collection", vec![string_val(cert_id)?]).await? {
+            ScVal::U32(collection_id) => collection_id,
+            ScVal::Void => return Ok(None),
+            other => return Err(anyhow!("Unexpected get_cert_collection result: {:?}", other)),
+        };
+
+        let collection = match self.read("get_collection", vec![ScVal::U32(collection_id)]).await? {
+            ScVal::Map(Some(fields)) => fields,
+            other => return Err(anyhow!("Unexpected get_collection result: {:?}", other)),
+        };
+
+        match map_field(&collection, "brand")? {
+            ScVal::String(brand) => Ok(Some((collection_id, brand.to_utf8_string_lossy()))),
+            other => Err(anyhow!("Unexpected brand value: {:?}", other)),
+        }
+    }
+
+    /// Get a certificate's lifecycle status: `Valid`, `Disputed`, `Suspended` or `Revoked`
+    pub async fn get_certificate_status(&self, cert_id: &CertId) -> Result<String> {
+        match self.read("get_status", vec![string_val(cert_id)?]).await? {
+            ScVal::Vec(Some(variant)) => match variant.first() {
+                Some(ScVal::Symbol(status)) => Ok(status.0.to_utf8_string_lossy()),
+                other => Err(anyhow!("Unexpected get_status variant: {:?}", other)),
+            },
+            other => Err(anyhow!("Unexpected get_status result: {:?}", other)),
+        }
+    }
+
+    /// Get when a certificate's warranty ends (unix seconds), if it has one
+    pub async fn get_warranty(&self, cert_id: &CertId) -> Result<Option<u64>> {
+        match self.read("get_warranty", vec![string_val(cert_id)?]).await? {
+            ScVal::U64(expires_at) => Ok(Some(expires_at)),
+            ScVal::Void => Ok(None),
+            other => Err(anyhow!("Unexpected get_warranty result: {:?}", other)),
+        }
     }
 
-    /// Transfer certificate ownership - simplified version
-    pub async fn transfer_certificate(
+    /// Build an unsigned transfer for the current owner to sign
+    ///
+    /// The owner is the transaction source, which covers the contract's owner
+    /// auth, so the owner's signature on the envelope is all it needs.
+    pub async fn prepare_transfer(
         &self,
-        cert_id: &str,
-        new_owner_address: &str,
-        current_owner_secret_key: &str,
-    ) -> Result<String> {
-        info!("Transferring certificate: {} to: {}", cert_id, new_owner_address);
-        
-        // Validate inputs
-        if cert_id.is_empty() || new_owner_address.is_empty() || current_owner_secret_key.is_empty() {
-            return Err(anyhow!("All parameters are required"));
-        }
-        
-        // For now, return a mock response
-        warn!("Using mock implementation - certificate transfer not fully implemented");
-        
-        let mock_tx_hash = format!("mock_transfer_tx_{}", uuid::Uuid::new_v4());
-        Ok(mock_tx_hash)
-    }
-
-    /// Revoke a certificate - simplified version
-    pub async fn revoke_certificate(&self, cert_id: &str) -> Result<String> {
-        info!("Revoking certificate: {}", cert_id);
-        
-        if cert_id.is_empty() {
-            return Err(anyhow!("Certificate ID cannot be empty"));
+        cert_id: &CertId,
+        new_owner_address: &StellarAddress,
+    ) -> Result<PreparedTransaction> {
+        info!("Preparing transfer of certificate: {} to: {}", cert_id, new_owner_address);
+
+        let owner = self.get_certificate_details(cert_id).await?.owner;
+        let source = ed25519::PublicKey::from_string(&owner)
+            .map_err(|_| anyhow!("Certificate {} is held by a contract, not an account", cert_id))?;
+
+        let args = vec![
+            string_val(cert_id)?,
+            address_val(new_owner_address)?,
+            ScVal::Void, // price
+            ScVal::Void, // currency
+            ScVal::Void, // memo
+        ];
+        let operation = self.invoke_operation("transfer", args)?;
+        let transaction = self.build_transaction(source.0, operation).await?;
+        let transaction = self.prepare_transaction(transaction).await?;
+        let hash = self.transaction_hash(&transaction)?;
+
+        let envelope = TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx: transaction,
+            signatures: Default::default(),
+        });
+        let envelope_xdr = envelope.to_xdr(Limits::none())
+            .map_err(|e| anyhow!("Failed to encode transaction envelope: {}", e))?;
+
+        Ok(PreparedTransaction {
+            transaction: BASE64.encode(envelope_xdr),
+            hash: hex::encode(hash),
+            source: owner,
+            network_passphrase: self.network_passphrase.clone(),
+        })
+    }
+
+    /// Decode a client-signed transaction and check it only invokes this contract
+    pub fn decode_signed_transaction(&self, transaction_xdr: &str) -> Result<TransactionEnvelope> {
+        let bytes = BASE64.decode(transaction_xdr.trim())
+            .map_err(|_| anyhow!("Invalid transaction: not valid base64"))?;
+        let envelope = TransactionEnvelope::from_xdr(bytes, Limits::none())
+            .map_err(|_| anyhow!("Invalid transaction: not a transaction envelope"))?;
+
+        let TransactionEnvelope::Tx(v1) = &envelope else {
+            return Err(anyhow!("Invalid transaction: expected a v1 transaction envelope"));
+        };
+        if v1.signatures.is_empty() {
+            return Err(anyhow!("Invalid transaction: not signed"));
+        }
+
+        let contract = self.contract_address()?;
+        for operation in v1.tx.operations.iter() {
+            match &operation.body {
+                OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+                    host_function: HostFunction::InvokeContract(invocation),
+                    ..
+                }) if invocation.contract_address == contract => {}
+                _ => {
+                    return Err(anyhow!(
+                        "Invalid transaction: only invocations of contract {} are accepted",
+                        self.contract_id
+                    ))
+                }
+            }
         }
-        
-        // For now, return a mock response
-        warn!("Using mock implementation - certificate revocation not fully implemented");
-        
-        let mock_tx_hash = format!("mock_revoke_tx_{}", uuid::Uuid::new_v4());
-        Ok(mock_tx_hash)
+
+        Ok(envelope)
     }
 
-    /// Check if certificate exists - simplified version
-    pub async fn certificate_exists(&self, cert_id: &str) -> Result<bool> {
+    /// Submit a transaction signed by the client, such as a prepared transfer
+    pub async fn submit_signed_transaction(&self, transaction_xdr: &str) -> Result<String> {
+        let envelope = self.decode_signed_transaction(transaction_xdr)?;
+        for cert_id in invoked_certificates(&envelope) {
+            self.cache.invalidate(&cert_id).await;
+        }
+        self.send_transaction(&envelope).await
+    }
+
+    /// Revoke a certificate
+    pub async fn revoke_certificate(&self, cert_id: &CertId) -> Result<String> {
+        info!("Revoking certificate: {}", cert_id);
+
+        let signer = self.admin_signer()?;
+        self.cache.invalidate(cert_id).await;
+        self.invoke(signer, "revoke", vec![string_val(cert_id)?]).await
+    }
+
+    /// Check if certificate exists
+    pub async fn certificate_exists(&self, cert_id: &CertId) -> Result<bool> {
         info!("Checking if certificate exists: {}", cert_id);
-        
-        // For demo purposes, return true if cert_id is not empty
-        warn!("Using mock implementation - certificate existence check not fully implemented");
-        
-        Ok(!cert_id.is_empty())
+
+        if let Some(exists) = self.cache.get(cert_id, "exists").await {
+            return Ok(exists);
+        }
+
+        let exists = match self.read("certificate_exists", vec![string_val(cert_id)?]).await? {
+            ScVal::Bool(exists) => exists,
+            other => return Err(anyhow!("Unexpected certificate_exists result: {:?}", other)),
+        };
+        self.cache.put(cert_id, "exists", &exists).await;
+        Ok(exists)
+    }
+
+    /// The admin address stored by `init`; fails while the contract is uninitialized
+    pub async fn get_admin(&self) -> Result<String> {
+        match self.read("get_admin", vec![]).await? {
+            ScVal::Address(admin) => Ok(address_to_string(&admin)),
+            other => Err(anyhow!("Unexpected get_admin result: {:?}", other)),
+        }
+    }
+
+    /// The admin's public key (G... strkey), as reported by the signer
+    pub fn admin_public_key(&self) -> Result<String> {
+        Ok(self.admin_signer()?.address())
+    }
+
+    /// Get the sequence number of the latest ledger known to the RPC
+    pub async fn get_latest_ledger(&self) -> Result<u32> {
+        let result = self.make_rpc_call("getLatestLedger", json!({})).await?;
+
+        result.get("sequence")
+            .and_then(Value::as_u64)
+            .map(|seq| seq as u32)
+            .ok_or_else(|| anyhow!("Missing sequence in getLatestLedger response"))
+    }
+
+    /// Get the ledger until which the contract instance entry stays live
+    pub async fn get_contract_instance_live_until(&self) -> Result<u32> {
+        let key = self.contract_instance_key()?;
+
+        let entry = self.get_ledger_entry(&key).await?
+            .ok_or_else(|| anyhow!("Contract instance {} not found", self.contract_id))?;
+
+        entry.get("liveUntilLedgerSeq")
+            .and_then(Value::as_u64)
+            .map(|seq| seq as u32)
+            .ok_or_else(|| anyhow!("Missing liveUntilLedgerSeq for contract instance"))
+    }
+
+    /// Names of the functions the deployed contract's wasm exports
+    ///
+    /// Read from the interface spec embedded in the wasm's `contractspecv0`
+    /// custom section.
+    pub async fn contract_functions(&self) -> Result<Vec<String>> {
+        let code_key = LedgerKey::ContractCode(LedgerKeyContractCode {
+            hash: self.contract_wasm_hash().await?,
+        });
+        let entry = self.get_ledger_entry(&code_key).await?
+            .ok_or_else(|| anyhow!("Wasm code of contract {} not found", self.contract_id))?;
+        let wasm = match decode_entry_data(&entry)? {
+            LedgerEntryData::ContractCode(code) => code.code.to_vec(),
+            _ => return Err(anyhow!("Ledger entry is not contract code")),
+        };
+
+        let spec = wasm_custom_section(&wasm, "contractspecv0")
+            .ok_or_else(|| anyhow!("Contract wasm has no interface spec"))?;
+        let mut reader = Limited::new(spec, Limits::none());
+        ScSpecEntry::read_xdr_iter(&mut reader)
+            .filter_map(|entry| match entry {
+                Ok(ScSpecEntry::FunctionV0(function)) => Some(Ok(function.name.0.to_utf8_string_lossy())),
+                Ok(_) => None,
+                Err(e) => Some(Err(anyhow!("Failed to decode contract spec: {}", e))),
+            })
+            .collect()
+    }
+
+    /// Hash of the wasm code the configured contract instance runs
+    async fn contract_wasm_hash(&self) -> Result<Hash> {
+        let instance = self.get_ledger_entry(&self.contract_instance_key()?).await?
+            .ok_or_else(|| anyhow!("Contract instance {} not found", self.contract_id))?;
+
+        match decode_entry_data(&instance)? {
+            LedgerEntryData::ContractData(data) => match data.val {
+                ScVal::ContractInstance(instance) => match instance.executable {
+                    ContractExecutable::Wasm(hash) => Ok(hash),
+                    ContractExecutable::StellarAsset => {
+                        Err(anyhow!("Contract {} has no wasm code", self.contract_id))
+                    }
+                },
+                _ => Err(anyhow!("Unexpected contract instance value")),
+            },
+            _ => Err(anyhow!("Ledger entry is not contract data")),
+        }
+    }
+
+    /// Get the native balance of an account, in stroops
+    pub async fn get_account_balance(&self, account_id: &str) -> Result<i64> {
+        Ok(self.get_account(account_id).await?.balance)
+    }
+
+    /// Extend the TTL of the contract instance and code
+    pub async fn extend_contract_ttl(&self, extend_to_ledgers: u32) -> Result<String> {
+        info!("Extending contract TTL by {} ledgers", extend_to_ledgers);
+
+        let signer = self.admin_signer()?;
+        let instance_key = self.contract_instance_key()?;
+        let code_key = LedgerKey::ContractCode(LedgerKeyContractCode {
+            hash: self.contract_wasm_hash().await?,
+        });
+        let footprint = LedgerFootprint {
+            read_only: vec![instance_key, code_key]
+                .try_into()
+                .map_err(|_| anyhow!("Footprint too large"))?,
+            read_write: Default::default(),
+        };
+        let operation = Operation {
+            source_account: None,
+            body: OperationBody::ExtendFootprintTtl(ExtendFootprintTtlOp {
+                ext: ExtensionPoint::V0,
+                extend_to: extend_to_ledgers,
+            }),
+        };
+
+        // The footprint has to be declared up front for simulation to price it
+        let mut transaction = self.build_transaction(signer.public_key(), operation).await?;
+        transaction.ext = TransactionExt::V1(SorobanTransactionData {
+            ext: ExtensionPoint::V0,
+            resources: SorobanResources {
+                footprint,
+                instructions: 0,
+                read_bytes: 0,
+                write_bytes: 0,
+            },
+            resource_fee: 0,
+        });
+
+        self.simulate_and_submit(signer, transaction).await
+    }
+
+    /// Upload contract wasm and deploy an instance of it from the admin account
+    ///
+    /// Each step is awaited for up to `timeout`, since the instance can only be
+    /// created once the code is on the ledger. `salt` makes the contract ID
+    /// unique among the admin's deployments. Returns the new contract's ID.
+    pub async fn deploy_contract(&self, wasm: &[u8], salt: [u8; 32], timeout: Duration) -> Result<String> {
+        let signer = self.admin_signer()?;
+        let wasm_hash = Hash(Sha256::digest(wasm).into());
+        info!("Uploading contract wasm {}", hex::encode(wasm_hash.0));
+
+        let upload = Operation {
+            source_account: None,
+            body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+                host_function: HostFunction::UploadContractWasm(
+                    wasm.to_vec().try_into().map_err(|_| anyhow!("Contract wasm too large"))?,
+                ),
+                auth: Default::default(),
+            }),
+        };
+        let transaction = self.build_transaction(signer.public_key(), upload).await?;
+        let hash = self.simulate_and_submit(signer, transaction).await?;
+        self.confirm(&hash, timeout).await?;
+
+        let preimage = ContractIdPreimage::Address(ContractIdPreimageFromAddress {
+            address: ScAddress::Account(AccountId(XdrPublicKey::PublicKeyTypeEd25519(Uint256(
+                signer.public_key(),
+            )))),
+            salt: Uint256(salt),
+        });
+        let contract_id = HashIdPreimage::ContractId(HashIdPreimageContractId {
+            network_id: Hash(Sha256::digest(self.network_passphrase.as_bytes()).into()),
+            contract_id_preimage: preimage.clone(),
+        })
+        .to_xdr(Limits::none())
+        .map_err(|e| anyhow!("Failed to encode contract ID preimage: {}", e))?;
+        let contract_id = stellar_strkey::Contract(Sha256::digest(contract_id).into()).to_string();
+
+        info!("Deploying contract {}", contract_id);
+        let create = Operation {
+            source_account: None,
+            body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+                host_function: HostFunction::CreateContract(CreateContractArgs {
+                    contract_id_preimage: preimage,
+                    executable: ContractExecutable::Wasm(wasm_hash),
+                }),
+                auth: Default::default(),
+            }),
+        };
+        let transaction = self.build_transaction(signer.public_key(), create).await?;
+        let hash = self.simulate_and_submit(signer, transaction).await?;
+        self.confirm(&hash, timeout).await?;
+
+        Ok(contract_id)
+    }
+
+    /// Wait for a transaction this client submitted, failing unless it succeeds
+    async fn confirm(&self, hash: &str, timeout: Duration) -> Result<u32> {
+        match self.wait_for_transaction(hash, timeout, Duration::from_secs(1)).await? {
+            TransactionOutcome::Success { ledger, .. } => Ok(ledger),
+            TransactionOutcome::Failed { ledger, summary } => Err(contract_failure(
+                summary.failure_message(hash, ledger),
+                summary.error.as_deref().unwrap_or_default(),
+            )),
+        }
+    }
+
+    /// Invoke a contract function authorized by `signer`
+    ///
+    /// Without channel accounts `signer` is also the transaction's source and
+    /// pays the fee. With them, the next idle channel account is the source
+    /// and `signer` only signs the invocation's authorization entries.
+    #[instrument(name = "contract_invoke", skip_all, fields(contract.function = function))]
+    async fn invoke(&self, signer: &dyn Signer, function: &str, args: Vec<ScVal>) -> Result<String> {
+        let operation = self.invoke_operation(function, args)?;
+        let Some(channels) = &self.channels else {
+            let transaction = self.build_transaction(signer.public_key(), operation).await?;
+            return self.simulate_and_submit(signer, transaction).await;
+        };
+
+        // Held until submission, so no other transaction takes the same sequence number
+        let channel = channels.acquire().await;
+        debug!("Invoking {} through channel account {}", function, channel.signer.address());
+
+        let transaction = self.build_transaction(channel.signer.public_key(), operation).await?;
+        let transaction = self.prepare_transaction(transaction).await?;
+        let transaction = self.authorize_invocation(signer, transaction).await?;
+        let envelope = self.sign_transaction(channel.signer.as_ref(), transaction).await?;
+        self.send_transaction(&envelope).await
+    }
+
+    /// Sign the authorization entries `signer` must provide, then re-price the transaction
+    ///
+    /// Simulation records the entries unsigned when the invoker isn't the
+    /// transaction's source. Verifying the signatures costs resources the
+    /// first simulation didn't count, so the signed transaction is simulated
+    /// again for its final footprint and fee.
+    async fn authorize_invocation(&self, signer: &dyn Signer, mut transaction: Transaction) -> Result<Transaction> {
+        let invoker = ScAddress::Account(AccountId(XdrPublicKey::PublicKeyTypeEd25519(Uint256(
+            signer.public_key(),
+        ))));
+        let expiration = self.get_latest_ledger().await? + AUTH_VALIDITY_LEDGERS;
+        let network_id = Hash(Sha256::digest(self.network_passphrase.as_bytes()).into());
+
+        let mut operations = transaction.operations.to_vec();
+        if let Some(OperationBody::InvokeHostFunction(invoke)) =
+            operations.first_mut().map(|operation| &mut operation.body)
+        {
+            let mut entries = invoke.auth.to_vec();
+            for entry in &mut entries {
+                let SorobanCredentials::Address(credentials) = &mut entry.credentials else {
+                    continue;
+                };
+                if credentials.address != invoker {
+                    continue;
+                }
+                sign_authorization(signer, &network_id, credentials, entry.root_invocation.clone(), expiration)
+                    .await?;
+            }
+            invoke.auth = entries
+                .try_into()
+                .map_err(|_| anyhow!("Too many authorization entries"))?;
+        }
+        transaction.operations = operations
+            .try_into()
+            .map_err(|_| anyhow!("Too many operations"))?;
+
+        let inclusion_fee = match &transaction.ext {
+            TransactionExt::V1(data) => transaction.fee.saturating_sub(u32::try_from(data.resource_fee)?),
+            TransactionExt::V0 => transaction.fee,
+        };
+        let simulation = self.simulate_transaction(&transaction).await?;
+        self.apply_fee(&mut transaction, inclusion_fee, simulation.min_resource_fee)?;
+        transaction.ext = TransactionExt::V1(simulation.transaction_data);
+
+        Ok(transaction)
+    }
+
+    /// Call a read-only contract function through simulation and return its result
+    ///
+    /// Nothing is signed or submitted, so the source account only has to be a
+    /// well-formed key; the admin's account is used when one is configured.
+    #[instrument(name = "contract_read", skip_all, fields(contract.function = function))]
+    async fn read(&self, function: &str, args: Vec<ScVal>) -> Result<ScVal> {
+        let transaction = self.unsigned_invocation(function, args)?;
+        self.simulate_transaction(&transaction).await?
+            .result
+            .ok_or_else(|| anyhow!("Simulation of {} returned no result", function))
+    }
+
+    /// A transaction invoking `function` that is only ever simulated
+    fn unsigned_invocation(&self, function: &str, args: Vec<ScVal>) -> Result<Transaction> {
+        let source = match self.admin_signer() {
+            Ok(signer) => signer.public_key(),
+            Err(_) => [0u8; 32],
+        };
+
+        Ok(Transaction {
+            source_account: MuxedAccount::Ed25519(Uint256(source)),
+            fee: self.fee_settings().base_fee,
+            seq_num: SequenceNumber(0),
+            cond: Preconditions::None,
+            memo: Memo::None,
+            operations: vec![self.invoke_operation(function, args)?]
+                .try_into()
+                .map_err(|_| anyhow!("Too many operations"))?,
+            ext: TransactionExt::V0,
+        })
+    }
+
+    /// Operation invoking a function on the configured contract
+    fn invoke_operation(&self, function: &str, args: Vec<ScVal>) -> Result<Operation> {
+        let invocation = InvokeContractArgs {
+            contract_address: self.contract_address()?,
+            function_name: ScSymbol(
+                function.try_into().map_err(|_| anyhow!("Invalid function name: {}", function))?,
+            ),
+            args: args.try_into().map_err(|_| anyhow!("Too many arguments"))?,
+        };
+
+        Ok(Operation {
+            source_account: None,
+            body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+                host_function: HostFunction::InvokeContract(invocation),
+                auth: Default::default(),
+            }),
+        })
+    }
+
+    /// Build a single-operation transaction with the given account as source
+    async fn build_transaction(&self, source: [u8; 32], operation: Operation) -> Result<Transaction> {
+        let account = self.get_account(&ed25519::PublicKey(source).to_string()).await?;
+
+        Ok(Transaction {
+            source_account: MuxedAccount::Ed25519(Uint256(source)),
+            fee: self.inclusion_fee().await,
+            seq_num: SequenceNumber(account.seq_num.0 + 1),
+            cond: Preconditions::None,
+            memo: Memo::None,
+            operations: vec![operation]
+                .try_into()
+                .map_err(|_| anyhow!("Too many operations"))?,
+            ext: TransactionExt::V0,
+        })
+    }
+
+    /// Simulate a transaction, apply the resources and auth it needs, then sign and send it
+    async fn simulate_and_submit(&self, signer: &dyn Signer, transaction: Transaction) -> Result<String> {
+        let transaction = self.prepare_transaction(transaction).await?;
+        let envelope = self.sign_transaction(signer, transaction).await?;
+        self.send_transaction(&envelope).await
+    }
+
+    /// Inclusion fee to bid on a new transaction, per the fee strategy
+    async fn inclusion_fee(&self) -> u32 {
+        let fees = self.fee_settings();
+        if fees.strategy != "surge" {
+            return fees.base_fee;
+        }
+        match self.get_fee_stats().await {
+            Ok(stats) => fees.inclusion_fee(Some(stats)),
+            Err(e) => {
+                warn!("Failed to get fee stats, bidding the base fee: {}", e);
+                fees.base_fee
+            }
+        }
+    }
+
+    /// Recent Soroban inclusion fees, via `getFeeStats`
+    async fn get_fee_stats(&self) -> Result<InclusionFeeStats> {
+        let result = self.make_rpc_call("getFeeStats", json!({})).await?;
+        let stats = result.get("sorobanInclusionFee")
+            .ok_or_else(|| anyhow!("Missing sorobanInclusionFee in getFeeStats response"))?;
+        let field = |name: &str| {
+            stats.get(name)
+                .and_then(|value| match value {
+                    Value::String(value) => value.parse::<u64>().ok(),
+                    other => other.as_u64(),
+                })
+                .unwrap_or_default()
+        };
+
+        Ok(InclusionFeeStats {
+            p50: field("p50"),
+            p90: field("p90"),
+            max: field("max"),
+        })
+    }
+
+    /// Set the fee to `inclusion_fee` plus the simulated resource fee, within `max_fee`
+    fn apply_fee(&self, transaction: &mut Transaction, inclusion_fee: u32, min_resource_fee: i64) -> Result<()> {
+        let fee = inclusion_fee
+            .checked_add(u32::try_from(min_resource_fee)?)
+            .ok_or_else(|| anyhow!("Transaction fee overflow"))?;
+        let max_fee = self.fee_settings().max_fee;
+        if fee > max_fee {
+            return Err(anyhow!(
+                "Transaction fee of {} stroops exceeds the maximum of {}",
+                fee,
+                max_fee
+            ));
+        }
+        transaction.fee = fee;
+        Ok(())
+    }
+
+    /// Current fees and the simulated cost of issuing a certificate
+    pub async fn estimate_fees(&self) -> Result<FeeEstimate> {
+        let stats = self.get_fee_stats().await?;
+        let owner = address_val(&self.admin_public_key()?)?;
+        let args = vec![
+            string_val(&format!("fee-estimate-{}", uuid::Uuid::new_v4()))?,
+            string_val(&"0".repeat(64))?,
+            owner,
+        ];
+        let transaction = self.unsigned_invocation("issue_certificate", args)?;
+        let simulation = self.simulate_transaction(&transaction).await?;
+        let fees = self.fee_settings();
+        let inclusion_fee = fees.inclusion_fee(Some(stats));
+
+        Ok(FeeEstimate {
+            strategy: fees.strategy,
+            inclusion_fee,
+            network_inclusion_fee_p50: stats.p50,
+            network_inclusion_fee_p90: stats.p90,
+            network_inclusion_fee_max: stats.max,
+            issue_resource_fee: simulation.min_resource_fee,
+            issue_total_fee: simulation.min_resource_fee + i64::from(inclusion_fee),
+            max_fee: fees.max_fee,
+            latest_ledger: self.get_latest_ledger().await?,
+        })
+    }
+
+    /// Hex hash of a v1 transaction envelope on this client's network
+    pub fn envelope_hash(&self, envelope: &TransactionEnvelope) -> Result<String> {
+        match envelope {
+            TransactionEnvelope::Tx(v1) => Ok(hex::encode(self.transaction_hash(&v1.tx)?)),
+            _ => Err(anyhow!("Invalid transaction: expected a v1 transaction envelope")),
+        }
     }
 
-    /// Make RPC call to Soroban network (placeholder for future implementation)
-    async fn _make_rpc_call(&self, method: &str, params: Value) -> Result<Value> {
+    /// A transaction this client sent recently, by hash
+    pub fn sent_transaction(&self, hash: &str) -> Option<TransactionEnvelope> {
+        let sent = self.sent.lock().unwrap_or_else(|e| e.into_inner());
+        sent.iter()
+            .rev()
+            .find(|(sent_hash, _)| sent_hash == hash)
+            .map(|(_, envelope)| TransactionEnvelope::Tx(envelope.clone()))
+    }
+
+    /// Resubmit a signed transaction wrapped in a fee bump paid by the sponsor
+    ///
+    /// `fee` is the bump's total fee; it defaults to ten times the inner
+    /// transaction's, the minimum for the bump to replace the original while
+    /// it is still pending. Returns the hash of the fee-bump transaction.
+    pub async fn fee_bump(&self, inner: TransactionEnvelope, fee: Option<i64>) -> Result<String> {
+        let TransactionEnvelope::Tx(inner) = inner else {
+            return Err(anyhow!("Invalid transaction: only v1 transactions can be fee-bumped"));
+        };
+        let sponsor = match &self.sponsor {
+            Some(sponsor) => sponsor.as_ref(),
+            None => self.admin_signer()?,
+        };
+
+        let inner_fee = i64::from(inner.tx.fee);
+        let fee = fee.unwrap_or(inner_fee * 10);
+        if fee <= inner_fee {
+            return Err(anyhow!("Invalid transaction: the fee bump must pay more than the inner transaction's {} stroops", inner_fee));
+        }
+        let max_fee = self.fee_settings().max_fee;
+        if fee > i64::from(max_fee) {
+            return Err(anyhow!(
+                "Invalid transaction: a fee bump of {} stroops exceeds the maximum of {}",
+                fee,
+                max_fee
+            ));
+        }
+
+        let bump = FeeBumpTransaction {
+            fee_source: MuxedAccount::Ed25519(Uint256(sponsor.public_key())),
+            fee,
+            inner_tx: FeeBumpTransactionInnerTx::Tx(inner),
+            ext: FeeBumpTransactionExt::V0,
+        };
+        let payload = TransactionSignaturePayload {
+            network_id: Hash(Sha256::digest(self.network_passphrase.as_bytes()).into()),
+            tagged_transaction: TransactionSignaturePayloadTaggedTransaction::TxFeeBump(bump.clone()),
+        };
+        let payload_xdr = payload.to_xdr(Limits::none())
+            .map_err(|e| anyhow!("Failed to encode signature payload: {}", e))?;
+        let signature = sign_hash(sponsor, &Sha256::digest(payload_xdr).into()).await?;
+
+        info!("Fee-bumping transaction with a fee of {} stroops from {}", fee, sponsor.address());
+        let envelope = TransactionEnvelope::TxFeeBump(FeeBumpTransactionEnvelope {
+            tx: bump,
+            signatures: vec![signature]
+                .try_into()
+                .map_err(|_| anyhow!("Too many signatures"))?,
+        });
+        self.send_transaction(&envelope).await
+    }
+
+    /// Simulate a transaction and apply the fee, resources and auth it needs
+    #[instrument(name = "simulate", skip_all)]
+    async fn prepare_transaction(&self, mut transaction: Transaction) -> Result<Transaction> {
+        let simulation = self.simulate_transaction(&transaction).await?;
+
+        // Before simulation the fee is just the inclusion fee
+        let inclusion_fee = transaction.fee;
+        self.apply_fee(&mut transaction, inclusion_fee, simulation.min_resource_fee)?;
+        transaction.ext = TransactionExt::V1(simulation.transaction_data);
+
+        let mut operations = transaction.operations.to_vec();
+        if let Some(OperationBody::InvokeHostFunction(invoke)) =
+            operations.first_mut().map(|operation| &mut operation.body)
+        {
+            invoke.auth = simulation
+                .auth
+                .try_into()
+                .map_err(|_| anyhow!("Too many authorization entries"))?;
+        }
+        transaction.operations = operations
+            .try_into()
+            .map_err(|_| anyhow!("Too many operations"))?;
+
+        Ok(transaction)
+    }
+
+    /// Ask the RPC to simulate a transaction and return its resource requirements
+    async fn simulate_transaction(&self, transaction: &Transaction) -> Result<Simulation> {
+        let envelope = TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx: transaction.clone(),
+            signatures: Default::default(),
+        });
+        let envelope_xdr = envelope.to_xdr(Limits::none())
+            .map_err(|e| anyhow!("Failed to encode transaction: {}", e))?;
+
+        let result = self
+            .make_rpc_call("simulateTransaction", json!({ "transaction": BASE64.encode(envelope_xdr) }))
+            .await?;
+
+        if let Some(error) = result.get("error").and_then(Value::as_str) {
+            return Err(contract_failure(format!("Simulation failed: {}", error), error));
+        }
+
+        let transaction_data = result.get("transactionData")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("Missing transactionData in simulation response"))?;
+        let transaction_data = SorobanTransactionData::from_xdr(
+            BASE64.decode(transaction_data)
+                .map_err(|e| anyhow!("Invalid base64 in transactionData: {}", e))?,
+            Limits::none(),
+        )
+        .map_err(|e| anyhow!("Failed to decode transactionData: {}", e))?;
+
+        let min_resource_fee = result.get("minResourceFee")
+            .and_then(|fee| match fee {
+                Value::String(fee) => fee.parse::<i64>().ok(),
+                other => other.as_i64(),
+            })
+            .ok_or_else(|| anyhow!("Missing minResourceFee in simulation response"))?;
+
+        let first_result = result.get("results")
+            .and_then(Value::as_array)
+            .and_then(|results| results.first());
+
+        let mut auth = Vec::new();
+        let entries = first_result
+            .and_then(|first| first.get("auth"))
+            .and_then(Value::as_array);
+        for entry in entries.into_iter().flatten() {
+            let entry = entry.as_str()
+                .ok_or_else(|| anyhow!("Invalid auth entry in simulation response"))?;
+            let bytes = BASE64.decode(entry)
+                .map_err(|e| anyhow!("Invalid base64 in auth entry: {}", e))?;
+            auth.push(
+                SorobanAuthorizationEntry::from_xdr(bytes, Limits::none())
+                    .map_err(|e| anyhow!("Failed to decode auth entry: {}", e))?,
+            );
+        }
+
+        let return_value = match first_result
+            .and_then(|first| first.get("xdr"))
+            .and_then(Value::as_str)
+        {
+            Some(xdr) => Some(
+                ScVal::from_xdr(
+                    BASE64.decode(xdr)
+                        .map_err(|e| anyhow!("Invalid base64 in simulation result: {}", e))?,
+                    Limits::none(),
+                )
+                .map_err(|e| anyhow!("Failed to decode simulation result: {}", e))?,
+            ),
+            None => None,
+        };
+
+        Ok(Simulation {
+            transaction_data,
+            min_resource_fee,
+            auth,
+            result: return_value,
+        })
+    }
+
+    /// Sign a transaction for this client's network
+    async fn sign_transaction(&self, signer: &dyn Signer, transaction: Transaction) -> Result<TransactionEnvelope> {
+        let hash = self.transaction_hash(&transaction)?;
+        let signature = sign_hash(signer, &hash).await?;
+
+        Ok(TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx: transaction,
+            signatures: vec![signature]
+                .try_into()
+                .map_err(|_| anyhow!("Too many signatures"))?,
+        }))
+    }
+
+    /// Hash that signers of a transaction commit to
+    fn transaction_hash(&self, transaction: &Transaction) -> Result<[u8; 32]> {
+        transaction_hash(&self.network_passphrase, transaction)
+    }
+
+    /// Submit a signed transaction and return its hash
+    ///
+    /// A `TRY_AGAIN_LATER` answer means the RPC didn't accept the transaction,
+    /// so it is resent after a backoff, up to the retry policy's attempts.
+    #[instrument(name = "submit", skip_all, fields(tx.hash))]
+    async fn send_transaction(&self, envelope: &TransactionEnvelope) -> Result<String> {
+        let envelope_xdr = envelope.to_xdr(Limits::none())
+            .map_err(|e| anyhow!("Failed to encode transaction envelope: {}", e))?;
+        let params = json!({ "transaction": BASE64.encode(envelope_xdr) });
+
+        let mut attempt = 1;
+        loop {
+            let result = self.make_rpc_call("sendTransaction", params.clone()).await?;
+
+            let status = result.get("status").and_then(Value::as_str).unwrap_or_default();
+            let hash = result.get("hash")
+                .and_then(Value::as_str)
+                .ok_or_else(|| anyhow!("Missing hash in sendTransaction response"))?;
+            Span::current().record("tx.hash", hash);
+
+            match status {
+                "PENDING" | "DUPLICATE" => {
+                    info!("Submitted transaction {} ({})", hash, status);
+                    if let TransactionEnvelope::Tx(v1) = envelope {
+                        let mut sent = self.sent.lock().unwrap_or_else(|e| e.into_inner());
+                        if sent.len() >= SENT_TRANSACTIONS_KEPT {
+                            sent.pop_front();
+                        }
+                        sent.push_back((hash.to_string(), v1.clone()));
+                    }
+                    return Ok(hash.to_string());
+                }
+                "TRY_AGAIN_LATER" if attempt < self.retry.max_attempts => {
+                    let delay = self.retry.backoff(attempt);
+                    warn!("RPC asked to try again later for transaction {}; resending in {:?}", hash, delay);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                "TRY_AGAIN_LATER" => {
+                    return Err(anyhow!("RPC asked to try again later for transaction {}", hash))
+                }
+                _ => {
+                    let detail = result.get("errorResultXdr")
+                        .and_then(Value::as_str)
+                        .unwrap_or("no error result");
+                    return Err(anyhow!("Transaction {} rejected with status {}: {}", hash, status, detail));
+                }
+            }
+        }
+    }
+
+    /// Poll `getTransaction` until a submitted transaction succeeds or fails
+    ///
+    /// The outcome carries the decoded result, return value and events; for
+    /// failures, the diagnostic events and the contract error they report.
+    /// Returns an error mentioning "not confirmed" if the transaction is still
+    /// unknown to the RPC once `timeout` has passed.
+    #[instrument(name = "wait_for_transaction", skip_all, fields(tx.hash = hash))]
+    pub async fn wait_for_transaction(
+        &self,
+        hash: &str,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> Result<TransactionOutcome> {
+        info!("Waiting for transaction {} to complete", hash);
+
+        let started = Instant::now();
+        loop {
+            let result = self.make_rpc_call("getTransaction", json!({ "hash": hash })).await?;
+            let ledger = result.get("ledger")
+                .and_then(Value::as_u64)
+                .unwrap_or_default() as u32;
+
+            match result.get("status").and_then(Value::as_str) {
+                Some("SUCCESS") => {
+                    let summary = call_summary(&result, false);
+                    return Ok(TransactionOutcome::Success { ledger, summary });
+                }
+                Some("FAILED") => {
+                    let summary = call_summary(&result, true);
+                    return Ok(TransactionOutcome::Failed { ledger, summary });
+                }
+                Some("NOT_FOUND") => {}
+                other => return Err(anyhow!("Unexpected getTransaction status: {:?}", other)),
+            }
+
+            if started.elapsed() >= timeout {
+                return Err(anyhow!(
+                    "Transaction {} not confirmed within {} seconds",
+                    hash,
+                    timeout.as_secs()
+                ));
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Fetch contract events via `getEvents`
+    ///
+    /// Pass the cursor of a previous page to continue after it, or a
+    /// `start_ledger` to begin a fresh scan.
+    pub async fn get_events(
+        &self,
+        start_ledger: u32,
+        cursor: Option<&str>,
+        limit: u32,
+    ) -> Result<EventPage> {
+        let mut params = json!({
+            "filters": [{ "type": "contract", "contractIds": [self.contract_id] }],
+            "pagination": { "limit": limit },
+        });
+        match cursor {
+            Some(cursor) => params["pagination"]["cursor"] = json!(cursor),
+            None => params["startLedger"] = json!(start_ledger),
+        }
+
+        let result = self.make_rpc_call("getEvents", params).await?;
+
+        let events = result.get("events")
+            .and_then(Value::as_array)
+            .map(|events| events.iter().filter_map(decode_event).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        let cursor = result.get("cursor")
+            .and_then(Value::as_str)
+            .filter(|cursor| !cursor.is_empty())
+            .map(str::to_string)
+            .or_else(|| events.last().map(|event| event.id.clone()))
+            .or_else(|| cursor.map(str::to_string));
+
+        let latest_ledger = result.get("latestLedger")
+            .and_then(Value::as_u64)
+            .unwrap_or_default() as u32;
+
+        Ok(EventPage { events, cursor, latest_ledger })
+    }
+
+    /// Load an account entry from the ledger
+    async fn get_account(&self, account_id: &str) -> Result<AccountEntry> {
+        let public_key = ed25519::PublicKey::from_string(account_id)
+            .map_err(|_| anyhow!("Invalid account ID: {}", account_id))?;
+
+        let key = LedgerKey::Account(LedgerKeyAccount {
+            account_id: AccountId(XdrPublicKey::PublicKeyTypeEd25519(Uint256(public_key.0))),
+        });
+
+        let entry = self.get_ledger_entry(&key).await?
+            .ok_or_else(|| anyhow!("Account {} not found", account_id))?;
+
+        match decode_entry_data(&entry)? {
+            LedgerEntryData::Account(account) => Ok(account),
+            _ => Err(anyhow!("Ledger entry for {} is not an account", account_id)),
+        }
+    }
+
+    /// Address of the configured contract
+    fn contract_address(&self) -> Result<ScAddress> {
+        let contract = stellar_strkey::Contract::from_string(&self.contract_id)
+            .map_err(|_| anyhow!("Invalid contract ID: {}", self.contract_id))?;
+        Ok(ScAddress::Contract(Hash(contract.0)))
+    }
+
+    /// Ledger key of the configured contract's instance entry
+    fn contract_instance_key(&self) -> Result<LedgerKey> {
+        Ok(LedgerKey::ContractData(LedgerKeyContractData {
+            contract: self.contract_address()?,
+            key: ScVal::LedgerKeyContractInstance,
+            durability: ContractDataDurability::Persistent,
+        }))
+    }
+
+    /// Fetch a single ledger entry via `getLedgerEntries`
+    async fn get_ledger_entry(&self, key: &LedgerKey) -> Result<Option<Value>> {
+        let key_xdr = key.to_xdr(Limits::none())
+            .map_err(|e| anyhow!("Failed to encode ledger key: {}", e))?;
+
+        let result = self
+            .make_rpc_call("getLedgerEntries", json!({ "keys": [BASE64.encode(key_xdr)] }))
+            .await?;
+
+        Ok(result.get("entries")
+            .and_then(Value::as_array)
+            .and_then(|entries| entries.first())
+            .cloned())
+    }
+
+    /// Make RPC call to Soroban network
+    ///
+    /// Requests the RPC didn't process (refused connections, 429, 503) are
+    /// retried with jittered exponential backoff. Timeouts and other 5xx
+    /// answers are retried too, except for `sendTransaction`, where the
+    /// transaction may already have been accepted. Errors reported by the RPC
+    /// itself are returned immediately. Calls that still fail count towards
+    /// opening the circuit breaker, which then refuses calls with
+    /// [`CircuitOpen`](crate::circuit::CircuitOpen) without contacting the RPC.
+    #[instrument(name = "rpc_call", skip_all, fields(rpc.method = method))]
+    async fn make_rpc_call(&self, method: &str, params: Value) -> Result<Value> {
+        self.breaker.admit()?;
+
         let request_body = json!({
             "jsonrpc": "2.0",
             "id": uuid::Uuid::new_v4().to_string(),
             "method": method,
             "params": params
         });
+        let idempotent = method != "sendTransaction";
+
+        let mut attempt = 1;
+        loop {
+            let failure = match self.send_rpc_request(&request_body).await {
+                Ok(result) => {
+                    self.breaker.record_success();
+                    return Ok(result);
+                }
+                Err(failure) => failure,
+            };
+            // An error answered by the RPC means it is up
+            if failure.retry == Retry::Never {
+                self.breaker.record_success();
+                return Err(failure.error);
+            }
+            let retryable = failure.retry == Retry::Always || idempotent;
+            if !retryable || attempt >= self.retry.max_attempts {
+                self.breaker.record_failure();
+                return Err(failure.error);
+            }
 
+            let delay = self.retry.backoff(attempt);
+            warn!(
+                "RPC {} failed (attempt {} of {}), retrying in {:?}: {}",
+                method, attempt, self.retry.max_attempts, delay, failure.error
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Send one JSON-RPC request and return its result
+    async fn send_rpc_request(&self, request_body: &Value) -> std::result::Result<Value, RpcFailure> {
         debug!("Making RPC call to: {}", self.rpc_url);
 
         let response = self.http_client
             .post(&self.rpc_url)
-            .json(&request_body)
+            .json(request_body)
             .send()
             .await
-            .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
+            .map_err(|e| RpcFailure {
+                retry: if e.is_connect() {
+                    Retry::Always
+                } else if e.is_timeout() {
+                    Retry::IfIdempotent
+                } else {
+                    Retry::Never
+                },
+                error: anyhow!("HTTP request failed: {}", e),
+            })?;
+
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            return Err(RpcFailure {
+                retry: match status {
+                    StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => Retry::Always,
+                    _ => Retry::IfIdempotent,
+                },
+                error: anyhow!("HTTP request failed: RPC answered {}", status),
+            });
+        }
 
         let response_body: Value = response.json().await
-            .map_err(|e| anyhow!("Failed to parse JSON response: {}", e))?;
+            .map_err(|e| RpcFailure::permanent(anyhow!("Failed to parse JSON response: {}", e)))?;
 
         debug!("RPC response: {}", response_body);
 
         if let Some(error) = response_body.get("error") {
-            return Err(anyhow!("RPC error: {}", error));
+            return Err(RpcFailure::permanent(anyhow!("RPC error: {}", error)));
         }
 
         response_body.get("result")
             .cloned()
-            .ok_or_else(|| anyhow!("No result in RPC response"))
+            .ok_or_else(|| RpcFailure::permanent(anyhow!("No result in RPC response")))
+    }
+}
+
+/// Certificate IDs a client transaction acts on, taken from each invocation's first argument
+fn invoked_certificates(envelope: &TransactionEnvelope) -> Vec<String> {
+    let TransactionEnvelope::Tx(v1) = envelope else {
+        return Vec::new();
+    };
+
+    v1.tx
+        .operations
+        .iter()
+        .filter_map(|operation| match &operation.body {
+            OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+                host_function: HostFunction::InvokeContract(invocation),
+                ..
+            }) => match invocation.args.first() {
+                Some(ScVal::String(cert_id)) => Some(cert_id.to_utf8_string_lossy()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// Hash that signers of a transaction commit to on the given network
+pub fn transaction_hash(network_passphrase: &str, transaction: &Transaction) -> Result<[u8; 32]> {
+    let payload = TransactionSignaturePayload {
+        network_id: Hash(Sha256::digest(network_passphrase.as_bytes()).into()),
+        tagged_transaction: TransactionSignaturePayloadTaggedTransaction::Tx(transaction.clone()),
+    };
+    let payload_xdr = payload.to_xdr(Limits::none())
+        .map_err(|e| anyhow!("Failed to encode signature payload: {}", e))?;
+
+    Ok(Sha256::digest(payload_xdr).into())
+}
+
+/// Sign an address-credentials authorization entry, valid until `expiration`
+///
+/// The signature is the `[{public_key, signature}]` vector checked by
+/// Stellar accounts' built-in `__check_auth`.
+async fn sign_authorization(
+    signer: &dyn Signer,
+    network_id: &Hash,
+    credentials: &mut SorobanAddressCredentials,
+    invocation: SorobanAuthorizedInvocation,
+    expiration: u32,
+) -> Result<()> {
+    let preimage = HashIdPreimage::SorobanAuthorization(HashIdPreimageSorobanAuthorization {
+        network_id: network_id.clone(),
+        nonce: credentials.nonce,
+        signature_expiration_ledger: expiration,
+        invocation,
+    });
+    let preimage_xdr = preimage.to_xdr(Limits::none())
+        .map_err(|e| anyhow!("Failed to encode authorization preimage: {}", e))?;
+    let signature = signer.sign(&Sha256::digest(preimage_xdr).into()).await?;
+
+    let bytes = |value: &[u8]| -> Result<ScVal> {
+        Ok(ScVal::Bytes(ScBytes(
+            value.to_vec().try_into().map_err(|_| anyhow!("Bytes value too long"))?,
+        )))
+    };
+    let fields = vec![
+        struct_field("public_key", bytes(&signer.public_key())?)?,
+        struct_field("signature", bytes(&signature)?)?,
+    ];
+    let signature = ScVal::Map(Some(ScMap(
+        fields.try_into().map_err(|_| anyhow!("Too many struct fields"))?,
+    )));
+
+    credentials.signature_expiration_ledger = expiration;
+    credentials.signature = ScVal::Vec(Some(ScVec(
+        vec![signature].try_into().map_err(|_| anyhow!("Too many signatures"))?,
+    )));
+    Ok(())
+}
+
+/// Sign a transaction hash, tagging the signature with the key's hint
+#[instrument(name = "sign", skip_all)]
+pub async fn sign_hash(signer: &dyn Signer, hash: &[u8; 32]) -> Result<DecoratedSignature> {
+    let signature = signer.sign(hash).await?;
+
+    let public_key = signer.public_key();
+    let mut hint = [0u8; 4];
+    hint.copy_from_slice(&public_key[28..]);
+
+    Ok(DecoratedSignature {
+        hint: SignatureHint(hint),
+        signature: Signature(
+            signature.to_vec()
+                .try_into()
+                .map_err(|_| anyhow!("Invalid signature length"))?,
+        ),
+    })
+}
+
+/// Build a keypair from a hex or Stellar (S...) secret key, or a BIP-39
+/// mnemonic, whose first SEP-5 account is used
+pub fn keypair_from_secret(secret_key: &str) -> Result<Keypair> {
+    if sep5::is_mnemonic(secret_key) {
+        return keypair_from_secret(&sep5::derive_secret(secret_key, "", 0)?);
+    }
+
+    let secret_bytes = if secret_key.len() == 64 {
+        // Hex format
+        hex::decode(secret_key)
+            .map_err(|_| anyhow!("Invalid secret key hex format"))?
+    } else if secret_key.starts_with('S') {
+        // Stellar secret key format
+        ed25519::PrivateKey::from_string(secret_key)
+            .map_err(|_| anyhow!("Invalid Stellar secret key format"))?
+            .0.to_vec()
+    } else {
+        return Err(anyhow!("Invalid secret key format"));
+    };
+
+    if secret_bytes.len() != SECRET_KEY_LENGTH {
+        return Err(anyhow!("Secret key must be 32 bytes"));
+    }
+
+    let secret = SecretKey::from_bytes(&secret_bytes)
+        .map_err(|_| anyhow!("Failed to create keypair from secret key"))?;
+    let public = PublicKey::from(&secret);
+
+    Ok(Keypair { secret, public })
+}
+
+/// Encode a string argument
+fn string_val(value: &str) -> Result<ScVal> {
+    Ok(ScVal::String(ScString(
+        value.try_into().map_err(|_| anyhow!("String argument too long"))?,
+    )))
+}
+
+/// Encode an account (G...) or contract (C...) address argument
+fn address_val(address: &str) -> Result<ScVal> {
+    let address = if let Ok(account) = ed25519::PublicKey::from_string(address) {
+        ScAddress::Account(AccountId(XdrPublicKey::PublicKeyTypeEd25519(Uint256(account.0))))
+    } else if let Ok(contract) = stellar_strkey::Contract::from_string(address) {
+        ScAddress::Contract(Hash(contract.0))
+    } else {
+        return Err(anyhow!("Invalid Stellar address: {}", address));
+    };
+
+    Ok(ScVal::Address(address))
+}
+
+/// Decode the `xdr` field of a `getLedgerEntries` entry
+fn decode_entry_data(entry: &Value) -> Result<LedgerEntryData> {
+    let xdr = entry.get("xdr")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow!("Missing xdr in ledger entry"))?;
+    let bytes = BASE64.decode(xdr)
+        .map_err(|e| anyhow!("Invalid base64 in ledger entry: {}", e))?;
+
+    LedgerEntryData::from_xdr(bytes, Limits::none())
+        .map_err(|e| anyhow!("Failed to decode ledger entry: {}", e))
+}
+
+/// Payload of the wasm custom section called `name`, if the module has one
+fn wasm_custom_section<'a>(wasm: &'a [u8], name: &str) -> Option<&'a [u8]> {
+    // Skip the magic number and version
+    let mut rest = wasm.strip_prefix(b"\0asm")?.get(4..)?;
+    while !rest.is_empty() {
+        let id = rest[0];
+        let (size, len) = read_leb128(&rest[1..])?;
+        let start = 1 + len;
+        let section = rest.get(start..start + size as usize)?;
+        rest = &rest[start + size as usize..];
+
+        if id == 0 {
+            let (name_len, len) = read_leb128(section)?;
+            let section_name = section.get(len..len + name_len as usize)?;
+            if section_name == name.as_bytes() {
+                return section.get(len + name_len as usize..);
+            }
+        }
+    }
+    None
+}
+
+/// Decode an unsigned LEB128 integer, returning it and its length in bytes
+fn read_leb128(bytes: &[u8]) -> Option<(u32, usize)> {
+    let mut value = 0u32;
+    for (i, byte) in bytes.iter().take(5).enumerate() {
+        value |= u32::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+/// Encode one field of a contract struct argument
+fn struct_field(name: &str, val: ScVal) -> Result<ScMapEntry> {
+    Ok(ScMapEntry {
+        key: ScVal::Symbol(ScSymbol(
+            name.try_into().map_err(|_| anyhow!("Invalid field name: {}", name))?,
+        )),
+        val,
+    })
+}
+
+/// Look up a field of a contract struct, which is encoded as a symbol-keyed map
+fn map_field<'a>(map: &'a ScMap, name: &str) -> Result<&'a ScVal> {
+    map.iter()
+        .find(|entry| matches!(&entry.key, ScVal::Symbol(key) if key.0.as_slice() == name.as_bytes()))
+        .map(|entry| &entry.val)
+        .ok_or_else(|| anyhow!("Missing field {} in contract result", name))
+}
+
+/// Render an address as a G... or C... strkey
+fn address_to_string(address: &ScAddress) -> String {
+    match address {
+        ScAddress::Account(AccountId(XdrPublicKey::PublicKeyTypeEd25519(Uint256(key)))) => {
+            ed25519::PublicKey(*key).to_string()
+        }
+        ScAddress::Contract(Hash(contract)) => stellar_strkey::Contract(*contract).to_string(),
+    }
+}
+
+/// Decode a `getEvents` entry into a lifecycle event
+///
+/// Returns `None` for events that don't follow the `(kind, cert_id)` topic
+/// layout used by the contract.
+fn decode_event(raw: &Value) -> Option<ContractEvent> {
+    let topics = raw.get("topic")?
+        .as_array()?
+        .iter()
+        .map(|topic| decode_scval(topic.as_str()?))
+        .collect::<Option<Vec<_>>>()?;
+
+    let event = match topics.first()? {
+        ScVal::Symbol(symbol) => symbol.0.to_utf8_string_lossy(),
+        _ => return None,
+    };
+    let cert_id = match topics.get(1)? {
+        ScVal::String(cert_id) => cert_id.0.to_utf8_string_lossy(),
+        _ => return None,
+    };
+    let value = decode_scval(raw.get("value")?.as_str()?)?;
+
+    Some(ContractEvent {
+        id: raw.get("id")?.as_str()?.to_string(),
+        data: event_data(&event, &value),
+        event,
+        cert_id,
+        ledger: raw.get("ledger")?.as_u64()? as u32,
+        ledger_closed_at: raw.get("ledgerClosedAt")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        transaction_hash: raw.get("txHash")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+    })
+}
+
+fn decode_scval(xdr: &str) -> Option<ScVal> {
+    decode_xdr(xdr)
+}
+
+fn decode_xdr<T: ReadXdr>(xdr: &str) -> Option<T> {
+    let bytes = BASE64.decode(xdr).ok()?;
+    T::from_xdr(bytes, Limits::none()).ok()
+}
+
+/// Summarise a completed `getTransaction` response
+///
+/// Undecodable or missing fields are left empty rather than failing, as the
+/// transaction's status is already known. Diagnostic events are only kept for
+/// failed transactions, where they explain what went wrong.
+fn call_summary(result: &Value, failed: bool) -> CallSummary {
+    let mut summary = CallSummary::default();
+
+    if let Some(tx_result) = result.get("resultXdr")
+        .and_then(Value::as_str)
+        .and_then(decode_xdr::<TransactionResult>)
+    {
+        summary.fee_charged = Some(tx_result.fee_charged);
+        summary.result_code = Some(tx_result.result.name().to_string());
+        let operations = match &tx_result.result {
+            TransactionResultResult::TxSuccess(ops) | TransactionResultResult::TxFailed(ops) => Some(ops),
+            TransactionResultResult::TxFeeBumpInnerSuccess(inner)
+            | TransactionResultResult::TxFeeBumpInnerFailed(inner) => match &inner.result.result {
+                InnerTransactionResultResult::TxSuccess(ops)
+                | InnerTransactionResultResult::TxFailed(ops) => Some(ops),
+                _ => None,
+            },
+            _ => None,
+        };
+        summary.operation_result = operations
+            .and_then(|ops| ops.first())
+            .map(|op| match op {
+                OperationResult::OpInner(OperationResultTr::InvokeHostFunction(result)) => result.name(),
+                OperationResult::OpInner(other) => other.name(),
+                other => other.name(),
+            })
+            .map(str::to_string);
     }
-}
\ No newline at end of file
+
+    let meta = result.get("resultMetaXdr")
+        .and_then(Value::as_str)
+        .and_then(decode_xdr::<TransactionMeta>);
+    let mut diagnostics = Vec::new();
+    if let Some(TransactionMeta::V3(meta)) = meta {
+        if let Some(soroban) = meta.soroban_meta {
+            summary.events = soroban.events.iter().map(emitted_event).collect();
+            if !failed {
+                summary.return_value = Some(scval_to_json(&soroban.return_value));
+            }
+            diagnostics = soroban.diagnostic_events.iter().map(|e| e.event.clone()).collect();
+        }
+    }
+
+    // Failed transactions often carry no meta; newer RPCs return the
+    // diagnostic events alongside instead
+    if diagnostics.is_empty() {
+        diagnostics = result.get("diagnosticEventsXdr")
+            .and_then(Value::as_array)
+            .map(|events| {
+                events.iter()
+                    .filter_map(|event| decode_xdr::<DiagnosticEvent>(event.as_str()?))
+                    .map(|event| event.event)
+                    .collect()
+            })
+            .unwrap_or_default();
+    }
+
+    if failed {
+        summary.error = diagnostics.iter().find_map(contract_error);
+        summary.diagnostic_events = diagnostics.iter().map(emitted_event).collect();
+    }
+
+    summary
+}
+
+fn emitted_event(event: &XdrContractEvent) -> EmittedEvent {
+    let ContractEventBody::V0(body) = &event.body;
+    EmittedEvent {
+        contract_id: event.contract_id.as_ref().map(|Hash(id)| stellar_strkey::Contract(*id).to_string()),
+        topics: body.topics.iter().map(scval_to_json).collect(),
+        data: scval_to_json(&body.data),
+    }
+}
+
+/// The error reported by an `error` diagnostic event, e.g.
+/// `Error(Contract, #3): ...`, in the host's own notation
+fn contract_error(event: &XdrContractEvent) -> Option<String> {
+    let ContractEventBody::V0(body) = &event.body;
+    match body.topics.first()? {
+        ScVal::Symbol(symbol) if symbol.0.as_slice() == b"error" => {}
+        _ => return None,
+    }
+
+    let code = match body.topics.get(1) {
+        Some(ScVal::Error(ScError::Contract(code))) => format!("Error(Contract, #{})", code),
+        Some(ScVal::Error(
+            error @ (ScError::WasmVm(code)
+            | ScError::Context(code)
+            | ScError::Storage(code)
+            | ScError::Object(code)
+            | ScError::Crypto(code)
+            | ScError::Events(code)
+            | ScError::Budget(code)
+            | ScError::Value(code)
+            | ScError::Auth(code)),
+        )) => format!("Error({}, {})", error.name(), code.name()),
+        _ => "Error".to_string(),
+    };
+
+    // The message is either the data itself or the first element of it
+    let message = match &body.data {
+        ScVal::String(message) => Some(message.0.to_utf8_string_lossy()),
+        ScVal::Vec(Some(items)) => match items.first() {
+            Some(ScVal::String(message)) => Some(message.0.to_utf8_string_lossy()),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    Some(match message {
+        Some(message) => format!("{}: {}", code, message),
+        None => code,
+    })
+}
+
+/// Name the fields of an event's data tuple, e.g. `(from, to)` for transfers
+fn event_data(event: &str, value: &ScVal) -> Value {
+    let fields: &[&str] = match event {
+        "issued" => &["owner", "metadata_hash"],
+        "transfer" => &["from", "to"],
+        "burned" => &["owner"],
+        "disputed" => &["reason_hash"],
+        _ => &[],
+    };
+
+    let values: Vec<Value> = match value {
+        // Structs already carry their field names
+        ScVal::Map(Some(_)) => return scval_to_json(value),
+        ScVal::Void => Vec::new(),
+        ScVal::Vec(Some(items)) => items.iter().map(scval_to_json).collect(),
+        other => vec![scval_to_json(other)],
+    };
+
+    if fields.len() == values.len() {
+        Value::Object(fields.iter().map(|field| field.to_string()).zip(values).collect())
+    } else {
+        json!({ "value": scval_to_json(value) })
+    }
+}
+
+/// Render a contract value as plain JSON
+fn scval_to_json(value: &ScVal) -> Value {
+    match value {
+        ScVal::Void => Value::Null,
+        ScVal::Bool(b) => json!(b),
+        ScVal::U32(n) => json!(n),
+        ScVal::I32(n) => json!(n),
+        ScVal::U64(n) => json!(n),
+        ScVal::I64(n) => json!(n),
+        ScVal::I128(parts) => json!(((i128::from(parts.hi) << 64) | i128::from(parts.lo)).to_string()),
+        ScVal::String(s) => json!(s.0.to_utf8_string_lossy()),
+        ScVal::Symbol(s) => json!(s.0.to_utf8_string_lossy()),
+        ScVal::Address(address) => json!(address_to_string(address)),
+        ScVal::Bytes(bytes) => json!(hex::encode(bytes.as_slice())),
+        ScVal::Vec(Some(items)) => Value::Array(items.iter().map(scval_to_json).collect()),
+        ScVal::Map(Some(map)) => Value::Object(
+            map.iter()
+                .map(|entry| match scval_to_json(&entry.key) {
+                    Value::String(key) => (key, scval_to_json(&entry.val)),
+                    key => (key.to_string(), scval_to_json(&entry.val)),
+                })
+                .collect(),
+        ),
+        other => json!(format!("{:?}", other)),
+    }
+}
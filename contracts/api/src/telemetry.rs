@@ -0,0 +1,130 @@
+use anyhow::{anyhow, Result};
+use axum::{
+    extract::{MatchedPath, Request},
+    http::HeaderMap,
+    middleware::Next,
+    response::Response,
+};
+use opentelemetry::{
+    global,
+    propagation::{Extractor, TextMapPropagator},
+    KeyValue,
+};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{
+    propagation::TraceContextPropagator,
+    runtime,
+    trace::{self, Sampler},
+    Resource,
+};
+use tracing::{field::Empty, info_span, Instrument};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{
+    filter::LevelFilter, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter,
+};
+
+/// Tunables for exporting traces
+#[derive(Debug, Clone)]
+pub struct TelemetrySettings {
+    /// OTLP gRPC collector endpoint; traces are only logged when empty
+    pub otlp_endpoint: String,
+    pub service_name: String,
+    /// Fraction of new traces recorded; traces started by callers follow their decision
+    pub sample_ratio: f64,
+}
+
+impl Default for TelemetrySettings {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: String::new(),
+            service_name: "veriluxe-api".to_string(),
+            sample_ratio: 1.0,
+        }
+    }
+}
+
+/// Install the global subscriber, exporting spans over OTLP when an endpoint is set
+pub fn init_tracing(settings: &TelemetrySettings) -> Result<()> {
+    let filter = EnvFilter::builder()
+        .with_default_directive(LevelFilter::INFO.into())
+        .from_env_lossy();
+
+    let otel = if settings.otlp_endpoint.is_empty() {
+        None
+    } else {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+
+        let tracer = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(
+                opentelemetry_otlp::new_exporter()
+                    .tonic()
+                    .with_endpoint(settings.otlp_endpoint.clone()),
+            )
+            .with_trace_config(
+                trace::config()
+                    .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+                        settings.sample_ratio,
+                    ))))
+                    .with_resource(Resource::new(vec![KeyValue::new(
+                        "service.name",
+                        settings.service_name.clone(),
+                    )])),
+            )
+            .install_batch(runtime::Tokio)
+            .map_err(|e| anyhow!("Failed to start the OTLP exporter: {}", e))?;
+
+        Some(tracing_opentelemetry::layer().with_tracer(tracer))
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer())
+        .with(otel)
+        .try_init()
+        .map_err(|e| anyhow!("Failed to set subscriber: {}", e))
+}
+
+/// Flush spans still buffered by the exporter
+pub fn shutdown_tracing() {
+    global::shutdown_tracer_provider();
+}
+
+/// Run each request in a server span, continuing the caller's trace from `traceparent`
+pub async fn trace_request(request: Request, next: Next) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+
+    let span = info_span!(
+        "http_request",
+        otel.name = %format!("{} {}", request.method(), route),
+        otel.kind = "server",
+        http.request.method = %request.method(),
+        http.route = %route,
+        http.response.status_code = Empty,
+    );
+    let parent = global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(request.headers()))
+    });
+    span.set_parent(parent);
+
+    let response = next.run(request).instrument(span.clone()).await;
+    span.record("http.response.status_code", response.status().as_u16());
+    response
+}
+
+/// Reads trace context from request headers
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|name| name.as_str()).collect()
+    }
+}
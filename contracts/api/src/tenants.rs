@@ -0,0 +1,429 @@
+use anyhow::{anyhow, Result};
+use axum::{
+    extract::{Request, State},
+    http::{header::RETRY_AFTER, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, env, sync::Arc};
+use tracing::{info, warn};
+
+use crate::{
+    auth::{bearer_claims, Authenticator, Claims, Role},
+    cache::{CacheSettings, ResponseCache},
+    models::{ErrorCode, ErrorResponse},
+    networks::{Network, Networks},
+    policy::check_tenant_scope,
+    secrets::SecretStore,
+    signer::{load_signer, SignerSettings},
+    usage::{ApiKeyUsage, Quotas, Refusal, UsageMeter},
+};
+
+/// Header naming the tenant a request acts for
+pub const TENANT_HEADER: &str = "x-tenant";
+
+/// Header carrying a tenant API key
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// A brand served by the platform, with its own contract and credentials
+#[derive(Debug, Clone, Deserialize)]
+pub struct TenantProfile {
+    pub id: String,
+    pub brand: String,
+    /// Network profile the contract is deployed on; the default network when unset
+    #[serde(default)]
+    pub network: Option<String>,
+    pub contract_id: String,
+    /// The tenant's issuer key; without one the tenant's contract is read-only
+    #[serde(default)]
+    pub signer: Option<TenantSigner>,
+    /// Keys granting the issuer role on this tenant
+    #[serde(default)]
+    pub api_keys: Vec<ApiKeyProfile>,
+}
+
+/// A tenant API key, given as the bare key or with an ID and quotas
+///
+/// Bare keys have no quotas, and are identified by the first 12 hex digits
+/// of their SHA-256.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ApiKeyProfile {
+    Key(String),
+    Metered {
+        /// Names the key in its usage report, without revealing it
+        id: String,
+        key: String,
+        /// Requests allowed per UTC day
+        #[serde(default)]
+        daily_quota: Option<u64>,
+        /// Requests allowed per UTC month
+        #[serde(default)]
+        monthly_quota: Option<u64>,
+    },
+}
+
+impl ApiKeyProfile {
+    fn key(&self) -> &str {
+        match self {
+            ApiKeyProfile::Key(key) | ApiKeyProfile::Metered { key, .. } => key,
+        }
+    }
+
+    fn id(&self) -> String {
+        match self {
+            ApiKeyProfile::Key(key) => hex::encode(&key_hash(key)[..6]),
+            ApiKeyProfile::Metered { id, .. } => id.clone(),
+        }
+    }
+
+    fn quotas(&self) -> Quotas {
+        match self {
+            ApiKeyProfile::Key(_) => Quotas::default(),
+            ApiKeyProfile::Metered {
+                daily_quota,
+                monthly_quota,
+                ..
+            } => Quotas {
+                daily: *daily_quota,
+                monthly: *monthly_quota,
+            },
+        }
+    }
+}
+
+/// Where a tenant's issuer key is kept
+///
+/// Secrets are referenced by the name of the environment variable holding
+/// them, or of the setting in the secrets provider's secret, so the tenant
+/// registry itself holds no key material. Settings not
+/// given here (AWS credentials, remote token, timeout) come from the
+/// admin signer's.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TenantSigner {
+    /// One of the `SIGNER` kinds
+    pub kind: String,
+    /// Variable holding the secret key of a `local` signer
+    #[serde(default)]
+    pub secret_key_env: String,
+    #[serde(default)]
+    pub keystore_path: String,
+    /// Variable holding the keystore password
+    #[serde(default)]
+    pub keystore_password_env: String,
+    /// Variable holding the BIP-39 passphrase of a mnemonic secret
+    #[serde(default)]
+    pub mnemonic_passphrase_env: String,
+    /// SEP-5 account derived from a mnemonic secret
+    #[serde(default)]
+    pub account_index: u32,
+    #[serde(default)]
+    pub remote_url: String,
+    #[serde(default)]
+    pub public_key: String,
+    #[serde(default)]
+    pub kms_key_id: String,
+}
+
+impl TenantSigner {
+    /// Signer settings for this key, filling the rest in from `defaults`
+    fn settings(&self, defaults: &SignerSettings, secrets: &SecretStore) -> Result<SignerSettings> {
+        let secret = |name: &str| -> Result<String> {
+            if name.is_empty() {
+                return Ok(String::new());
+            }
+            env::var(name)
+                .ok()
+                .or_else(|| secrets.get(name))
+                .ok_or_else(|| anyhow!("{} is required in the environment or the secrets provider", name))
+        };
+
+        Ok(SignerSettings {
+            kind: self.kind.clone(),
+            secret_key: secret(&self.secret_key_env)?,
+            keystore_path: self.keystore_path.clone(),
+            keystore_password: secret(&self.keystore_password_env)?,
+            mnemonic_passphrase: secret(&self.mnemonic_passphrase_env)?,
+            account_index: self.account_index,
+            remote_url: self.remote_url.clone(),
+            public_key: self.public_key.clone(),
+            kms_key_id: self.kms_key_id.clone(),
+            ..defaults.clone()
+        })
+    }
+}
+
+/// A tenant resolved for a request
+#[derive(Clone)]
+pub struct Tenant {
+    pub id: String,
+    pub brand: String,
+    /// Client for the tenant's own contract
+    pub network: Network,
+}
+
+/// A tenant API key, as identified in usage reports
+#[derive(Debug, Clone)]
+pub struct ApiKey {
+    pub id: String,
+    pub tenant: String,
+    pub quotas: Quotas,
+}
+
+impl ApiKey {
+    /// Name of the key's usage counters, unique across tenants
+    fn counter(&self) -> String {
+        format!("{}:{}", self.tenant, self.id)
+    }
+}
+
+/// The tenants served by this instance, by ID and by API key
+#[derive(Clone, Default)]
+pub struct Tenants {
+    by_id: Arc<HashMap<String, Tenant>>,
+    /// Keyed by the SHA-256 of each API key, so keys aren't kept in memory
+    by_key: Arc<HashMap<[u8; 32], ApiKey>>,
+    key_ids: Arc<HashMap<String, ApiKey>>,
+    usage: UsageMeter,
+}
+
+impl Tenants {
+    /// Build clients and load issuer keys for every tenant
+    pub async fn connect(
+        profiles: &[TenantProfile],
+        networks: &Networks,
+        signer_defaults: &SignerSettings,
+        secrets: &SecretStore,
+        cache: &CacheSettings,
+    ) -> Result<Self> {
+        let mut by_id = HashMap::new();
+        let mut by_key = HashMap::new();
+        let mut key_ids = HashMap::new();
+
+        for profile in profiles {
+            if by_id.contains_key(&profile.id) {
+                return Err(anyhow!("Duplicate tenant ID: {}", profile.id));
+            }
+
+            let base = networks.get(profile.network.as_deref()).ok_or_else(|| {
+                anyhow!(
+                    "Tenant {} uses unknown network {}",
+                    profile.id,
+                    profile.network.as_deref().unwrap_or_default()
+                )
+            })?;
+            let signer = match &profile.signer {
+                Some(signer) => Some(
+                    load_signer(&signer.settings(signer_defaults, secrets)?)
+                        .map_err(|e| anyhow!("Failed to load signer of tenant {}: {}", profile.id, e))?,
+                ),
+                None => None,
+            };
+            let cache = ResponseCache::connect(cache.clone(), &profile.contract_id).await?;
+
+            for key_profile in &profile.api_keys {
+                let key = ApiKey {
+                    id: key_profile.id(),
+                    tenant: profile.id.clone(),
+                    quotas: key_profile.quotas(),
+                };
+                if key.quotas.daily == Some(0) || key.quotas.monthly == Some(0) {
+                    return Err(anyhow!("Quotas of API key {} must be above 0", key.id));
+                }
+                if key_ids.insert(key.id.clone(), key.clone()).is_some() {
+                    return Err(anyhow!("API key ID {} is already in use", key.id));
+                }
+                if by_key.insert(key_hash(key_profile.key()), key).is_some() {
+                    return Err(anyhow!("API key of tenant {} is already in use", profile.id));
+                }
+            }
+
+            info!(
+                "Serving tenant {} ({}) on contract {}",
+                profile.id, profile.brand, profile.contract_id
+            );
+            by_id.insert(
+                profile.id.clone(),
+                Tenant {
+                    id: profile.id.clone(),
+                    brand: profile.brand.clone(),
+                    network: Network {
+                        name: profile.id.clone(),
+                        client: base.client.for_contract(&profile.contract_id, signer, cache),
+                        is_default: false,
+                    },
+                },
+            );
+        }
+
+        // Usage is only worth sharing through Redis when there are keys to count
+        let usage = if key_ids.is_empty() {
+            UsageMeter::default()
+        } else {
+            UsageMeter::connect(&cache.redis_url).await?
+        };
+
+        Ok(Self {
+            by_id: Arc::new(by_id),
+            by_key: Arc::new(by_key),
+            key_ids: Arc::new(key_ids),
+            usage,
+        })
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Tenant> {
+        self.by_id.get(id)
+    }
+
+    /// The tenant an API key belongs to, and the key's ID and quotas
+    pub fn authenticate(&self, api_key: &str) -> Option<(&Tenant, &ApiKey)> {
+        let key = self.by_key.get(&key_hash(api_key))?;
+        self.by_id.get(&key.tenant).map(|tenant| (tenant, key))
+    }
+
+    /// Requests made with the API key `id` today and this month
+    pub async fn usage(&self, id: &str) -> Result<Option<ApiKeyUsage>> {
+        let Some(key) = self.key_ids.get(id) else {
+            return Ok(None);
+        };
+        let (daily, monthly) = self.usage.usage(&key.counter(), key.quotas).await?;
+        Ok(Some(ApiKeyUsage {
+            id: key.id.clone(),
+            tenant: key.tenant.clone(),
+            daily,
+            monthly,
+        }))
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_id.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_id.is_empty()
+    }
+}
+
+fn key_hash(api_key: &str) -> [u8; 32] {
+    Sha256::digest(api_key.as_bytes()).into()
+}
+
+/// Scope the request to the tenant named by `X-Tenant` or owning `X-API-Key`
+///
+/// The tenant's contract replaces the selected network for the rest of the
+/// request. An API key also authenticates the request with the issuer role,
+/// as the subject `tenant:<id>`, and is counted against its quotas: requests
+/// past them are answered with 429 and `QUOTA_EXCEEDED`. Bearer tokens sent
+/// with `X-Tenant` are only admitted by [`enforce`](crate::policy::enforce)
+/// when scoped to that tenant; with authentication disabled, `X-Tenant` is
+/// refused with 401 unless it comes with a valid bearer token for the tenant. Requests
+/// naming no tenant are served from the platform's own registry.
+pub async fn select_tenant(
+    State((tenants, auth)): State<(Tenants, Authenticator)>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let header = |name: &str| {
+        request
+            .headers()
+            .get(name)
+            .map(|value| value.to_str().unwrap_or_default().trim().to_string())
+    };
+    let tenant_id = header(TENANT_HEADER);
+    let api_key = header(API_KEY_HEADER);
+
+    let (tenant, claims) = match (api_key, tenant_id) {
+        (None, None) => return Ok(next.run(request).await),
+        (Some(api_key), tenant_id) => {
+            let (tenant, key) = tenants.authenticate(&api_key).ok_or_else(|| {
+                warn!("Rejected unknown API key");
+                (
+                    StatusCode::UNAUTHORIZED,
+                    Json(ErrorResponse::unauthorized("Invalid API key".to_string())),
+                )
+            })?;
+            let tenant = tenant.clone();
+            if let Some(tenant_id) = tenant_id.filter(|id| *id != tenant.id) {
+                return Err((
+                    StatusCode::FORBIDDEN,
+                    Json(ErrorResponse::forbidden(format!(
+                        "API key is not valid for tenant {}",
+                        tenant_id
+                    ))),
+                ));
+            }
+
+            match tenants.usage.record(&key.counter(), key.quotas).await {
+                Ok(()) => {}
+                Err(Refusal::Exceeded {
+                    period,
+                    quota,
+                    resets_at,
+                }) => {
+                    warn!("API key {} of tenant {} is over its {} quota", key.id, tenant.id, period.as_str());
+                    let wait = (resets_at - Utc::now()).num_seconds().max(1);
+                    let error = ErrorResponse::new(
+                        format!(
+                            "API key {} has used its {} quota of {} requests; it resets at {}",
+                            key.id,
+                            period.as_str(),
+                            quota,
+                            resets_at.to_rfc3339()
+                        ),
+                        429,
+                    )
+                    .with_code(ErrorCode::QuotaExceeded);
+                    return Ok((
+                        StatusCode::TOO_MANY_REQUESTS,
+                        [(RETRY_AFTER, wait.to_string())],
+                        Json(error),
+                    )
+                        .into_response());
+                }
+                Err(Refusal::Unavailable) => {
+                    return Err((
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        Json(ErrorResponse::new(
+                            "Usage metering is unavailable, try again later".to_string(),
+                            503,
+                        )),
+                    ))
+                }
+            }
+
+            let claims = Claims {
+                sub: format!("tenant:{}", tenant.id),
+                roles: vec![Role::Issuer],
+                exp: u64::MAX,
+                tenant: Some(tenant.id.clone()),
+            };
+            (tenant, Some(claims))
+        }
+        (None, Some(tenant_id)) => {
+            let tenant = tenants.get(&tenant_id).cloned().ok_or_else(|| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse::bad_request(format!("Unknown tenant {}", tenant_id))),
+                )
+            })?;
+            // Policies aren't enforced without authentication, so a token
+            // scoped to the tenant stands in for the checks they would make
+            if !auth.is_enabled() {
+                let claims = bearer_claims(&auth, &request)?;
+                check_tenant_scope(&claims, Some(&tenant.id))?;
+            }
+            (tenant, None)
+        }
+    };
+
+    request.extensions_mut().insert(tenant.network.clone());
+    if let Some(claims) = claims {
+        request.extensions_mut().insert(claims);
+    }
+    request.extensions_mut().insert(tenant);
+    Ok(next.run(request).await)
+}
@@ -0,0 +1,94 @@
+use anyhow::{anyhow, Result};
+use axum::{
+    extract::Host,
+    http::Uri,
+    response::Redirect,
+    Router,
+};
+use axum_server::tls_rustls::RustlsConfig;
+use std::net::SocketAddr;
+use tokio::net::TcpListener;
+use tracing::{error, info};
+
+use crate::server::ServerSettings;
+
+/// Tunables for serving HTTPS directly
+#[derive(Debug, Clone, Default)]
+pub struct TlsSettings {
+    pub enabled: bool,
+    /// PEM certificate chain
+    pub cert_path: String,
+    /// PEM private key
+    pub key_path: String,
+    /// Plain HTTP port redirecting to HTTPS; 0 disables the redirect
+    pub http_redirect_port: u16,
+}
+
+/// Serve `app` on `address`, over HTTPS when TLS is enabled
+///
+/// HTTP/1.1 and HTTP/2 are both served, tuned with `server`; over TLS the
+/// protocol is negotiated with ALPN, in plain text HTTP/2 needs prior knowledge.
+pub async fn serve(
+    app: Router,
+    address: &str,
+    settings: &TlsSettings,
+    server: &ServerSettings,
+) -> Result<()> {
+    if !settings.enabled {
+        let listener = TcpListener::bind(address).await?;
+        let mut http = axum_server::from_tcp(listener.into_std()?);
+        server.configure(http.http_builder());
+        http.serve(app.into_make_service()).await?;
+        return Ok(());
+    }
+
+    if settings.cert_path.is_empty() || settings.key_path.is_empty() {
+        return Err(anyhow!("TLS_CERT_PATH and TLS_KEY_PATH are required when TLS is enabled"));
+    }
+    let tls = RustlsConfig::from_pem_file(&settings.cert_path, &settings.key_path)
+        .await
+        .map_err(|e| anyhow!("Failed to load TLS certificate or key: {}", e))?;
+    let address: SocketAddr = address
+        .parse()
+        .map_err(|_| anyhow!("API_HOST must be an IP address when TLS is enabled"))?;
+
+    if settings.http_redirect_port != 0 {
+        let redirect_address = SocketAddr::new(address.ip(), settings.http_redirect_port);
+        let listener = TcpListener::bind(redirect_address).await?;
+        info!("Redirecting HTTP on {} to HTTPS", redirect_address);
+
+        let redirect = redirect_app(address.port());
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, redirect).await {
+                error!("HTTP redirect server stopped: {}", e);
+            }
+        });
+    }
+
+    let mut https = axum_server::bind_rustls(address, tls);
+    server.configure(https.http_builder());
+    https.serve(app.into_make_service()).await?;
+    Ok(())
+}
+
+/// Answers every request with a permanent redirect to the same URL over HTTPS
+fn redirect_app(https_port: u16) -> Router {
+    Router::new().fallback(move |Host(host): Host, uri: Uri| async move {
+        Redirect::permanent(&https_url(&host, https_port, &uri))
+    })
+}
+
+fn https_url(host: &str, https_port: u16, uri: &Uri) -> String {
+    // Drop the HTTP port, keeping bracketed IPv6 addresses intact
+    let host = match host.rsplit_once(':') {
+        Some((name, port)) if port.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => host,
+    };
+    let path = uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+
+    if https_port == 443 {
+        format!("https://{}{}", host, path)
+    } else {
+        format!("https://{}:{}{}", host, https_port, path)
+    }
+}
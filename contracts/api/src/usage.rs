@@ -0,0 +1,296 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use redis::aio::ConnectionManager;
+use serde::Serialize;
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::Mutex;
+use tracing::warn;
+use utoipa::ToSchema;
+
+/// Requests an API key may make per UTC day and month; unlimited when unset
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Quotas {
+    pub daily: Option<u64>,
+    pub monthly: Option<u64>,
+}
+
+/// A quota period
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Day,
+    Month,
+}
+
+impl Period {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Period::Day => "daily",
+            Period::Month => "monthly",
+        }
+    }
+}
+
+/// Why a request was refused by a [`UsageMeter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Refusal {
+    /// The key's quota for the period is used up until `resets_at`
+    Exceeded {
+        period: Period,
+        quota: u64,
+        resets_at: DateTime<Utc>,
+    },
+    /// The usage store couldn't be reached
+    Unavailable,
+}
+
+/// Use of an API key's quota in the current day or month
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PeriodUsage {
+    /// The UTC day (`2026-10-15`) or month (`2026-10`) counted
+    pub period: String,
+    /// Requests made in the period
+    pub used: u64,
+    /// Requests allowed in the period; unlimited when null
+    pub quota: Option<u64>,
+    pub remaining: Option<u64>,
+    /// When the count starts over
+    pub resets_at: DateTime<Utc>,
+}
+
+/// Requests made with an API key, against its quotas
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ApiKeyUsage {
+    pub id: String,
+    pub tenant: String,
+    pub daily: PeriodUsage,
+    pub monthly: PeriodUsage,
+}
+
+/// The UTC day and month a request falls in
+struct Window {
+    day: NaiveDate,
+    month: String,
+    day_resets_at: DateTime<Utc>,
+    month_resets_at: DateTime<Utc>,
+}
+
+impl Window {
+    fn now() -> Self {
+        let day = Utc::now().date_naive();
+        let next_day = day.succ_opt().unwrap_or(day);
+        let next_month = if day.month() == 12 {
+            NaiveDate::from_ymd_opt(day.year() + 1, 1, 1)
+        } else {
+            NaiveDate::from_ymd_opt(day.year(), day.month() + 1, 1)
+        }
+        .unwrap_or(next_day);
+
+        Self {
+            day,
+            month: day.format("%Y-%m").to_string(),
+            day_resets_at: next_day.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc(),
+            month_resets_at: next_month.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc(),
+        }
+    }
+
+    /// Seconds a counter of `period` must outlive, with a minute to spare
+    fn ttl_secs(&self, period: Period) -> i64 {
+        let resets_at = match period {
+            Period::Day => self.day_resets_at,
+            Period::Month => self.month_resets_at,
+        };
+        (resets_at - Utc::now()).num_seconds().max(0) + 60
+    }
+
+    /// The period whose quota `daily` and `monthly` requests would break, if any
+    fn exceeded(&self, quotas: Quotas, daily: u64, monthly: u64) -> Option<Refusal> {
+        let over = |quota: Option<u64>, used: u64| quota.filter(|quota| used > *quota);
+        if let Some(quota) = over(quotas.monthly, monthly) {
+            return Some(Refusal::Exceeded {
+                period: Period::Month,
+                quota,
+                resets_at: self.month_resets_at,
+            });
+        }
+        over(quotas.daily, daily).map(|quota| Refusal::Exceeded {
+            period: Period::Day,
+            quota,
+            resets_at: self.day_resets_at,
+        })
+    }
+
+    fn usage(&self, quotas: Quotas, daily: u64, monthly: u64) -> (PeriodUsage, PeriodUsage) {
+        (
+            PeriodUsage {
+                period: self.day.to_string(),
+                used: daily,
+                quota: quotas.daily,
+                remaining: quotas.daily.map(|quota| quota.saturating_sub(daily)),
+                resets_at: self.day_resets_at,
+            },
+            PeriodUsage {
+                period: self.month.clone(),
+                used: monthly,
+                quota: quotas.monthly,
+                remaining: quotas.monthly.map(|quota| quota.saturating_sub(monthly)),
+                resets_at: self.month_resets_at,
+            },
+        )
+    }
+}
+
+/// A key's counts in the day and month they were last made in
+#[derive(Default)]
+struct Counts {
+    day: Option<NaiveDate>,
+    daily: u64,
+    month: String,
+    monthly: u64,
+}
+
+impl Counts {
+    /// Start over the counts of periods that have ended
+    fn roll(&mut self, window: &Window) {
+        if self.day != Some(window.day) {
+            self.day = Some(window.day);
+            self.daily = 0;
+        }
+        if self.month != window.month {
+            self.month = window.month.clone();
+            self.monthly = 0;
+        }
+    }
+}
+
+/// Counts the requests made with each API key per UTC day and month
+///
+/// Counts are kept in memory, or in Redis when `REDIS_URL` is set so that
+/// every instance behind a load balancer enforces the same quotas.
+#[derive(Clone)]
+pub struct UsageMeter {
+    backend: Backend,
+}
+
+#[derive(Clone)]
+enum Backend {
+    Memory(Arc<Mutex<HashMap<String, Counts>>>),
+    Redis(ConnectionManager),
+}
+
+impl Default for UsageMeter {
+    fn default() -> Self {
+        Self {
+            backend: Backend::Memory(Arc::new(Mutex::new(HashMap::new()))),
+        }
+    }
+}
+
+impl UsageMeter {
+    /// Count usage in Redis, or in memory when `redis_url` is empty
+    pub async fn connect(redis_url: &str) -> Result<Self> {
+        if redis_url.is_empty() {
+            return Ok(Self::default());
+        }
+
+        let conn = redis::Client::open(redis_url)
+            .map_err(|e| anyhow!("Invalid REDIS_URL: {}", e))?
+            .get_connection_manager()
+            .await
+            .map_err(|e| anyhow!("Failed to connect to Redis: {}", e))?;
+        Ok(Self {
+            backend: Backend::Redis(conn),
+        })
+    }
+
+    /// Count a request made with the key `counter`, unless it breaks a quota
+    ///
+    /// Refused requests aren't counted against either quota.
+    pub async fn record(&self, counter: &str, quotas: Quotas) -> Result<(), Refusal> {
+        let window = Window::now();
+
+        match &self.backend {
+            Backend::Memory(counts) => {
+                let mut counts = counts.lock().await;
+                let counts = counts.entry(counter.to_string()).or_default();
+                counts.roll(&window);
+                if let Some(refusal) = window.exceeded(quotas, counts.daily + 1, counts.monthly + 1) {
+                    return Err(refusal);
+                }
+                counts.daily += 1;
+                counts.monthly += 1;
+                Ok(())
+            }
+            Backend::Redis(conn) => {
+                let (day_key, month_key) = redis_keys(counter, &window);
+                let unavailable = |e: redis::RedisError| {
+                    warn!("Usage store unavailable: {}", e);
+                    Refusal::Unavailable
+                };
+
+                let (daily, monthly): (i64, i64) = redis::pipe()
+                    .atomic()
+                    .cmd("INCR").arg(&day_key)
+                    .cmd("EXPIRE").arg(&day_key).arg(window.ttl_secs(Period::Day)).ignore()
+                    .cmd("INCR").arg(&month_key)
+                    .cmd("EXPIRE").arg(&month_key).arg(window.ttl_secs(Period::Month)).ignore()
+                    .query_async(&mut conn.clone())
+                    .await
+                    .map_err(unavailable)?;
+
+                let refusal = window.exceeded(quotas, daily.max(0) as u64, monthly.max(0) as u64);
+                if let Some(refusal) = refusal {
+                    // Take the refused request back off the counts
+                    redis::pipe()
+                        .atomic()
+                        .cmd("DECR").arg(&day_key).ignore()
+                        .cmd("DECR").arg(&month_key).ignore()
+                        .query_async::<_, ()>(&mut conn.clone())
+                        .await
+                        .map_err(unavailable)?;
+                    return Err(refusal);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Requests made with the key `counter` in the current day and month
+    pub async fn usage(&self, counter: &str, quotas: Quotas) -> Result<(PeriodUsage, PeriodUsage)> {
+        let window = Window::now();
+
+        let (daily, monthly) = match &self.backend {
+            Backend::Memory(counts) => {
+                let mut counts = counts.lock().await;
+                match counts.get_mut(counter) {
+                    Some(counts) => {
+                        counts.roll(&window);
+                        (counts.daily, counts.monthly)
+                    }
+                    None => (0, 0),
+                }
+            }
+            Backend::Redis(conn) => {
+                let (day_key, month_key) = redis_keys(counter, &window);
+                let (daily, monthly): (Option<i64>, Option<i64>) = redis::pipe()
+                    .cmd("GET").arg(&day_key)
+                    .cmd("GET").arg(&month_key)
+                    .query_async(&mut conn.clone())
+                    .await
+                    .map_err(|e| anyhow!("Failed to read usage of {}: {}", counter, e))?;
+                (
+                    daily.unwrap_or_default().max(0) as u64,
+                    monthly.unwrap_or_default().max(0) as u64,
+                )
+            }
+        };
+
+        Ok(window.usage(quotas, daily, monthly))
+    }
+}
+
+fn redis_keys(counter: &str, window: &Window) -> (String, String) {
+    (
+        format!("veriluxe:usage:{}:{}", counter, window.day),
+        format!("veriluxe:usage:{}:{}", counter, window.month),
+    )
+}
@@ -0,0 +1,125 @@
+use axum::{
+    async_trait,
+    extract::{rejection::JsonRejection, FromRequest, Request},
+    http::StatusCode,
+    Json,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::borrow::Cow;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+use crate::models::{CertId, ErrorResponse, FieldError};
+
+/// Limits on request bodies
+#[derive(Debug, Clone)]
+pub struct LimitSettings {
+    /// Larger request bodies are refused with 413
+    pub max_body_bytes: usize,
+    /// Larger `/admin/import` files are refused with 413
+    pub max_import_bytes: usize,
+}
+
+impl Default for LimitSettings {
+    fn default() -> Self {
+        Self {
+            max_body_bytes: 64 * 1024,
+            max_import_bytes: 2 * 1024 * 1024,
+        }
+    }
+}
+
+/// A JSON request body that passed its model's `#[validate]` rules
+///
+/// Bodies that aren't JSON are refused with the status axum gives them.
+/// Bodies with a field that doesn't parse, such as a malformed address, are
+/// refused with 422 naming that field, and bodies breaking a rule with 422,
+/// listing every field that failed in `errors`.
+pub struct Valid<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for Valid<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, Json<ErrorResponse>);
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(body) = Json::<Value>::from_request(request, state)
+            .await
+            .map_err(unreadable)?;
+        let value: T = serde_path_to_error::deserialize(body).map_err(mismatched)?;
+        value.validate().map_err(|errors| {
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(ErrorResponse::invalid_fields(field_errors(&errors))),
+            )
+        })?;
+        Ok(Self(value))
+    }
+}
+
+fn unreadable(rejection: JsonRejection) -> (StatusCode, Json<ErrorResponse>) {
+    let status = rejection.status();
+    (
+        status,
+        Json(ErrorResponse::new(rejection.body_text(), status.as_u16())),
+    )
+}
+
+/// Name the field that failed to parse, when the failure is in one
+fn mismatched(
+    error: serde_path_to_error::Error<serde_json::Error>,
+) -> (StatusCode, Json<ErrorResponse>) {
+    let field = error.path().to_string();
+    let message = error.inner().to_string();
+    let response = if field == "." {
+        ErrorResponse::new(message, 422)
+    } else {
+        ErrorResponse::invalid_fields(vec![FieldError {
+            field,
+            code: "invalid".to_string(),
+            message,
+        }])
+    };
+    (StatusCode::UNPROCESSABLE_ENTITY, Json(response))
+}
+
+/// Every failed rule, ordered by field so responses are stable
+fn field_errors(errors: &ValidationErrors) -> Vec<FieldError> {
+    let mut fields: Vec<FieldError> = errors
+        .field_errors()
+        .into_iter()
+        .flat_map(|(field, errors)| {
+            errors.iter().map(move |error| FieldError {
+                field: field.to_string(),
+                code: error.code.to_string(),
+                message: error
+                    .message
+                    .as_ref()
+                    .map(|message| message.to_string())
+                    .unwrap_or_else(|| format!("{} is invalid", field)),
+            })
+        })
+        .collect();
+    fields.sort_by(|a, b| a.field.cmp(&b.field));
+    fields
+}
+
+fn invalid(code: &'static str, message: &'static str) -> ValidationError {
+    let mut error = ValidationError::new(code);
+    error.message = Some(Cow::Borrowed(message));
+    error
+}
+
+/// Validator for the IDs of certificates being issued
+pub fn validate_issuable(cert_id: &CertId) -> Result<(), ValidationError> {
+    if !cert_id.is_issuable() {
+        return Err(invalid(
+            "cert_id",
+            "cert_id must be 3-64 characters from [A-Za-z0-9._-]",
+        ));
+    }
+    Ok(())
+}
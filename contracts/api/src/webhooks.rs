@@ -0,0 +1,532 @@
+use anyhow::{anyhow, Result};
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, RwLock as SettingsLock},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{sync::RwLock, task::JoinHandle};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+use crate::{models::ContractEvent, soroban_client::SorobanClient};
+
+/// Lifecycle events a webhook can subscribe to, as emitted by the contract
+pub const WEBHOOK_EVENTS: &[&str] = &["issued", "transfer", "revoked", "burned", "disputed"];
+
+/// Number of delivery records kept in memory
+const DELIVERY_LOG_SIZE: usize = 1000;
+
+/// Maximum number of events requested per `getEvents` poll
+const EVENT_PAGE_LIMIT: u32 = 100;
+
+/// Shortest signing secret accepted from callers
+const MIN_SECRET_LEN: usize = 16;
+
+/// Tunables for outgoing webhook deliveries
+#[derive(Debug, Clone)]
+pub struct WebhookSettings {
+    pub enabled: bool,
+    pub poll_interval_secs: u64,
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for each one after it
+    pub retry_delay_ms: u64,
+    pub max_retry_delay_ms: u64,
+    pub timeout_secs: u64,
+    /// Subscriptions from the configuration, beside those registered through the API
+    pub endpoints: Vec<WebhookEndpoint>,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            poll_interval_secs: 5,
+            max_attempts: 5,
+            retry_delay_ms: 5000,
+            max_retry_delay_ms: 300_000,
+            timeout_secs: 10,
+            endpoints: Vec::new(),
+        }
+    }
+}
+
+/// A subscription set in `WEBHOOK_ENDPOINTS`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WebhookEndpoint {
+    pub url: String,
+    /// Events to deliver; every event when empty
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// Key for the `X-VeriLuxe-Signature` HMAC
+    pub secret: String,
+}
+
+impl WebhookEndpoint {
+    /// Check the events and secret, as for subscriptions registered through the API
+    pub fn validate(&self) -> Result<()> {
+        check_subscription(&self.events, Some(&self.secret))
+    }
+
+    /// The subscription, with an ID derived from the URL so that it is kept across reloads
+    fn webhook(&self, created_at: u64) -> Webhook {
+        let id = hex::encode(Sha256::digest(self.url.as_bytes()));
+        Webhook {
+            id: format!("config-{}", &id[..16]),
+            url: self.url.clone(),
+            events: all_events_if_empty(self.events.clone()),
+            secret: Some(self.secret.clone()),
+            configured: true,
+            created_at,
+        }
+    }
+}
+
+/// A registered webhook subscription
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<String>,
+    /// Key for the `X-VeriLuxe-Signature` HMAC; only returned on registration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// Set in `WEBHOOK_ENDPOINTS`; removing it only lasts until the next reload
+    pub configured: bool,
+    pub created_at: u64,
+}
+
+/// Outcome of delivering one event to one webhook
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Pending,
+    /// An attempt failed and another is scheduled at `next_attempt_at`
+    Retrying,
+    Delivered,
+    /// Every attempt failed; kept until the webhook is removed
+    DeadLetter,
+}
+
+/// Record of a delivery attempt, kept for debugging
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Delivery {
+    pub id: String,
+    pub webhook_id: String,
+    pub event_id: String,
+    pub event: String,
+    pub cert_id: String,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    pub response_status: Option<u16>,
+    pub error: Option<String>,
+    /// When the next retry is due (unix seconds), while `retrying`
+    pub next_attempt_at: Option<u64>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+/// Background service that follows contract events and notifies webhooks
+///
+/// Subscriptions registered through the API and delivery records are kept in
+/// memory, so they are lost when the process restarts. Once the log is full
+/// the oldest records are dropped first, except dead letters, which stay
+/// until their webhook is removed.
+#[derive(Clone)]
+pub struct WebhookDispatcher {
+    client: SorobanClient,
+    /// Replaced on reload; `enabled` and `poll_interval_secs` only apply at startup
+    settings: Arc<SettingsLock<WebhookSettings>>,
+    http_client: Client,
+    webhooks: Arc<RwLock<HashMap<String, Webhook>>>,
+    deliveries: Arc<RwLock<VecDeque<Delivery>>>,
+}
+
+impl WebhookDispatcher {
+    pub fn new(client: SorobanClient, settings: WebhookSettings) -> Self {
+        let now = now_secs();
+        let webhooks = settings
+            .endpoints
+            .iter()
+            .map(|endpoint| endpoint.webhook(now))
+            .map(|webhook| (webhook.id.clone(), webhook))
+            .collect();
+
+        Self {
+            client,
+            settings: Arc::new(SettingsLock::new(settings)),
+            http_client: Client::new(),
+            webhooks: Arc::new(RwLock::new(webhooks)),
+            deliveries: Arc::new(RwLock::new(VecDeque::new())),
+        }
+    }
+
+    /// The settings in effect
+    pub fn settings(&self) -> WebhookSettings {
+        self.settings.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Apply reloaded settings
+    ///
+    /// Retries and timeouts change for the next attempt, including those of
+    /// deliveries already under way. Configured endpoints are added, updated or
+    /// removed (with their delivery records); subscriptions registered through
+    /// the API are kept.
+    pub async fn apply_settings(&self, settings: WebhookSettings) {
+        let now = now_secs();
+        let configured: HashMap<String, Webhook> = settings
+            .endpoints
+            .iter()
+            .map(|endpoint| endpoint.webhook(now))
+            .map(|webhook| (webhook.id.clone(), webhook))
+            .collect();
+
+        let mut removed = Vec::new();
+        {
+            let mut webhooks = self.webhooks.write().await;
+            webhooks.retain(|id, webhook| {
+                let keep = !webhook.configured || configured.contains_key(id);
+                if !keep {
+                    removed.push(id.clone());
+                }
+                keep
+            });
+            for (id, mut webhook) in configured {
+                if let Some(existing) = webhooks.get(&id) {
+                    webhook.created_at = existing.created_at;
+                }
+                webhooks.insert(id, webhook);
+            }
+        }
+        if !removed.is_empty() {
+            self.deliveries
+                .write()
+                .await
+                .retain(|delivery| !removed.contains(&delivery.webhook_id));
+        }
+
+        *self.settings.write().unwrap_or_else(|e| e.into_inner()) = settings;
+    }
+
+    /// Start following contract events, if enabled
+    pub fn spawn(&self) -> Option<JoinHandle<()>> {
+        let settings = self.settings();
+        if !settings.enabled {
+            info!("Webhook dispatcher disabled");
+            return None;
+        }
+        let dispatcher = self.clone();
+        Some(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(settings.poll_interval_secs));
+            let mut start_ledger = None;
+            let mut cursor = None;
+            loop {
+                interval.tick().await;
+                if let Err(e) = dispatcher.poll(&mut start_ledger, &mut cursor).await {
+                    error!("Webhook event poll failed: {}", e);
+                }
+            }
+        }))
+    }
+
+    /// Add a subscription for `events`, or for every event when empty
+    ///
+    /// Deliveries are signed with `secret`, or with a random one when it is
+    /// `None`. The returned webhook is the only one that carries the secret.
+    pub async fn register(
+        &self,
+        url: String,
+        events: Vec<String>,
+        secret: Option<String>,
+    ) -> Result<Webhook> {
+        check_subscription(&events, secret.as_deref())?;
+
+        let webhook = Webhook {
+            id: uuid::Uuid::new_v4().to_string(),
+            url,
+            events: all_events_if_empty(events),
+            secret: Some(secret.unwrap_or_else(|| hex::encode(rand::random::<[u8; 32]>()))),
+            configured: false,
+            created_at: now_secs(),
+        };
+
+        self.webhooks.write().await.insert(webhook.id.clone(), webhook.clone());
+        info!("Registered webhook {} for {}", webhook.id, webhook.url);
+        Ok(webhook)
+    }
+
+    /// All registered subscriptions, without their secrets
+    pub async fn list(&self) -> Vec<Webhook> {
+        let mut webhooks: Vec<Webhook> = self
+            .webhooks
+            .read()
+            .await
+            .values()
+            .map(|webhook| Webhook {
+                secret: None,
+                ..webhook.clone()
+            })
+            .collect();
+        webhooks.sort_by_key(|webhook| webhook.created_at);
+        webhooks
+    }
+
+    /// Remove a subscription and its delivery records, returning whether it existed
+    pub async fn remove(&self, id: &str) -> bool {
+        let removed = self.webhooks.write().await.remove(id).is_some();
+        if removed {
+            self.deliveries.write().await.retain(|delivery| delivery.webhook_id != id);
+        }
+        removed
+    }
+
+    /// A webhook's delivery records, newest first, optionally only those in `status`
+    ///
+    /// Returns `None` for unknown webhooks.
+    pub async fn deliveries(
+        &self,
+        webhook_id: &str,
+        status: Option<DeliveryStatus>,
+    ) -> Option<Vec<Delivery>> {
+        if !self.webhooks.read().await.contains_key(webhook_id) {
+            return None;
+        }
+
+        Some(
+            self.deliveries
+                .read()
+                .await
+                .iter()
+                .rev()
+                .filter(|delivery| delivery.webhook_id == webhook_id)
+                .filter(|delivery| status.map_or(true, |status| delivery.status == status))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Fetch new contract events and dispatch them
+    ///
+    /// The first poll starts at the latest ledger, so events emitted before the
+    /// dispatcher started are not replayed.
+    async fn poll(&self, start_ledger: &mut Option<u32>, cursor: &mut Option<String>) -> Result<()> {
+        let start = match *start_ledger {
+            Some(ledger) => ledger,
+            None => self.client.get_latest_ledger().await?,
+        };
+
+        let page = self
+            .client
+            .get_events(start, cursor.as_deref(), EVENT_PAGE_LIMIT)
+            .await?;
+
+        for event in page.events {
+            self.dispatch(event).await;
+        }
+
+        *start_ledger = Some(start.max(page.latest_ledger));
+        if page.cursor.is_some() {
+            *cursor = page.cursor;
+        }
+        Ok(())
+    }
+
+    /// Queue a delivery to every webhook subscribed to the event
+    async fn dispatch(&self, event: ContractEvent) {
+        let subscribers: Vec<Webhook> = self
+            .webhooks
+            .read()
+            .await
+            .values()
+            .filter(|webhook| webhook.events.contains(&event.event))
+            .cloned()
+            .collect();
+
+        for webhook in subscribers {
+            let now = now_secs();
+            let delivery = Delivery {
+                id: uuid::Uuid::new_v4().to_string(),
+                webhook_id: webhook.id.clone(),
+                event_id: event.id.clone(),
+                event: event.event.clone(),
+                cert_id: event.cert_id.clone(),
+                status: DeliveryStatus::Pending,
+                attempts: 0,
+                response_status: None,
+                error: None,
+                next_attempt_at: None,
+                created_at: now,
+                updated_at: now,
+            };
+
+            {
+                let mut deliveries = self.deliveries.write().await;
+                if deliveries.len() >= DELIVERY_LOG_SIZE {
+                    let oldest = deliveries
+                        .iter()
+                        .position(|delivery| delivery.status != DeliveryStatus::DeadLetter)
+                        .unwrap_or(0);
+                    deliveries.remove(oldest);
+                }
+                deliveries.push_back(delivery.clone());
+            }
+
+            let dispatcher = self.clone();
+            let event = event.clone();
+            tokio::spawn(async move { dispatcher.deliver(&webhook, &delivery.id, &event).await });
+        }
+    }
+
+    /// POST the event to a webhook, retrying with exponential backoff until it
+    /// answers with a 2xx or runs out of attempts
+    async fn deliver(&self, webhook: &Webhook, delivery_id: &str, event: &ContractEvent) {
+        let body = match serde_json::to_vec(event) {
+            Ok(body) => body,
+            Err(e) => {
+                error!("Failed to encode event {}: {}", event.id, e);
+                return;
+            }
+        };
+
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let result = self.send(webhook, delivery_id, event, &body).await;
+
+            let delivered = matches!(result, Ok(status) if (200..300).contains(&status));
+            let (response_status, error) = match result {
+                Ok(status) if delivered => (Some(status), None),
+                Ok(status) => (Some(status), Some(format!("Webhook responded with {}", status))),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            let settings = self.settings();
+            let retry = !delivered && attempt < settings.max_attempts;
+            let delay = retry_delay(&settings, attempt);
+
+            self.update(delivery_id, |delivery| {
+                delivery.attempts = attempt;
+                delivery.response_status = response_status;
+                delivery.error = error.clone();
+                delivery.next_attempt_at = None;
+                if delivered {
+                    delivery.status = DeliveryStatus::Delivered;
+                } else if retry {
+                    delivery.status = DeliveryStatus::Retrying;
+                    delivery.next_attempt_at = Some(now_secs() + delay.as_secs());
+                } else {
+                    delivery.status = DeliveryStatus::DeadLetter;
+                }
+            })
+            .await;
+
+            if delivered {
+                info!("Delivered {} event {} to webhook {}", event.event, event.id, webhook.id);
+                return;
+            }
+            if !retry {
+                error!(
+                    "Dead-lettered delivery {} to webhook {} after {} attempts: {:?}",
+                    delivery_id, webhook.id, attempt, error
+                );
+                return;
+            }
+
+            warn!(
+                "Delivery {} attempt {} failed, retrying in {}ms: {:?}",
+                delivery_id,
+                attempt,
+                delay.as_millis(),
+                error
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    async fn send(
+        &self,
+        webhook: &Webhook,
+        delivery_id: &str,
+        event: &ContractEvent,
+        body: &[u8],
+    ) -> Result<u16> {
+        let mut request = self
+            .http_client
+            .post(&webhook.url)
+            .header("Content-Type", "application/json")
+            .header("X-VeriLuxe-Event", &event.event)
+            .header("X-VeriLuxe-Delivery", delivery_id)
+            .timeout(Duration::from_secs(self.settings().timeout_secs));
+
+        if let Some(secret) = &webhook.secret {
+            let signature = sign_payload(secret, body)?;
+            request = request.header("X-VeriLuxe-Signature", format!("sha256={}", signature));
+        }
+
+        let response = request
+            .body(body.to_vec())
+            .send()
+            .await
+            .map_err(|e| anyhow!("Webhook request failed: {}", e))?;
+
+        Ok(response.status().as_u16())
+    }
+
+    async fn update(&self, id: &str, change: impl FnOnce(&mut Delivery)) {
+        if let Some(delivery) = self.deliveries.write().await.iter_mut().find(|d| d.id == id) {
+            change(delivery);
+            delivery.updated_at = now_secs();
+        }
+    }
+}
+
+/// Refuse unknown events and secrets too short to sign with
+fn check_subscription(events: &[String], secret: Option<&str>) -> Result<()> {
+    if let Some(unknown) = events.iter().find(|e| !WEBHOOK_EVENTS.contains(&e.as_str())) {
+        return Err(anyhow!("Unknown webhook event: {}", unknown));
+    }
+    if secret.is_some_and(|secret| secret.len() < MIN_SECRET_LEN) {
+        return Err(anyhow!(
+            "Webhook secret must be at least {} characters",
+            MIN_SECRET_LEN
+        ));
+    }
+    Ok(())
+}
+
+fn all_events_if_empty(events: Vec<String>) -> Vec<String> {
+    if events.is_empty() {
+        WEBHOOK_EVENTS.iter().map(|e| e.to_string()).collect()
+    } else {
+        events
+    }
+}
+
+/// Delay after the given failed attempt: the base delay, doubled for every
+/// attempt before it, up to the configured maximum
+fn retry_delay(settings: &WebhookSettings, attempt: u32) -> Duration {
+    let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+    Duration::from_millis(
+        settings
+            .retry_delay_ms
+            .saturating_mul(factor)
+            .min(settings.max_retry_delay_ms),
+    )
+}
+
+/// Hex-encoded HMAC-SHA256 of a payload, sent as `X-VeriLuxe-Signature`
+fn sign_payload(secret: &str, body: &[u8]) -> Result<String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|e| anyhow!("Invalid webhook signing secret: {}", e))?;
+    mac.update(body);
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
@@ -3,25 +3,135 @@ use axum::{
     http::{Request, StatusCode},
     response::Response,
 };
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hmac::{Hmac, Mac};
 use serde_json::{json, Value};
+use sha2::Sha256;
+use sqlx::PgPool;
+use std::sync::Arc;
+use stellar_xdr::curr::{
+    Limits, Preconditions, ReadXdr, TimeBounds, TimePoint, Transaction, TransactionEnvelope, WriteXdr,
+};
 use tokio_test;
 use tower::ServiceExt;
 use veriluxe_api::{
-    config::Config,
+    audit::{AuditLog, AuditSettings},
+    auth::{AuthSettings, Authenticator, Claims, Role},
+    cache::{CacheSettings, ResponseCache},
+    channels::ChannelSettings,
+    circuit::CircuitSettings,
+    config::{Config, ConfigOptions},
+    contract_client::ContractClient,
+    cors::{CorsOrigins, CorsSettings},
+    federation::Federation,
+    fees::FeeSettings,
+    friendbot::Friendbot,
+    grpc::GrpcSettings,
+    health::{HealthChecker, HealthSettings},
+    horizon::HorizonSettings,
+    import::{ImportManager, ImportSettings},
     handlers::AppState,
+    indexer::{Indexer, IndexerSettings},
+    jobs::{JobQueue, JobSettings},
+    mock_client::MockContractClient,
+    networks::{NetworkSettings, Networks},
+    notifications::NotificationSettings,
+    pagination::{CertificateCursor, CertificateSort},
+    qr::{QrCodec, QrSettings},
+    reconcile::{ReconcileSettings, Reconciler},
+    replay::{signing_payload, ReplayGuard, ReplaySettings},
+    reload::ConfigReloader,
+    rent::{RentManager, RentSettings},
     routes::create_router,
-    soroban_client::SorobanClient,
+    sandbox::SandboxSettings,
+    secrets::{SecretStore, SecretsSettings},
+    server::ServerSettings,
+    sep10::{Sep10Settings, WebAuth},
+    signer::{load_signer, LocalSigner, Signer, SignerSettings},
+    soroban_client::{sign_hash, transaction_hash, HttpSettings, RetrySettings, SorobanClient, SubmitSettings},
+    telemetry::TelemetrySettings,
+    tenants::{TenantProfile, Tenants},
+    tls::TlsSettings,
+    validation::LimitSettings,
+    webhooks::{WebhookDispatcher, WebhookSettings},
 };
 
 async fn create_test_app() -> Result<axum::Router, Box<dyn std::error::Error>> {
+    create_test_app_with_auth(AuthSettings::default()).await
+}
+
+async fn create_test_app_with_auth(
+    auth: AuthSettings,
+) -> Result<axum::Router, Box<dyn std::error::Error>> {
+    build_test_app(auth, None, ReplaySettings::default(), Vec::new()).await
+}
+
+/// An app whose default network is served by an in-memory contract
+async fn create_mock_app(client: MockContractClient) -> axum::Router {
+    build_test_app(AuthSettings::default(), Some(Arc::new(client)), ReplaySettings::default(), Vec::new())
+        .await
+        .expect("Failed to create test app")
+}
+
+async fn build_test_app(
+    auth: AuthSettings,
+    network_client: Option<Arc<dyn ContractClient>>,
+    replay: ReplaySettings,
+    tenants: Vec<TenantProfile>,
+) -> Result<axum::Router, Box<dyn std::error::Error>> {
+    Ok(create_router(test_state(auth, network_client, replay, tenants).await?))
+}
+
+/// The state of a test app, for tests that switch on optional services
+async fn test_state(
+    auth: AuthSettings,
+    network_client: Option<Arc<dyn ContractClient>>,
+    replay: ReplaySettings,
+    tenants: Vec<TenantProfile>,
+) -> Result<AppState, Box<dyn std::error::Error>> {
     // Use test configuration
     let config = Config {
         soroban_network_passphrase: "Test SDF Network ; September 2015".to_string(),
         soroban_rpc_url: "https://soroban-testnet.stellar.org:443".to_string(),
         fashion_auth_contract_id: "test_contract_id".to_string(),
-        admin_secret_key: "test_admin_secret_key".to_string(),
+        networks: NetworkSettings::default(),
+        tenants,
+        signer: SignerSettings {
+            secret_key: "SADQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQP54X".to_string(),
+            ..SignerSettings::default()
+        },
+        channels: ChannelSettings::default(),
         api_host: "127.0.0.1".to_string(),
         api_port: 3000,
+        federation_peers: Vec::new(),
+        rent: RentSettings::default(),
+        submit: SubmitSettings::default(),
+        fees: FeeSettings::default(),
+        http: HttpSettings::default(),
+        cors: CorsSettings::default(),
+        limits: LimitSettings::default(),
+        retry: RetrySettings::default(),
+        circuit: CircuitSettings::default(),
+        jobs: JobSettings::default(),
+        import: ImportSettings::default(),
+        webhooks: WebhookSettings::default(),
+        indexer: IndexerSettings::default(),
+        reconcile: ReconcileSettings::default(),
+        audit: AuditSettings::default(),
+        notifications: NotificationSettings::default(),
+        auth,
+        replay,
+        sep10: Sep10Settings::default(),
+        cache: CacheSettings::default(),
+        health: HealthSettings::default(),
+        telemetry: TelemetrySettings::default(),
+        tls: TlsSettings::default(),
+        server: ServerSettings::default(),
+        grpc: GrpcSettings::default(),
+        qr: QrSettings::default(),
+        horizon: HorizonSettings::default(),
+        sandbox: SandboxSettings::default(),
+        secrets: SecretsSettings::default(),
     };
 
     // Create mock Soroban client (this would need proper mocking in a real test)
@@ -29,11 +139,47 @@ async fn create_test_app() -> Result<axum::Router, Box<dyn std::error::Error>> {
         config.soroban_rpc_url,
         config.soroban_network_passphrase,
         config.fashion_auth_contract_id,
-        config.admin_secret_key,
-    )?;
+        load_signer(&config.signer)?,
+    );
+    let network_client = network_client.unwrap_or_else(|| Arc::new(soroban_client.clone()));
+    let networks = Networks::single(&config.networks.default_name, network_client);
+    let tenants = Tenants::connect(
+        &config.tenants,
+        &networks,
+        &config.signer,
+        &SecretStore::default(),
+        &config.cache,
+    )
+    .await?;
 
-    let app_state = AppState { soroban_client };
-    Ok(create_router(app_state))
+    let app_state = AppState {
+        rent_manager: RentManager::new(soroban_client.clone(), config.rent),
+        health: HealthChecker::new(soroban_client.clone(), config.health),
+        job_queue: JobQueue::new(soroban_client.clone(), config.jobs, config.submit.clone()),
+        imports: ImportManager::new(config.import, config.submit.clone()),
+        webhooks: WebhookDispatcher::new(soroban_client.clone(), config.webhooks),
+        networks,
+        tenants,
+        qr: QrCodec::new(soroban_client.signer(), config.qr),
+        soroban_client,
+        federation: Federation::empty(),
+        submit: config.submit,
+        indexer: None,
+        reconciler: None,
+        audit: None,
+        notifier: None,
+        auth: Authenticator::new(&config.auth)?,
+        web_auth: None,
+        friendbot: None,
+        dev_tools: config.sandbox.dev_tools_enabled(),
+        horizon: None,
+        cors: CorsOrigins::default(),
+        reloader: None,
+        replay: ReplayGuard::connect(&config.replay, "", "test").await?,
+        limits: config.limits,
+        server: config.server,
+    };
+    Ok(app_state)
 }
 
 #[tokio::test]
@@ -58,6 +204,65 @@ async fn test_health_check() {
     assert_eq!(body_json["data"], "healthy");
 }
 
+#[tokio::test]
+async fn test_large_responses_are_compressed() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    let request = Request::builder()
+        .uri("/api-docs/openapi.json")
+        .header("accept-encoding", "gzip")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-encoding"], "gzip");
+
+    // Small responses and clients not accepting an encoding get plain bodies
+    let request = Request::builder()
+        .uri("/health")
+        .header("accept-encoding", "gzip")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert!(response.headers().get("content-encoding").is_none());
+
+    let request = Request::builder()
+        .uri("/api-docs/openapi.json")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert!(response.headers().get("content-encoding").is_none());
+}
+
+#[tokio::test]
+async fn test_health_probes() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    let request = Request::builder()
+        .uri("/health/live")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The test contract ID doesn't exist, so the API is never ready
+    let request = Request::builder()
+        .uri("/health/ready")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body_json: Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(body_json["success"], false);
+    assert_eq!(body_json["data"]["ready"], false);
+    assert!(!body_json["data"]["checks"].as_array().unwrap().is_empty());
+}
+
 #[tokio::test]
 async fn test_init_contract_missing_admin() {
     let app = create_test_app().await.expect("Failed to create test app");
@@ -97,7 +302,7 @@ async fn test_issue_certificate_validation() {
 
     let response = app.oneshot(request).await.unwrap();
 
-    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
 
     let body = axum::body::to_bytes(response.into_body(), usize::MAX)
         .await
@@ -109,35 +314,115 @@ async fn test_issue_certificate_validation() {
         .as_str()
         .unwrap()
         .contains("Certificate ID cannot be empty"));
+    assert_eq!(body_json["error_code"], "INVALID_REQUEST");
+    assert_eq!(body_json["field"], "cert_id");
 }
 
 #[tokio::test]
-async fn test_get_certificate_empty_id() {
+async fn test_issue_certificate_invalid_owner() {
     let app = create_test_app().await.expect("Failed to create test app");
 
+    let request_body = json!({
+        "cert_id": "CERT-001",
+        "metadata_hash": METADATA_CID,
+        "owner_address": "GXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX"
+    });
+
     let request = Request::builder()
-        .uri("/certificates/")
-        .body(Body::empty())
+        .method("POST")
+        .uri("/certificates")
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_vec(&request_body).unwrap()))
         .unwrap();
 
     let response = app.oneshot(request).await.unwrap();
 
-    // This should return 404 as the route doesn't exist
-    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body_json: Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(body_json["success"], false);
+    assert_eq!(body_json["field"], "owner_address");
+    assert_eq!(body_json["errors"][0]["code"], "invalid");
+    assert!(body_json["error"]
+        .as_str()
+        .unwrap()
+        .contains("Stellar public key"));
 }
 
 #[tokio::test]
-async fn test_verify_certificate_validation() {
+async fn test_request_validation_lists_field_errors() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    let issue = |cert_id: &str, metadata_hash: &str| {
+        let request_body = json!({
+            "cert_id": cert_id,
+            "metadata_hash": metadata_hash,
+            "owner_address": MOCK_OWNER
+        });
+        Request::builder()
+            .method("POST")
+            .uri("/certificates")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&request_body).unwrap()))
+            .unwrap()
+    };
+
+    // Test a metadata hash that isn't a CID or sha256 digest
+    let response = app.clone().oneshot(issue("CERT-001", "QmHash123")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let body_json = json_body(response).await;
+    assert_eq!(body_json["error_code"], "INVALID_REQUEST");
+    let errors = body_json["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0]["field"], "metadata_hash");
+    assert_eq!(errors[0]["code"], "invalid");
+
+    // Test an ID that can be looked up but not issued
+    let response = app.clone().oneshot(issue("CERT:001", METADATA_CID)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let errors = json_body(response).await["errors"].clone();
+    assert_eq!(errors[0]["field"], "cert_id");
+    assert_eq!(errors[0]["code"], "cert_id");
+
+    // Test an ID in the path that can't be a certificate's
+    let request = Request::builder()
+        .uri("/certificates/CERT%20001")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(json_body(response).await["error_code"], "INVALID_CERT_ID");
+
+    // Test body over MAX_BODY_BYTES
+    let request_body = json!({ "metadata_hash": "Qm".repeat(64 * 1024) });
+    let request = Request::builder()
+        .method("POST")
+        .uri("/certificates/CERT-001/verify")
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_vec(&request_body).unwrap()))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(json_body(response).await["error_code"], "INVALID_REQUEST");
+}
+
+#[tokio::test]
+async fn test_verify_qr_invalid_payload() {
     let app = create_test_app().await.expect("Failed to create test app");
 
-    // Test with empty metadata_hash
     let request_body = json!({
-        "metadata_hash": ""
+        "payload": "VLX1.eyJjZXJ0X2lkIjoiQ0VSVC0wMDEifQ.not-a-signature"
     });
 
     let request = Request::builder()
         .method("POST")
-        .uri("/certificates/CERT001/verify")
+        .uri("/verify/qr")
         .header("content-type", "application/json")
         .body(Body::from(serde_json::to_vec(&request_body).unwrap()))
         .unwrap();
@@ -152,27 +437,40 @@ async fn test_verify_certificate_validation() {
     let body_json: Value = serde_json::from_slice(&body).unwrap();
 
     assert_eq!(body_json["success"], false);
-    assert!(body_json["error"]
-        .as_str()
-        .unwrap()
-        .contains("Metadata hash cannot be empty"));
+    assert_eq!(body_json["error_code"], "QR_INVALID");
 }
 
 #[tokio::test]
-async fn test_transfer_certificate_validation() {
+async fn test_public_verify_empty_hash() {
     let app = create_test_app().await.expect("Failed to create test app");
 
-    // Test with empty new_owner_address
-    let request_body = json!({
-        "new_owner_address": "",
-        "current_owner_secret_key": "test_secret_key"
-    });
+    let request = Request::builder()
+        .uri("/public/verify?cert_id=CERT-001&hash=")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body_json: Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(body_json["success"], false);
+    assert_eq!(body_json["field"], "hash");
+    assert_eq!(body_json["error_code"], "INVALID_REQUEST");
+}
+
+#[tokio::test]
+async fn test_unknown_network() {
+    let app = create_test_app().await.expect("Failed to create test app");
 
     let request = Request::builder()
-        .method("POST")
-        .uri("/certificates/CERT001/transfer")
-        .header("content-type", "application/json")
-        .body(Body::from(serde_json::to_vec(&request_body).unwrap()))
+        .uri("/certificates/CERT-001/exists")
+        .header("x-network", "mainnet")
+        .body(Body::empty())
         .unwrap();
 
     let response = app.oneshot(request).await.unwrap();
@@ -185,8 +483,1898 @@ async fn test_transfer_certificate_validation() {
     let body_json: Value = serde_json::from_slice(&body).unwrap();
 
     assert_eq!(body_json["success"], false);
-    assert!(body_json["error"]
-        .as_str()
-        .unwrap()
-        .contains("New owner address cannot be empty"));
-}
\ No newline at end of file
+    assert_eq!(body_json["error_code"], "INVALID_REQUEST");
+}
+
+#[tokio::test]
+async fn test_unknown_tenant() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    let request = Request::builder()
+        .uri("/certificates/CERT-001/exists")
+        .header("x-tenant", "acme")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = Request::builder()
+        .uri("/certificates/CERT-001/exists")
+        .header("x-api-key", "not-a-key")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body_json: Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(body_json["error_code"], "UNAUTHORIZED");
+}
+
+#[tokio::test]
+async fn test_export_requires_indexer() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    let request = Request::builder()
+        .uri("/admin/export?format=xml")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body_json: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body_json["field"], "format");
+
+    // Exports read from the indexer, which the test app doesn't run
+    let request = Request::builder()
+        .uri("/admin/export?format=csv&collection_id=1")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn test_import_validates_rows() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/admin/import?format=csv")
+        .header("content-type", "text/csv")
+        .body(Body::from("id,hash\nCERT-001,QmHash123\n"))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let csv = format!(
+        "cert_id,metadata_hash,owner_address\n\
+         CERT-001,{},GDQNY3PBOJOKYZSRMK2S7LHHGWZIUISD4QORETLMXEWXBI7KFZZMKTL3\n\
+         CERT-002,QmHash456,not-an-address\n",
+        METADATA_CID
+    );
+    let request = Request::builder()
+        .method("POST")
+        .uri("/admin/import?format=csv")
+        .header("content-type", "text/csv")
+        .body(Body::from(csv))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body_json: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body_json["data"]["total"], 2);
+    assert_eq!(body_json["data"]["invalid"], 1);
+    assert_eq!(body_json["data"]["rows"][1]["status"], "invalid");
+
+    // The report stays available while the import runs
+    let import_id = body_json["data"]["id"].as_str().unwrap();
+    let request = Request::builder()
+        .uri(format!("/admin/import/{}", import_id))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_audit_log_disabled() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    let request = Request::builder()
+        .uri("/admin/audit?outcome=maybe")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // The test app doesn't keep an audit log
+    let request = Request::builder()
+        .uri("/admin/audit?outcome=failure")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let request = Request::builder()
+        .uri("/admin/audit/verify")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn test_certificate_history_requires_indexer() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    let request = Request::builder()
+        .uri("/certificates/CERT-001/history")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn test_reconciliation_disabled() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    let request = Request::builder()
+        .uri("/admin/reconciliation")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn test_config_reload_unavailable() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/admin/config/reload")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn test_config_reload_applies_changes() {
+    let path = std::env::temp_dir().join(format!("veriluxe-reload-{}.toml", std::process::id()));
+    let contract_id = stellar_strkey::Contract([7; 32]).to_string();
+    let write_settings = |extra: &str| {
+        let settings = format!(
+            "fashion_auth_contract_id = \"{}\"\n\
+             admin_secret_key = \"SADQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQP54X\"\n{}",
+            contract_id, extra
+        );
+        std::fs::write(&path, settings).unwrap();
+    };
+    write_settings("cors_allowed_origins = \"https://shop.example\"\napi_port = 3000\n");
+
+    let options = ConfigOptions {
+        file: Some(path.clone()),
+        ..ConfigOptions::default()
+    };
+    let (_, settings) = Config::load(&options).unwrap();
+    let mut state = test_state(AuthSettings::default(), None, ReplaySettings::default(), Vec::new())
+        .await
+        .expect("Failed to create test app");
+    let cors = state.cors.clone();
+    state.reloader = Some(ConfigReloader::new(
+        options,
+        settings,
+        state.soroban_client.clone(),
+        state.webhooks.clone(),
+        cors.clone(),
+    ));
+    let app = create_router(state);
+    let reload = || {
+        Request::builder()
+            .method("POST")
+            .uri("/admin/config/reload")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    // Reloadable settings apply at once, the rest wait for a restart
+    write_settings("cors_allowed_origins = \"https://atelier.example\"\napi_port = 3001\n");
+    let response = app.clone().oneshot(reload()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let data = json_body(response).await["data"].clone();
+    assert_eq!(data["applied"], json!(["CORS_ALLOWED_ORIGINS"]));
+    assert_eq!(data["requires_restart"], json!(["API_PORT"]));
+    assert!(cors.allows("https://atelier.example"));
+    assert!(!cors.allows("https://shop.example"));
+
+    // An invalid file is refused and the running settings are kept
+    write_settings("cors_allowed_origins = \"https://rogue.example\"\nfee_strategy = \"bogus\"\n");
+    let response = app.oneshot(reload()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(cors.allows("https://atelier.example"));
+    assert!(!cors.allows("https://rogue.example"));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+/// Postgres for the tests of database-backed features, from `TEST_DATABASE_URL`
+///
+/// Those tests pass without running when it isn't set.
+async fn test_database() -> Option<(String, PgPool)> {
+    let Some(url) = std::env::var("TEST_DATABASE_URL").ok().filter(|url| !url.is_empty()) else {
+        eprintln!("TEST_DATABASE_URL is not set, skipping");
+        return None;
+    };
+    let pool = PgPool::connect(&url).await.expect("Failed to connect to TEST_DATABASE_URL");
+    Some((url, pool))
+}
+
+/// A suffix keeping the rows of one test run apart from earlier runs'
+fn unique_suffix() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    format!("{}-{}", std::process::id(), nanos)
+}
+
+#[tokio::test]
+async fn test_audit_log_records_and_verifies() {
+    let Some((database_url, pool)) = test_database().await else {
+        return;
+    };
+    let client = MockContractClient::initialized(MOCK_OWNER)
+        .with_certificate("CERT-001", "QmHash123", MOCK_OWNER);
+    let auth = AuthSettings {
+        enabled: false,
+        jwt_secret: "test_jwt_secret".to_string(),
+        jwt_issuer: String::new(),
+    };
+    let mut state = test_state(auth, Some(Arc::new(client)), ReplaySettings::default(), Vec::new())
+        .await
+        .expect("Failed to create test app");
+    let settings = AuditSettings {
+        enabled: true,
+        database_url,
+        ..AuditSettings::default()
+    };
+    state.audit = AuditLog::connect(&settings).await.unwrap();
+    let app = create_router(state);
+
+    // Entries name the token's subject, so this run's are easy to pick out
+    let actor = format!("auditor-{}", unique_suffix());
+    let token = jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &Claims {
+            sub: actor.clone(),
+            roles: vec![Role::Admin],
+            exp: 4_102_444_800,
+            tenant: None,
+        },
+        &jsonwebtoken::EncodingKey::from_secret(b"test_jwt_secret"),
+    )
+    .unwrap();
+    let revoke = |cert_id: &str| {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/certificates/{}/revoke", cert_id))
+            .header("authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let get = |uri: String| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+    let response = app.clone().oneshot(revoke("CERT-001")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app.clone().oneshot(revoke("CERT-404")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app.clone().oneshot(get(format!("/admin/audit?actor={}", actor))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let entries = json_body(response).await["data"]["entries"].clone();
+    assert_eq!(entries.as_array().unwrap().len(), 2);
+    assert_eq!(entries[0]["path"], "/certificates/CERT-404/revoke");
+    assert_eq!(entries[0]["outcome"], "failure");
+    assert_eq!(entries[1]["action"], "POST /certificates/:id/revoke");
+    assert_eq!(entries[1]["outcome"], "success");
+    assert_eq!(entries[0]["prev_hash"], entries[1]["entry_hash"]);
+
+    let response = app.clone().oneshot(get("/admin/audit/verify".to_string())).await.unwrap();
+    assert_eq!(json_body(response).await["data"]["valid"], true);
+
+    // Edit an entry behind the table's back, then put it back
+    let id = entries[1]["id"].as_i64().unwrap();
+    let tamper = |actor: String| {
+        let pool = pool.clone();
+        async move {
+            let mut tx = pool.begin().await.unwrap();
+            sqlx::query("ALTER TABLE audit_log DISABLE TRIGGER audit_log_append_only")
+                .execute(&mut *tx)
+                .await
+                .unwrap();
+            sqlx::query("UPDATE audit_log SET actor = $1 WHERE id = $2")
+                .bind(actor)
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .unwrap();
+            sqlx::query("ALTER TABLE audit_log ENABLE TRIGGER audit_log_append_only")
+                .execute(&mut *tx)
+                .await
+                .unwrap();
+            tx.commit().await.unwrap();
+        }
+    };
+    tamper("someone-else".to_string()).await;
+    let response = app.clone().oneshot(get("/admin/audit/verify".to_string())).await.unwrap();
+    let verification = json_body(response).await["data"].clone();
+    tamper(actor).await;
+    assert_eq!(verification["valid"], false);
+    assert_eq!(verification["broken_at"], id);
+
+    // Without the trigger disabled the table refuses edits
+    let refused = sqlx::query("DELETE FROM audit_log WHERE id = $1").bind(id).execute(&pool).await;
+    assert!(refused.is_err());
+}
+
+/// An indexer on the test database, without its polling loop
+async fn test_indexer(state: &AppState, database_url: String) -> Indexer {
+    let settings = IndexerSettings {
+        enabled: true,
+        database_url,
+        ..IndexerSettings::default()
+    };
+    Indexer::connect(state.soroban_client.clone(), settings).await.unwrap().unwrap()
+}
+
+#[tokio::test]
+async fn test_certificate_history_from_index() {
+    let Some((database_url, pool)) = test_database().await else {
+        return;
+    };
+    let mut state = test_state(AuthSettings::default(), None, ReplaySettings::default(), Vec::new())
+        .await
+        .expect("Failed to create test app");
+    state.indexer = Some(test_indexer(&state, database_url).await);
+    let app = create_router(state);
+
+    let cert_id = format!("HIST-{}", unique_suffix());
+    for (event, ledger, data) in [
+        ("transfer", 20, json!({ "from": MOCK_OWNER, "to": "GBUYER" })),
+        ("issued", 10, json!({ "owner": MOCK_OWNER })),
+    ] {
+        sqlx::query(
+            "INSERT INTO certificate_events (id, cert_id, event, ledger, transaction_hash, data)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(format!("{}-{}", cert_id, ledger))
+        .bind(&cert_id)
+        .bind(event)
+        .bind(i64::from(ledger))
+        .bind(format!("tx-{}", ledger))
+        .bind(sqlx::types::Json(data))
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let history = |cert_id: &str| {
+        Request::builder()
+            .uri(format!("/certificates/{}/history", cert_id))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = app.clone().oneshot(history(&cert_id)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let events = json_body(response).await["data"].clone();
+    assert_eq!(events.as_array().unwrap().len(), 2);
+    assert_eq!(events[0]["event"], "issued");
+    assert_eq!(events[0]["ledger"], 10);
+    assert_eq!(events[1]["event"], "transfer");
+    assert_eq!(events[1]["data"]["to"], "GBUYER");
+
+    let response = app.oneshot(history("HIST-never-indexed")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(json_body(response).await["error_code"], "CERT_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn test_reconciliation_repairs_drift() {
+    let Some((database_url, pool)) = test_database().await else {
+        return;
+    };
+    let suffix = unique_suffix();
+    let in_sync = format!("RECON-{}-A", suffix);
+    let stale_owner = format!("RECON-{}-B", suffix);
+    let burned = format!("RECON-{}-C", suffix);
+
+    let client = MockContractClient::initialized(MOCK_OWNER)
+        .with_certificate(&in_sync, "QmHash123", MOCK_OWNER)
+        .with_certificate(&stale_owner, "QmHash456", MOCK_OWNER);
+    let client: Arc<dyn ContractClient> = Arc::new(client);
+    let mut state = test_state(AuthSettings::default(), Some(client.clone()), ReplaySettings::default(), Vec::new())
+        .await
+        .expect("Failed to create test app");
+    let indexer = test_indexer(&state, database_url).await;
+
+    // The index missed a transfer of B and the burn of C
+    for (cert_id, owner, hash) in [
+        (&in_sync, MOCK_OWNER, "QmHash123"),
+        (&stale_owner, "GPREVIOUSOWNER", "QmHash456"),
+        (&burned, MOCK_OWNER, "QmHash789"),
+    ] {
+        sqlx::query(
+            "INSERT INTO certificates (cert_id, owner, metadata_hash, issued_ledger, updated_ledger)
+             VALUES ($1, $2, $3, 1, 1)",
+        )
+        .bind(cert_id)
+        .bind(owner)
+        .bind(hash)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let settings = ReconcileSettings {
+        enabled: true,
+        ..ReconcileSettings::default()
+    };
+    let reconciler = Reconciler::new(client, Some(&indexer), settings).unwrap().unwrap();
+    reconciler.run().await;
+    state.reconciler = Some(reconciler);
+    let app = create_router(state);
+
+    let request = Request::builder()
+        .uri("/admin/reconciliation")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report = json_body(response).await["data"].clone();
+    assert_eq!(report["status"], "completed");
+
+    // Other runs' rows share the table, so only look at this run's drift
+    let drift: Vec<&Value> = report["drift"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|drift| drift["cert_id"].as_str().unwrap().contains(&suffix))
+        .collect();
+    assert_eq!(drift.len(), 2);
+    assert!(drift.iter().all(|drift| drift["repaired"] == true));
+    assert!(drift.iter().any(|drift| drift["cert_id"] == stale_owner.as_str()
+        && drift["field"] == "owner"
+        && drift["on_chain"] == MOCK_OWNER));
+    assert!(drift.iter().any(|drift| drift["cert_id"] == burned.as_str()
+        && drift["field"] == "status"
+        && drift["on_chain"] == "burned"));
+
+    assert_eq!(indexer.get_certificate(&stale_owner).await.unwrap().unwrap().owner, MOCK_OWNER);
+    assert_eq!(indexer.get_certificate(&burned).await.unwrap().unwrap().status, "burned");
+    assert_eq!(indexer.get_certificate(&in_sync).await.unwrap().unwrap().status, "active");
+}
+
+#[tokio::test]
+async fn test_get_certificate_empty_id() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    let request = Request::builder()
+        .uri("/certificates/")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    // This should return 404 as the route doesn't exist
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_verify_certificate_validation() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    // Test with empty metadata_hash
+    let request_body = json!({
+        "metadata_hash": ""
+    });
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/certificates/CERT001/verify")
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_vec(&request_body).unwrap()))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body_json: Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(body_json["success"], false);
+    assert!(body_json["error"]
+        .as_str()
+        .unwrap()
+        .contains("Metadata hash cannot be empty"));
+}
+
+#[tokio::test]
+async fn test_transfer_certificate_validation() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    // Test with empty new_owner_address
+    let request_body = json!({
+        "new_owner_address": ""
+    });
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/certificates/CERT001/transfer/prepare")
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_vec(&request_body).unwrap()))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body_json: Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(body_json["success"], false);
+    assert_eq!(body_json["field"], "new_owner_address");
+    assert!(body_json["error"]
+        .as_str()
+        .unwrap()
+        .contains("Address cannot be empty"));
+}
+
+#[tokio::test]
+async fn test_submit_transaction_validation() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    // Test with a transaction that isn't envelope XDR
+    let request_body = json!({
+        "transaction": "not-a-transaction"
+    });
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/transactions/submit")
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_vec(&request_body).unwrap()))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body_json: Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(body_json["success"], false);
+    assert!(body_json["error"]
+        .as_str()
+        .unwrap()
+        .contains("Invalid transaction"));
+}
+
+#[tokio::test]
+async fn test_federated_verify_validation() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    let request = Request::builder()
+        .uri("/verify?cert_id=CERT001&metadata_hash=")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body_json: Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(body_json["success"], false);
+    assert!(body_json["error"]
+        .as_str()
+        .unwrap()
+        .contains("Metadata hash cannot be empty"));
+}
+
+#[tokio::test]
+async fn test_revoke_certificate_invalid_wait_flag() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/certificates/CERT001/revoke?wait=maybe")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    // The query string is rejected before anything is submitted
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_get_unknown_job() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    let request = Request::builder()
+        .uri("/jobs/00000000-0000-0000-0000-000000000000")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body_json: Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(body_json["success"], false);
+    assert!(body_json["error"].as_str().unwrap().contains("not found"));
+}
+
+#[tokio::test]
+async fn test_fee_bump_validation() {
+    let app = create_test_app().await.expect("Failed to create test app");
+    let hash = "a".repeat(64);
+
+    // Not submitted by this instance, and no envelope given
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/transactions/{}/fee-bump", hash))
+        .header("content-type", "application/json")
+        .body(Body::from(json!({}).to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/transactions/{}/fee-bump", hash))
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "transaction": "not-xdr" }).to_string()))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body_json: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body_json["field"], "transaction");
+}
+
+#[tokio::test]
+async fn test_register_webhook_validation() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    // Test non-http URL
+    let request = Request::builder()
+        .method("POST")
+        .uri("/webhooks")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({
+                "url": "ftp://example.com/hook"
+            })
+            .to_string(),
+        ))
+        .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Test unknown event
+    let request = Request::builder()
+        .method("POST")
+        .uri("/webhooks")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({
+                "url": "https://example.com/hook",
+                "events": ["minted"]
+            })
+            .to_string(),
+        ))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body_json: Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(body_json["success"], false);
+    assert!(body_json["error"]
+        .as_str()
+        .unwrap()
+        .contains("Unknown webhook event"));
+}
+
+#[tokio::test]
+async fn test_webhook_secrets_and_deliveries() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    // Caller-supplied secrets must be long enough to be worth signing with
+    let request = Request::builder()
+        .method("POST")
+        .uri("/webhooks")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({ "url": "https://example.com/hook", "secret": "short" }).to_string(),
+        ))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // The generated secret is returned on registration only
+    let request = Request::builder()
+        .method("POST")
+        .uri("/webhooks")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "url": "https://example.com/hook" }).to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body_json = json_body(response).await;
+    let webhook_id = body_json["data"]["id"].as_str().unwrap().to_string();
+    assert_eq!(body_json["data"]["secret"].as_str().unwrap().len(), 64);
+
+    let request = Request::builder().uri("/webhooks").body(Body::empty()).unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let body_json = json_body(response).await;
+    assert!(body_json["data"][0].get("secret").is_none());
+
+    let request = Request::builder()
+        .uri(format!("/webhooks/{}/deliveries?status=dead_letter", webhook_id))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(json_body(response).await["data"], json!([]));
+
+    let request = Request::builder()
+        .uri("/webhooks/unknown/deliveries")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_list_certificates_validation() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    // Test out-of-range limit
+    let request = Request::builder()
+        .uri("/certificates?limit=0")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Listing reads from the indexer, which the test app doesn't run
+    let request = Request::builder()
+        .uri("/certificates?limit=10")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn test_list_certificates_sort_and_cursor_validation() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    // Test unknown sort
+    let request = Request::builder()
+        .uri("/certificates?sort=price")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Test a cursor that isn't one the API issued
+    let request = Request::builder()
+        .uri("/certificates/search?cursor=CERT001")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Test a cursor issued for a different sort
+    let issued_order = CertificateSort::parse("-issued_at").unwrap();
+    let cursor = CertificateCursor::new(issued_order, Some(1200), "CERT001").encode();
+    let request = Request::builder()
+        .uri(format!("/certificates?sort=last_transfer&cursor={}", cursor))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // A matching cursor passes validation and reaches the indexer
+    let request = Request::builder()
+        .uri(format!("/certificates?sort=-issued_at&cursor={}", cursor))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn test_search_certificates_validation() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    // Test unknown status
+    let request = Request::builder()
+        .uri("/certificates/search?status=lost")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body_json: Value = serde_json::from_slice(&body).unwrap();
+    assert!(body_json["error"]
+        .as_str()
+        .unwrap()
+        .contains("Status must be one of"));
+
+    // Test inverted date range
+    let request = Request::builder()
+        .uri("/certificates/search?issued_from=2024-06-01T00:00:00Z&issued_to=2024-01-01T00:00:00Z")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+fn test_token(secret: &str, roles: Vec<Role>) -> String {
+    tenant_token(secret, roles, None)
+}
+
+fn tenant_token(secret: &str, roles: Vec<Role>, tenant: Option<&str>) -> String {
+    let claims = Claims {
+        sub: "tester".to_string(),
+        roles,
+        exp: 4_102_444_800,
+        tenant: tenant.map(str::to_string),
+    };
+    jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &claims,
+        &jsonwebtoken::EncodingKey::from_secret(secret.as_bytes()),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_role_authorization() {
+    let app = create_test_app_with_auth(AuthSettings {
+        enabled: true,
+        jwt_secret: "test_jwt_secret".to_string(),
+        jwt_issuer: String::new(),
+    })
+    .await
+    .expect("Failed to create test app");
+
+    let init_request = |token: Option<String>| {
+        let mut builder = Request::builder()
+            .method("POST")
+            .uri("/init")
+            .header("content-type", "application/json");
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {}", token));
+        }
+        builder
+            .body(Body::from(json!({ "admin_address": "" }).to_string()))
+            .unwrap()
+    };
+
+    // Test missing token
+    let response = app.clone().oneshot(init_request(None)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Test token signed with another secret
+    let forged = test_token("other_secret", vec![Role::Admin]);
+    let response = app.clone().oneshot(init_request(Some(forged))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Test token without the admin role
+    let reader = test_token("test_jwt_secret", vec![Role::ReadOnly]);
+    let response = app.clone().oneshot(init_request(Some(reader))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Health stays public
+    let request = Request::builder()
+        .uri("/health")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_owner_routes_require_session() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    // Test challenge while SEP-10 is disabled
+    let request = Request::builder()
+        .uri("/auth?account=GDQNY3PBOJOKYZSRMK2S7LHHGWZIUISD4QORETLMXEWXBI7KFZZMKTL3")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    // Owner routes need a session token even with authentication disabled
+    let request = Request::builder()
+        .uri("/me/certificates")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Without JWT_SECRET no token is accepted
+    let token = test_token("", vec![Role::Owner]);
+    let request = Request::builder()
+        .uri("/me/certificates")
+        .header("authorization", format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+const SEP10_SERVER_KEY: &str = "0101010101010101010101010101010101010101010101010101010101010101";
+const SEP10_CLIENT_KEY: &str = "0202020202020202020202020202020202020202020202020202020202020202";
+
+/// A SEP-10 service for `home_domain`, signing challenges with `signing_key`
+fn test_web_auth(home_domain: &str, signing_key: &str) -> WebAuth {
+    let settings = Sep10Settings {
+        enabled: true,
+        home_domain: home_domain.to_string(),
+        signing_key: signing_key.to_string(),
+        ..Sep10Settings::default()
+    };
+    WebAuth::new(
+        settings,
+        "Test SDF Network ; September 2015".to_string(),
+        None,
+        sep10_authenticator(),
+    )
+    .unwrap()
+    .unwrap()
+}
+
+fn sep10_authenticator() -> Authenticator {
+    Authenticator::new(&AuthSettings {
+        enabled: true,
+        jwt_secret: "test_jwt_secret".to_string(),
+        jwt_issuer: String::new(),
+    })
+    .unwrap()
+}
+
+/// Decode a challenge, let `edit` change it, and re-sign it with `signers`
+async fn sign_challenge(
+    challenge: &str,
+    edit: impl FnOnce(&mut Transaction),
+    signers: &[&LocalSigner],
+) -> String {
+    let bytes = BASE64.decode(challenge).unwrap();
+    let TransactionEnvelope::Tx(mut envelope) = TransactionEnvelope::from_xdr(bytes, Limits::none()).unwrap() else {
+        panic!("Challenge is not a v1 envelope");
+    };
+    let unchanged = envelope.tx.clone();
+    edit(&mut envelope.tx);
+
+    let hash = transaction_hash("Test SDF Network ; September 2015", &envelope.tx).unwrap();
+    let mut signatures = if envelope.tx == unchanged {
+        envelope.signatures.to_vec()
+    } else {
+        Vec::new()
+    };
+    for signer in signers {
+        signatures.push(sign_hash(*signer, &hash).await.unwrap());
+    }
+    envelope.signatures = signatures.try_into().unwrap();
+
+    BASE64.encode(TransactionEnvelope::Tx(envelope).to_xdr(Limits::none()).unwrap())
+}
+
+#[tokio::test]
+async fn test_sep10_round_trip() {
+    let client = LocalSigner::from_secret(SEP10_CLIENT_KEY).unwrap();
+    let web_auth = test_web_auth("veriluxe.test", SEP10_SERVER_KEY);
+
+    let challenge = web_auth.challenge(&client.address()).await.unwrap();
+    let signed = sign_challenge(&challenge, |_| {}, &[&client]).await;
+    let token = web_auth.verify(&signed, None).unwrap();
+
+    // The session is an owner token for the client's account
+    let claims = sep10_authenticator().verify(&token).unwrap();
+    assert_eq!(claims.sub, client.address());
+    assert_eq!(claims.roles, vec![Role::Owner]);
+    assert_eq!(claims.tenant, None);
+
+    // Without the client's signature nothing proves control of the account
+    let error = web_auth.verify(&challenge, None).unwrap_err();
+    assert!(error.to_string().contains("client signature"));
+
+    // Challenges are only valid for the home domain they were built for
+    let elsewhere = test_web_auth("elsewhere.test", SEP10_SERVER_KEY);
+    let error = elsewhere.verify(&signed, None).unwrap_err();
+    assert!(error.to_string().contains("not for elsewhere.test"));
+
+    // Nor by a server holding another key
+    let foreign = test_web_auth("veriluxe.test", SEP10_CLIENT_KEY);
+    let error = foreign.verify(&signed, None).unwrap_err();
+    assert!(error.to_string().contains("not issued by this server"));
+}
+
+#[tokio::test]
+async fn test_sep10_rejects_expired_challenge() {
+    let server = LocalSigner::from_secret(SEP10_SERVER_KEY).unwrap();
+    let client = LocalSigner::from_secret(SEP10_CLIENT_KEY).unwrap();
+    let web_auth = test_web_auth("veriluxe.test", SEP10_SERVER_KEY);
+
+    let challenge = web_auth.challenge(&client.address()).await.unwrap();
+    let expire = |transaction: &mut Transaction| {
+        transaction.cond = Preconditions::Time(TimeBounds {
+            min_time: TimePoint(1_700_000_000),
+            max_time: TimePoint(1_700_000_900),
+        });
+    };
+    // Signed by both parties, so only the time bounds are wrong
+    let signed = sign_challenge(&challenge, expire, &[&server, &client]).await;
+    let error = web_auth.verify(&signed, None).unwrap_err();
+    assert!(error.to_string().contains("expired"));
+}
+
+#[tokio::test]
+async fn test_sep10_session_scoped_to_tenant() {
+    let owner = LocalSigner::from_secret(SEP10_CLIENT_KEY).unwrap();
+    let client = MockContractClient::initialized(MOCK_OWNER)
+        .with_certificate("CERT-001", "QmHash123", &owner.address());
+    let tenants = serde_json::from_value(json!([
+        { "id": "acme", "brand": "Acme Couture", "contract_id": "CACME" }
+    ]))
+    .unwrap();
+    let auth = AuthSettings {
+        enabled: true,
+        jwt_secret: "test_jwt_secret".to_string(),
+        jwt_issuer: String::new(),
+    };
+    let mut state = test_state(auth, Some(Arc::new(client)), ReplaySettings::default(), tenants)
+        .await
+        .expect("Failed to create test state");
+    let web_auth = test_web_auth("veriluxe.test", SEP10_SERVER_KEY);
+    state.web_auth = Some(web_auth.clone());
+    let app = create_router(state);
+
+    // Sign in through the tenant, as the brand's own app would
+    let challenge = web_auth.challenge(&owner.address()).await.unwrap();
+    let signed = sign_challenge(&challenge, |_| {}, &[&owner]).await;
+    let sign_in = |tenant: Option<&str>| {
+        let mut builder = Request::builder()
+            .method("POST")
+            .uri("/auth")
+            .header("content-type", "application/json");
+        if let Some(tenant) = tenant {
+            builder = builder.header("x-tenant", tenant);
+        }
+        builder
+            .body(Body::from(json!({ "transaction": signed }).to_string()))
+            .unwrap()
+    };
+    let response = app.clone().oneshot(sign_in(Some("acme"))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let token = json_body(response).await["token"].as_str().unwrap().to_string();
+    let claims = sep10_authenticator().verify(&token).unwrap();
+    assert_eq!(claims.tenant.as_deref(), Some("acme"));
+
+    let prepare = |token: &str, tenant: Option<&str>| {
+        let mut builder = Request::builder()
+            .method("POST")
+            .uri("/certificates/CERT-001/transfer/prepare")
+            .header("content-type", "application/json")
+            .header("authorization", format!("Bearer {}", token));
+        if let Some(tenant) = tenant {
+            builder = builder.header("x-tenant", tenant);
+        }
+        builder
+            .body(Body::from(json!({ "new_owner_address": MOCK_OWNER }).to_string()))
+            .unwrap()
+    };
+
+    // The session works for the owner's certificates at the tenant
+    let response = app.clone().oneshot(prepare(&token, Some("acme"))).await.unwrap();
+    assert_ne!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_ne!(response.status(), StatusCode::FORBIDDEN);
+
+    // But not on the platform registry, and platform sessions not at the tenant
+    let response = app.clone().oneshot(prepare(&token, None)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = app.clone().oneshot(sign_in(None)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let platform = json_body(response).await["token"].as_str().unwrap().to_string();
+    let response = app.oneshot(prepare(&platform, Some("acme"))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_transfer_policy_checks_ownership() {
+    let client = MockContractClient::initialized(MOCK_OWNER)
+        .with_certificate("CERT-001", "QmHash123", MOCK_OWNER)
+        .with_certificate("CERT-002", "QmHash456", "tester");
+    let auth = AuthSettings {
+        enabled: true,
+        jwt_secret: "test_jwt_secret".to_string(),
+        jwt_issuer: String::new(),
+    };
+    let app = build_test_app(auth, Some(Arc::new(client)), ReplaySettings::default(), Vec::new())
+        .await
+        .expect("Failed to create test app");
+
+    let prepare = |cert_id: &str, roles: Vec<Role>| {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/certificates/{}/transfer/prepare", cert_id))
+            .header("content-type", "application/json")
+            .header(
+                "authorization",
+                format!("Bearer {}", test_token("test_jwt_secret", roles)),
+            )
+            .body(Body::from(json!({ "new_owner_address": MOCK_OWNER }).to_string()))
+            .unwrap()
+    };
+
+    // Owner tokens can't touch certificates held by someone else
+    let response = app.clone().oneshot(prepare("CERT-001", vec![Role::Owner])).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = app.clone().oneshot(prepare("CERT-404", vec![Role::Owner])).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(json_body(response).await["error_code"], "CERT_NOT_FOUND");
+
+    // Their own certificate and staff tokens get through to the handler
+    for (cert_id, role) in [("CERT-002", Role::Owner), ("CERT-001", Role::Issuer)] {
+        let response = app.clone().oneshot(prepare(cert_id, vec![role])).await.unwrap();
+        assert_ne!(response.status(), StatusCode::FORBIDDEN);
+        assert_ne!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    // Roles are checked before ownership
+    let response = app.oneshot(prepare("CERT-002", vec![Role::ReadOnly])).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(json_body(response).await["error"]
+        .as_str()
+        .unwrap()
+        .contains("Requires one of the roles"));
+}
+
+#[tokio::test]
+async fn test_tokens_scoped_to_tenant() {
+    let client = MockContractClient::initialized(MOCK_OWNER)
+        .with_certificate("CERT-001", "QmHash123", MOCK_OWNER);
+    let tenants = serde_json::from_value(json!([
+        { "id": "acme", "brand": "Acme Couture", "contract_id": "CACME" },
+        { "id": "globex", "brand": "Globex Atelier", "contract_id": "CGLOBEX" }
+    ]))
+    .unwrap();
+    let auth = AuthSettings {
+        enabled: true,
+        jwt_secret: "test_jwt_secret".to_string(),
+        jwt_issuer: String::new(),
+    };
+    let app = build_test_app(auth, Some(Arc::new(client)), ReplaySettings::default(), tenants)
+        .await
+        .expect("Failed to create test app");
+
+    let prepare = |tenant_header: Option<&str>, token_tenant: Option<&str>| {
+        let token = tenant_token("test_jwt_secret", vec![Role::Issuer], token_tenant);
+        let mut builder = Request::builder()
+            .method("POST")
+            .uri("/certificates/CERT-001/transfer/prepare")
+            .header("content-type", "application/json")
+            .header("authorization", format!("Bearer {}", token));
+        if let Some(tenant) = tenant_header {
+            builder = builder.header("x-tenant", tenant);
+        }
+        builder
+            .body(Body::from(json!({ "new_owner_address": MOCK_OWNER }).to_string()))
+            .unwrap()
+    };
+
+    // Platform tokens can't borrow a tenant's contract and signer
+    let response = app.clone().oneshot(prepare(Some("acme"), None)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(json_body(response).await["error_code"], "FORBIDDEN");
+
+    // Nor can one tenant's tokens act for another, or on the platform registry
+    let response = app.clone().oneshot(prepare(Some("globex"), Some("acme"))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = app.clone().oneshot(prepare(None, Some("acme"))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    for (tenant_header, token_tenant) in [(Some("acme"), Some("acme")), (None, None)] {
+        let response = app.clone().oneshot(prepare(tenant_header, token_tenant)).await.unwrap();
+        assert_ne!(response.status(), StatusCode::FORBIDDEN);
+        assert_ne!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    // Public verification still takes X-Tenant alone
+    let request = Request::builder()
+        .uri("/certificates/CERT-001/exists")
+        .header("x-tenant", "acme")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_tenants_require_auth() {
+    let path = std::env::temp_dir().join(format!("veriluxe-tenants-{}.toml", std::process::id()));
+    let contract_id = stellar_strkey::Contract([7; 32]).to_string();
+    let write_settings = |extra: &str| {
+        let settings = format!(
+            "fashion_auth_contract_id = \"{}\"\n\
+             admin_secret_key = \"SADQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQP54X\"\n{}\n\
+             [[tenants]]\nid = \"acme\"\nbrand = \"Acme Couture\"\ncontract_id = \"{}\"\n",
+            contract_id, extra, contract_id
+        );
+        std::fs::write(&path, settings).unwrap();
+    };
+    let options = ConfigOptions {
+        file: Some(path.clone()),
+        ..ConfigOptions::default()
+    };
+
+    write_settings("");
+    let error = Config::load(&options).err().expect("Tenants without authentication were accepted");
+    assert!(error.to_string().contains("TENANTS requires AUTH_ENABLED"));
+
+    write_settings("auth_enabled = true\njwt_secret = \"test_jwt_secret\"");
+    assert!(Config::load(&options).is_ok());
+    std::fs::remove_file(&path).unwrap();
+
+    // Without authentication, X-Tenant alone doesn't borrow the tenant's signer
+    let client = MockContractClient::initialized(MOCK_OWNER)
+        .with_certificate("CERT-001", "QmHash123", MOCK_OWNER);
+    let tenants = serde_json::from_value(json!([
+        { "id": "acme", "brand": "Acme Couture", "contract_id": "CACME" }
+    ]))
+    .unwrap();
+    let auth = AuthSettings {
+        enabled: false,
+        jwt_secret: "test_jwt_secret".to_string(),
+        jwt_issuer: String::new(),
+    };
+    let app = build_test_app(auth, Some(Arc::new(client)), ReplaySettings::default(), tenants)
+        .await
+        .expect("Failed to create test app");
+
+    let revoke = |token: Option<String>| {
+        let mut builder = Request::builder()
+            .method("POST")
+            .uri("/certificates/CERT-001/revoke")
+            .header("x-tenant", "acme");
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {}", token));
+        }
+        builder.body(Body::empty()).unwrap()
+    };
+
+    let response = app.clone().oneshot(revoke(None)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(json_body(response).await["error_code"], "UNAUTHORIZED");
+
+    let platform = tenant_token("test_jwt_secret", vec![Role::Admin], None);
+    let response = app.clone().oneshot(revoke(Some(platform))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let staff = tenant_token("test_jwt_secret", vec![Role::Admin], Some("acme"));
+    let response = app.oneshot(revoke(Some(staff))).await.unwrap();
+    assert_ne!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_ne!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_api_key_quotas() {
+    let client = MockContractClient::initialized(MOCK_OWNER)
+        .with_certificate("CERT-001", "QmHash123", MOCK_OWNER);
+    let tenants = serde_json::from_value(json!([{
+        "id": "acme",
+        "brand": "Acme Couture",
+        "contract_id": "CACME",
+        "api_keys": [
+            { "id": "acme-partner", "key": "acme-partner-key", "daily_quota": 2 },
+            "acme-internal-key"
+        ]
+    }]))
+    .unwrap();
+    let app = build_test_app(AuthSettings::default(), Some(Arc::new(client)), ReplaySettings::default(), tenants)
+        .await
+        .expect("Failed to create test app");
+
+    let get = |api_key: &str| {
+        Request::builder()
+            .uri("/certificates/CERT-001")
+            .header("x-api-key", api_key)
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    for _ in 0..2 {
+        let response = app.clone().oneshot(get("acme-partner-key")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    let response = app.clone().oneshot(get("acme-partner-key")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(response.headers().contains_key("retry-after"));
+    assert_eq!(json_body(response).await["error_code"], "QUOTA_EXCEEDED");
+
+    // Keys without quotas are counted but never refused
+    for _ in 0..3 {
+        let response = app.clone().oneshot(get("acme-internal-key")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let usage = |id: &str| {
+        Request::builder()
+            .uri(format!("/admin/api-keys/{}/usage", id))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = app.clone().oneshot(usage("acme-partner")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let data = json_body(response).await["data"].clone();
+    assert_eq!(data["tenant"], "acme");
+    assert_eq!(data["daily"]["used"], 2);
+    assert_eq!(data["daily"]["quota"], 2);
+    assert_eq!(data["daily"]["remaining"], 0);
+    assert_eq!(data["monthly"]["used"], 2);
+    assert!(data["monthly"]["quota"].is_null());
+
+    let response = app.oneshot(usage("acme-missing")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_replayed_revocation_rejected() {
+    let client = MockContractClient::initialized(MOCK_OWNER)
+        .with_certificate("CERT-001", "QmHash123", MOCK_OWNER);
+    let replay = ReplaySettings {
+        enabled: true,
+        max_nonces: 2,
+        ..ReplaySettings::default()
+    };
+    let app = build_test_app(AuthSettings::default(), Some(Arc::new(client)), replay, Vec::new())
+        .await
+        .expect("Failed to create test app");
+
+    let token = "caller-token";
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let sign = |method: &str, path: &str, body: &str, nonce: &str, timestamp: u64| {
+        let payload = signing_payload(method, path, body.as_bytes(), nonce, timestamp);
+        let mut mac = Hmac::<Sha256>::new_from_slice(token.as_bytes()).unwrap();
+        mac.update(payload.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    };
+    let revoke = |nonce: Option<&str>, timestamp: u64| {
+        let mut builder = Request::builder()
+            .method("POST")
+            .uri("/certificates/CERT-001/revoke")
+            .header("authorization", format!("Bearer {}", token))
+            .header("x-request-timestamp", timestamp.to_string());
+        if let Some(nonce) = nonce {
+            let signature = sign("POST", "/certificates/CERT-001/revoke", "", nonce, timestamp);
+            builder = builder
+                .header("x-request-nonce", nonce)
+                .header("x-request-signature", signature);
+        }
+        builder.body(Body::empty()).unwrap()
+    };
+
+    // Test missing nonce
+    let response = app.clone().oneshot(revoke(None, now)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Test a nonce and timestamp lifted onto another request
+    let nonce = "5a9e3c71-lifted-request";
+    let request = Request::builder()
+        .method("POST")
+        .uri("/certificates/CERT-002/revoke")
+        .header("authorization", format!("Bearer {}", token))
+        .header("x-request-nonce", nonce)
+        .header("x-request-timestamp", now.to_string())
+        .header("x-request-signature", sign("POST", "/certificates/CERT-001/revoke", "", nonce, now))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Test stale timestamp
+    let response = app
+        .clone()
+        .oneshot(revoke(Some("7b0c6a1e-stale-request"), now - 3600))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(json_body(response).await["error_code"], "REPLAYED_REQUEST");
+
+    let nonce = Some("0f4e8a52-9d1c-4b7e-a3f6-5c2d8e1b9a70");
+    let response = app.clone().oneshot(revoke(nonce, now)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The same request sent again is refused before reaching the contract
+    let response = app.clone().oneshot(revoke(nonce, now)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(json_body(response).await["error_code"], "REPLAYED_REQUEST");
+
+    // Once the nonce store is full new requests are refused, not forgotten
+    let response = app.clone().oneshot(revoke(Some("c81d4f02-fills-the-store"), now)).await.unwrap();
+    assert_ne!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let response = app.oneshot(revoke(Some("e29b7a63-past-the-store"), now)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn test_notification_preferences_unavailable() {
+    let app = create_test_app_with_auth(AuthSettings {
+        enabled: true,
+        jwt_secret: "test_jwt_secret".to_string(),
+        jwt_issuer: String::new(),
+    })
+    .await
+    .expect("Failed to create test app");
+
+    let request = Request::builder()
+        .method("PUT")
+        .uri("/me/notifications")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "email": "owner@example.com" }).to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // The test app has no database to keep contact details in
+    let token = test_token("test_jwt_secret", vec![Role::Owner]);
+    let request = Request::builder()
+        .uri("/me/notifications")
+        .header("authorization", format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn test_response_cache_invalidation() {
+    let settings = CacheSettings {
+        enabled: true,
+        ..CacheSettings::default()
+    };
+    let cache = ResponseCache::connect(settings, "test").await.unwrap();
+
+    cache.put("CERT-001", "exists", &true).await;
+    assert_eq!(cache.get::<bool>("CERT-001", "exists").await, Some(true));
+    assert_eq!(cache.get::<bool>("CERT-002", "exists").await, None);
+
+    // A write drops the certificate and keeps it out of the cache for a while
+    cache.invalidate("CERT-001").await;
+    assert_eq!(cache.get::<bool>("CERT-001", "exists").await, None);
+    cache.put("CERT-001", "exists", &false).await;
+    assert_eq!(cache.get::<bool>("CERT-001", "exists").await, None);
+}
+
+const MOCK_OWNER: &str = "GDQNY3PBOJOKYZSRMK2S7LHHGWZIUISD4QORETLMXEWXBI7KFZZMKTL3";
+
+const METADATA_CID: &str = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+
+async fn json_body(response: Response) -> Value {
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn test_issue_and_get_certificate() {
+    let app = create_mock_app(MockContractClient::initialized(MOCK_OWNER)).await;
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/certificates?wait=true")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({
+                "cert_id": "CERT-001",
+                "metadata_hash": METADATA_CID,
+                "owner_address": MOCK_OWNER
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body_json = json_body(response).await;
+    assert_eq!(body_json["data"]["status"], "confirmed");
+    assert!(body_json["data"]["ledger"].is_u64());
+    assert!(body_json["data"]["result"]["events"].is_array());
+
+    let request = Request::builder()
+        .uri("/certificates/CERT-001")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body_json = json_body(response).await;
+    assert_eq!(body_json["data"]["owner"], MOCK_OWNER);
+    assert_eq!(body_json["data"]["metadata_hash"], METADATA_CID);
+    assert_eq!(body_json["data"]["is_valid"], true);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/certificates/CERT-001/verify")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "metadata_hash": "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o" }).to_string()))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(json_body(response).await["data"]["is_valid"], false);
+}
+
+#[tokio::test]
+async fn test_verify_explains_result() {
+    let client = MockContractClient::initialized(MOCK_OWNER)
+        .with_certificate("CERT-001", METADATA_CID, MOCK_OWNER);
+    client.set_collection("CERT-001", 1, "Maison Lumière");
+    let app = create_mock_app(client.clone()).await;
+
+    let verify = |cert_id: &str, metadata_hash: &str| {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/certificates/{}/verify", cert_id))
+            .header("content-type", "application/json")
+            .body(Body::from(json!({ "metadata_hash": metadata_hash }).to_string()))
+            .unwrap()
+    };
+
+    let response = app.clone().oneshot(verify("CERT-001", METADATA_CID)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let data = json_body(response).await["data"].clone();
+    assert_eq!(data["is_valid"], true);
+    assert_eq!(data["status"], "valid");
+    assert_eq!(data["reason"], "Authentic VeriLuxe certificate");
+    assert_eq!(data["brand"], "Maison Lumière");
+
+    let other_hash = "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o";
+    let response = app.clone().oneshot(verify("CERT-001", other_hash)).await.unwrap();
+    let data = json_body(response).await["data"].clone();
+    assert_eq!(data["is_valid"], false);
+    assert_eq!(data["status"], "hash_mismatch");
+
+    // An ended warranty doesn't make the item any less authentic
+    client.set_warranty("CERT-001", 1);
+    let response = app.clone().oneshot(verify("CERT-001", METADATA_CID)).await.unwrap();
+    let data = json_body(response).await["data"].clone();
+    assert_eq!(data["is_valid"], true);
+    assert_eq!(data["status"], "expired");
+
+    client.set_status("CERT-001", "Revoked");
+    let response = app.clone().oneshot(verify("CERT-001", METADATA_CID)).await.unwrap();
+    let data = json_body(response).await["data"].clone();
+    assert_eq!(data["is_valid"], false);
+    assert_eq!(data["status"], "revoked");
+    assert_eq!(data["reason"], "This certificate has been revoked");
+
+    let response = app.oneshot(verify("CERT-404", METADATA_CID)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let data = json_body(response).await["data"].clone();
+    assert_eq!(data["is_valid"], false);
+    assert_eq!(data["status"], "unknown_certificate");
+    assert!(data["brand"].is_null());
+}
+
+#[tokio::test]
+async fn test_verify_speaks_accept_language() {
+    let client = MockContractClient::initialized(MOCK_OWNER)
+        .with_certificate("CERT-001", METADATA_CID, MOCK_OWNER);
+    let app = create_mock_app(client).await;
+
+    let verify = |accept_language: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/certificates/CERT-001/verify")
+            .header("content-type", "application/json")
+            .header("accept-language", accept_language)
+            .body(Body::from(json!({ "metadata_hash": METADATA_CID }).to_string()))
+            .unwrap()
+    };
+
+    let response = app.clone().oneshot(verify("fr-CH, fr;q=0.9, en;q=0.8")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-language"], "fr");
+    let body_json = json_body(response).await;
+    assert_eq!(body_json["message"], "Vérification du certificat réussie");
+    assert_eq!(body_json["data"]["reason"], "Certificat VeriLuxe authentique");
+    assert_eq!(body_json["data"]["status"], "valid");
+
+    // Languages without translations fall back to English
+    let response = app.oneshot(verify("pt-BR")).await.unwrap();
+    assert_eq!(response.headers()["content-language"], "en");
+    let body_json = json_body(response).await;
+    assert_eq!(body_json["message"], "Certificate verification successful");
+    assert_eq!(body_json["data"]["reason"], "Authentic VeriLuxe certificate");
+}
+
+#[tokio::test]
+async fn test_get_unknown_certificate() {
+    let app = create_mock_app(MockContractClient::initialized(MOCK_OWNER)).await;
+
+    let request = Request::builder()
+        .uri("/certificates/CERT-404")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let body_json = json_body(response).await;
+    assert_eq!(body_json["success"], false);
+    assert_eq!(body_json["error_code"], "CERT_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn test_get_certificate_field_selection() {
+    let client = MockContractClient::initialized(MOCK_OWNER).with_certificate(
+        "CERT-001",
+        "QmHash123",
+        MOCK_OWNER,
+    );
+    let app = create_mock_app(client).await;
+
+    let request = Request::builder()
+        .uri("/certificates/CERT-001?fields=owner,is_valid")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body_json = json_body(response).await;
+    assert_eq!(body_json["data"], json!({ "owner": MOCK_OWNER, "is_valid": true }));
+
+    // Unknown fields are rejected rather than silently dropped
+    let request = Request::builder()
+        .uri("/certificates/CERT-001?fields=owner,price")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = Request::builder()
+        .uri("/certificates?fields=owner,is_valid")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_contract_error_mapping() {
+    let client = MockContractClient::initialized(MOCK_OWNER).with_certificate(
+        "CERT-001",
+        "QmHash123",
+        MOCK_OWNER,
+    );
+    let app = create_mock_app(client.clone()).await;
+
+    let issue = || {
+        Request::builder()
+            .method("POST")
+            .uri("/certificates")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({
+                    "cert_id": "CERT-001",
+                    "metadata_hash": METADATA_CID,
+                    "owner_address": MOCK_OWNER
+                })
+                .to_string(),
+            ))
+            .unwrap()
+    };
+
+    let response = app.clone().oneshot(issue()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(json_body(response).await["error_code"], "DUPLICATE_CERT_ID");
+
+    // Typed contract errors are mapped by code, wherever they appear in the message
+    client.fail_with("Simulation failed: HostError: Error(Contract, #5)");
+    let response = app.clone().oneshot(issue()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(json_body(response).await["error_code"], "FORBIDDEN");
+
+    client.fail_with("Simulation failed: HostError: Error(Contract, #6)");
+    let response = app.clone().oneshot(issue()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::GONE);
+    assert_eq!(json_body(response).await["error_code"], "CERT_REVOKED");
+
+    client.fail_with("HostError: Error(WasmVm, InvalidAction): Contract is paused");
+    let response = app.clone().oneshot(issue()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(json_body(response).await["error_code"], "CONTRACT_PAUSED");
+
+    // Uninitialized contracts are reported as such
+    let app = create_mock_app(MockContractClient::new()).await;
+    let response = app.oneshot(issue()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(json_body(response).await["error_code"], "CONTRACT_NOT_INITIALIZED");
+}
+
+#[tokio::test]
+async fn test_dev_fund_unavailable() {
+    // The test app has no friendbot, as on mainnet
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/dev/fund")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({}).to_string()))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let body_json = json_body(response).await;
+    assert_eq!(body_json["success"], false);
+    assert!(body_json["error"].as_str().unwrap().contains("testnet"));
+}
+
+#[tokio::test]
+async fn test_dev_utilities_unavailable() {
+    // A friendbot alone, as on testnet, doesn't enable the utilities
+    let mut state = test_state(AuthSettings::default(), None, ReplaySettings::default(), Vec::new())
+        .await
+        .expect("Failed to create test state");
+    state.friendbot = Some(Friendbot::new(
+        "http://localhost:8000/friendbot".to_string(),
+        reqwest::Client::new(),
+    ));
+    let app = create_router(state.clone());
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/dev/keypairs")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = Request::builder()
+        .uri(format!("/dev/strkey/convert?value={}", MOCK_OWNER))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // Enabled with DEV_TOOLS_ENABLED or in sandbox mode
+    state.dev_tools = true;
+    let app = create_router(state);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/dev/keypairs")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .uri(format!("/dev/strkey/convert?value={}", MOCK_OWNER))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_account_lookup_validation() {
+    let app = create_test_app().await.expect("Failed to create test app");
+
+    let request = Request::builder()
+        .uri("/accounts/not-an-address")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(json_body(response).await["field"], "address");
+
+    // The test app has no Horizon configured
+    let request = Request::builder()
+        .uri(format!("/accounts/{}", MOCK_OWNER))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn test_mnemonic_admin_signer() {
+    // First test vector of SEP-5
+    let mnemonic = "illness spike retreat truth genius clock brain pass fit cave bargain toe";
+    let settings = SignerSettings {
+        secret_key: mnemonic.to_string(),
+        ..SignerSettings::default()
+    };
+    let signer = load_signer(&settings).unwrap();
+    assert_eq!(
+        signer.address(),
+        "GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6"
+    );
+
+    // Other accounts of the same mnemonic
+    let settings = SignerSettings {
+        account_index: 1,
+        ..settings
+    };
+    assert_ne!(
+        load_signer(&settings).unwrap().address(),
+        "GDRXE2BQUC3AZNPVFSCEZ76NJ3WWL25FYFK6RGZGIEKWE4SOOHSUJUJ6"
+    );
+
+    // Words outside the BIP-39 list
+    let settings = SignerSettings {
+        secret_key: mnemonic.replace("toe", "veriluxe"),
+        ..SignerSettings::default()
+    };
+    assert!(load_signer(&settings).is_err());
+}
@@ -10,12 +10,164 @@
 //! - Verify authenticity 
 //! - Transfer ownership
 //! - Revoke certificates (admin only)
+//! - Delegated transfers through per-certificate and operator approvals
+//! - Transfer history with optional sale price and memo
+//! - Burn certificates whose physical item was destroyed (owner only)
+//! - In-place wasm upgrades (admin only)
+//! - Explicit TTL management so certificates don't expire from the ledger
+//! - NFT-style interface (`balance_of`, `owner_of`, `token_uri`) for wallets and marketplaces
+//! - Brand royalties paid in a token on priced secondary transfers
+//! - Escrowed sales settled atomically against a token payment
+//! - Owner-initiated lock freezing a certificate against transfer
+//! - M-of-N multisig proposals for destructive admin actions
+//! - Merkle-root batch registration with on-demand claims
+//! - On-chain sha256 check of raw metadata against the stored hash
+//! - NFC/RFID tag binding with tag-to-certificate lookup
+//! - Parent/child sets whose children can follow the parent on transfer
+//! - Collections grouping certificates by brand product line and season
+//! - Repair and service records added by authorized service centers
+//! - Valuation history appended by licensed appraisers
+//! - Warranty expiry tracked separately from authenticity
+//! - Per-issue fees paid in a token to the registry treasury
+//! - Compliance mode restricting transfers to allowlisted recipients
+//! - Reverse lookup from metadata hash to certificate ID
+//! - Metadata hash uniqueness enforced at issuance
+//! - Registered issuers with reserved certificate ID prefixes
+//! - Certificate ID format validation with typed errors
+//! - Re-initialization guard and storage schema migration
+//! - Owner-opened disputes that freeze a certificate until the admin rules
+//! - Structured item attributes with lookup by brand and serial number
+//! - Admin-tunable operational configuration (batch size, fees, TTLs, cooldown)
+//! - Claimable certificates redeemed with a printed claim code
+//! - Issuer deactivation that can suspend the issuer's certificates pending review
+//! - Transfer count and last transfer ledger exposed as velocity signals
+//! - Point-in-time validity queries backed by a status change log
+//! - Typed document attachments (photos, receipt, reports) per certificate
+//! - Limited-edition numbering with a capped per-collection counter
+//! - Pluggable policy contract consulted before issuance and transfers
+//! - Consensual transfers that require the recipient's signature
+//! - Lifecycle events (issued, transfer, revoked, burned, disputed, attrs) for off-chain indexers
 
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Env, Map, String, Symbol};
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractimpl, contracttype, panic_with_error,
+    symbol_short, token,
+    xdr::ToXdr, Address, Bytes, BytesN, Env, IntoVal, Map, String, Symbol, Val, Vec,
+};
 
 // Storage keys for persistent data
 const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
 const CERTS_KEY: Symbol = symbol_short!("CERTS");
+const APPROVALS_KEY: Symbol = symbol_short!("APPROVALS");
+const OPERATORS_KEY: Symbol = symbol_short!("OPERATORS");
+const HISTORY_KEY: Symbol = symbol_short!("HISTORY");
+const BURNED_KEY: Symbol = symbol_short!("BURNED");
+const SCHEMA_KEY: Symbol = symbol_short!("SCHEMA");
+const BALANCE_KEY: Symbol = symbol_short!("BALANCE");
+const ROYALTY_KEY: Symbol = symbol_short!("ROYALTY");
+const LISTING_KEY: Symbol = symbol_short!("LISTING");
+const LOCKED_KEY: Symbol = symbol_short!("LOCKED");
+const PAUSED_KEY: Symbol = symbol_short!("PAUSED");
+const MULTISIG_KEY: Symbol = symbol_short!("MULTISIG");
+const PROPOSAL_KEY: Symbol = symbol_short!("PROPOSAL");
+const PROP_SEQ_KEY: Symbol = symbol_short!("PROP_SEQ");
+const ROOT_KEY: Symbol = symbol_short!("ROOT");
+const ROOT_LEFT_KEY: Symbol = symbol_short!("ROOT_LEFT");
+const TAG_KEY: Symbol = symbol_short!("TAG");
+const TAG_INDEX_KEY: Symbol = symbol_short!("TAG_IDX");
+const CHILDREN_KEY: Symbol = symbol_short!("CHILDREN");
+const PARENT_KEY: Symbol = symbol_short!("PARENT");
+const BUNDLE_KEY: Symbol = symbol_short!("BUNDLE");
+const COLLECTION_KEY: Symbol = symbol_short!("COLL");
+const COLL_SEQ_KEY: Symbol = symbol_short!("COLL_SEQ");
+const COLL_ISSUER_KEY: Symbol = symbol_short!("COLL_ISS");
+const COLL_SIZE_KEY: Symbol = symbol_short!("COLL_SIZE");
+const COLL_ITEM_KEY: Symbol = symbol_short!("COLL_ITEM");
+const CERT_COLL_KEY: Symbol = symbol_short!("CERT_COLL");
+const ROLE_KEY: Symbol = symbol_short!("ROLE");
+const SERVICE_KEY: Symbol = symbol_short!("SERVICE");
+const APPRAISAL_KEY: Symbol = symbol_short!("APPRAISAL");
+const WARRANTY_KEY: Symbol = symbol_short!("WARRANTY");
+const FEE_KEY: Symbol = symbol_short!("FEE");
+const KYC_MODE_KEY: Symbol = symbol_short!("KYC_MODE");
+const ALLOWLIST_KEY: Symbol = symbol_short!("ALLOWLIST");
+const HASH_INDEX_KEY: Symbol = symbol_short!("HASH_IDX");
+const ISSUER_KEY: Symbol = symbol_short!("ISSUER");
+const PREFIX_KEY: Symbol = symbol_short!("PREFIX");
+const PREFIX_STEM_KEY: Symbol = symbol_short!("PFX_STEM");
+const PREFIX_LENS_KEY: Symbol = symbol_short!("PFX_LENS");
+const DISPUTE_KEY: Symbol = symbol_short!("DISPUTE");
+const ATTRS_KEY: Symbol = symbol_short!("ATTRS");
+const SERIAL_KEY: Symbol = symbol_short!("SERIAL");
+const CONFIG_KEY: Symbol = symbol_short!("CONFIG");
+const CLAIM_KEY: Symbol = symbol_short!("CLAIM");
+const ISSUER_STATUS_KEY: Symbol = symbol_short!("ISS_STAT");
+const CLEARED_KEY: Symbol = symbol_short!("CLEARED");
+const XFER_STATS_KEY: Symbol = symbol_short!("XFER_STAT");
+const STATUS_LOG_KEY: Symbol = symbol_short!("STAT_LOG");
+const ATTACH_KEY: Symbol = symbol_short!("ATTACH");
+const EDITION_KEY: Symbol = symbol_short!("EDITION");
+const MINTED_KEY: Symbol = symbol_short!("MINTED");
+const CERT_EDITION_KEY: Symbol = symbol_short!("CERT_ED");
+const POLICY_KEY: Symbol = symbol_short!("POLICY");
+
+// Event topics, each published with the certificate ID as the second topic
+const ISSUED_EVENT: Symbol = symbol_short!("issued");
+const TRANSFER_EVENT: Symbol = symbol_short!("transfer");
+const REVOKED_EVENT: Symbol = symbol_short!("revoked");
+const BURNED_EVENT: Symbol = symbol_short!("burned");
+const DISPUTED_EVENT: Symbol = symbol_short!("disputed");
+const ATTRIBUTES_EVENT: Symbol = symbol_short!("attrs");
+
+/// Layout version of the data this wasm writes to storage
+/// 
+/// * 1 - all certificates in a single instance-storage map under `CERTS`
+/// * 2 - one persistent entry per certificate keyed by `(CERTS, cert_id)`
+const STORAGE_VERSION: u32 = 2;
+
+/// Crate version baked into the wasm at build time
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Default ledgers a touched entry is kept alive for (~30 days at 5s per ledger)
+const TTL_EXTEND_TO: u32 = 518_400;
+/// Default TTL below which entries are bumped (~7 days)
+const TTL_THRESHOLD: u32 = 120_960;
+
+/// Collection name and symbol reported through the NFT-style interface
+const TOKEN_NAME: &str = "VeriLuxe Certificate";
+const TOKEN_SYMBOL: &str = "VLUX";
+/// Prefix turning a metadata hash (IPFS CID) into a token URI
+const TOKEN_URI_PREFIX: &[u8] = b"ipfs://";
+/// Longest token URI `token_uri` can assemble
+const MAX_TOKEN_URI_LEN: usize = 256;
+/// Basis points in 100%
+const MAX_ROYALTY_BPS: u32 = 10_000;
+/// Default largest page or batch a single call processes
+const MAX_PAGE_SIZE: u32 = 100;
+/// Shortest certificate ID accepted at issuance
+const MIN_CERT_ID_LEN: u32 = 3;
+/// Longest certificate ID accepted at issuance
+const MAX_CERT_ID_LEN: usize = 64;
+/// Length of a hex-encoded sha256 digest
+const SHA256_HEX_LEN: usize = 64;
+
+/// Typed errors returned to callers
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    /// Certificate ID is shorter than 3 or longer than 64 bytes
+    InvalidCertIdLength = 1,
+    /// Certificate ID contains whitespace or a character outside `[A-Za-z0-9._-]`
+    InvalidCertIdChar = 2,
+    /// No certificate was issued under this ID
+    CertificateNotFound = 3,
+    /// A certificate was already issued under this ID
+    CertificateExists = 4,
+    /// Caller isn't approved to transfer the certificate or isn't its issuer
+    NotAuthorized = 5,
+    /// The certificate was revoked and can no longer change hands
+    CertificateRevoked = 6,
+}
 
 /// Certificate structure containing all authenticity data
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -29,6 +181,376 @@ pub struct Certificate {
     pub is_valid: bool,
 }
 
+/// Certificate data plus transfer velocity, returned by `get_certificate_details`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct CertificateDetails {
+    /// Current owner of the certificate
+    pub owner: Address,
+    /// Hash of the item's metadata (usually IPFS hash)
+    pub metadata_hash: String,
+    /// Whether the certificate is currently valid
+    pub is_valid: bool,
+    /// Number of times the certificate changed hands
+    pub transfer_count: u32,
+    /// Ledger of the latest transfer (0 if never transferred)
+    pub last_transfer_ledger: u32,
+}
+
+/// A single ownership change recorded in a certificate's history
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TransferRecord {
+    /// Owner before the transfer
+    pub from: Address,
+    /// Owner after the transfer
+    pub to: Address,
+    /// Sale price in the currency's smallest unit, if the transfer was a sale
+    pub price: Option<i128>,
+    /// Currency code of the sale price (e.g. `USD`, `XLM`)
+    pub currency: Option<Symbol>,
+    /// Free-form note attached by the seller
+    pub memo: Option<String>,
+    /// Token contract the price was paid in, if it was settled on-chain
+    pub token: Option<Address>,
+    /// Ledger sequence in which the transfer happened
+    pub ledger: u32,
+    /// Ledger close time of the transfer
+    pub timestamp: u64,
+}
+
+/// Royalty owed to the brand on priced secondary transfers
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RoyaltyConfig {
+    /// Share of the sale price in basis points (100 = 1%)
+    pub bps: u32,
+    /// Address receiving the royalty
+    pub recipient: Address,
+    /// Token contract sales are settled in
+    pub token: Address,
+    /// Currency code sales must be quoted in to settle with `token`
+    pub currency: Symbol,
+}
+
+/// Fee charged to the issuer for every certificate issued
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct IssuanceFee {
+    /// Token contract the fee is paid in
+    pub token: Address,
+    /// Fee per certificate in the token's smallest unit
+    pub amount: i128,
+    /// Address collecting the fees
+    pub treasury: Address,
+}
+
+/// A certificate held in escrow by the contract while it is for sale
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Listing {
+    /// Owner who listed the certificate and receives the payment
+    pub seller: Address,
+    /// Asking price in the token's smallest unit
+    pub price: i128,
+    /// Token contract the buyer pays in
+    pub token: Address,
+}
+
+/// Admin signers and the number of approvals a proposal needs
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct MultisigConfig {
+    /// Addresses allowed to propose and approve admin actions
+    pub signers: Vec<Address>,
+    /// Approvals required before an action executes
+    pub threshold: u32,
+}
+
+/// Destructive admin action that needs a quorum once multisig is enabled
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum AdminAction {
+    /// Revoke a certificate
+    Revoke(String),
+    /// Halt issuance and transfers
+    Pause,
+    /// Resume issuance and transfers
+    Unpause,
+    /// Switch to an uploaded wasm build
+    Upgrade(BytesN<32>),
+    /// Hand the admin role to another address
+    SetAdmin(Address),
+    /// Replace the signer set and threshold (an empty set disables multisig)
+    SetMultisig(Vec<Address>, u32),
+    /// Rule on an open dispute: certificate ID, outcome and ruling hash
+    ResolveDispute(String, DisputeOutcome, String),
+    /// Register or remove the transfer policy contract
+    SetPolicy(Option<Address>),
+    /// Turn compliance mode on or off
+    SetAllowlistMode(bool),
+    /// Take an address off the allowlist
+    RemoveFromAllowlist(Address),
+    /// Stop an issuer, suspending its certificates if set
+    DeactivateIssuer(Address, bool),
+    /// Replace the operational parameters
+    SetConfig(Config),
+    /// Bring storage up to this build's schema
+    Migrate,
+}
+
+/// Ruling on a disputed certificate
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum DisputeOutcome {
+    /// The certificate stands and can be transferred again
+    Valid,
+    /// The certificate is revoked
+    Revoked,
+}
+
+/// A dispute raised by a certificate's owner
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Dispute {
+    /// Owner who opened the dispute
+    pub opened_by: Address,
+    /// IPFS hash of the owner's statement and evidence
+    pub reason_hash: String,
+    /// Ledger close time the dispute was opened at
+    pub opened_at: u64,
+    /// Whether the dispute is still open or how it was ruled
+    pub status: DisputeStatus,
+}
+
+/// Progress of a dispute
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum DisputeStatus {
+    /// Awaiting the admin's ruling; transfers are blocked
+    Open,
+    /// Ruled on by the admin
+    Resolved(Ruling),
+}
+
+/// The admin's decision on a dispute
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Ruling {
+    /// Whether the certificate stands or is revoked
+    pub outcome: DisputeOutcome,
+    /// IPFS hash of the ruling document
+    pub ruling_hash: String,
+    /// Ledger close time the dispute was resolved at
+    pub resolved_at: u64,
+}
+
+/// Lifecycle state of a certificate, reported by `get_status`
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum CertStatus {
+    /// Authentic and transferable
+    Valid,
+    /// Frozen by an open dispute
+    Disputed,
+    /// Frozen because its issuer was deactivated, pending review
+    Suspended,
+    /// Revoked by the admin
+    Revoked,
+}
+
+/// A pending or executed multisig proposal
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Proposal {
+    /// Action to run once the threshold is met
+    pub action: AdminAction,
+    /// Signers that approved so far; only those still in the signer set count
+    pub approvals: Vec<Address>,
+    /// Whether the action already ran
+    pub executed: bool,
+}
+
+/// Optional data attached to a certificate at issuance
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[contracttype]
+pub struct IssueOptions {
+    /// ID of the NFC/RFID chip embedded in the item
+    pub tag_id: Option<BytesN<32>>,
+    /// Collection the item belongs to
+    pub collection_id: Option<u32>,
+    /// When the brand's warranty ends (unix seconds)
+    pub warranty_expires_at: Option<u64>,
+    /// Allow a metadata hash that another certificate already uses
+    pub allow_duplicate_hash: bool,
+}
+
+/// One certificate in an `issue_batch` call
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct BatchIssue {
+    pub cert_id: String,
+    pub metadata_hash: String,
+    pub owner: Address,
+}
+
+/// A brand or partner allowed to issue certificates in its own ID range
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Issuer {
+    /// Display name of the brand
+    pub name: String,
+    /// Prefix every certificate ID from this issuer must start with
+    pub prefix: String,
+}
+
+/// Standing of a deactivated issuer
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum IssuerStatus {
+    /// Can issue certificates
+    Active,
+    /// Can no longer issue; existing certificates are unaffected
+    Deactivated,
+    /// Can no longer issue, and its certificates are suspended pending review
+    Suspended,
+}
+
+/// Structured description of the certified item
+/// 
+/// Stored next to the certificate rather than inside it so certificates
+/// written by earlier builds keep decoding.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ItemAttributes {
+    /// Brand that made the item
+    pub brand: String,
+    /// Model or line name
+    pub model: String,
+    /// Manufacturer serial number
+    pub serial_number: String,
+    /// Main material (e.g. `Calfskin`)
+    pub material: String,
+    /// Year of manufacture
+    pub manufacture_year: u32,
+}
+
+/// Warranty standing of a certificate, reported by `warranty_status`
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum WarrantyStatus {
+    /// Authentic and covered by warranty
+    Active,
+    /// Authentic but the warranty has ended
+    Expired,
+    /// Authentic and issued without a warranty
+    NoWarranty,
+    /// The certificate was revoked, so neither guarantee holds
+    Revoked,
+}
+
+/// Position of a certificate within a limited edition ("No. 37 of 200")
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Edition {
+    /// Number of this item within the edition, starting at 1
+    pub edition_number: u32,
+    /// Declared size of the edition
+    pub edition_total: u32,
+}
+
+/// Third-party role the admin can grant to an address
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum Role {
+    /// Repair shop allowed to add service records
+    ServiceCenter,
+    /// Licensed appraiser allowed to add valuations
+    Appraiser,
+}
+
+/// A valuation of the item by a licensed appraiser
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Appraisal {
+    /// Appraiser who valued the item
+    pub appraiser: Address,
+    /// Appraised value in the currency's smallest unit
+    pub value: i128,
+    /// Currency code of the value (e.g. `USD`)
+    pub currency: Symbol,
+    /// When the item was appraised (unix seconds)
+    pub appraised_at: u64,
+    /// IPFS hash of the appraisal report
+    pub report_hash: String,
+}
+
+/// A repair or service performed on the item
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ServiceRecord {
+    /// Service center that did the work
+    pub center: Address,
+    /// Kind of service (e.g. `OVERHAUL`, `REPAIR`)
+    pub service_type: Symbol,
+    /// IPFS hash of the service report
+    pub report_hash: String,
+    /// Ledger sequence the record was added in
+    pub ledger: u32,
+    /// Ledger close time the record was added at
+    pub timestamp: u64,
+}
+
+/// A brand product line certificates can be grouped under
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Collection {
+    /// Brand running the product line
+    pub brand: String,
+    /// Season or year of the line (e.g. `FW24`)
+    pub season: String,
+    /// Free-form description
+    pub description: String,
+}
+
+/// Operational parameters the admin can tune without a wasm upgrade
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Config {
+    /// Largest page or batch a single call processes
+    pub max_batch_size: u32,
+    /// Fee per issued certificate, paid in the token set by `set_issuance_fee`
+    pub issuance_fee: i128,
+    /// Remaining TTL (ledgers) below which touched entries are bumped
+    pub ttl_threshold: u32,
+    /// TTL (ledgers) touched entries are bumped to
+    pub ttl_extend_to: u32,
+    /// Seconds a certificate must wait between transfers (0 = no cooldown)
+    pub transfer_cooldown: u64,
+}
+
+/// Build information reported by `get_version`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct VersionInfo {
+    /// Semver of the contract crate this wasm was built from
+    pub version: String,
+    /// Storage layout version this wasm reads and writes
+    pub storage_version: u32,
+}
+
+/// Interface an external compliance policy contract must implement
+/// 
+/// Each hook returns `true` to allow the operation; returning `false` or
+/// panicking rejects it.
+#[contractclient(name = "PolicyClient")]
+pub trait Policy {
+    /// Called before a certificate is issued to `owner`
+    fn check_issue(env: Env, cert_id: String, owner: Address) -> bool;
+    /// Called before a certificate moves from `from` to `to`
+    fn check_transfer(env: Env, cert_id: String, from: Address, to: Address) -> bool;
+}
+
 /// Main contract for fashion authenticity certificates
 #[contract]
 pub struct FashionAuthContract;
@@ -45,15 +567,19 @@ impl FashionAuthContract {
     /// # Panics
     /// * If admin authentication fails
     pub fn init(env: Env, admin: Address) {
+        if env.storage().instance().has(&ADMIN_KEY) {
+            panic!("Contract already initialized");
+        }
+
         // Require authentication from the admin
         admin.require_auth();
         
         // Store the admin address in persistent storage
         env.storage().instance().set(&ADMIN_KEY, &admin);
-        
-        // Initialize empty certificates map
-        let certs: Map<String, Certificate> = Map::new(&env);
-        env.storage().instance().set(&CERTS_KEY, &certs);
+
+        // Record the storage layout so later upgrades know what they're reading
+        env.storage().instance().set(&SCHEMA_KEY, &STORAGE_VERSION);
+        bump_instance(&env);
     }
 
     /// Issue a new authenticity certificate (admin only)
@@ -67,6 +593,8 @@ impl FashionAuthContract {
     /// # Panics
     /// * If called by non-admin
     /// * If certificate ID already exists
+    /// * If another certificate already uses the metadata hash
+    /// * If certificate ID was burned
     /// * If contract is not initialized
     pub fn issue_certificate(
         env: Env,
@@ -79,219 +607,4698 @@ impl FashionAuthContract {
             .expect("Contract not initialized");
         admin.require_auth();
 
-        // Get existing certificates map
-        let mut certs: Map<String, Certificate> = env.storage().instance()
-            .get(&CERTS_KEY)
-            .unwrap_or(Map::new(&env));
-
-        // Prevent duplicate certificate IDs
-        if certs.contains_key(cert_id.clone()) {
-            panic!("Certificate already exists");
-        }
+        create_cert(&env, &admin, &cert_id, metadata_hash, owner, false);
+        charge_issuance_fee(&env, &admin);
+    }
 
-        // Create new certificate with valid status
-        let certificate = Certificate {
-            owner: owner.clone(),
-            metadata_hash: metadata_hash.clone(),
-            is_valid: true,
-        };
+    /// Issue a certificate with optional issuance data (admin only)
+    /// 
+    /// Set `options.allow_duplicate_hash` to knowingly reuse a metadata hash
+    /// that is already registered.
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Unique certificate identifier
+    /// * `metadata_hash` - IPFS hash of certificate metadata
+    /// * `owner` - Initial owner of the certificate
+    /// * `options` - Optional data such as the item's NFC/RFID tag
+    /// 
+    /// # Panics
+    /// * Under the same conditions as `issue_certificate`
+    /// * If the tag is already bound to another certificate
+    /// * If `options.collection_id` belongs to an issuer
+    pub fn issue_with_options(
+        env: Env,
+        cert_id: String,
+        metadata_hash: String,
+        owner: Address,
+        options: IssueOptions,
+    ) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
 
-        // Store certificate and update persistent storage
-        certs.set(cert_id, certificate);
-        env.storage().instance().set(&CERTS_KEY, &certs);
+        create_cert(&env, &admin, &cert_id, metadata_hash, owner, options.allow_duplicate_hash);
+        charge_issuance_fee(&env, &admin);
+        apply_issue_options(&env, &admin, &cert_id, options);
     }
 
-    /// Verify a certificate by ID and metadata hash
+    /// Issue several certificates in one transaction (admin only)
+    /// 
+    /// The batch is atomic: if any entry can't be issued, none are.
     /// 
     /// # Arguments
     /// * `env` - Soroban environment
-    /// * `cert_id` - Certificate identifier to verify
-    /// * `metadata_hash` - Expected metadata hash
+    /// * `entries` - Certificates to issue, at most the configured batch size
     /// 
-    /// # Returns
-    /// * `true` if certificate exists, is valid, and metadata hash matches
-    /// * `false` otherwise
-    pub fn verify(env: Env, cert_id: String, metadata_hash: String) -> bool {
-        // Get certificates map
-        let certs: Map<String, Certificate> = env.storage().instance()
-            .get(&CERTS_KEY)
-            .unwrap_or(Map::new(&env));
+    /// # Panics
+    /// * If the batch is empty or larger than the configured batch size
+    /// * Under the same conditions as `issue_certificate`, for any entry
+    pub fn issue_batch(env: Env, entries: Vec<BatchIssue>) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
 
-        // Check if certificate exists and verify conditions
-        if let Some(certificate) = certs.get(cert_id) {
-            // Must be valid AND metadata hash must match
-            certificate.is_valid && certificate.metadata_hash == metadata_hash
-        } else {
-            false
+        if entries.is_empty() || entries.len() > read_config(&env).max_batch_size {
+            panic!("Invalid batch size");
+        }
+
+        for entry in entries.iter() {
+            create_cert(&env, &admin, &entry.cert_id, entry.metadata_hash, entry.owner, false);
+            charge_issuance_fee(&env, &admin);
         }
     }
 
-    /// Get complete certificate details by ID
+    /// Issue a certificate in a registered issuer's own ID range
     /// 
     /// # Arguments
     /// * `env` - Soroban environment
-    /// * `cert_id` - Certificate identifier
-    /// 
-    /// # Returns
-    /// * Complete Certificate struct
+    /// * `issuer` - Registered issuer creating the certificate
+    /// * `cert_id` - Unique identifier starting with the issuer's prefix
+    /// * `metadata_hash` - IPFS hash of certificate metadata
+    /// * `owner` - Initial owner of the certificate
+    /// * `options` - Optional issuance data
     /// 
     /// # Panics
-    /// * If certificate doesn't exist
-    pub fn get_certificate_details(env: Env, cert_id: String) -> Certificate {
-        // Get certificates map
-        let certs: Map<String, Certificate> = env.storage().instance()
-            .get(&CERTS_KEY)
-            .unwrap_or(Map::new(&env));
+    /// * If `issuer` is not registered
+    /// * If `cert_id` doesn't start with the issuer's prefix
+    /// * If `options.collection_id` belongs to another issuer
+    /// * Under the same conditions as `issue_with_options`
+    pub fn issue_as_issuer(
+        env: Env,
+        issuer: Address,
+        cert_id: String,
+        metadata_hash: String,
+        owner: Address,
+        options: IssueOptions,
+    ) {
+        issuer.require_auth();
+        let info = require_active_issuer(&env, &issuer);
+        if !has_prefix(&cert_id, &info.prefix) {
+            panic!("Certificate ID outside issuer namespace");
+        }
 
-        // Return certificate or panic if not found
-        certs.get(cert_id).expect("Certificate not found")
+        create_cert(&env, &issuer, &cert_id, metadata_hash, owner, options.allow_duplicate_hash);
+        charge_issuance_fee(&env, &issuer);
+        apply_issue_options(&env, &issuer, &cert_id, options);
     }
 
-    /// Transfer certificate ownership (current owner only)
+    /// Register an issuer and reserve its certificate ID prefix (admin only)
+    /// 
+    /// Once reserved, IDs starting with the prefix can only be issued by
+    /// that issuer, including by the admin.
     /// 
     /// # Arguments
     /// * `env` - Soroban environment
-    /// * `cert_id` - Certificate to transfer
-    /// * `new_owner` - Address of the new owner
+    /// * `issuer` - Address of the brand or partner
+    /// * `name` - Display name of the brand
+    /// * `prefix` - ID prefix reserved for the issuer (e.g. `HERMES-`)
     /// 
     /// # Panics
-    /// * If called by non-owner
-    /// * If certificate doesn't exist
-    /// * If certificate is invalid/revoked
-    pub fn transfer(env: Env, cert_id: String, new_owner: Address) {
-        // Get certificates map
-        let mut certs: Map<String, Certificate> = env.storage().instance()
-            .get(&CERTS_KEY)
-            .unwrap_or(Map::new(&env));
-
-        // Get existing certificate
-        let mut certificate = certs.get(cert_id.clone())
-            .expect("Certificate not found");
+    /// * If called by non-admin
+    /// * If the issuer is already registered
+    /// * If the prefix is empty, longer than a certificate ID, or overlaps another issuer's prefix
+    pub fn register_issuer(env: Env, issuer: Address, name: String, prefix: String) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
 
-        // Require authentication from current owner
-        certificate.owner.require_auth();
+        let key = (ISSUER_KEY, issuer.clone());
+        if env.storage().persistent().has(&key) {
+            panic!("Issuer already registered");
+        }
+        if prefix.is_empty() || prefix.len() as usize > MAX_CERT_ID_LEN {
+            panic!("Invalid issuer prefix length");
+        }
 
-        // Prevent transfer of invalid certificates
-        if !certificate.is_valid {
-            panic!("Cannot transfer invalid certificate");
+        // Neither prefix may contain the other, or the ranges would overlap
+        if prefix_owner(&env, &prefix).is_some()
+            || env.storage().persistent().has(&(PREFIX_STEM_KEY, prefix.clone()))
+        {
+            panic!("Prefix overlaps an existing issuer");
         }
+        reserve_prefix(&env, &issuer, &prefix);
 
-        // Update ownership
-        certificate.owner = new_owner;
+        env.storage().persistent().set(&key, &Issuer { name, prefix });
+        bump_persistent(&env, &key);
+    }
 
-        // Save updated certificate
-        certs.set(cert_id, certificate);
-        env.storage().instance().set(&CERTS_KEY, &certs);
+    /// Get a registered issuer
+    pub fn get_issuer(env: Env, issuer: Address) -> Option<Issuer> {
+        env.storage().persistent().get(&(ISSUER_KEY, issuer))
     }
 
-    /// Revoke a certificate (admin only)
+    /// Stop an issuer from issuing, optionally suspending its certificates (admin only)
+    /// 
+    /// Suspension is applied lazily: every certificate in the issuer's prefix
+    /// reads as `Suspended` (failing verification and blocking transfers)
+    /// until the admin clears it with `clear_suspension` or reactivates the
+    /// issuer, so no per-certificate writes are needed.
     /// 
     /// # Arguments
     /// * `env` - Soroban environment
-    /// * `cert_id` - Certificate to revoke
+    /// * `issuer` - Registered issuer to deactivate
+    /// * `suspend_certificates` - Whether its existing certificates are suspended
     /// 
     /// # Panics
     /// * If called by non-admin
-    /// * If certificate doesn't exist
-    /// * If contract is not initialized
-    pub fn revoke(env: Env, cert_id: String) {
-        // Get admin address and require authentication
+    /// * If suspending while multisig is enabled (use `propose` instead)
+    /// * If the issuer is not registered
+    pub fn deactivate_issuer(env: Env, issuer: Address, suspend_certificates: bool) {
         let admin: Address = env.storage().instance().get(&ADMIN_KEY)
             .expect("Contract not initialized");
         admin.require_auth();
+        if suspend_certificates {
+            ensure_no_multisig(&env);
+        }
 
-        // Get certificates map
-        let mut certs: Map<String, Certificate> = env.storage().instance()
-            .get(&CERTS_KEY)
-            .unwrap_or(Map::new(&env));
+        execute_action(&env, AdminAction::DeactivateIssuer(issuer, suspend_certificates));
+    }
 
-        // Get existing certificate
-        let mut certificate = certs.get(cert_id.clone())
-            .expect("Certificate not found");
+    /// Let a deactivated issuer issue again and lift any suspension (admin only)
+    pub fn reactivate_issuer(env: Env, issuer: Address) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
 
-        // Mark certificate as invalid
-        certificate.is_valid = false;
+        env.storage().persistent().remove(&(ISSUER_STATUS_KEY, issuer));
+    }
 
-        // Save updated certificate
-        certs.set(cert_id, certificate);
-        env.storage().instance().set(&CERTS_KEY, &certs);
+    /// Get whether an issuer is active, deactivated or suspended
+    pub fn get_issuer_status(env: Env, issuer: Address) -> IssuerStatus {
+        issuer_status(&env, &issuer)
     }
 
-    /// Get the current admin address (utility function)
+    /// Clear a reviewed certificate from its issuer's suspension (admin only)
+    /// 
+    /// # Panics
+    /// * If called by non-admin
+    /// * If certificate doesn't exist
+    pub fn clear_suspension(env: Env, cert_id: String) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+        if !has_cert(&env, &cert_id) {
+            panic_with_error!(env, Error::CertificateNotFound);
+        }
+
+        let key = (CLEARED_KEY, cert_id);
+        env.storage().persistent().set(&key, &true);
+        bump_persistent(&env, &key);
+    }
+
+    /// Record the structured attributes of a certified item
     /// 
     /// # Arguments
     /// * `env` - Soroban environment
-    /// 
-    /// # Returns
-    /// * Admin address
+    /// * `caller` - Admin, or the issuer whose prefix the certificate ID falls under
+    /// * `cert_id` - Certificate to describe
+    /// * `attributes` - Brand, model, serial number, material and year
     /// 
     /// # Panics
-    /// * If contract is not initialized
-    pub fn get_admin(env: Env) -> Address {
-        env.storage().instance().get(&ADMIN_KEY)
-            .expect("Contract not initialized")
+    /// * If certificate doesn't exist
+    /// * If `caller` is neither the admin nor the certificate's issuer
+    /// * If another certificate already uses the brand and serial number
+    pub fn set_attributes(env: Env, caller: Address, cert_id: String, attributes: ItemAttributes) {
+        caller.require_auth();
+        require_cert_issuer(&env, &caller, &cert_id);
+        if !has_cert(&env, &cert_id) {
+            panic_with_error!(env, Error::CertificateNotFound);
+        }
+
+        let serial_key = (SERIAL_KEY, attributes.brand.clone(), attributes.serial_number.clone());
+        let existing: Option<String> = env.storage().persistent().get(&serial_key);
+        if existing.is_some_and(|id| id != cert_id) {
+            panic!("Serial number already registered");
+        }
+
+        clear_attributes(&env, &cert_id);
+        let attrs_key = (ATTRS_KEY, cert_id.clone());
+        env.storage().persistent().set(&attrs_key, &attributes);
+        env.storage().persistent().set(&serial_key, &cert_id);
+        bump_persistent(&env, &attrs_key);
+        bump_persistent(&env, &serial_key);
+        env.events().publish((ATTRIBUTES_EVENT, cert_id), attributes);
     }
 
-    /// Check if a certificate exists
+    /// Get the structured attributes of a certified item, if recorded
+    pub fn get_attributes(env: Env, cert_id: String) -> Option<ItemAttributes> {
+        env.storage().persistent().get(&(ATTRS_KEY, cert_id))
+    }
+
+    /// Attach a typed document hash to a certificate
     /// 
     /// # Arguments
     /// * `env` - Soroban environment
-    /// * `cert_id` - Certificate identifier to check
+    /// * `caller` - Admin, or the issuer whose prefix the certificate ID falls under
+    /// * `cert_id` - Certificate to attach to
+    /// * `kind` - Document type (e.g. `PHOTO`, `RECEIPT`, `AUTH_RPT`, `CARE`)
+    /// * `document_hash` - IPFS hash of the document
     /// 
-    /// # Returns
-    /// * `true` if certificate exists, `false` otherwise
-    pub fn certificate_exists(env: Env, cert_id: String) -> bool {
-        let certs: Map<String, Certificate> = env.storage().instance()
-            .get(&CERTS_KEY)
+    /// # Panics
+    /// * If certificate doesn't exist
+    /// * If `caller` is neither the admin nor the certificate's issuer
+    pub fn add_attachment(env: Env, caller: Address, cert_id: String, kind: Symbol, document_hash: String) {
+        caller.require_auth();
+        require_cert_issuer(&env, &caller, &cert_id);
+        if !has_cert(&env, &cert_id) {
+            panic_with_error!(env, Error::CertificateNotFound);
+        }
+
+        let key = (ATTACH_KEY, cert_id);
+        let mut attachments: Map<Symbol, Vec<String>> = env.storage().persistent()
+            .get(&key)
             .unwrap_or(Map::new(&env));
-        
-        certs.contains_key(cert_id)
+        let mut hashes = attachments.get(kind.clone()).unwrap_or(Vec::new(&env));
+        hashes.push_back(document_hash);
+        attachments.set(kind, hashes);
+        env.storage().persistent().set(&key, &attachments);
+        bump_persistent(&env, &key);
     }
-}
 
-/// Comprehensive test module
-#[cfg(test)]
-mod test {
-    use super::*;
-    use soroban_sdk::{testutils::Address as _, Address, Env};
+    /// Get the document hashes of one type attached to a certificate, oldest first
+    pub fn get_attachments(env: Env, cert_id: String, kind: Symbol) -> Vec<String> {
+        let attachments: Option<Map<Symbol, Vec<String>>> = env.storage().persistent()
+            .get(&(ATTACH_KEY, cert_id));
+        attachments
+            .and_then(|a| a.get(kind))
+            .unwrap_or(Vec::new(&env))
+    }
 
-    /// Test contract initialization and certificate issuance
-    #[test]
-    fn test_init_and_issue_certificate() {
-        let env = Env::default();
+    /// Get every document hash attached to a certificate, grouped by type
+    pub fn get_all_attachments(env: Env, cert_id: String) -> Map<Symbol, Vec<String>> {
+        env.storage().persistent()
+            .get(&(ATTACH_KEY, cert_id))
+            .unwrap_or(Map::new(&env))
+    }
+
+    /// Find the certificate for a brand's serial number
+    pub fn find_by_serial(env: Env, brand: String, serial_number: String) -> Option<String> {
+        env.storage().persistent().get(&(SERIAL_KEY, brand, serial_number))
+    }
+
+    /// Set or extend a certificate's warranty expiry (admin only)
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate to update
+    /// * `expires_at` - New warranty end (unix seconds)
+    /// 
+    /// # Panics
+    /// * If called by non-admin
+    /// * If certificate doesn't exist
+    pub fn set_warranty(env: Env, cert_id: String, expires_at: u64) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+        if !has_cert(&env, &cert_id) {
+            panic_with_error!(env, Error::CertificateNotFound);
+        }
+
+        let key = (WARRANTY_KEY, cert_id);
+        env.storage().persistent().set(&key, &expires_at);
+        bump_persistent(&env, &key);
+    }
+
+    /// Get the warranty expiry of a certificate, if it has one
+    pub fn get_warranty(env: Env, cert_id: String) -> Option<u64> {
+        env.storage().persistent().get(&(WARRANTY_KEY, cert_id))
+    }
+
+    /// Report warranty standing separately from authenticity
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate to check
+    /// 
+    /// # Panics
+    /// * If certificate doesn't exist
+    pub fn warranty_status(env: Env, cert_id: String) -> WarrantyStatus {
+        let certificate = require_cert(&env, &cert_id);
+        if !certificate.is_valid {
+            return WarrantyStatus::Revoked;
+        }
+
+        let expires_at: Option<u64> = env.storage().persistent().get(&(WARRANTY_KEY, cert_id));
+        match expires_at {
+            Some(expires_at) if env.ledger().timestamp() < expires_at => WarrantyStatus::Active,
+            Some(_) => WarrantyStatus::Expired,
+            None => WarrantyStatus::NoWarranty,
+        }
+    }
+
+    /// Create a collection certificates can be issued into (registered issuers)
+    /// 
+    /// The issuer owns the collection: only it can update the collection or
+    /// issue into it with `issue_as_issuer`.
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `issuer` - Active registered issuer running the product line
+    /// * `collection` - Brand, season and description of the product line
+    /// 
+    /// # Returns
+    /// * Collection ID
+    /// 
+    /// # Panics
+    /// * If `issuer` is not registered or is deactivated
+    pub fn create_collection(env: Env, issuer: Address, collection: Collection) -> u32 {
+        issuer.require_auth();
+        require_active_issuer(&env, &issuer);
+
+        let id: u32 = env.storage().instance().get(&COLL_SEQ_KEY).unwrap_or(0) + 1;
+        env.storage().instance().set(&COLL_SEQ_KEY, &id);
+        bump_instance(&env);
+
+        let key = (COLLECTION_KEY, id);
+        let issuer_key = (COLL_ISSUER_KEY, id);
+        env.storage().persistent().set(&key, &collection);
+        env.storage().persistent().set(&issuer_key, &issuer);
+        bump_persistent(&env, &key);
+        bump_persistent(&env, &issuer_key);
+        id
+    }
+
+    /// Update a collection's details (the collection's issuer only)
+    /// 
+    /// # Panics
+    /// * If the collection doesn't exist
+    /// * If not authorized by the collection's issuer, or that issuer is deactivated
+    pub fn update_collection(env: Env, collection_id: u32, collection: Collection) {
+        let key = (COLLECTION_KEY, collection_id);
+        if !env.storage().persistent().has(&key) {
+            panic!("Collection not found");
+        }
+        require_collection_issuer(&env, collection_id);
+
+        env.storage().persistent().set(&key, &collection);
+        bump_persistent(&env, &key);
+    }
+
+    /// Get the issuer owning a collection
+    pub fn get_collection_issuer(env: Env, collection_id: u32) -> Option<Address> {
+        env.storage().persistent().get(&(COLL_ISSUER_KEY, collection_id))
+    }
+
+    /// Grant or withdraw a third-party role (admin only)
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `account` - Address receiving or losing the role
+    /// * `role` - Role to change
+    /// * `granted` - `true` to grant, `false` to withdraw
+    pub fn set_role(env: Env, account: Address, role: Role, granted: bool) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+
+        let key = (ROLE_KEY, role, account);
+        if granted {
+            env.storage().persistent().set(&key, &true);
+            bump_persistent(&env, &key);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Check whether an address holds a role
+    pub fn has_role(env: Env, account: Address, role: Role) -> bool {
+        env.storage().persistent().has(&(ROLE_KEY, role, account))
+    }
+
+    /// Record a repair or service on a certificate (service centers only)
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `center` - Service center adding the record
+    /// * `cert_id` - Certificate of the serviced item
+    /// * `service_type` - Kind of service performed
+    /// * `report_hash` - IPFS hash of the service report
+    /// 
+    /// # Panics
+    /// * If `center` is not an authorized service center
+    /// * If certificate doesn't exist
+    pub fn add_service_record(
+        env: Env,
+        center: Address,
+        cert_id: String,
+        service_type: Symbol,
+        report_hash: String,
+    ) {
+        center.require_auth();
+        require_role(&env, &center, Role::ServiceCenter);
+        if !has_cert(&env, &cert_id) {
+            panic_with_error!(env, Error::CertificateNotFound);
+        }
+
+        let key = (SERVICE_KEY, cert_id);
+        let mut records: Vec<ServiceRecord> = env.storage().persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(&env));
+        records.push_back(ServiceRecord {
+            center,
+            service_type,
+            report_hash,
+            ledger: env.ledger().sequence(),
+            timestamp: env.ledger().timestamp(),
+        });
+        env.storage().persistent().set(&key, &records);
+        bump_persistent(&env, &key);
+    }
+
+    /// Get a certificate's service records, oldest first
+    pub fn get_service_history(env: Env, cert_id: String) -> Vec<ServiceRecord> {
+        env.storage().persistent()
+            .get(&(SERVICE_KEY, cert_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Append a valuation to a certificate (appraisers only)
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `appraiser` - Licensed appraiser adding the valuation
+    /// * `cert_id` - Certificate of the appraised item
+    /// * `value` - Appraised value in the currency's smallest unit
+    /// * `currency` - Currency code of the value
+    /// * `appraised_at` - When the appraisal took place (unix seconds)
+    /// * `report_hash` - IPFS hash of the appraisal report
+    /// 
+    /// # Panics
+    /// * If `appraiser` is not a licensed appraiser
+    /// * If certificate doesn't exist
+    /// * If the value isn't positive or the date is in the future
+    pub fn add_appraisal(
+        env: Env,
+        appraiser: Address,
+        cert_id: String,
+        value: i128,
+        currency: Symbol,
+        appraised_at: u64,
+        report_hash: String,
+    ) {
+        appraiser.require_auth();
+        require_role(&env, &appraiser, Role::Appraiser);
+        if !has_cert(&env, &cert_id) {
+            panic_with_error!(env, Error::CertificateNotFound);
+        }
+        if value <= 0 {
+            panic!("Appraised value must be positive");
+        }
+        if appraised_at > env.ledger().timestamp() {
+            panic!("Appraisal date is in the future");
+        }
+
+        let key = (APPRAISAL_KEY, cert_id);
+        let mut appraisals: Vec<Appraisal> = env.storage().persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(&env));
+        appraisals.push_back(Appraisal { appraiser, value, currency, appraised_at, report_hash });
+        env.storage().persistent().set(&key, &appraisals);
+        bump_persistent(&env, &key);
+    }
+
+    /// Get a certificate's valuation history in the order it was recorded
+    pub fn get_appraisals(env: Env, cert_id: String) -> Vec<Appraisal> {
+        env.storage().persistent()
+            .get(&(APPRAISAL_KEY, cert_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Declare a collection as a limited edition of `edition_total` items (admin only)
+    /// 
+    /// Certificates issued into the collection are then numbered 1..=total
+    /// in issuance order, and issuance stops once the edition is full.
+    /// The size can't be changed once declared.
+    /// 
+    /// # Panics
+    /// * If called by non-admin
+    /// * If the collection doesn't exist, already has certificates, or already has an edition size
+    /// * If `edition_total` is zero
+    pub fn set_edition_size(env: Env, collection_id: u32, edition_total: u32) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+
+        if !env.storage().persistent().has(&(COLLECTION_KEY, collection_id)) {
+            panic!("Collection not found");
+        }
+        if edition_total == 0 {
+            panic!("Edition size must be positive");
+        }
+        let key = (EDITION_KEY, collection_id);
+        if env.storage().persistent().has(&key)
+            || env.storage().persistent().has(&(COLL_SIZE_KEY, collection_id))
+        {
+            panic!("Edition size can only be set on a new collection");
+        }
+
+        env.storage().persistent().set(&key, &edition_total);
+        bump_persistent(&env, &key);
+    }
+
+    /// Get a certificate's edition number and edition size, if it belongs to a limited edition
+    pub fn get_edition(env: Env, cert_id: String) -> Option<Edition> {
+        env.storage().persistent().get(&(CERT_EDITION_KEY, cert_id))
+    }
+
+    /// Get a collection by ID
+    pub fn get_collection(env: Env, collection_id: u32) -> Option<Collection> {
+        env.storage().persistent().get(&(COLLECTION_KEY, collection_id))
+    }
+
+    /// Get the collection a certificate was issued into, if any
+    pub fn get_cert_collection(env: Env, cert_id: String) -> Option<u32> {
+        let entry: Option<(u32, u32)> = env.storage().persistent().get(&(CERT_COLL_KEY, cert_id));
+        entry.map(|(collection_id, _)| collection_id)
+    }
+
+    /// List certificate IDs in a collection
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `collection_id` - Collection to list
+    /// * `start` - Index of the first entry to return
+    /// * `limit` - Maximum entries to return (capped at the configured batch size)
+    pub fn list_by_collection(env: Env, collection_id: u32, start: u32, limit: u32) -> Vec<String> {
+        let size: u32 = env.storage().persistent()
+            .get(&(COLL_SIZE_KEY, collection_id))
+            .unwrap_or(0);
+        let max = read_config(&env).max_batch_size;
+        let end = size.min(start.saturating_add(limit.min(max)));
+
+        let mut ids = Vec::new(&env);
+        for index in start..end {
+            if let Some(cert_id) = env.storage().persistent().get(&(COLL_ITEM_KEY, collection_id, index)) {
+                ids.push_back(cert_id);
+            }
+        }
+        ids
+    }
+
+    /// Check that a scanned tag belongs to a valid certificate
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate identifier to verify
+    /// * `tag_id` - ID read from the item's chip
+    /// 
+    /// # Returns
+    /// * `true` if certificate exists, is valid, and is bound to `tag_id`
+    /// * `false` otherwise
+    pub fn verify_tag(env: Env, cert_id: String, tag_id: BytesN<32>) -> bool {
+        let bound: Option<BytesN<32>> = env.storage().persistent().get(&(TAG_KEY, cert_id.clone()));
+        bound == Some(tag_id)
+            && read_cert(&env, &cert_id).is_some_and(|c| c.is_valid)
+            && !is_suspended(&env, &cert_id)
+    }
+
+    /// Get the tag bound to a certificate, if any
+    pub fn get_tag(env: Env, cert_id: String) -> Option<BytesN<32>> {
+        env.storage().persistent().get(&(TAG_KEY, cert_id))
+    }
+
+    /// Resolve a scanned tag to its certificate ID
+    pub fn get_cert_by_tag(env: Env, tag_id: BytesN<32>) -> Option<String> {
+        env.storage().persistent().get(&(TAG_INDEX_KEY, tag_id))
+    }
+
+    /// Link a certificate into a set under a parent (owner only)
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `parent_id` - Main item of the set (e.g. the handbag)
+    /// * `child_id` - Accessory belonging to it (e.g. the dust bag)
+    /// 
+    /// # Panics
+    /// * If either certificate doesn't exist
+    /// * If called by anyone other than the parent's owner
+    /// * If the two certificates have different owners
+    /// * If the child already has a parent or the link would form a cycle
+    pub fn link_child(env: Env, parent_id: String, child_id: String) {
+        let parent = require_cert(&env, &parent_id);
+        let child = require_cert(&env, &child_id);
+        parent.owner.require_auth();
+
+        if parent.owner != child.owner {
+            panic!("Parent and child must share an owner");
+        }
+        let parent_key = (PARENT_KEY, child_id.clone());
+        if env.storage().persistent().has(&parent_key) {
+            panic!("Certificate already has a parent");
+        }
+
+        // Walk up from the parent to make sure the child isn't one of its ancestors
+        let mut ancestor = Some(parent_id.clone());
+        while let Some(id) = ancestor {
+            if id == child_id {
+                panic!("Link would create a cycle");
+            }
+            ancestor = env.storage().persistent().get(&(PARENT_KEY, id));
+        }
+
+        let children_key = (CHILDREN_KEY, parent_id.clone());
+        let mut children: Vec<String> = env.storage().persistent()
+            .get(&children_key)
+            .unwrap_or(Vec::new(&env));
+        children.push_back(child_id);
+        env.storage().persistent().set(&children_key, &children);
+        env.storage().persistent().set(&parent_key, &parent_id);
+        bump_persistent(&env, &children_key);
+        bump_persistent(&env, &parent_key);
+    }
+
+    /// Remove a certificate from its parent's set (parent owner only)
+    /// 
+    /// # Panics
+    /// * If the parent doesn't exist
+    /// * If called by anyone other than the parent's owner
+    /// * If the child isn't linked to this parent
+    pub fn unlink_child(env: Env, parent_id: String, child_id: String) {
+        let parent = require_cert(&env, &parent_id);
+        parent.owner.require_auth();
+
+        let linked: Option<String> = env.storage().persistent().get(&(PARENT_KEY, child_id.clone()));
+        if linked != Some(parent_id) {
+            panic!("Certificate is not a child of parent");
+        }
+        detach_child(&env, &child_id);
+    }
+
+    /// Choose whether transferring a parent also moves its children (owner only)
+    /// 
+    /// # Panics
+    /// * If certificate doesn't exist
+    /// * If called by anyone other than the owner
+    pub fn set_transfer_children(env: Env, parent_id: String, enabled: bool) {
+        let parent = require_cert(&env, &parent_id);
+        parent.owner.require_auth();
+
+        let key = (BUNDLE_KEY, parent_id);
+        if enabled {
+            env.storage().persistent().set(&key, &true);
+            bump_persistent(&env, &key);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Get the certificates linked under a parent
+    pub fn get_children(env: Env, parent_id: String) -> Vec<String> {
+        env.storage().persistent()
+            .get(&(CHILDREN_KEY, parent_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the parent a certificate is linked under, if any
+    pub fn get_parent(env: Env, child_id: String) -> Option<String> {
+        env.storage().persistent().get(&(PARENT_KEY, child_id))
+    }
+
+    /// Issue a certificate held by the contract until someone redeems its claim code (admin only)
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Unique identifier for the certificate
+    /// * `metadata_hash` - IPFS hash of certificate metadata
+    /// * `claim_hash` - sha256 of the claim code printed in the box
+    /// 
+    /// # Panics
+    /// * Under the same conditions as `issue_certificate`
+    pub fn issue_claimable(env: Env, cert_id: String, metadata_hash: String, claim_hash: BytesN<32>) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+
+        let pending_owner = env.current_contract_address();
+        create_cert(&env, &admin, &cert_id, metadata_hash, pending_owner, false);
+        charge_issuance_fee(&env, &admin);
+
+        let key = (CLAIM_KEY, cert_id);
+        env.storage().persistent().set(&key, &claim_hash);
+        bump_persistent(&env, &key);
+    }
+
+    /// Redeem a claim code, taking ownership of a pending certificate
+    /// 
+    /// The code is revealed on submission, so claimers should submit as soon
+    /// as they open the box.
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `claimer` - Address becoming the owner
+    /// * `cert_id` - Certificate being claimed
+    /// * `preimage` - Claim code whose sha256 matches the stored claim hash
+    /// 
+    /// # Panics
+    /// * If the certificate is not awaiting a claim
+    /// * If the claim code is wrong
+    pub fn claim(env: Env, claimer: Address, cert_id: String, preimage: Bytes) {
+        claimer.require_auth();
+        ensure_not_paused(&env);
+        ensure_allowed_recipient(&env, &claimer);
+
+        let key = (CLAIM_KEY, cert_id.clone());
+        let claim_hash: BytesN<32> = env.storage().persistent().get(&key)
+            .expect("Certificate not claimable");
+        if env.crypto().sha256(&preimage).to_bytes() != claim_hash {
+            panic!("Invalid claim code");
+        }
+
+        let mut certificate = require_cert(&env, &cert_id);
+        let pending_owner = certificate.owner.clone();
+        ensure_policy_allows_transfer(&env, &cert_id, &pending_owner, &claimer);
+        certificate.owner = claimer.clone();
+        write_cert(&env, &cert_id, &certificate);
+        move_balance(&env, Some(&pending_owner), Some(&claimer));
+        env.storage().persistent().remove(&key);
+
+        append_history(&env, cert_id, TransferRecord {
+            from: pending_owner,
+            to: claimer,
+            price: None,
+            currency: None,
+            memo: None,
+            token: None,
+            ledger: env.ledger().sequence(),
+            timestamp: env.ledger().timestamp(),
+        });
+    }
+
+    /// Check whether a certificate is still awaiting its claim code
+    pub fn is_claimable(env: Env, cert_id: String) -> bool {
+        env.storage().persistent().has(&(CLAIM_KEY, cert_id))
+    }
+
+    /// Publish a Merkle root committing to a batch of certificates (admin only)
+    /// 
+    /// Each leaf is `sha256(xdr((cert_id, metadata_hash, owner)))`; interior
+    /// nodes hash the two children in ascending byte order. Certificates are
+    /// only written to storage when claimed with `claim_from_root`.
+    /// 
+    /// The issuance fee is charged up front for every leaf, and at most
+    /// `leaf_count` certificates can be claimed from the root.
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `root` - Root of the batch tree
+    /// * `leaf_count` - Number of certificates in the batch
+    /// 
+    /// # Panics
+    /// * If called by non-admin
+    /// * If contract is not initialized
+    /// * If `leaf_count` is zero or the root was already published
+    /// * If the admin can't cover the issuance fee for the batch
+    pub fn publish_root(env: Env, root: BytesN<32>, leaf_count: u32) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+
+        if leaf_count == 0 {
+            panic!("Batch must have at least one leaf");
+        }
+        let key = (ROOT_KEY, root.clone());
+        if env.storage().persistent().has(&key) {
+            panic!("Root already published");
+        }
+
+        charge_batch_fee(&env, &admin, leaf_count);
+
+        env.storage().persistent().set(&key, &env.ledger().sequence());
+        bump_persistent(&env, &key);
+        let left_key = (ROOT_LEFT_KEY, root);
+        env.storage().persistent().set(&left_key, &leaf_count);
+        bump_persistent(&env, &left_key);
+    }
+
+    /// Check whether a batch root has been published
+    pub fn is_root_published(env: Env, root: BytesN<32>) -> bool {
+        env.storage().persistent().has(&(ROOT_KEY, root))
+    }
+
+    /// Materialize a certificate committed to by a published batch root
+    /// 
+    /// Anyone may submit the claim; the certificate always goes to the
+    /// `owner` committed in the leaf.
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `proof` - Sibling hashes from the leaf up to the root
+    /// * `cert_id` - Certificate identifier from the batch
+    /// * `metadata_hash` - Metadata hash from the batch
+    /// * `owner` - Owner from the batch
+    /// 
+    /// # Panics
+    /// * If the proof doesn't lead to a published root
+    /// * If every leaf the root was published with has been claimed
+    /// * If the certificate was already claimed or its ID was burned
+    pub fn claim_from_root(
+        env: Env,
+        proof: Vec<BytesN<32>>,
+        cert_id: String,
+        metadata_hash: String,
+        owner: Address,
+    ) {
+        let mut node = merkle_leaf(&env, &cert_id, &metadata_hash, &owner);
+        for sibling in proof.iter() {
+            node = merkle_parent(&env, &node, &sibling);
+        }
+        if !env.storage().persistent().has(&(ROOT_KEY, node.clone())) {
+            panic!("Invalid Merkle proof");
+        }
+
+        // The fee was paid for `leaf_count` leaves when the root was published
+        let left_key = (ROOT_LEFT_KEY, node);
+        let left: u32 = env.storage().persistent().get(&left_key).unwrap_or(0);
+        if left == 0 {
+            panic!("Batch root fully claimed");
+        }
+        env.storage().persistent().set(&left_key, &(left - 1));
+        bump_persistent(&env, &left_key);
+
+        // Batches are published by the admin, so they can't reach into issuer ranges
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        create_cert(&env, &admin, &cert_id, metadata_hash, owner, false);
+    }
+
+    /// Verify a certificate by ID and metadata hash
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate identifier to verify
+    /// * `metadata_hash` - Expected metadata hash
+    /// 
+    /// # Returns
+    /// * `true` if certificate exists, is valid, and metadata hash matches
+    /// * `false` otherwise
+    pub fn verify(env: Env, cert_id: String, metadata_hash: String) -> bool {
+        // Check if certificate exists and verify conditions
+        if let Some(certificate) = read_cert(&env, &cert_id) {
+            // Must be valid AND metadata hash must match
+            certificate.is_valid
+                && certificate.metadata_hash == metadata_hash
+                && !is_suspended(&env, &cert_id)
+        } else {
+            false
+        }
+    }
+
+    /// Check whether a certificate was valid at a past ledger
+    /// 
+    /// Certificates issued before status changes were logged report their
+    /// current validity for every ledger.
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate to check
+    /// * `ledger_seq` - Ledger sequence to evaluate
+    /// 
+    /// # Returns
+    /// * `true` if the certificate had been issued and was neither revoked nor burned at `ledger_seq`
+    pub fn was_valid_at(env: Env, cert_id: String, ledger_seq: u32) -> bool {
+        let burned: Option<u32> = env.storage().persistent().get(&(BURNED_KEY, cert_id.clone()));
+        if burned.is_some_and(|burned_at| ledger_seq >= burned_at) {
+            return false;
+        }
+
+        let log: Option<Vec<(u32, bool)>> = env.storage().persistent()
+            .get(&(STATUS_LOG_KEY, cert_id.clone()));
+        let Some(log) = log else {
+            return read_cert(&env, &cert_id).is_some_and(|c| c.is_valid);
+        };
+
+        // The latest change at or before the ledger decides
+        let mut valid = false;
+        for (changed_at, is_valid) in log.iter() {
+            if changed_at > ledger_seq {
+                break;
+            }
+            valid = is_valid;
+        }
+        valid
+    }
+
+    /// Find the certificates issued for a metadata hash
+    /// 
+    /// Certificates issued before the index existed are not included.
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `metadata_hash` - Hash read from the item (e.g. its QR code)
+    /// 
+    /// # Returns
+    /// * Matching certificate IDs, oldest first (empty if none)
+    pub fn find_by_metadata_hash(env: Env, metadata_hash: String) -> Vec<String> {
+        env.storage().persistent()
+            .get(&(HASH_INDEX_KEY, metadata_hash))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Verify a certificate against its raw metadata, hashing it on-chain
+    /// 
+    /// Only applies to certificates whose `metadata_hash` is the hex-encoded
+    /// sha256 of the metadata document (case-insensitive).
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate identifier to verify
+    /// * `metadata_bytes` - Raw metadata document
+    /// 
+    /// # Returns
+    /// * `true` if certificate exists, is valid, and `sha256(metadata_bytes)` matches
+    /// * `false` otherwise
+    pub fn verify_metadata(env: Env, cert_id: String, metadata_bytes: Bytes) -> bool {
+        let Some(certificate) = read_cert(&env, &cert_id) else {
+            return false;
+        };
+        if !certificate.is_valid
+            || is_suspended(&env, &cert_id)
+            || certificate.metadata_hash.len() as usize != SHA256_HEX_LEN
+        {
+            return false;
+        }
+
+        let mut stored = [0u8; SHA256_HEX_LEN];
+        certificate.metadata_hash.copy_into_slice(&mut stored);
+        let digest = env.crypto().sha256(&metadata_bytes).to_array();
+        digest.iter().zip(stored.chunks(2)).all(|(byte, pair)| {
+            hex_value(pair[0]) == Some(byte >> 4) && hex_value(pair[1]) == Some(byte & 0x0f)
+        })
+    }
+
+    /// Get complete certificate details by ID
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate identifier
+    /// 
+    /// # Returns
+    /// * Certificate data with its transfer count and last transfer ledger
+    /// 
+    /// # Panics
+    /// * If certificate doesn't exist
+    pub fn get_certificate_details(env: Env, cert_id: String) -> CertificateDetails {
+        // Return certificate or panic if not found
+        let certificate = require_cert(&env, &cert_id);
+        let (transfer_count, last_transfer_ledger) = read_transfer_stats(&env, &cert_id);
+
+        CertificateDetails {
+            owner: certificate.owner,
+            metadata_hash: certificate.metadata_hash,
+            is_valid: certificate.is_valid,
+            transfer_count,
+            last_transfer_ledger,
+        }
+    }
+
+    /// Transfer certificate ownership (current owner only)
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate to transfer
+    /// * `new_owner` - Address of the new owner
+    /// * `price` - Optional sale price, paid by the new owner when a royalty is configured
+    /// * `currency` - Currency of the sale price (required when `price` is set)
+    /// * `memo` - Optional note recorded in the certificate's history
+    /// 
+    /// # Panics
+    /// * If called by non-owner
+    /// * If certificate doesn't exist
+    /// * If certificate is invalid/revoked
+    /// * If certificate is locked
+    /// * If the sale details are inconsistent
+    /// * If the new owner can't pay a royalty-bearing sale
+    pub fn transfer(
+        env: Env,
+        cert_id: String,
+        new_owner: Address,
+        price: Option<i128>,
+        currency: Option<Symbol>,
+        memo: Option<String>,
+    ) {
+        // Get existing certificate
+        let certificate = require_cert(&env, &cert_id);
+
+        // Require authentication from current owner
+        certificate.owner.require_auth();
+
+        // Prevent transfer of invalid certificates
+        if !certificate.is_valid {
+            panic_with_error!(env, Error::CertificateRevoked);
+        }
+
+        complete_transfer(&env, cert_id, certificate, new_owner, price, currency, memo);
+    }
+
+    /// Transfer certificate ownership with the recipient's consent
+    /// 
+    /// Behaves like `transfer` but also requires `new_owner` to sign, so a
+    /// certificate can't be pushed onto an address that didn't agree to
+    /// receive it.
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate to transfer
+    /// * `new_owner` - Address of the new owner (must authorize)
+    /// * `price` - Optional sale price, paid by the new owner when a royalty is configured
+    /// * `currency` - Currency of the sale price (required when `price` is set)
+    /// * `memo` - Optional note recorded in the certificate's history
+    /// 
+    /// # Panics
+    /// * If not authorized by both the current and the new owner
+    /// * Under the same conditions as `transfer`
+    pub fn transfer_with_consent(
+        env: Env,
+        cert_id: String,
+        new_owner: Address,
+        price: Option<i128>,
+        currency: Option<Symbol>,
+        memo: Option<String>,
+    ) {
+        let certificate = require_cert(&env, &cert_id);
+
+        // Both sides of the transfer must sign
+        certificate.owner.require_auth();
+        new_owner.require_auth();
+
+        if !certificate.is_valid {
+            panic_with_error!(env, Error::CertificateRevoked);
+        }
+
+        complete_transfer(&env, cert_id, certificate, new_owner, price, currency, memo);
+    }
+
+    /// Configure the royalty paid on priced resales of a collection's items
+    /// (the collection's issuer only)
+    /// 
+    /// Once set, any transfer of a certificate in the collection that supplies
+    /// a price must be quoted in `currency`; the new owner pays the price in
+    /// `token`, split between the seller and the royalty recipient.
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `collection_id` - Collection the royalty applies to
+    /// * `bps` - Royalty share in basis points (at most 10000)
+    /// * `recipient` - Address receiving royalties
+    /// * `token` - Token contract used to settle sales
+    /// * `currency` - Currency code matching `token`
+    /// 
+    /// # Panics
+    /// * If the collection doesn't exist
+    /// * If not authorized by the collection's issuer, or that issuer is deactivated
+    /// * If `bps` exceeds 10000
+    pub fn set_royalty(
+        env: Env,
+        collection_id: u32,
+        bps: u32,
+        recipient: Address,
+        token: Address,
+        currency: Symbol,
+    ) {
+        require_collection_issuer(&env, collection_id);
+
+        if bps > MAX_ROYALTY_BPS {
+            panic!("Royalty cannot exceed 10000 bps");
+        }
+
+        let key = (ROYALTY_KEY, collection_id);
+        let config = RoyaltyConfig { bps, recipient, token, currency };
+        env.storage().persistent().set(&key, &config);
+        bump_persistent(&env, &key);
+    }
+
+    /// Charge a fee in `token` for every certificate issued (admin only)
+    /// 
+    /// The issuer pays `amount` to `treasury` as part of each issuance call.
+    /// An `amount` of zero turns the fee off.
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `token` - Token contract the fee is paid in
+    /// * `amount` - Fee per certificate
+    /// * `treasury` - Address collecting the fees
+    /// 
+    /// # Panics
+    /// * If called by non-admin
+    /// * If `amount` is negative
+    pub fn set_issuance_fee(env: Env, token: Address, amount: i128, treasury: Address) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+
+        if amount < 0 {
+            panic!("Issuance fee cannot be negative");
+        }
+        if amount == 0 {
+            env.storage().instance().remove(&FEE_KEY);
+        } else {
+            env.storage().instance().set(&FEE_KEY, &IssuanceFee { token, amount, treasury });
+        }
+        bump_instance(&env);
+    }
+
+    /// Get the issuance fee, if one is charged
+    pub fn get_issuance_fee(env: Env) -> Option<IssuanceFee> {
+        env.storage().instance().get(&FEE_KEY)
+    }
+
+    /// Tune operational parameters (admin only)
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `config` - New parameters; `issuance_fee` updates the amount of the
+    ///   fee configured with `set_issuance_fee` (0 turns it off)
+    /// 
+    /// # Panics
+    /// * If called by non-admin
+    /// * If `max_batch_size` is zero or the TTL threshold exceeds the extension
+    /// * If multisig is enabled (use `propose` instead)
+    /// * If a non-zero fee is set before the fee token is configured
+    pub fn set_config(env: Env, config: Config) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+        ensure_no_multisig(&env);
+
+        execute_action(&env, AdminAction::SetConfig(config));
+    }
+
+    /// Register or remove the policy contract consulted on issue and transfer (admin only)
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `policy` - Contract implementing `Policy`, or `None` to remove it
+    /// 
+    /// # Panics
+    /// * If called by non-admin
+    /// * If multisig is enabled (use `propose` instead)
+    pub fn set_policy(env: Env, policy: Option<Address>) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+        ensure_no_multisig(&env);
+
+        execute_action(&env, AdminAction::SetPolicy(policy));
+    }
+
+    /// Get the registered policy contract, if any
+    pub fn get_policy(env: Env) -> Option<Address> {
+        env.storage().instance().get(&POLICY_KEY)
+    }
+
+    /// Get the operational parameters in effect
+    pub fn get_config(env: Env) -> Config {
+        read_config(&env)
+    }
+
+    /// Get a collection's royalty configuration, if any
+    pub fn get_royalty(env: Env, collection_id: u32) -> Option<RoyaltyConfig> {
+        env.storage().persistent().get(&(ROYALTY_KEY, collection_id))
+    }
+
+    /// List a certificate for sale, moving it into the contract's escrow (current owner only)
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate to sell
+    /// * `price` - Asking price in the token's smallest unit
+    /// * `token` - Token contract the buyer must pay in
+    /// 
+    /// # Panics
+    /// * If called by non-owner
+    /// * If certificate doesn't exist
+    /// * If certificate is invalid/revoked
+    /// * If certificate is locked
+    /// * If the price is not positive
+    pub fn list_for_sale(env: Env, cert_id: String, price: i128, token: Address) {
+        // Get existing certificate
+        let mut certificate = require_cert(&env, &cert_id);
+
+        // Require authentication from current owner
+        certificate.owner.require_auth();
+
+        if !certificate.is_valid {
+            panic!("Cannot list invalid certificate");
+        }
+        if is_locked(&env, &cert_id) {
+            panic!("Certificate is locked");
+        }
+        if is_disputed(&env, &cert_id) {
+            panic!("Certificate is disputed");
+        }
+        if is_suspended(&env, &cert_id) {
+            panic!("Certificate is suspended");
+        }
+        if price <= 0 {
+            panic!("Listing price must be positive");
+        }
+
+        // The contract holds the certificate until it is bought or delisted
+        let seller = certificate.owner.clone();
+        let escrow = env.current_contract_address();
+        certificate.owner = escrow.clone();
+        write_cert(&env, &cert_id, &certificate);
+        move_balance(&env, Some(&seller), Some(&escrow));
+        clear_approval(&env, cert_id.clone());
+
+        let key = (LISTING_KEY, cert_id);
+        env.storage().persistent().set(&key, &Listing { seller, price, token });
+        bump_persistent(&env, &key);
+    }
+
+    /// Buy a listed certificate, paying the seller and receiving ownership atomically
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `buyer` - Address paying for and receiving the certificate
+    /// * `cert_id` - Listed certificate
+    /// 
+    /// # Panics
+    /// * If buyer authentication fails
+    /// * If the certificate is not listed
+    /// * If certificate was revoked while listed
+    /// * If the buyer can't pay the listing price
+    pub fn buy(env: Env, buyer: Address, cert_id: String) {
+        let key = (LISTING_KEY, cert_id.clone());
+        let listing: Listing = env.storage().persistent().get(&key)
+            .expect("Certificate not listed");
+
+        ensure_not_paused(&env);
+        ensure_allowed_recipient(&env, &buyer);
+        ensure_cooldown_elapsed(&env, &cert_id);
+        let mut certificate = require_cert(&env, &cert_id);
+        if !certificate.is_valid {
+            panic!("Cannot buy invalid certificate");
+        }
+        if is_suspended(&env, &cert_id) {
+            panic!("Certificate is suspended");
+        }
+
+        ensure_policy_allows_transfer(&env, &cert_id, &listing.seller, &buyer);
+
+        // Payment and ownership change happen in the same invocation
+        pay_sale(&env, &cert_id, &listing.token, &listing.seller, &buyer, listing.price);
+
+        env.storage().persistent().remove(&key);
+        certificate.owner = buyer.clone();
+        write_cert(&env, &cert_id, &certificate);
+        move_balance(&env, Some(&env.current_contract_address()), Some(&buyer));
+        carry_children(&env, &cert_id, &listing.seller, &buyer);
+
+        append_history(&env, cert_id, TransferRecord {
+            from: listing.seller,
+            to: buyer,
+            price: Some(listing.price),
+            currency: None,
+            memo: None,
+            token: Some(listing.token),
+            ledger: env.ledger().sequence(),
+            timestamp: env.ledger().timestamp(),
+        });
+    }
+
+    /// Withdraw a listing, returning the certificate to the seller (seller only)
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Listed certificate
+    /// 
+    /// # Panics
+    /// * If called by anyone but the seller
+    /// * If the certificate is not listed
+    pub fn cancel_listing(env: Env, cert_id: String) {
+        let key = (LISTING_KEY, cert_id.clone());
+        let listing: Listing = env.storage().persistent().get(&key)
+            .expect("Certificate not listed");
+        listing.seller.require_auth();
+
+        let mut certificate = require_cert(&env, &cert_id);
+
+        env.storage().persistent().remove(&key);
+        certificate.owner = listing.seller.clone();
+        write_cert(&env, &cert_id, &certificate);
+        move_balance(&env, Some(&env.current_contract_address()), Some(&listing.seller));
+    }
+
+    /// Freeze a certificate against transfer, e.g. while the item is in a vault (current owner only)
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate to lock
+    /// 
+    /// # Panics
+    /// * If called by non-owner
+    /// * If certificate doesn't exist
+    pub fn lock(env: Env, cert_id: String) {
+        let certificate = require_cert(&env, &cert_id);
+        certificate.owner.require_auth();
+
+        let key = (LOCKED_KEY, cert_id);
+        env.storage().persistent().set(&key, &true);
+        bump_persistent(&env, &key);
+    }
+
+    /// Lift a lock placed with `lock` (current owner only)
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate to unlock
+    /// 
+    /// # Panics
+    /// * If called by non-owner
+    /// * If certificate doesn't exist
+    pub fn unlock(env: Env, cert_id: String) {
+        let certificate = require_cert(&env, &cert_id);
+        certificate.owner.require_auth();
+
+        env.storage().persistent().remove(&(LOCKED_KEY, cert_id));
+    }
+
+    /// Open a dispute, blocking transfers until it is resolved (owner only)
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate being disputed
+    /// * `reason_hash` - IPFS hash of the owner's statement and evidence
+    /// 
+    /// # Panics
+    /// * If certificate doesn't exist or is revoked
+    /// * If called by anyone other than the owner
+    /// * If a dispute is already open
+    pub fn open_dispute(env: Env, cert_id: String, reason_hash: String) {
+        let certificate = require_cert(&env, &cert_id);
+        certificate.owner.require_auth();
+
+        if !certificate.is_valid {
+            panic!("Cannot dispute invalid certificate");
+        }
+        if is_disputed(&env, &cert_id) {
+            panic!("Dispute already open");
+        }
+
+        let key = (DISPUTE_KEY, cert_id);
+        let mut disputes: Vec<Dispute> = env.storage().persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(&env));
+        disputes.push_back(Dispute {
+            opened_by: certificate.owner,
+            reason_hash: reason_hash.clone(),
+            opened_at: env.ledger().timestamp(),
+            status: DisputeStatus::Open,
+        });
+        env.storage().persistent().set(&key, &disputes);
+        bump_persistent(&env, &key);
+
+        env.events().publish((DISPUTED_EVENT, key.1), reason_hash);
+    }
+
+    /// Resolve an open dispute with a recorded ruling (admin only)
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Disputed certificate
+    /// * `outcome` - `Valid` to release it, `Revoked` to revoke it
+    /// * `ruling_hash` - IPFS hash of the ruling document
+    /// 
+    /// # Panics
+    /// * If called by non-admin
+    /// * If no dispute is open
+    /// * If the outcome is `Revoked` while multisig is enabled (use `propose` instead)
+    pub fn resolve_dispute(env: Env, cert_id: String, outcome: DisputeOutcome, ruling_hash: String) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+        if outcome == DisputeOutcome::Revoked {
+            ensure_no_multisig(&env);
+        }
+
+        execute_action(&env, AdminAction::ResolveDispute(cert_id, outcome, ruling_hash));
+    }
+
+    /// Get every dispute raised on a certificate, oldest first
+    pub fn get_disputes(env: Env, cert_id: String) -> Vec<Dispute> {
+        env.storage().persistent()
+            .get(&(DISPUTE_KEY, cert_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get whether a certificate is valid, disputed or revoked
+    /// 
+    /// # Panics
+    /// * If certificate doesn't exist
+    pub fn get_status(env: Env, cert_id: String) -> CertStatus {
+        let certificate = require_cert(&env, &cert_id);
+        if !certificate.is_valid {
+            CertStatus::Revoked
+        } else if is_disputed(&env, &cert_id) {
+            CertStatus::Disputed
+        } else if is_suspended(&env, &cert_id) {
+            CertStatus::Suspended
+        } else {
+            CertStatus::Valid
+        }
+    }
+
+    /// Check whether a certificate is locked against transfer
+    pub fn is_locked(env: Env, cert_id: String) -> bool {
+        is_locked(&env, &cert_id)
+    }
+
+    /// Get the active sale listing for a certificate, if any
+    pub fn get_listing(env: Env, cert_id: String) -> Option<Listing> {
+        env.storage().persistent().get(&(LISTING_KEY, cert_id))
+    }
+
+    /// Get the transfer history of a certificate, oldest first
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate identifier
+    /// 
+    /// # Returns
+    /// * Recorded transfers (empty if the certificate never changed hands)
+    pub fn get_history(env: Env, cert_id: String) -> Vec<TransferRecord> {
+        env.storage().persistent()
+            .get(&(HISTORY_KEY, cert_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Approve an operator to transfer a single certificate (current owner only)
+    /// 
+    /// The approval is cleared automatically when the certificate changes hands.
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate the operator may transfer
+    /// * `operator` - Address allowed to call `transfer_from` for this certificate
+    /// 
+    /// # Panics
+    /// * If called by non-owner
+    /// * If certificate doesn't exist
+    pub fn approve(env: Env, cert_id: String, operator: Address) {
+        // Only the current owner can grant an approval
+        let certificate = require_cert(&env, &cert_id);
+        certificate.owner.require_auth();
+
+        // Store approval, replacing any previous one
+        let key = (APPROVALS_KEY, cert_id);
+        env.storage().persistent().set(&key, &operator);
+        bump_persistent(&env, &key);
+    }
+
+    /// Grant or withdraw an operator's right to transfer all of an owner's certificates
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `owner` - Owner granting the approval
+    /// * `operator` - Address acting on the owner's behalf (e.g. a marketplace)
+    /// * `approved` - `true` to grant, `false` to withdraw
+    /// 
+    /// # Panics
+    /// * If owner authentication fails
+    pub fn approve_all(env: Env, owner: Address, operator: Address, approved: bool) {
+        // Require authentication from the owner
+        owner.require_auth();
+
+        let key = (OPERATORS_KEY, owner, operator);
+        if approved {
+            env.storage().persistent().set(&key, &true);
+            bump_persistent(&env, &key);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Get the operator approved for a single certificate, if any
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate identifier
+    /// 
+    /// # Returns
+    /// * Approved operator address, or `None`
+    pub fn get_approved(env: Env, cert_id: String) -> Option<Address> {
+        env.storage().persistent().get(&(APPROVALS_KEY, cert_id))
+    }
+
+    /// Check whether an operator may transfer all certificates of an owner
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `owner` - Certificate owner
+    /// * `operator` - Operator address
+    /// 
+    /// # Returns
+    /// * `true` if the operator is approved for all of the owner's certificates
+    pub fn is_approved_for_all(env: Env, owner: Address, operator: Address) -> bool {
+        env.storage().persistent()
+            .get(&(OPERATORS_KEY, owner, operator))
+            .unwrap_or(false)
+    }
+
+    /// Transfer a certificate on the owner's behalf (owner or approved operator)
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `spender` - Address performing the transfer
+    /// * `cert_id` - Certificate to transfer
+    /// * `new_owner` - Address of the new owner
+    /// * `price` - Optional sale price, paid by the new owner when a royalty is configured
+    /// * `currency` - Currency of the sale price (required when `price` is set)
+    /// * `memo` - Optional note recorded in the certificate's history
+    /// 
+    /// # Panics
+    /// * If spender authentication fails
+    /// * If spender is neither the owner nor an approved operator
+    /// * If certificate doesn't exist
+    /// * If certificate is invalid/revoked
+    /// * If certificate is locked
+    /// * If the sale details are inconsistent
+    /// * If the new owner can't pay a royalty-bearing sale
+    pub fn transfer_from(
+        env: Env,
+        spender: Address,
+        cert_id: String,
+        new_owner: Address,
+        price: Option<i128>,
+        currency: Option<Symbol>,
+        memo: Option<String>,
+    ) {
+        // Require authentication from whoever is moving the certificate
+        spender.require_auth();
+
+        // Get existing certificate
+        let certificate = require_cert(&env, &cert_id);
+
+        // Spender must be the owner, the approved operator, or an operator for all
+        if spender != certificate.owner
+            && Self::get_approved(env.clone(), cert_id.clone()) != Some(spender.clone())
+            && !Self::is_approved_for_all(env.clone(), certificate.owner.clone(), spender)
+        {
+            panic_with_error!(env, Error::NotAuthorized);
+        }
+
+        // Prevent transfer of invalid certificates
+        if !certificate.is_valid {
+            panic_with_error!(env, Error::CertificateRevoked);
+        }
+
+        complete_transfer(&env, cert_id, certificate, new_owner, price, currency, memo);
+    }
+
+    /// Revoke a certificate (admin only)
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate to revoke
+    /// 
+    /// # Panics
+    /// * If called by non-admin
+    /// * If multisig is enabled (use `propose` instead)
+    /// * If certificate doesn't exist
+    /// * If contract is not initialized
+    pub fn revoke(env: Env, cert_id: String) {
+        // Get admin address and require authentication
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+        ensure_no_multisig(&env);
+
+        execute_action(&env, AdminAction::Revoke(cert_id));
+    }
+
+    /// Burn a certificate whose physical item was destroyed (current owner only)
+    /// 
+    /// The certificate is removed from the registry and its ID is retired, so it
+    /// can never be re-issued. The transfer history is kept for provenance.
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate to burn
+    /// 
+    /// # Panics
+    /// * If called by non-owner
+    /// * If certificate doesn't exist
+    /// * If a dispute over the certificate is open
+    /// * If the owner locked the certificate
+    pub fn burn(env: Env, cert_id: String) {
+        // Get existing certificate
+        let certificate = require_cert(&env, &cert_id);
+
+        // Require authentication from current owner
+        certificate.owner.require_auth();
+
+        // The ruling has to be made on a certificate that still exists
+        if is_disputed(&env, &cert_id) {
+            panic!("Certificate is disputed");
+        }
+        if is_locked(&env, &cert_id) {
+            panic!("Certificate is locked");
+        }
+
+        // Remove certificate and any outstanding approval
+        remove_cert(&env, &cert_id);
+        clear_approval(&env, cert_id.clone());
+        move_balance(&env, Some(&certificate.owner), None);
+
+        // A destroyed item leaves its set, and its accessories stand alone
+        detach_child(&env, &cert_id);
+        for child_id in Self::get_children(env.clone(), cert_id.clone()).iter() {
+            env.storage().persistent().remove(&(PARENT_KEY, child_id));
+        }
+        env.storage().persistent().remove(&(CHILDREN_KEY, cert_id.clone()));
+        env.storage().persistent().remove(&(BUNDLE_KEY, cert_id.clone()));
+
+        remove_from_collection(&env, &cert_id);
+        clear_attributes(&env, &cert_id);
+        unindex_hash(&env, &cert_id, certificate.metadata_hash.clone());
+
+        // The chip went with the item, so free it from the reverse index
+        let tag_key = (TAG_KEY, cert_id.clone());
+        if let Some(tag_id) = env.storage().persistent().get::<_, BytesN<32>>(&tag_key) {
+            env.storage().persistent().remove(&(TAG_INDEX_KEY, tag_id));
+            env.storage().persistent().remove(&tag_key);
+        }
+
+        // Retire the ID, remembering the ledger it was burned in
+        let key = (BURNED_KEY, cert_id.clone());
+        env.storage().persistent().set(&key, &env.ledger().sequence());
+        bump_persistent(&env, &key);
+
+        env.events().publish((BURNED_EVENT, cert_id), certificate.owner);
+    }
+
+    /// Check if a certificate ID has been burned
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate identifier to check
+    /// 
+    /// # Returns
+    /// * `true` if the certificate was burned, `false` otherwise
+    pub fn is_burned(env: Env, cert_id: String) -> bool {
+        env.storage().persistent().has(&(BURNED_KEY, cert_id))
+    }
+
+    /// Replace the contract's wasm with a previously uploaded build (admin only)
+    /// 
+    /// Certificates and all other storage are kept across the upgrade.
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `new_wasm_hash` - Hash of the uploaded wasm to switch to
+    /// 
+    /// # Panics
+    /// * If called by non-admin
+    /// * If multisig is enabled (use `propose` instead)
+    /// * If contract is not initialized
+    /// * If no wasm with that hash has been uploaded
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        // Get admin address and require authentication
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+        ensure_no_multisig(&env);
+
+        execute_action(&env, AdminAction::Upgrade(new_wasm_hash));
+    }
+
+    /// Bring stored data up to this build's schema after an upgrade (admin only)
+    /// 
+    /// v1 → v2 moves certificates out of the instance map into their own
+    /// persistent entries and backfills owner balances and the metadata
+    /// hash index, which v1 didn't track.
+    /// 
+    /// # Returns
+    /// * Storage version after the migration
+    /// 
+    /// # Panics
+    /// * If called by non-admin
+    /// * If multisig is enabled (use `propose` instead)
+    /// * If storage is already at this build's version
+    pub fn migrate(env: Env) -> u32 {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+        ensure_no_multisig(&env);
+
+        execute_action(&env, AdminAction::Migrate);
+        STORAGE_VERSION
+    }
+
+    /// Halt issuance, transfers and sales (admin only)
+    /// 
+    /// # Panics
+    /// * If called by non-admin
+    /// * If multisig is enabled (use `propose` instead)
+    pub fn pause(env: Env) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+        ensure_no_multisig(&env);
+
+        execute_action(&env, AdminAction::Pause);
+    }
+
+    /// Resume issuance, transfers and sales (admin only)
+    /// 
+    /// # Panics
+    /// * If called by non-admin
+    /// * If multisig is enabled (use `propose` instead)
+    pub fn unpause(env: Env) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+        ensure_no_multisig(&env);
+
+        execute_action(&env, AdminAction::Unpause);
+    }
+
+    /// Turn compliance mode on or off (admin only)
+    /// 
+    /// While on, certificates can only be transferred or sold to addresses
+    /// on the allowlist.
+    /// 
+    /// # Panics
+    /// * If called by non-admin
+    /// * If multisig is enabled (use `propose` instead)
+    pub fn set_allowlist_mode(env: Env, enabled: bool) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+        ensure_no_multisig(&env);
+
+        execute_action(&env, AdminAction::SetAllowlistMode(enabled));
+    }
+
+    /// Check whether compliance mode is on
+    pub fn is_allowlist_mode(env: Env) -> bool {
+        env.storage().instance().get(&KYC_MODE_KEY).unwrap_or(false)
+    }
+
+    /// Add a verified counterparty to the allowlist (admin only)
+    pub fn add_to_allowlist(env: Env, account: Address) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+
+        let key = (ALLOWLIST_KEY, account);
+        env.storage().persistent().set(&key, &true);
+        bump_persistent(&env, &key);
+    }
+
+    /// Remove an address from the allowlist (admin only)
+    /// 
+    /// # Panics
+    /// * If called by non-admin
+    /// * If multisig is enabled (use `propose` instead)
+    pub fn remove_from_allowlist(env: Env, account: Address) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+        ensure_no_multisig(&env);
+
+        execute_action(&env, AdminAction::RemoveFromAllowlist(account));
+    }
+
+    /// Check whether an address is on the allowlist
+    pub fn is_allowlisted(env: Env, account: Address) -> bool {
+        env.storage().persistent().has(&(ALLOWLIST_KEY, account))
+    }
+
+    /// Check whether the contract is paused
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().instance().get(&PAUSED_KEY).unwrap_or(false)
+    }
+
+    /// Hand the admin role to another address (admin only)
+    /// 
+    /// # Panics
+    /// * If called by non-admin
+    /// * If multisig is enabled (use `propose` instead)
+    pub fn set_admin(env: Env, new_admin: Address) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+        ensure_no_multisig(&env);
+
+        execute_action(&env, AdminAction::SetAdmin(new_admin));
+    }
+
+    /// Enable M-of-N approval for destructive admin actions (admin only)
+    /// 
+    /// Once enabled, `revoke`, `pause`, `unpause`, `upgrade`, `migrate`,
+    /// `set_admin`, `set_config`, `set_policy`, `set_allowlist_mode`,
+    /// `remove_from_allowlist`, suspending issuers, dispute rulings and signer
+    /// changes only run through `propose` / `approve_proposal`.
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `signers` - Addresses allowed to propose and approve
+    /// * `threshold` - Approvals required for an action to execute
+    /// 
+    /// # Panics
+    /// * If called by non-admin
+    /// * If multisig is already enabled (propose `SetMultisig` instead)
+    /// * If the threshold is zero or larger than the signer set
+    pub fn set_multisig(env: Env, signers: Vec<Address>, threshold: u32) {
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+        ensure_no_multisig(&env);
+
+        execute_action(&env, AdminAction::SetMultisig(signers, threshold));
+    }
+
+    /// Get the multisig configuration, if enabled
+    pub fn get_multisig(env: Env) -> Option<MultisigConfig> {
+        env.storage().instance().get(&MULTISIG_KEY)
+    }
+
+    /// Propose an admin action; the proposer's approval is counted (signers only)
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `proposer` - Signer creating the proposal
+    /// * `action` - Action to run once enough signers approve
+    /// 
+    /// # Returns
+    /// * Proposal ID
+    /// 
+    /// # Panics
+    /// * If multisig is not enabled
+    /// * If the proposer is not a signer
+    pub fn propose(env: Env, proposer: Address, action: AdminAction) -> u32 {
+        proposer.require_auth();
+        let config = require_signer(&env, &proposer);
+
+        let id: u32 = env.storage().instance().get(&PROP_SEQ_KEY).unwrap_or(0) + 1;
+        env.storage().instance().set(&PROP_SEQ_KEY, &id);
+
+        let mut approvals = Vec::new(&env);
+        approvals.push_back(proposer);
+        let proposal = Proposal { action, approvals, executed: false };
+        store_proposal(&env, id, proposal, &config);
+        id
+    }
+
+    /// Approve a pending proposal, executing it once the threshold is met (signers only)
+    /// 
+    /// Approvals from addresses since removed from the signer set don't count
+    /// towards the threshold.
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `signer` - Approving signer
+    /// * `proposal_id` - Proposal to approve
+    /// 
+    /// # Panics
+    /// * If multisig is not enabled
+    /// * If the approver is not a signer
+    /// * If the proposal doesn't exist, already ran, or was already approved by this signer
+    pub fn approve_proposal(env: Env, signer: Address, proposal_id: u32) {
+        signer.require_auth();
+        let config = require_signer(&env, &signer);
+
+        let mut proposal: Proposal = env.storage().persistent()
+            .get(&(PROPOSAL_KEY, proposal_id))
+            .expect("Proposal not found");
+        if proposal.executed {
+            panic!("Proposal already executed");
+        }
+        if proposal.approvals.contains(&signer) {
+            panic!("Proposal already approved by signer");
+        }
+
+        proposal.approvals.push_back(signer);
+        store_proposal(&env, proposal_id, proposal, &config);
+    }
+
+    /// Get a proposal by ID
+    pub fn get_proposal(env: Env, proposal_id: u32) -> Option<Proposal> {
+        env.storage().persistent().get(&(PROPOSAL_KEY, proposal_id))
+    }
+
+    /// Get the version of the deployed contract build
+    /// 
+    /// Lets tooling detect which entrypoints the deployed wasm supports.
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// 
+    /// # Returns
+    /// * Contract semver and storage schema version compiled into the wasm
+    pub fn get_version(env: Env) -> VersionInfo {
+        VersionInfo {
+            version: String::from_str(&env, CONTRACT_VERSION),
+            storage_version: STORAGE_VERSION,
+        }
+    }
+
+    /// Get the current admin address (utility function)
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// 
+    /// # Returns
+    /// * Admin address
+    /// 
+    /// # Panics
+    /// * If contract is not initialized
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized")
+    }
+
+    /// Check if a certificate exists
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate identifier to check
+    /// 
+    /// # Returns
+    /// * `true` if certificate exists, `false` otherwise
+    pub fn certificate_exists(env: Env, cert_id: String) -> bool {
+        has_cert(&env, &cert_id)
+    }
+
+    /// Name of the certificate collection (NFT-style metadata)
+    pub fn name(env: Env) -> String {
+        String::from_str(&env, TOKEN_NAME)
+    }
+
+    /// Symbol of the certificate collection (NFT-style metadata)
+    pub fn symbol(env: Env) -> String {
+        String::from_str(&env, TOKEN_SYMBOL)
+    }
+
+    /// Number of certificates held by an address
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `owner` - Address to count certificates for
+    /// 
+    /// # Returns
+    /// * Certificates issued or transferred to `owner` under storage schema v2+
+    pub fn balance_of(env: Env, owner: Address) -> u32 {
+        env.storage().persistent()
+            .get(&(BALANCE_KEY, owner))
+            .unwrap_or(0)
+    }
+
+    /// Current owner of a certificate
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate identifier
+    /// 
+    /// # Returns
+    /// * Owner address
+    /// 
+    /// # Panics
+    /// * If certificate doesn't exist
+    pub fn owner_of(env: Env, cert_id: String) -> Address {
+        require_cert(&env, &cert_id).owner
+    }
+
+    /// URI of the certificate's metadata, derived from its metadata hash
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate identifier
+    /// 
+    /// # Returns
+    /// * `ipfs://<metadata_hash>`
+    /// 
+    /// # Panics
+    /// * If certificate doesn't exist
+    /// * If the resulting URI is longer than 256 bytes
+    pub fn token_uri(env: Env, cert_id: String) -> String {
+        let certificate = require_cert(&env, &cert_id);
+
+        let prefix_len = TOKEN_URI_PREFIX.len();
+        let hash_len = certificate.metadata_hash.len() as usize;
+        if prefix_len + hash_len > MAX_TOKEN_URI_LEN {
+            panic!("Token URI too long");
+        }
+
+        let mut buf = [0u8; MAX_TOKEN_URI_LEN];
+        buf[..prefix_len].copy_from_slice(TOKEN_URI_PREFIX);
+        certificate.metadata_hash.copy_into_slice(&mut buf[prefix_len..prefix_len + hash_len]);
+        String::from_bytes(&env, &buf[..prefix_len + hash_len])
+    }
+
+    /// Extend the TTL of a certificate's storage entries (admin only)
+    /// 
+    /// Writes already keep entries alive for ~30 days; this lets the admin keep
+    /// rarely touched certificates alive for longer.
+    /// 
+    /// # Arguments
+    /// * `env` - Soroban environment
+    /// * `cert_id` - Certificate whose entries should be extended
+    /// * `ledgers` - Number of ledgers the entries should stay live for
+    /// 
+    /// # Panics
+    /// * If called by non-admin
+    /// * If contract is not initialized
+    /// * If neither the certificate nor its burn record exists
+    pub fn bump_storage(env: Env, cert_id: String, ledgers: u32) {
+        // Get admin address and require authentication
+        let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+            .expect("Contract not initialized");
+        admin.require_auth();
+
+        let storage = env.storage().persistent();
+        let cert_key = (CERTS_KEY, cert_id.clone());
+        let burned_key = (BURNED_KEY, cert_id.clone());
+        if !storage.has(&cert_key) && !storage.has(&burned_key) {
+            panic_with_error!(env, Error::CertificateNotFound);
+        }
+
+        // Extend every persistent entry belonging to this certificate
+        if let Some(certificate) = storage.get::<_, Certificate>(&cert_key) {
+            storage.extend_ttl(&cert_key, ledgers, ledgers);
+            let hash_key = (HASH_INDEX_KEY, certificate.metadata_hash);
+            if storage.has(&hash_key) {
+                storage.extend_ttl(&hash_key, ledgers, ledgers);
+            }
+        }
+        if storage.has(&burned_key) {
+            storage.extend_ttl(&burned_key, ledgers, ledgers);
+        }
+        let history_key = (HISTORY_KEY, cert_id.clone());
+        if storage.has(&history_key) {
+            storage.extend_ttl(&history_key, ledgers, ledgers);
+        }
+        let edition_key = (CERT_EDITION_KEY, cert_id.clone());
+        if storage.has(&edition_key) {
+            storage.extend_ttl(&edition_key, ledgers, ledgers);
+        }
+        let attach_key = (ATTACH_KEY, cert_id.clone());
+        if storage.has(&attach_key) {
+            storage.extend_ttl(&attach_key, ledgers, ledgers);
+        }
+        let status_log_key = (STATUS_LOG_KEY, cert_id.clone());
+        if storage.has(&status_log_key) {
+            storage.extend_ttl(&status_log_key, ledgers, ledgers);
+        }
+        let stats_key = (XFER_STATS_KEY, cert_id.clone());
+        if storage.has(&stats_key) {
+            storage.extend_ttl(&stats_key, ledgers, ledgers);
+        }
+        let approval_key = (APPROVALS_KEY, cert_id.clone());
+        if storage.has(&approval_key) {
+            storage.extend_ttl(&approval_key, ledgers, ledgers);
+        }
+        let listing_key = (LISTING_KEY, cert_id.clone());
+        if storage.has(&listing_key) {
+            storage.extend_ttl(&listing_key, ledgers, ledgers);
+        }
+        let locked_key = (LOCKED_KEY, cert_id.clone());
+        if storage.has(&locked_key) {
+            storage.extend_ttl(&locked_key, ledgers, ledgers);
+        }
+        let children_key = (CHILDREN_KEY, cert_id.clone());
+        if storage.has(&children_key) {
+            storage.extend_ttl(&children_key, ledgers, ledgers);
+        }
+        let parent_key = (PARENT_KEY, cert_id.clone());
+        if storage.has(&parent_key) {
+            storage.extend_ttl(&parent_key, ledgers, ledgers);
+        }
+        let bundle_key = (BUNDLE_KEY, cert_id.clone());
+        if storage.has(&bundle_key) {
+            storage.extend_ttl(&bundle_key, ledgers, ledgers);
+        }
+        let service_key = (SERVICE_KEY, cert_id.clone());
+        if storage.has(&service_key) {
+            storage.extend_ttl(&service_key, ledgers, ledgers);
+        }
+        let appraisal_key = (APPRAISAL_KEY, cert_id.clone());
+        if storage.has(&appraisal_key) {
+            storage.extend_ttl(&appraisal_key, ledgers, ledgers);
+        }
+        let attrs_key = (ATTRS_KEY, cert_id.clone());
+        if let Some(attributes) = storage.get::<_, ItemAttributes>(&attrs_key) {
+            storage.extend_ttl(&attrs_key, ledgers, ledgers);
+            let serial_key = (SERIAL_KEY, attributes.brand, attributes.serial_number);
+            storage.extend_ttl(&serial_key, ledgers, ledgers);
+        }
+        let cleared_key = (CLEARED_KEY, cert_id.clone());
+        if storage.has(&cleared_key) {
+            storage.extend_ttl(&cleared_key, ledgers, ledgers);
+        }
+        let claim_key = (CLAIM_KEY, cert_id.clone());
+        if storage.has(&claim_key) {
+            storage.extend_ttl(&claim_key, ledgers, ledgers);
+        }
+        let dispute_key = (DISPUTE_KEY, cert_id.clone());
+        if storage.has(&dispute_key) {
+            storage.extend_ttl(&dispute_key, ledgers, ledgers);
+        }
+        let warranty_key = (WARRANTY_KEY, cert_id.clone());
+        if storage.has(&warranty_key) {
+            storage.extend_ttl(&warranty_key, ledgers, ledgers);
+        }
+        let cert_coll_key = (CERT_COLL_KEY, cert_id.clone());
+        if let Some((collection_id, index)) = storage.get::<_, (u32, u32)>(&cert_coll_key) {
+            storage.extend_ttl(&cert_coll_key, ledgers, ledgers);
+            storage.extend_ttl(&(COLL_ITEM_KEY, collection_id, index), ledgers, ledgers);
+        }
+        let tag_key = (TAG_KEY, cert_id);
+        if let Some(tag_id) = storage.get::<_, BytesN<32>>(&tag_key) {
+            storage.extend_ttl(&tag_key, ledgers, ledgers);
+            storage.extend_ttl(&(TAG_INDEX_KEY, tag_id), ledgers, ledgers);
+        }
+
+        // The instance holds the admin and config every call depends on
+        env.storage().instance().extend_ttl(ledgers, ledgers);
+    }
+}
+
+/// Extend the instance TTL (admin, config) if it is running low
+fn bump_instance(env: &Env) {
+    let config = read_config(env);
+    env.storage().instance().extend_ttl(config.ttl_threshold, config.ttl_extend_to);
+}
+
+/// Extend the TTL of a persistent entry if it is running low
+fn bump_persistent<K>(env: &Env, key: &K)
+where
+    K: IntoVal<Env, Val>,
+{
+    let config = read_config(env);
+    env.storage().persistent().extend_ttl(key, config.ttl_threshold, config.ttl_extend_to);
+}
+
+/// Load the operational config, falling back to the built-in defaults
+fn read_config(env: &Env) -> Config {
+    let config: Option<Config> = env.storage().instance().get(&CONFIG_KEY);
+    let mut config = config.unwrap_or(Config {
+        max_batch_size: MAX_PAGE_SIZE,
+        issuance_fee: 0,
+        ttl_threshold: TTL_THRESHOLD,
+        ttl_extend_to: TTL_EXTEND_TO,
+        transfer_cooldown: 0,
+    });
+
+    // `set_issuance_fee` may have changed the amount since the config was saved
+    let fee: Option<IssuanceFee> = env.storage().instance().get(&FEE_KEY);
+    config.issuance_fee = fee.map(|fee| fee.amount).unwrap_or(0);
+    config
+}
+
+/// Panic if the certificate changed hands less than the configured cooldown ago
+fn ensure_cooldown_elapsed(env: &Env, cert_id: &String) {
+    let cooldown = read_config(env).transfer_cooldown;
+    if cooldown == 0 {
+        return;
+    }
+
+    let history: Option<Vec<TransferRecord>> = env.storage().persistent()
+        .get(&(HISTORY_KEY, cert_id.clone()));
+    if let Some(last) = history.and_then(|h| h.last()) {
+        if env.ledger().timestamp() < last.timestamp.saturating_add(cooldown) {
+            panic!("Transfer cooldown active");
+        }
+    }
+}
+
+/// Load a certificate, falling back to the schema v1 instance map
+fn read_cert(env: &Env, cert_id: &String) -> Option<Certificate> {
+    let cert: Option<Certificate> = env.storage().persistent()
+        .get(&(CERTS_KEY, cert_id.clone()));
+    if cert.is_some() {
+        return cert;
+    }
+
+    let legacy: Option<Map<String, Certificate>> = env.storage().instance().get(&CERTS_KEY);
+    legacy.and_then(|certs| certs.get(cert_id.clone()))
+}
+
+/// Load a certificate, failing with `CertificateNotFound` if it doesn't exist
+fn require_cert(env: &Env, cert_id: &String) -> Certificate {
+    read_cert(env, cert_id).unwrap_or_else(|| panic_with_error!(env, Error::CertificateNotFound))
+}
+
+/// Check whether a certificate is stored under either schema
+fn has_cert(env: &Env, cert_id: &String) -> bool {
+    read_cert(env, cert_id).is_some()
+}
+
+/// Store a certificate in its own persistent entry and keep it alive
+fn write_cert(env: &Env, cert_id: &String, certificate: &Certificate) {
+    let key = (CERTS_KEY, cert_id.clone());
+    env.storage().persistent().set(&key, certificate);
+    bump_persistent(env, &key);
+    bump_instance(env);
+}
+
+/// Delete a certificate from both the persistent entries and the v1 map
+fn remove_cert(env: &Env, cert_id: &String) {
+    env.storage().persistent().remove(&(CERTS_KEY, cert_id.clone()));
+
+    let legacy: Option<Map<String, Certificate>> = env.storage().instance().get(&CERTS_KEY);
+    if let Some(mut certs) = legacy {
+        if certs.contains_key(cert_id.clone()) {
+            certs.remove(cert_id.clone());
+            env.storage().instance().set(&CERTS_KEY, &certs);
+        }
+    }
+}
+
+/// Store the optional data supplied with an issuance by `issuer`
+fn apply_issue_options(env: &Env, issuer: &Address, cert_id: &String, options: IssueOptions) {
+    if let Some(tag_id) = options.tag_id {
+        bind_tag(env, cert_id, tag_id);
+    }
+    if let Some(collection_id) = options.collection_id {
+        add_to_collection(env, issuer, cert_id, collection_id);
+    }
+    if let Some(expires_at) = options.warranty_expires_at {
+        let key = (WARRANTY_KEY, cert_id.clone());
+        env.storage().persistent().set(&key, &expires_at);
+        bump_persistent(env, &key);
+    }
+}
+
+/// Panic unless `caller` is the admin or the issuer owning `cert_id`'s prefix
+fn require_cert_issuer(env: &Env, caller: &Address, cert_id: &String) {
+    let admin: Address = env.storage().instance().get(&ADMIN_KEY)
+        .expect("Contract not initialized");
+    if *caller == admin {
+        return;
+    }
+
+    let issuer: Option<Issuer> = env.storage().persistent().get(&(ISSUER_KEY, caller.clone()));
+    if !issuer.is_some_and(|issuer| has_prefix(cert_id, &issuer.prefix)) {
+        panic_with_error!(env, Error::NotAuthorized);
+    }
+    if issuer_status(env, caller) != IssuerStatus::Active {
+        panic!("Issuer is deactivated");
+    }
+}
+
+/// Panic unless `issuer` is registered and active, returning its registration
+fn require_active_issuer(env: &Env, issuer: &Address) -> Issuer {
+    let info: Issuer = env.storage().persistent()
+        .get(&(ISSUER_KEY, issuer.clone()))
+        .expect("Issuer not registered");
+    if issuer_status(env, issuer) != IssuerStatus::Active {
+        panic!("Issuer is deactivated");
+    }
+    info
+}
+
+/// Require authorization from the active issuer owning `collection_id`
+fn require_collection_issuer(env: &Env, collection_id: u32) -> Address {
+    let issuer: Address = env.storage().persistent()
+        .get(&(COLL_ISSUER_KEY, collection_id))
+        .expect("Collection not found");
+    issuer.require_auth();
+    require_active_issuer(env, &issuer);
+    issuer
+}
+
+/// Get an issuer's standing; issuers without a status entry are active
+fn issuer_status(env: &Env, issuer: &Address) -> IssuerStatus {
+    env.storage().persistent()
+        .get(&(ISSUER_STATUS_KEY, issuer.clone()))
+        .unwrap_or(IssuerStatus::Active)
+}
+
+/// Whether the issuer owning `cert_id`'s prefix was suspended and the
+/// certificate hasn't been cleared on review since
+fn is_suspended(env: &Env, cert_id: &String) -> bool {
+    let Some(issuer) = prefix_owner(env, cert_id) else {
+        return false;
+    };
+
+    issuer_status(env, &issuer) == IssuerStatus::Suspended
+        && !env.storage().persistent().has(&(CLEARED_KEY, cert_id.clone()))
+}
+
+/// Remove a certificate's attributes and serial number index entry
+fn clear_attributes(env: &Env, cert_id: &String) {
+    let attrs_key = (ATTRS_KEY, cert_id.clone());
+    let attributes: Option<ItemAttributes> = env.storage().persistent().get(&attrs_key);
+    if let Some(attributes) = attributes {
+        env.storage().persistent().remove(&(SERIAL_KEY, attributes.brand, attributes.serial_number));
+        env.storage().persistent().remove(&attrs_key);
+    }
+}
+
+/// Panic if `cert_id` falls in a prefix reserved for someone other than `issuer`
+fn ensure_namespace(env: &Env, issuer: &Address, cert_id: &String) {
+    // Prefixes don't overlap, so IDs in the issuer's own range are nobody else's
+    let registered: Option<Issuer> = env.storage().persistent().get(&(ISSUER_KEY, issuer.clone()));
+    if registered.is_some_and(|registered| has_prefix(cert_id, &registered.prefix)) {
+        return;
+    }
+
+    if prefix_owner(env, cert_id).is_some_and(|owner| owner != *issuer) {
+        panic!("Certificate ID is in another issuer's namespace");
+    }
+}
+
+/// Reserve `prefix` for `issuer`
+/// 
+/// Each shorter stem of the prefix is marked too, so a later prefix that
+/// the new one would extend is refused without listing every issuer.
+fn reserve_prefix(env: &Env, issuer: &Address, prefix: &String) {
+    let key = (PREFIX_KEY, prefix.clone());
+    env.storage().persistent().set(&key, issuer);
+    bump_persistent(env, &key);
+
+    let len = prefix.len() as usize;
+    let mut buf = [0u8; MAX_CERT_ID_LEN];
+    prefix.copy_into_slice(&mut buf[..len]);
+    for stem_len in 1..len {
+        let key = (PREFIX_STEM_KEY, String::from_bytes(env, &buf[..stem_len]));
+        env.storage().persistent().set(&key, &true);
+        bump_persistent(env, &key);
+    }
+
+    // At most one entry per possible length, however many issuers register
+    let mut lens: Vec<u32> = env.storage().instance()
+        .get(&PREFIX_LENS_KEY)
+        .unwrap_or(Vec::new(env));
+    if !lens.contains(prefix.len()) {
+        lens.push_back(prefix.len());
+        env.storage().instance().set(&PREFIX_LENS_KEY, &lens);
+    }
+}
+
+/// The issuer whose reserved prefix `value` starts with, if any
+/// 
+/// Only the lengths of registered prefixes are looked up, and at most one
+/// can match since prefixes don't overlap.
+fn prefix_owner(env: &Env, value: &String) -> Option<Address> {
+    let value_len = value.len() as usize;
+    // IDs longer than issuance allows predate namespaces and belong to no issuer
+    if value_len > MAX_CERT_ID_LEN {
+        return None;
+    }
+    let lens: Vec<u32> = env.storage().instance().get(&PREFIX_LENS_KEY)?;
+
+    let mut buf = [0u8; MAX_CERT_ID_LEN];
+    value.copy_into_slice(&mut buf[..value_len]);
+    lens.iter()
+        .filter(|len| *len as usize <= value_len)
+        .find_map(|len| {
+            env.storage().persistent().get(&(PREFIX_KEY, String::from_bytes(env, &buf[..len as usize])))
+        })
+}
+
+/// Reject malformed certificate IDs before they are stored forever
+fn validate_cert_id(env: &Env, cert_id: &String) {
+    let len = cert_id.len();
+    if len < MIN_CERT_ID_LEN || len as usize > MAX_CERT_ID_LEN {
+        panic_with_error!(env, Error::InvalidCertIdLength);
+    }
+
+    let mut buf = [0u8; MAX_CERT_ID_LEN];
+    cert_id.copy_into_slice(&mut buf[..len as usize]);
+    let allowed = |c: &u8| c.is_ascii_alphanumeric() || matches!(c, b'-' | b'_' | b'.');
+    if !buf[..len as usize].iter().all(allowed) {
+        panic_with_error!(env, Error::InvalidCertIdChar);
+    }
+}
+
+/// Whether `value` starts with `prefix`
+fn has_prefix(value: &String, prefix: &String) -> bool {
+    let value_len = value.len() as usize;
+    let prefix_len = prefix.len() as usize;
+    // IDs longer than issuance allows predate namespaces and belong to no issuer
+    if prefix_len > value_len || value_len > MAX_CERT_ID_LEN {
+        return false;
+    }
+
+    let mut value_buf = [0u8; MAX_CERT_ID_LEN];
+    let mut prefix_buf = [0u8; MAX_CERT_ID_LEN];
+    value.copy_into_slice(&mut value_buf[..value_len]);
+    prefix.copy_into_slice(&mut prefix_buf[..prefix_len]);
+    value_buf[..prefix_len] == prefix_buf[..prefix_len]
+}
+
+/// Collect the configured issuance fee from the issuer
+fn charge_issuance_fee(env: &Env, issuer: &Address) {
+    let fee: Option<IssuanceFee> = env.storage().instance().get(&FEE_KEY);
+    if let Some(fee) = fee {
+        token::Client::new(env, &fee.token).transfer(issuer, &fee.treasury, &fee.amount);
+    }
+}
+
+/// Charge the issuance fee for `count` certificates in one payment
+fn charge_batch_fee(env: &Env, issuer: &Address, count: u32) {
+    let fee: Option<IssuanceFee> = env.storage().instance().get(&FEE_KEY);
+    if let Some(fee) = fee {
+        let amount = fee.amount.checked_mul(i128::from(count))
+            .expect("Batch fee overflows");
+        token::Client::new(env, &fee.token).transfer(issuer, &fee.treasury, &amount);
+    }
+}
+
+/// Store a freshly issued certificate after checking the ID and hash are free
+fn create_cert(
+    env: &Env,
+    issuer: &Address,
+    cert_id: &String,
+    metadata_hash: String,
+    owner: Address,
+    allow_duplicate_hash: bool,
+) {
+    validate_cert_id(env, cert_id);
+    ensure_not_paused(env);
+    ensure_namespace(env, issuer, cert_id);
+    if let Some(policy) = read_policy(env) {
+        if !policy.check_issue(cert_id, &owner) {
+            panic!("Rejected by policy contract");
+        }
+    }
+
+    // Prevent duplicate certificate IDs
+    if has_cert(env, cert_id) {
+        panic_with_error!(env, Error::CertificateExists);
+    }
+
+    // Burned IDs are retired forever so they can't be reused for another item
+    if env.storage().persistent().has(&(BURNED_KEY, cert_id.clone())) {
+        panic!("Certificate ID was burned");
+    }
+
+    // Index the hash so holders of the metadata can find the certificate
+    let index_key = (HASH_INDEX_KEY, metadata_hash.clone());
+    let mut indexed: Vec<String> = env.storage().persistent()
+        .get(&index_key)
+        .unwrap_or(Vec::new(env));
+    // One genuine item's metadata must not back a second (counterfeit) certificate
+    if !indexed.is_empty() && !allow_duplicate_hash {
+        panic!("Metadata hash already registered");
+    }
+    indexed.push_back(cert_id.clone());
+    env.storage().persistent().set(&index_key, &indexed);
+    bump_persistent(env, &index_key);
+
+    // Create new certificate with valid status
+    let certificate = Certificate {
+        owner: owner.clone(),
+        metadata_hash,
+        is_valid: true,
+    };
+
+    // Store certificate and update persistent storage
+    write_cert(env, cert_id, &certificate);
+    move_balance(env, None, Some(&owner));
+    log_status(env, cert_id, true);
+
+    env.events().publish((ISSUED_EVENT, cert_id.clone()), (owner, certificate.metadata_hash));
+}
+
+/// Remove a certificate from its parent's children, if it has a parent
+fn detach_child(env: &Env, child_id: &String) {
+    let parent_key = (PARENT_KEY, child_id.clone());
+    let Some(parent_id) = env.storage().persistent().get::<_, String>(&parent_key) else {
+        return;
+    };
+
+    let children_key = (CHILDREN_KEY, parent_id);
+    let mut children: Vec<String> = env.storage().persistent()
+        .get(&children_key)
+        .unwrap_or(Vec::new(env));
+    if let Some(index) = children.first_index_of(child_id) {
+        children.remove(index);
+    }
+    if children.is_empty() {
+        env.storage().persistent().remove(&children_key);
+    } else {
+        env.storage().persistent().set(&children_key, &children);
+    }
+    env.storage().persistent().remove(&parent_key);
+}
+
+/// Move a parent's children along with it when its owner opted in
+/// 
+/// Only children still held by `from` move; a child listed in escrow
+/// stays with its listing. Each child must pass the same checks as a
+/// transfer of its own, otherwise the whole transfer is refused.
+fn carry_children(env: &Env, parent_id: &String, from: &Address, to: &Address) {
+    if !env.storage().persistent().has(&(BUNDLE_KEY, parent_id.clone())) {
+        return;
+    }
+
+    let children: Vec<String> = env.storage().persistent()
+        .get(&(CHILDREN_KEY, parent_id.clone()))
+        .unwrap_or(Vec::new(env));
+    for child_id in children.iter() {
+        let Some(mut child) = read_cert(env, &child_id) else {
+            continue;
+        };
+        if child.owner != *from {
+            continue;
+        }
+        if !child.is_valid {
+            panic_with_error!(env, Error::CertificateRevoked);
+        }
+        if is_locked(env, &child_id) {
+            panic!("Certificate is locked");
+        }
+        if is_disputed(env, &child_id) {
+            panic!("Certificate is disputed");
+        }
+        if is_suspended(env, &child_id) {
+            panic!("Certificate is suspended");
+        }
+        ensure_allowed_recipient(env, to);
+        ensure_policy_allows_transfer(env, &child_id, from, to);
+
+        child.owner = to.clone();
+        write_cert(env, &child_id, &child);
+        move_balance(env, Some(from), Some(to));
+        clear_approval(env, child_id.clone());
+        carry_children(env, &child_id, from, to);
+
+        append_history(env, child_id, TransferRecord {
+            from: from.clone(),
+            to: to.clone(),
+            price: None,
+            currency: None,
+            memo: None,
+            token: None,
+            ledger: env.ledger().sequence(),
+            timestamp: env.ledger().timestamp(),
+        });
+    }
+}
+
+/// Panic unless `account` holds `role`
+fn require_role(env: &Env, account: &Address, role: Role) {
+    if !env.storage().persistent().has(&(ROLE_KEY, role, account.clone())) {
+        panic!("Missing required role");
+    }
+}
+
+/// Drop a certificate from the metadata hash index
+fn unindex_hash(env: &Env, cert_id: &String, metadata_hash: String) {
+    let index_key = (HASH_INDEX_KEY, metadata_hash);
+    let indexed: Option<Vec<String>> = env.storage().persistent().get(&index_key);
+    let Some(mut indexed) = indexed else {
+        return;
+    };
+
+    if let Some(index) = indexed.first_index_of(cert_id) {
+        indexed.remove(index);
+    }
+    if indexed.is_empty() {
+        env.storage().persistent().remove(&index_key);
+    } else {
+        env.storage().persistent().set(&index_key, &indexed);
+    }
+}
+
+/// Append a certificate issued by `issuer` to a collection's index
+/// 
+/// Only the issuer owning the collection can fill it, the admin included,
+/// so nobody else can spend a limited edition's numbers.
+fn add_to_collection(env: &Env, issuer: &Address, cert_id: &String, collection_id: u32) {
+    if !env.storage().persistent().has(&(COLLECTION_KEY, collection_id)) {
+        panic!("Collection not found");
+    }
+    let owner: Option<Address> = env.storage().persistent().get(&(COLL_ISSUER_KEY, collection_id));
+    if owner.is_some_and(|owner| owner != *issuer) {
+        panic!("Collection belongs to another issuer");
+    }
+
+    let size_key = (COLL_SIZE_KEY, collection_id);
+    let index: u32 = env.storage().persistent().get(&size_key).unwrap_or(0);
+    let item_key = (COLL_ITEM_KEY, collection_id, index);
+    let cert_key = (CERT_COLL_KEY, cert_id.clone());
+    env.storage().persistent().set(&item_key, cert_id);
+    env.storage().persistent().set(&cert_key, &(collection_id, index));
+    env.storage().persistent().set(&size_key, &(index + 1));
+    bump_persistent(env, &item_key);
+    bump_persistent(env, &cert_key);
+    bump_persistent(env, &size_key);
+
+    // Limited editions number items in issuance order; burned numbers aren't reused
+    let edition_total: Option<u32> = env.storage().persistent().get(&(EDITION_KEY, collection_id));
+    if let Some(edition_total) = edition_total {
+        let minted_key = (MINTED_KEY, collection_id);
+        let edition_number = env.storage().persistent().get::<_, u32>(&minted_key).unwrap_or(0) + 1;
+        if edition_number > edition_total {
+            panic!("Edition is fully issued");
+        }
+        let edition_key = (CERT_EDITION_KEY, cert_id.clone());
+        env.storage().persistent().set(&minted_key, &edition_number);
+        env.storage().persistent().set(&edition_key, &Edition { edition_number, edition_total });
+        bump_persistent(env, &minted_key);
+        bump_persistent(env, &edition_key);
+    }
+}
+
+/// Drop a certificate from its collection, moving the last entry into its slot
+fn remove_from_collection(env: &Env, cert_id: &String) {
+    let cert_key = (CERT_COLL_KEY, cert_id.clone());
+    let Some((collection_id, index)) = env.storage().persistent().get::<_, (u32, u32)>(&cert_key) else {
+        return;
+    };
+
+    let size_key = (COLL_SIZE_KEY, collection_id);
+    let last: u32 = env.storage().persistent().get::<_, u32>(&size_key).unwrap_or(1) - 1;
+    if index != last {
+        let moved: String = env.storage().persistent()
+            .get(&(COLL_ITEM_KEY, collection_id, last))
+            .expect("Collection index corrupted");
+        env.storage().persistent().set(&(COLL_ITEM_KEY, collection_id, index), &moved);
+        env.storage().persistent().set(&(CERT_COLL_KEY, moved), &(collection_id, index));
+    }
+    env.storage().persistent().remove(&(COLL_ITEM_KEY, collection_id, last));
+    env.storage().persistent().set(&size_key, &last);
+    env.storage().persistent().remove(&cert_key);
+}
+
+/// Bind a chip ID to a certificate in both directions
+fn bind_tag(env: &Env, cert_id: &String, tag_id: BytesN<32>) {
+    let index_key = (TAG_INDEX_KEY, tag_id.clone());
+    if env.storage().persistent().has(&index_key) {
+        panic!("Tag already bound to a certificate");
+    }
+
+    let tag_key = (TAG_KEY, cert_id.clone());
+    env.storage().persistent().set(&tag_key, &tag_id);
+    env.storage().persistent().set(&index_key, cert_id);
+    bump_persistent(env, &tag_key);
+    bump_persistent(env, &index_key);
+}
+
+/// Decode one hex digit, accepting either case
+fn hex_value(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Hash a batch entry into its Merkle leaf
+fn merkle_leaf(env: &Env, cert_id: &String, metadata_hash: &String, owner: &Address) -> BytesN<32> {
+    let data = (cert_id.clone(), metadata_hash.clone(), owner.clone()).to_xdr(env);
+    env.crypto().sha256(&data).to_bytes()
+}
+
+/// Hash two Merkle nodes, ordering them so proofs need no direction bits
+fn merkle_parent(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    let mut data = Bytes::from_array(env, &first.to_array());
+    data.append(&Bytes::from_array(env, &second.to_array()));
+    env.crypto().sha256(&data).to_bytes()
+}
+
+/// Move v1 instance-map certificates into per-certificate entries
+fn migrate_v1_certificates(env: &Env) {
+    let legacy: Option<Map<String, Certificate>> = env.storage().instance().get(&CERTS_KEY);
+    let Some(certs) = legacy else {
+        return;
+    };
+
+    for (cert_id, certificate) in certs.iter() {
+        // Certificates rewritten since the upgrade already live in their own entry
+        if !env.storage().persistent().has(&(CERTS_KEY, cert_id.clone())) {
+            write_cert(env, &cert_id, &certificate);
+            move_balance(env, None, Some(&certificate.owner));
+        }
+
+        // v1 allowed duplicate hashes, so index them all rather than reject any
+        let index_key = (HASH_INDEX_KEY, certificate.metadata_hash);
+        let mut indexed: Vec<String> = env.storage().persistent()
+            .get(&index_key)
+            .unwrap_or(Vec::new(env));
+        if !indexed.contains(&cert_id) {
+            indexed.push_back(cert_id);
+            env.storage().persistent().set(&index_key, &indexed);
+            bump_persistent(env, &index_key);
+        }
+    }
+    env.storage().instance().remove(&CERTS_KEY);
+}
+
+/// Run an admin action whose authorization was already checked
+fn execute_action(env: &Env, action: AdminAction) {
+    match action {
+        AdminAction::Revoke(cert_id) => {
+            // Get existing certificate
+            let mut certificate = require_cert(env, &cert_id);
+
+            // Mark certificate as invalid
+            certificate.is_valid = false;
+
+            // Save updated certificate
+            write_cert(env, &cert_id, &certificate);
+            log_status(env, &cert_id, false);
+            env.events().publish((REVOKED_EVENT, cert_id), ());
+        }
+        AdminAction::Pause => env.storage().instance().set(&PAUSED_KEY, &true),
+        AdminAction::Unpause => env.storage().instance().remove(&PAUSED_KEY),
+        AdminAction::Upgrade(new_wasm_hash) => {
+            // Contracts deployed before the marker existed use the original layout
+            if !env.storage().instance().has(&SCHEMA_KEY) {
+                env.storage().instance().set(&SCHEMA_KEY, &1u32);
+            }
+
+            env.deployer().update_current_contract_wasm(new_wasm_hash);
+        }
+        AdminAction::SetAdmin(new_admin) => env.storage().instance().set(&ADMIN_KEY, &new_admin),
+        AdminAction::ResolveDispute(cert_id, outcome, ruling_hash) => {
+            let key = (DISPUTE_KEY, cert_id.clone());
+            let mut disputes: Vec<Dispute> = env.storage().persistent()
+                .get(&key)
+                .unwrap_or(Vec::new(env));
+            let Some(mut dispute) = disputes.last().filter(|d| d.status == DisputeStatus::Open) else {
+                panic!("No open dispute");
+            };
+
+            if outcome == DisputeOutcome::Revoked {
+                execute_action(env, AdminAction::Revoke(cert_id));
+            }
+            dispute.status = DisputeStatus::Resolved(Ruling {
+                outcome,
+                ruling_hash,
+                resolved_at: env.ledger().timestamp(),
+            });
+            disputes.set(disputes.len() - 1, dispute);
+            env.storage().persistent().set(&key, &disputes);
+            bump_persistent(env, &key);
+        }
+        AdminAction::SetMultisig(signers, threshold) => {
+            if signers.is_empty() {
+                env.storage().instance().remove(&MULTISIG_KEY);
+            } else {
+                if threshold == 0 || threshold > signers.len() {
+                    panic!("Invalid multisig threshold");
+                }
+                env.storage().instance().set(&MULTISIG_KEY, &MultisigConfig { signers, threshold });
+            }
+        }
+        AdminAction::SetPolicy(policy) => match policy {
+            Some(policy) => env.storage().instance().set(&POLICY_KEY, &policy),
+            None => env.storage().instance().remove(&POLICY_KEY),
+        },
+        AdminAction::SetAllowlistMode(enabled) => {
+            if enabled {
+                env.storage().instance().set(&KYC_MODE_KEY, &true);
+            } else {
+                env.storage().instance().remove(&KYC_MODE_KEY);
+            }
+        }
+        AdminAction::RemoveFromAllowlist(account) => {
+            env.storage().persistent().remove(&(ALLOWLIST_KEY, account));
+        }
+        AdminAction::DeactivateIssuer(issuer, suspend_certificates) => {
+            if !env.storage().persistent().has(&(ISSUER_KEY, issuer.clone())) {
+                panic!("Issuer not registered");
+            }
+
+            let status = if suspend_certificates {
+                IssuerStatus::Suspended
+            } else {
+                IssuerStatus::Deactivated
+            };
+            let key = (ISSUER_STATUS_KEY, issuer);
+            env.storage().persistent().set(&key, &status);
+            bump_persistent(env, &key);
+        }
+        AdminAction::SetConfig(config) => {
+            if config.max_batch_size == 0 {
+                panic!("Batch size must be positive");
+            }
+            if config.ttl_threshold > config.ttl_extend_to {
+                panic!("TTL threshold cannot exceed TTL extension");
+            }
+            if config.issuance_fee < 0 {
+                panic!("Issuance fee cannot be negative");
+            }
+
+            // The fee amount lives with its token and treasury
+            let fee: Option<IssuanceFee> = env.storage().instance().get(&FEE_KEY);
+            match fee {
+                Some(_) if config.issuance_fee == 0 => env.storage().instance().remove(&FEE_KEY),
+                Some(mut fee) => {
+                    fee.amount = config.issuance_fee;
+                    env.storage().instance().set(&FEE_KEY, &fee);
+                }
+                None if config.issuance_fee > 0 => panic!("Issuance fee token not configured"),
+                None => {}
+            }
+
+            env.storage().instance().set(&CONFIG_KEY, &config);
+        }
+        AdminAction::Migrate => {
+            let version: u32 = env.storage().instance().get(&SCHEMA_KEY).unwrap_or(1);
+            if version >= STORAGE_VERSION {
+                panic!("Storage already at current version");
+            }
+
+            if version < 2 {
+                migrate_v1_certificates(env);
+            }
+
+            env.storage().instance().set(&SCHEMA_KEY, &STORAGE_VERSION);
+        }
+    }
+    bump_instance(env);
+}
+
+/// Save a proposal, executing it once approvals from current signers reach the threshold
+fn store_proposal(env: &Env, id: u32, mut proposal: Proposal, config: &MultisigConfig) {
+    let approvals = proposal.approvals.iter()
+        .filter(|approver| config.signers.contains(approver))
+        .count() as u32;
+    if approvals >= config.threshold {
+        proposal.executed = true;
+    }
+
+    let key = (PROPOSAL_KEY, id);
+    env.storage().persistent().set(&key, &proposal);
+    bump_persistent(env, &key);
+
+    // Persist first so an upgrade action still leaves the proposal marked executed
+    if proposal.executed {
+        execute_action(env, proposal.action);
+    }
+}
+
+/// Panic unless multisig is enabled and `signer` is one of its signers
+fn require_signer(env: &Env, signer: &Address) -> MultisigConfig {
+    let config: MultisigConfig = env.storage().instance().get(&MULTISIG_KEY)
+        .expect("Multisig not enabled");
+    if !config.signers.contains(signer) {
+        panic!("Not a multisig signer");
+    }
+    config
+}
+
+/// Panic if destructive admin actions must go through multisig proposals
+fn ensure_no_multisig(env: &Env) {
+    if env.storage().instance().has(&MULTISIG_KEY) {
+        panic!("Action requires multisig approval");
+    }
+}
+
+/// Panic if the admin paused issuance and transfers
+fn ensure_not_paused(env: &Env) {
+    if env.storage().instance().get(&PAUSED_KEY).unwrap_or(false) {
+        panic!("Contract is paused");
+    }
+}
+
+/// Whether the certificate's latest dispute is still awaiting a ruling
+fn is_disputed(env: &Env, cert_id: &String) -> bool {
+    let disputes: Option<Vec<Dispute>> = env.storage().persistent().get(&(DISPUTE_KEY, cert_id.clone()));
+    disputes
+        .and_then(|d| d.last())
+        .is_some_and(|d| d.status == DisputeStatus::Open)
+}
+
+/// Client for the registered policy contract, if one is set
+fn read_policy(env: &Env) -> Option<PolicyClient<'_>> {
+    let policy: Option<Address> = env.storage().instance().get(&POLICY_KEY);
+    policy.map(|policy| PolicyClient::new(env, &policy))
+}
+
+/// Panic if the registered policy contract rejects the transfer
+fn ensure_policy_allows_transfer(env: &Env, cert_id: &String, from: &Address, to: &Address) {
+    if let Some(policy) = read_policy(env) {
+        if !policy.check_transfer(cert_id, from, to) {
+            panic!("Rejected by policy contract");
+        }
+    }
+}
+
+/// Panic if compliance mode is on and `recipient` isn't allowlisted
+fn ensure_allowed_recipient(env: &Env, recipient: &Address) {
+    if env.storage().instance().get(&KYC_MODE_KEY).unwrap_or(false)
+        && !env.storage().persistent().has(&(ALLOWLIST_KEY, recipient.clone()))
+    {
+        panic!("Recipient not on allowlist");
+    }
+}
+
+/// Whether the owner has frozen `cert_id` against transfer
+fn is_locked(env: &Env, cert_id: &String) -> bool {
+    env.storage().persistent()
+        .get(&(LOCKED_KEY, cert_id.clone()))
+        .unwrap_or(false)
+}
+
+/// Remove the single-certificate approval for `cert_id`, if present
+fn clear_approval(env: &Env, cert_id: String) {
+    env.storage().persistent().remove(&(APPROVALS_KEY, cert_id));
+}
+
+/// Hand a valid certificate to `new_owner` and record the change in its history
+fn complete_transfer(
+    env: &Env,
+    cert_id: String,
+    mut certificate: Certificate,
+    new_owner: Address,
+    price: Option<i128>,
+    currency: Option<Symbol>,
+    memo: Option<String>,
+) {
+    ensure_not_paused(env);
+    ensure_allowed_recipient(env, &new_owner);
+    if is_locked(env, &cert_id) {
+        panic!("Certificate is locked");
+    }
+    if is_disputed(env, &cert_id) {
+        panic!("Certificate is disputed");
+    }
+    if is_suspended(env, &cert_id) {
+        panic!("Certificate is suspended");
+    }
+    ensure_cooldown_elapsed(env, &cert_id);
+    validate_sale(&price, &currency);
+    let previous_owner = certificate.owner.clone();
+    ensure_policy_allows_transfer(env, &cert_id, &previous_owner, &new_owner);
+
+    // Settle royalty-bearing sales before ownership changes hands
+    let mut token = None;
+    if let (Some(price), Some(currency)) = (price, currency.clone()) {
+        token = settle_sale(env, &cert_id, &previous_owner, &new_owner, price, currency);
+    }
+
+    // Update ownership
+    certificate.owner = new_owner.clone();
+
+    // Save updated certificate
+    write_cert(env, &cert_id, &certificate);
+    move_balance(env, Some(&previous_owner), Some(&new_owner));
+
+    // Approvals do not survive a change of ownership
+    clear_approval(env, cert_id.clone());
+    carry_children(env, &cert_id, &previous_owner, &new_owner);
+
+    // Append the ownership change to the certificate's history
+    append_history(env, cert_id, TransferRecord {
+        from: previous_owner,
+        to: new_owner,
+        price,
+        currency,
+        memo,
+        token,
+        ledger: env.ledger().sequence(),
+        timestamp: env.ledger().timestamp(),
+    });
+}
+
+/// The royalty configured for the collection `cert_id` belongs to, if any
+fn read_royalty(env: &Env, cert_id: &String) -> Option<RoyaltyConfig> {
+    let (collection_id, _): (u32, u32) = env.storage().persistent().get(&(CERT_COLL_KEY, cert_id.clone()))?;
+    env.storage().persistent().get(&(ROYALTY_KEY, collection_id))
+}
+
+/// Collect a quoted sale price from the buyer when a royalty is configured
+/// 
+/// Sales are only settled on-chain when the certificate's collection has a
+/// royalty; otherwise the price is just recorded in the history.
+/// 
+/// Returns the token the sale was settled in, if any.
+fn settle_sale(
+    env: &Env,
+    cert_id: &String,
+    seller: &Address,
+    buyer: &Address,
+    price: i128,
+    currency: Symbol,
+) -> Option<Address> {
+    let config = read_royalty(env, cert_id)?;
+
+    if currency != config.currency {
+        panic!("Sale currency does not match royalty token");
+    }
+
+    pay_sale(env, cert_id, &config.token, seller, buyer, price);
+    Some(config.token)
+}
+
+/// Move `price` of `token` from the buyer, splitting off the brand's royalty
+/// 
+/// The royalty only applies when `token` is the collection's royalty token.
+fn pay_sale(env: &Env, cert_id: &String, token: &Address, seller: &Address, buyer: &Address, price: i128) {
+    // The buyer authorizes the payment as part of this call
+    buyer.require_auth();
+
+    let (royalty, recipient) = match read_royalty(env, cert_id) {
+        Some(config) if config.token == *token => (
+            royalty_share(price, config.bps),
+            Some(config.recipient),
+        ),
+        _ => (0, None),
+    };
+
+    let client = token::Client::new(env, token);
+    if let Some(recipient) = recipient {
+        if royalty > 0 {
+            client.transfer(buyer, &recipient, &royalty);
+        }
+    }
+    if price > royalty {
+        client.transfer(buyer, seller, &(price - royalty));
+    }
+}
+
+/// `bps` basis points of `price`, rounded down
+/// 
+/// The price is divided before multiplying so large prices can't overflow.
+fn royalty_share(price: i128, bps: u32) -> i128 {
+    let bps = i128::from(bps);
+    let max = i128::from(MAX_ROYALTY_BPS);
+    price / max * bps + price % max * bps / max
+}
+
+/// Move one certificate's worth of balance between owners (`None` for mint/burn)
+/// 
+/// Certificates issued under schema v1 were never counted, so decrements saturate at zero.
+fn move_balance(env: &Env, from: Option<&Address>, to: Option<&Address>) {
+    let storage = env.storage().persistent();
+    if let Some(from) = from {
+        let key = (BALANCE_KEY, from.clone());
+        let balance: u32 = storage.get(&key).unwrap_or(0);
+        if balance <= 1 {
+            storage.remove(&key);
+        } else {
+            storage.set(&key, &(balance - 1));
+            bump_persistent(env, &key);
+        }
+    }
+    if let Some(to) = to {
+        let key = (BALANCE_KEY, to.clone());
+        let balance: u32 = storage.get(&key).unwrap_or(0);
+        storage.set(&key, &(balance + 1));
+        bump_persistent(env, &key);
+    }
+}
+
+/// Reject sale details that cannot be interpreted
+fn validate_sale(price: &Option<i128>, currency: &Option<Symbol>) {
+    if let Some(price) = price {
+        if *price < 0 {
+            panic!("Sale price cannot be negative");
+        }
+        if currency.is_none() {
+            panic!("Currency required when price is set");
+        }
+    }
+}
+
+/// Append a transfer record to the persistent history of `cert_id`
+fn append_history(env: &Env, cert_id: String, record: TransferRecord) {
+    env.events().publish(
+        (TRANSFER_EVENT, cert_id.clone()),
+        (record.from.clone(), record.to.clone()),
+    );
+
+    let (count, _) = read_transfer_stats(env, &cert_id);
+    let stats_key = (XFER_STATS_KEY, cert_id.clone());
+    env.storage().persistent().set(&stats_key, &(count + 1, record.ledger));
+    bump_persistent(env, &stats_key);
+
+    let key = (HISTORY_KEY, cert_id);
+    let mut history: Vec<TransferRecord> = env.storage().persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    history.push_back(record);
+    env.storage().persistent().set(&key, &history);
+    bump_persistent(env, &key);
+}
+
+/// Record a validity change at the current ledger for `was_valid_at`
+fn log_status(env: &Env, cert_id: &String, is_valid: bool) {
+    let key = (STATUS_LOG_KEY, cert_id.clone());
+    let mut log: Vec<(u32, bool)> = env.storage().persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    log.push_back((env.ledger().sequence(), is_valid));
+    env.storage().persistent().set(&key, &log);
+    bump_persistent(env, &key);
+}
+
+/// Get a certificate's transfer count and last transfer ledger
+/// 
+/// Certificates transferred before the counter existed derive it from
+/// their history.
+fn read_transfer_stats(env: &Env, cert_id: &String) -> (u32, u32) {
+    let stats: Option<(u32, u32)> = env.storage().persistent().get(&(XFER_STATS_KEY, cert_id.clone()));
+    stats.unwrap_or_else(|| {
+        let history: Vec<TransferRecord> = env.storage().persistent()
+            .get(&(HISTORY_KEY, cert_id.clone()))
+            .unwrap_or(Vec::new(env));
+        (history.len(), history.last().map(|r| r.ledger).unwrap_or(0))
+    })
+}
+
+/// Comprehensive test module
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{
+        testutils::{storage::Persistent as _, Address as _, Events as _, Ledger as _},
+        vec, Address, Env,
+    };
+
+    /// Test contract initialization and certificate issuance
+    #[test]
+    fn test_init_and_issue_certificate() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+
+        // Initialize contract
+        client.init(&admin);
+
+        // Verify admin is set correctly
+        assert_eq!(client.get_admin(), admin);
+
+        // Issue a certificate
+        client.issue_certificate(
+            &String::from_str(&env, "CERT001"),
+            &String::from_str(&env, "QmHash123"),
+            &owner,
+        );
+
+        // Verify certificate exists
+        assert!(client.certificate_exists(&String::from_str(&env, "CERT001")));
+        
+        // Verify certificate details
+        let cert = client.get_certificate_details(&String::from_str(&env, "CERT001"));
+        assert_eq!(cert.owner, owner);
+        assert_eq!(cert.metadata_hash, String::from_str(&env, "QmHash123"));
+        assert!(cert.is_valid);
+    }
+
+    /// Test certificate verification functionality
+    #[test]
+    fn test_verify_certificate() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+
+        client.init(&admin);
+        client.issue_certificate(
+            &String::from_str(&env, "CERT001"),
+            &String::from_str(&env, "QmHash123"),
+            &owner,
+        );
+
+        // Valid verification should return true
+        assert!(client.verify(
+            &String::from_str(&env, "CERT001"),
+            &String::from_str(&env, "QmHash123")
+        ));
+
+        // Wrong metadata hash should return false
+        assert!(!client.verify(
+            &String::from_str(&env, "CERT001"),
+            &String::from_str(&env, "WrongHash")
+        ));
+
+        // Non-existent certificate should return false
+        assert!(!client.verify(
+            &String::from_str(&env, "CERT999"),
+            &String::from_str(&env, "QmHash123")
+        ));
+    }
+
+    /// Test certificate ownership transfer
+    #[test]
+    fn test_transfer_certificate() {
+
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner1 = Address::generate(&env);
+        let owner2 = Address::generate(&env);
+
+        client.init(&admin);
+        client.issue_certificate(
+            &String::from_str(&env, "CERT001"),
+            &String::from_str(&env, "QmHash123"),
+            &owner1,
+        );
+
+        // Transfer certificate to new owner
+        client.transfer(&String::from_str(&env, "CERT001"), &owner2, &None, &None, &None);
+
+        // Verify ownership change
+        let cert = client.get_certificate_details(&String::from_str(&env, "CERT001"));
+        assert_eq!(cert.owner, owner2);
+        assert!(cert.is_valid); // Should still be valid
+    }
+
+    /// Test certificate revocation
+    #[test]
+    fn test_revoke_certificate() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+
+        client.init(&admin);
+        client.issue_certificate(
+            &String::from_str(&env, "CERT001"),
+            &String::from_str(&env, "QmHash123"),
+            &owner,
+        );
+
+        // Revoke certificate
+        client.revoke(&String::from_str(&env, "CERT001"));
+
+        // Verify certificate is marked invalid
+        let cert = client.get_certificate_details(&String::from_str(&env, "CERT001"));
+        assert!(!cert.is_valid);
+
+        // Verify verification now fails for revoked certificate
+        assert!(!client.verify(
+            &String::from_str(&env, "CERT001"),
+            &String::from_str(&env, "QmHash123")
+        ));
+    }
+
+    /// Test error cases
+    #[test]
+    #[should_panic(expected = "Error(Contract, #4)")]
+    fn test_duplicate_certificate_id() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+
+        client.init(&admin);
+        
+        // Issue first certificate
+        client.issue_certificate(
+            &String::from_str(&env, "CERT001"),
+            &String::from_str(&env, "QmHash123"),
+            &owner,
+        );
+
+        // Try to issue duplicate - should panic
+        client.issue_certificate(
+            &String::from_str(&env, "CERT001"),
+            &String::from_str(&env, "QmHash456"),
+            &owner,
+        );
+    }
+
+    /// Test transferring revoked certificate fails
+    #[test]
+    #[should_panic(expected = "Error(Contract, #6)")]
+    fn test_transfer_revoked_certificate() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner1 = Address::generate(&env);
+        let owner2 = Address::generate(&env);
+
+        client.init(&admin);
+        client.issue_certificate(
+            &String::from_str(&env, "CERT001"),
+            &String::from_str(&env, "QmHash123"),
+            &owner1,
+        );
+
+        // Revoke certificate
+        client.revoke(&String::from_str(&env, "CERT001"));
+
+        // Try to transfer revoked certificate - should panic
+        client.transfer(&String::from_str(&env, "CERT001"), &owner2, &None, &None, &None);
+    }
+
+    /// Test delegated transfers through single and operator approvals
+    #[test]
+    fn test_transfer_from_with_approvals() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner1 = Address::generate(&env);
+        let owner2 = Address::generate(&env);
+        let marketplace = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+
+        client.init(&admin);
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &owner1);
+
+        // Single-certificate approval lets the operator move it once
+        client.approve(&cert_id, &marketplace);
+        assert_eq!(client.get_approved(&cert_id), Some(marketplace.clone()));
+        client.transfer_from(&marketplace, &cert_id, &owner2, &None, &None, &None);
+        assert_eq!(client.get_certificate_details(&cert_id).owner, owner2);
+
+        // Approval is cleared after the transfer
+        assert_eq!(client.get_approved(&cert_id), None);
+
+        // Operator approval covers every certificate of the new owner
+        client.approve_all(&owner2, &marketplace, &true);
+        assert!(client.is_approved_for_all(&owner2, &marketplace));
+        client.transfer_from(&marketplace, &cert_id, &owner1, &None, &None, &None);
+        assert_eq!(client.get_certificate_details(&cert_id).owner, owner1);
+
+        client.approve_all(&owner2, &marketplace, &false);
+        assert!(!client.is_approved_for_all(&owner2, &marketplace));
+    }
+
+    /// Test transfer_from fails without an approval
+    #[test]
+    #[should_panic(expected = "Error(Contract, #5)")]
+    fn test_transfer_from_without_approval() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let stranger = Address::generate(&env);
+
+        client.init(&admin);
+        client.issue_certificate(
+            &String::from_str(&env, "CERT001"),
+            &String::from_str(&env, "QmHash123"),
+            &owner,
+        );
+
+        client.transfer_from(
+            &stranger,
+            &String::from_str(&env, "CERT001"),
+            &stranger,
+            &None,
+            &None,
+            &None,
+        );
+    }
+
+    /// Test sale price and memo are recorded in the transfer history
+    #[test]
+    fn test_transfer_records_sale_history() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner1 = Address::generate(&env);
+        let owner2 = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+
+        client.init(&admin);
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &owner1);
+        assert_eq!(client.get_history(&cert_id).len(), 0);
+
+        client.transfer(
+            &cert_id,
+            &owner2,
+            &Some(250_000),
+            &Some(symbol_short!("USD")),
+            &Some(String::from_str(&env, "Resale via boutique")),
+        );
+
+        let history = client.get_history(&cert_id);
+        assert_eq!(history.len(), 1);
+        let record = history.get(0).unwrap();
+        assert_eq!(record.from, owner1);
+        assert_eq!(record.to, owner2);
+        assert_eq!(record.price, Some(250_000));
+        assert_eq!(record.currency, Some(symbol_short!("USD")));
+        assert_eq!(record.memo, Some(String::from_str(&env, "Resale via boutique")));
+    }
+
+    /// Test a price without a currency is rejected
+    #[test]
+    #[should_panic(expected = "Currency required when price is set")]
+    fn test_transfer_price_requires_currency() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner1 = Address::generate(&env);
+        let owner2 = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+
+        client.init(&admin);
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &owner1);
+        client.transfer(&cert_id, &owner2, &Some(1_000), &None, &None);
+    }
+
+    /// Test owner burn removes the certificate and retires its ID
+    #[test]
+    fn test_burn_certificate() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+
+        client.init(&admin);
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &owner);
+
+        client.burn(&cert_id);
+
+        assert!(!client.certificate_exists(&cert_id));
+        assert!(client.is_burned(&cert_id));
+        assert!(!client.verify(&cert_id, &String::from_str(&env, "QmHash123")));
+
+        // A burned ID can never be issued again
+        let result = client.try_issue_certificate(
+            &cert_id,
+            &String::from_str(&env, "QmHash456"),
+            &owner,
+        );
+        assert!(result.is_err());
+    }
+
+    /// Test upgrade requires admin authorization
+    #[test]
+    fn test_upgrade_requires_admin() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        client.init(&admin);
+
+        // Storage version marker is written at init
+        let version: u32 = env.as_contract(&contract_id, || {
+            env.storage().instance().get(&SCHEMA_KEY).unwrap()
+        });
+        assert_eq!(version, STORAGE_VERSION);
+
+        // Without the admin's signature the upgrade is rejected
+        env.set_auths(&[]);
+        let result = client.try_upgrade(&BytesN::from_array(&env, &[0u8; 32]));
+        assert!(result.is_err());
+    }
+
+    /// Test version info reflects the compiled build
+    #[test]
+    fn test_get_version() {
+        let env = Env::default();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let info = client.get_version();
+        assert_eq!(info.version, String::from_str(&env, CONTRACT_VERSION));
+        assert_eq!(info.storage_version, STORAGE_VERSION);
+    }
+
+    /// Test certificates live in persistent storage and can be bumped by the admin
+    #[test]
+    fn test_bump_storage_extends_certificate_ttl() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+
+        client.init(&admin);
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &owner);
+
+        // Issuance keeps the certificate alive for the default window
+        let ttl = env.as_contract(&contract_id, || {
+            env.storage().persistent().get_ttl(&(CERTS_KEY, cert_id.clone()))
+        });
+        assert_eq!(ttl, TTL_EXTEND_TO);
+
+        client.bump_storage(&cert_id, &1_000_000);
+        let ttl = env.as_contract(&contract_id, || {
+            env.storage().persistent().get_ttl(&(CERTS_KEY, cert_id.clone()))
+        });
+        assert_eq!(ttl, 1_000_000);
+    }
+
+    /// Test certificates written by schema v1 remain readable
+    #[test]
+    fn test_reads_legacy_certificate_map() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner1 = Address::generate(&env);
+        let owner2 = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+        client.init(&admin);
+
+        // Simulate a certificate stored by the v1 layout
+        env.as_contract(&contract_id, || {
+            let mut certs: Map<String, Certificate> = Map::new(&env);
+            certs.set(cert_id.clone(), Certificate {
+                owner: owner1.clone(),
+                metadata_hash: String::from_str(&env, "QmHash123"),
+                is_valid: true,
+            });
+            env.storage().instance().set(&CERTS_KEY, &certs);
+        });
+
+        assert!(client.verify(&cert_id, &String::from_str(&env, "QmHash123")));
+
+        // Writing it moves it into its own persistent entry
+        client.transfer(&cert_id, &owner2, &None, &None, &None);
+        assert_eq!(client.get_certificate_details(&cert_id).owner, owner2);
+    }
+
+    /// Test the NFT-style views track ownership and metadata
+    #[test]
+    fn test_nft_interface() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner1 = Address::generate(&env);
+        let owner2 = Address::generate(&env);
+        let cert1 = String::from_str(&env, "CERT001");
+        let cert2 = String::from_str(&env, "CERT002");
+
+        client.init(&admin);
+        client.issue_certificate(&cert1, &String::from_str(&env, "QmHash123"), &owner1);
+        client.issue_certificate(&cert2, &String::from_str(&env, "QmHash456"), &owner1);
+
+        assert_eq!(client.balance_of(&owner1), 2);
+        assert_eq!(client.owner_of(&cert1), owner1);
+        assert_eq!(client.token_uri(&cert1), String::from_str(&env, "ipfs://QmHash123"));
+        assert_eq!(client.symbol(), String::from_str(&env, "VLUX"));
+
+        client.transfer(&cert1, &owner2, &None, &None, &None);
+        assert_eq!(client.balance_of(&owner1), 1);
+        assert_eq!(client.balance_of(&owner2), 1);
+        assert_eq!(client.owner_of(&cert1), owner2);
+
+        client.burn(&cert2);
+        assert_eq!(client.balance_of(&owner1), 0);
+    }
+
+    /// Test priced transfers split the payment between seller and brand
+    #[test]
+    fn test_transfer_pays_royalty() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let brand = Address::generate(&env);
+        let seller = Address::generate(&env);
+        let buyer = Address::generate(&env);
+        let cert_id = String::from_str(&env, "MAISON-001");
+
+        // Settlement token with funds for the buyer
+        let asset = env.register_stellar_asset_contract_v2(admin.clone());
+        token::StellarAssetClient::new(&env, &asset.address()).mint(&buyer, &10_000);
+        let token_client = token::Client::new(&env, &asset.address());
+
+        client.init(&admin);
+        let collection_id = royalty_collection(&env, &client, &brand, &asset.address());
+        let options = IssueOptions { collection_id: Some(collection_id), ..Default::default() };
+        client.issue_as_issuer(&brand, &cert_id, &String::from_str(&env, "QmHash123"), &seller, &options);
+
+        client.transfer(&cert_id, &buyer, &Some(10_000), &Some(symbol_short!("USDC")), &None);
+
+        // 5% to the brand, the rest to the seller
+        assert_eq!(token_client.balance(&brand), 500);
+        assert_eq!(token_client.balance(&seller), 9_500);
+        assert_eq!(token_client.balance(&buyer), 0);
+        assert_eq!(client.owner_of(&cert_id), buyer);
+
+        // Items outside the collection owe no royalty
+        let other = String::from_str(&env, "CERT002");
+        client.issue_certificate(&other, &String::from_str(&env, "QmHash456"), &seller);
+        client.transfer(&other, &buyer, &Some(10_000), &Some(symbol_short!("EUR")), &None);
+        assert_eq!(token_client.balance(&brand), 500);
+    }
+
+    /// Test royalties on prices near the token's limit don't overflow
+    #[test]
+    fn test_royalty_on_large_price() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let brand = Address::generate(&env);
+        let seller = Address::generate(&env);
+        let buyer = Address::generate(&env);
+        let cert_id = String::from_str(&env, "MAISON-001");
+        let price = i128::MAX;
+
+        let asset = env.register_stellar_asset_contract_v2(admin.clone());
+        token::StellarAssetClient::new(&env, &asset.address()).mint(&buyer, &price);
+        let token_client = token::Client::new(&env, &asset.address());
+
+        client.init(&admin);
+        let collection_id = royalty_collection(&env, &client, &brand, &asset.address());
+        let options = IssueOptions { collection_id: Some(collection_id), ..Default::default() };
+        client.issue_as_issuer(&brand, &cert_id, &String::from_str(&env, "QmHash123"), &seller, &options);
+
+        client.transfer(&cert_id, &buyer, &Some(price), &Some(symbol_short!("USDC")), &None);
+        assert_eq!(token_client.balance(&brand), price / 20);
+        assert_eq!(token_client.balance(&seller), price - price / 20);
+    }
+
+    /// A collection run by `brand` as issuer, with a 5% USDC royalty paid to it
+    fn royalty_collection(
+        env: &Env,
+        client: &FashionAuthContractClient,
+        brand: &Address,
+        token: &Address,
+    ) -> u32 {
+        client.register_issuer(brand, &String::from_str(env, "Maison"), &String::from_str(env, "MAISON-"));
+        let collection_id = client.create_collection(brand, &Collection {
+            brand: String::from_str(env, "Maison"),
+            season: String::from_str(env, "FW24"),
+            description: String::from_str(env, "Runway handbags"),
+        });
+        client.set_royalty(&collection_id, &500, brand, token, &symbol_short!("USDC"));
+        assert!(client.get_royalty(&collection_id).is_some());
+        collection_id
+    }
+
+    /// Test a sale quoted in another currency is rejected once royalties are on
+    #[test]
+    #[should_panic(expected = "Sale currency does not match royalty token")]
+    fn test_royalty_currency_mismatch() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let brand = Address::generate(&env);
+        let seller = Address::generate(&env);
+        let buyer = Address::generate(&env);
+        let cert_id = String::from_str(&env, "MAISON-001");
+        let asset = env.register_stellar_asset_contract_v2(admin.clone());
+
+        client.init(&admin);
+        let collection_id = royalty_collection(&env, &client, &brand, &asset.address());
+        let options = IssueOptions { collection_id: Some(collection_id), ..Default::default() };
+        client.issue_as_issuer(&brand, &cert_id, &String::from_str(&env, "QmHash123"), &seller, &options);
+
+        client.transfer(&cert_id, &buyer, &Some(10_000), &Some(symbol_short!("EUR")), &None);
+    }
+
+    /// Test an escrowed sale swaps payment and ownership atomically
+    #[test]
+    fn test_escrow_sale() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let seller = Address::generate(&env);
+        let buyer = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+
+        let asset = env.register_stellar_asset_contract_v2(admin.clone());
+        token::StellarAssetClient::new(&env, &asset.address()).mint(&buyer, &7_000);
+        let token_client = token::Client::new(&env, &asset.address());
+
+        client.init(&admin);
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &seller);
+
+        // Listing moves the certificate into escrow
+        client.list_for_sale(&cert_id, &7_000, &asset.address());
+        assert_eq!(client.owner_of(&cert_id), contract_id);
+        assert_eq!(client.get_listing(&cert_id).unwrap().seller, seller);
+
+        client.buy(&buyer, &cert_id);
+
+        assert_eq!(client.owner_of(&cert_id), buyer);
+        assert_eq!(token_client.balance(&seller), 7_000);
+        assert_eq!(client.get_listing(&cert_id), None);
+
+        let record = client.get_history(&cert_id).get(0).unwrap();
+        assert_eq!(record.from, seller);
+        assert_eq!(record.price, Some(7_000));
+        assert_eq!(record.token, Some(asset.address()));
+    }
+
+    /// Test cancelling a listing returns the certificate to the seller
+    #[test]
+    fn test_cancel_listing() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let seller = Address::generate(&env);
+        let token = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+
+        client.init(&admin);
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &seller);
+        client.list_for_sale(&cert_id, &7_000, &token);
+        client.cancel_listing(&cert_id);
+
+        assert_eq!(client.owner_of(&cert_id), seller);
+        assert_eq!(client.balance_of(&seller), 1);
+        assert_eq!(client.get_listing(&cert_id), None);
+    }
+
+    /// Test a locked certificate can't be transferred until unlocked
+    #[test]
+    fn test_lock_blocks_transfer() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner1 = Address::generate(&env);
+        let owner2 = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+
+        client.init(&admin);
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &owner1);
+
+        client.lock(&cert_id);
+        assert!(client.is_locked(&cert_id));
+        let result = client.try_transfer(&cert_id, &owner2, &None, &None, &None);
+        assert!(result.is_err());
+        assert!(client.try_burn(&cert_id).is_err());
+
+        client.unlock(&cert_id);
+        assert!(!client.is_locked(&cert_id));
+        client.transfer(&cert_id, &owner2, &None, &None, &None);
+        assert_eq!(client.owner_of(&cert_id), owner2);
+    }
+
+    /// Test revocation waits for the multisig quorum
+    #[test]
+    fn test_multisig_revoke_requires_quorum() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let signer1 = Address::generate(&env);
+        let signer2 = Address::generate(&env);
+        let signer3 = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+
+        client.init(&admin);
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &owner);
+
+        let mut signers = Vec::new(&env);
+        signers.push_back(signer1.clone());
+        signers.push_back(signer2.clone());
+        signers.push_back(signer3.clone());
+        client.set_multisig(&signers, &2);
+
+        // A single admin key can no longer revoke
+        assert!(client.try_revoke(&cert_id).is_err());
+
+        let id = client.propose(&signer1, &AdminAction::Revoke(cert_id.clone()));
+        assert!(client.get_certificate_details(&cert_id).is_valid);
+
+        client.approve_proposal(&signer2, &id);
+        assert!(!client.get_certificate_details(&cert_id).is_valid);
+        assert!(client.get_proposal(&id).unwrap().executed);
+    }
+
+    /// Test approvals from signers removed since don't count towards the quorum
+    #[test]
+    fn test_multisig_ignores_removed_signers() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let signer1 = Address::generate(&env);
+        let signer2 = Address::generate(&env);
+        let signer3 = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+
+        client.init(&admin);
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &owner);
+
+        let mut signers = Vec::new(&env);
+        signers.push_back(signer1.clone());
+        signers.push_back(signer2.clone());
+        signers.push_back(signer3.clone());
+        client.set_multisig(&signers, &2);
+
+        // signer1 proposes a revocation, then is voted out of the signer set
+        let revoke = client.propose(&signer1, &AdminAction::Revoke(cert_id.clone()));
+        let mut remaining = Vec::new(&env);
+        remaining.push_back(signer2.clone());
+        remaining.push_back(signer3.clone());
+        let rotate = client.propose(&signer2, &AdminAction::SetMultisig(remaining, 2));
+        client.approve_proposal(&signer3, &rotate);
+        assert!(!client.get_multisig().unwrap().signers.contains(&signer1));
+
+        // signer1's stale approval no longer makes up the quorum
+        client.approve_proposal(&signer2, &revoke);
+        assert!(client.get_certificate_details(&cert_id).is_valid);
+        assert!(!client.get_proposal(&revoke).unwrap().executed);
+
+        client.approve_proposal(&signer3, &revoke);
+        assert!(!client.get_certificate_details(&cert_id).is_valid);
+    }
+
+    /// Set up a contract whose admin actions need 2 of 2 signers
+    fn multisig_client(env: &Env) -> (FashionAuthContractClient<'_>, Address, Address) {
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(env, &contract_id);
+
+        let signer1 = Address::generate(env);
+        let signer2 = Address::generate(env);
+        client.init(&Address::generate(env));
+
+        let mut signers = Vec::new(env);
+        signers.push_back(signer1.clone());
+        signers.push_back(signer2.clone());
+        client.set_multisig(&signers, &2);
+        (client, signer1, signer2)
+    }
+
+    /// Test installing a policy contract needs the quorum
+    #[test]
+    #[should_panic(expected = "Action requires multisig approval")]
+    fn test_multisig_blocks_set_policy() {
+        let env = Env::default();
+        let (client, _, _) = multisig_client(&env);
+        client.set_policy(&Some(Address::generate(&env)));
+    }
+
+    /// Test compliance mode can't be switched on by the admin alone
+    #[test]
+    #[should_panic(expected = "Action requires multisig approval")]
+    fn test_multisig_blocks_set_allowlist_mode() {
+        let env = Env::default();
+        let (client, _, _) = multisig_client(&env);
+        client.set_allowlist_mode(&true);
+    }
+
+    /// Test allowlisted addresses can't be removed by the admin alone
+    #[test]
+    #[should_panic(expected = "Action requires multisig approval")]
+    fn test_multisig_blocks_remove_from_allowlist() {
+        let env = Env::default();
+        let (client, _, _) = multisig_client(&env);
+        let account = Address::generate(&env);
+        client.add_to_allowlist(&account);
+        client.remove_from_allowlist(&account);
+    }
+
+    /// Test suspending an issuer's certificates needs the quorum
+    #[test]
+    #[should_panic(expected = "Action requires multisig approval")]
+    fn test_multisig_blocks_issuer_suspension() {
+        let env = Env::default();
+        let (client, _, _) = multisig_client(&env);
+        let issuer = Address::generate(&env);
+        client.register_issuer(&issuer, &String::from_str(&env, "Acme"), &String::from_str(&env, "ACME-"));
+
+        // Deactivating without suspending stays with the admin
+        client.deactivate_issuer(&issuer, &false);
+        client.deactivate_issuer(&issuer, &true);
+    }
+
+    /// Test operational parameters can't be changed by the admin alone
+    #[test]
+    #[should_panic(expected = "Action requires multisig approval")]
+    fn test_multisig_blocks_set_config() {
+        let env = Env::default();
+        let (client, _, _) = multisig_client(&env);
+        client.set_config(&client.get_config());
+    }
+
+    /// Test storage migrations need the quorum
+    #[test]
+    #[should_panic(expected = "Action requires multisig approval")]
+    fn test_multisig_blocks_migrate() {
+        let env = Env::default();
+        let (client, _, _) = multisig_client(&env);
+        client.migrate();
+    }
+
+    /// Test compliance mode goes through a proposal once multisig is enabled
+    #[test]
+    fn test_multisig_allowlist_mode_proposal() {
+        let env = Env::default();
+        let (client, signer1, signer2) = multisig_client(&env);
+
+        let id = client.propose(&signer1, &AdminAction::SetAllowlistMode(true));
+        assert!(!client.is_allowlist_mode());
+
+        client.approve_proposal(&signer2, &id);
+        assert!(client.is_allowlist_mode());
+    }
+
+    /// Test pausing halts transfers
+    #[test]
+    #[should_panic(expected = "Contract is paused")]
+    fn test_pause_blocks_transfer() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner1 = Address::generate(&env);
+        let owner2 = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+
+        client.init(&admin);
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &owner1);
+        client.pause();
+        client.transfer(&cert_id, &owner2, &None, &None, &None);
+    }
+
+    /// Test claiming certificates from a published batch root
+    #[test]
+    fn test_claim_from_root() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner1 = Address::generate(&env);
+        let owner2 = Address::generate(&env);
+        let cert1 = String::from_str(&env, "CERT001");
+        let cert2 = String::from_str(&env, "CERT002");
+        let hash1 = String::from_str(&env, "QmHash123");
+        let hash2 = String::from_str(&env, "QmHash456");
+
+        let leaf1 = merkle_leaf(&env, &cert1, &hash1, &owner1);
+        let leaf2 = merkle_leaf(&env, &cert2, &hash2, &owner2);
+        let root = merkle_parent(&env, &leaf1, &leaf2);
+
+        let treasury = Address::generate(&env);
+        let asset = env.register_stellar_asset_contract_v2(admin.clone());
+        token::StellarAssetClient::new(&env, &asset.address()).mint(&admin, &200);
+        let token_client = token::Client::new(&env, &asset.address());
+
+        client.init(&admin);
+        client.set_issuance_fee(&asset.address(), &100, &treasury);
+        client.publish_root(&root, &2);
+        assert!(client.is_root_published(&root));
+        assert!(client.try_publish_root(&root, &2).is_err());
+
+        // Every leaf is paid for up front, so claims move no more funds
+        assert_eq!(token_client.balance(&treasury), 200);
+        assert_eq!(token_client.balance(&admin), 0);
+
+        let mut proof = Vec::new(&env);
+        proof.push_back(leaf2.clone());
+        client.claim_from_root(&proof, &cert1, &hash1, &owner1);
+        assert!(client.verify(&cert1, &hash1));
+        assert_eq!(client.owner_of(&cert1), owner1);
+        assert!(!client.certificate_exists(&cert2));
+
+        // Claims are one-shot and can't be redirected to another owner
+        assert!(client.try_claim_from_root(&proof, &cert1, &hash1, &owner1).is_err());
+        let mut proof2 = Vec::new(&env);
+        proof2.push_back(leaf1);
+        assert!(client.try_claim_from_root(&proof2, &cert2, &hash2, &owner1).is_err());
+        client.claim_from_root(&proof2, &cert2, &hash2, &owner2);
+        assert_eq!(client.owner_of(&cert2), owner2);
+        assert_eq!(token_client.balance(&treasury), 200);
+    }
+
+    /// Test a root yields no more certificates than the leaves it was paid for
+    #[test]
+    #[should_panic(expected = "Batch root fully claimed")]
+    fn test_claim_from_root_caps_leaf_count() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner1 = Address::generate(&env);
+        let owner2 = Address::generate(&env);
+        let cert1 = String::from_str(&env, "CERT001");
+        let cert2 = String::from_str(&env, "CERT002");
+        let hash1 = String::from_str(&env, "QmHash123");
+        let hash2 = String::from_str(&env, "QmHash456");
+
+        let leaf1 = merkle_leaf(&env, &cert1, &hash1, &owner1);
+        let leaf2 = merkle_leaf(&env, &cert2, &hash2, &owner2);
+        let root = merkle_parent(&env, &leaf1, &leaf2);
+
+        client.init(&admin);
+        // Declared as a single-leaf batch although the tree holds two
+        client.publish_root(&root, &1);
+
+        let mut proof = Vec::new(&env);
+        proof.push_back(leaf2);
+        client.claim_from_root(&proof, &cert1, &hash1, &owner1);
+        let mut proof2 = Vec::new(&env);
+        proof2.push_back(leaf1);
+        client.claim_from_root(&proof2, &cert2, &hash2, &owner2);
+    }
+
+    /// Test hashing raw metadata on-chain against the stored digest
+    #[test]
+    fn test_verify_metadata() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+        let metadata = Bytes::from_slice(&env, b"abc");
+        // sha256("abc")
+        let digest = String::from_str(
+            &env,
+            "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD",
+        );
+
+        client.init(&admin);
+        client.issue_certificate(&cert_id, &digest, &owner);
+
+        assert!(client.verify_metadata(&cert_id, &metadata));
+        assert!(!client.verify_metadata(&cert_id, &Bytes::from_slice(&env, b"abd")));
+
+        client.revoke(&cert_id);
+        assert!(!client.verify_metadata(&cert_id, &metadata));
+    }
+
+    /// Test binding an NFC tag at issuance and resolving it back
+    #[test]
+    fn test_tag_binding() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+        let hash = String::from_str(&env, "QmHash123");
+        let tag_id = BytesN::from_array(&env, &[7u8; 32]);
+        let options = IssueOptions { tag_id: Some(tag_id.clone()), ..Default::default() };
+
+        client.init(&admin);
+        client.issue_with_options(&cert_id, &hash, &owner, &options);
+
+        assert!(client.verify_tag(&cert_id, &tag_id));
+        assert!(!client.verify_tag(&cert_id, &BytesN::from_array(&env, &[8u8; 32])));
+        assert_eq!(client.get_cert_by_tag(&tag_id), Some(cert_id.clone()));
+
+        // A chip can only back one certificate
+        let other = String::from_str(&env, "CERT002");
+        assert!(client.try_issue_with_options(&other, &String::from_str(&env, "QmHash456"), &owner, &options).is_err());
+
+        client.burn(&cert_id);
+        assert_eq!(client.get_cert_by_tag(&tag_id), None);
+        assert!(!client.verify_tag(&cert_id, &tag_id));
+    }
+
+    /// Test linking a set and moving it with the parent
+    #[test]
+    fn test_parent_child_sets() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner1 = Address::generate(&env);
+        let owner2 = Address::generate(&env);
+        let bag = String::from_str(&env, "BAG001");
+        let wallet = String::from_str(&env, "WALLET001");
+        let hash = String::from_str(&env, "QmHash123");
+
+        client.init(&admin);
+        client.issue_certificate(&bag, &hash, &owner1);
+        client.issue_certificate(&wallet, &String::from_str(&env, "QmHash456"), &owner1);
+
+        client.link_child(&bag, &wallet);
+        assert_eq!(client.get_children(&bag).len(), 1);
+        assert_eq!(client.get_parent(&wallet), Some(bag.clone()));
+
+        // Sets can't loop back on themselves
+        assert!(client.try_link_child(&wallet, &bag).is_err());
+
+        // Without the rule, children stay behind
+        client.transfer(&bag, &owner2, &None, &None, &None);
+        assert_eq!(client.owner_of(&wallet), owner1);
+        client.transfer(&bag, &owner1, &None, &None, &None);
+
+        client.set_transfer_children(&bag, &true);
+        client.transfer(&bag, &owner2, &None, &None, &None);
+        assert_eq!(client.owner_of(&wallet), owner2);
+        assert_eq!(client.balance_of(&owner2), 2);
+        assert_eq!(client.get_history(&wallet).len(), 1);
+
+        client.unlink_child(&bag, &wallet);
+        assert_eq!(client.get_parent(&wallet), None);
+        assert_eq!(client.get_children(&bag).len(), 0);
+    }
+
+    /// Test issuing into a collection and listing its certificates
+    #[test]
+    fn test_collections() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let hash = String::from_str(&env, "QmHash123");
+        let cert1 = String::from_str(&env, "MAISON-001");
+        let cert2 = String::from_str(&env, "MAISON-002");
+        let cert3 = String::from_str(&env, "MAISON-003");
+
+        let issuer = Address::generate(&env);
+        client.init(&admin);
+        client.register_issuer(&issuer, &String::from_str(&env, "Maison"), &String::from_str(&env, "MAISON-"));
+        let collection_id = client.create_collection(&issuer, &Collection {
+            brand: String::from_str(&env, "Maison"),
+            season: String::from_str(&env, "FW24"),
+            description: String::from_str(&env, "Runway handbags"),
+        });
+        let options = IssueOptions { collection_id: Some(collection_id), ..Default::default() };
+        client.issue_as_issuer(&issuer, &cert1, &String::from_str(&env, "QmHash1"), &owner, &options);
+        client.issue_as_issuer(&issuer, &cert2, &String::from_str(&env, "QmHash2"), &owner, &options);
+        client.issue_as_issuer(&issuer, &cert3, &String::from_str(&env, "QmHash3"), &owner, &options);
+
+        assert_eq!(client.get_cert_collection(&cert2), Some(collection_id));
+        assert_eq!(client.list_by_collection(&collection_id, &0, &10).len(), 3);
+        assert_eq!(client.list_by_collection(&collection_id, &1, &1).get(0), Some(cert2.clone()));
+
+        // Burning keeps the listing dense
+        client.burn(&cert1);
+        let remaining = client.list_by_collection(&collection_id, &0, &10);
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining.contains(&cert2) && remaining.contains(&cert3));
+
+        let missing = IssueOptions { collection_id: Some(99), ..Default::default() };
+        let cert4 = String::from_str(&env, "CERT004");
+        assert!(client.try_issue_with_options(&cert4, &hash, &owner, &missing).is_err());
+
+        // Not even the admin can issue into an issuer's collection
+        assert!(client.try_issue_with_options(&cert4, &hash, &owner, &options).is_err());
+
+        // The collection belongs to the issuer that created it
+        assert_eq!(client.get_collection_issuer(&collection_id), Some(issuer.clone()));
+        let update = Collection {
+            brand: String::from_str(&env, "Maison"),
+            season: String::from_str(&env, "FW24"),
+            description: String::from_str(&env, "Runway and resort handbags"),
+        };
+        client.update_collection(&collection_id, &update);
+        assert_eq!(client.get_collection(&collection_id), Some(update.clone()));
+
+        let rival = Address::generate(&env);
+        client.register_issuer(&rival, &String::from_str(&env, "Rival"), &String::from_str(&env, "RIVAL-"));
+        let rival_cert = String::from_str(&env, "RIVAL-001");
+        assert!(client.try_issue_as_issuer(&rival, &rival_cert, &hash, &owner, &options).is_err());
+
+        // Only registered, active issuers run collections
+        assert!(client.try_create_collection(&Address::generate(&env), &update).is_err());
+        client.deactivate_issuer(&issuer, &false);
+        assert!(client.try_create_collection(&issuer, &update).is_err());
+        assert!(client.try_update_collection(&collection_id, &update).is_err());
+    }
+
+    /// Test service centers adding records to a certificate
+    #[test]
+    fn test_service_records() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let center = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+        let report = String::from_str(&env, "QmReport1");
+
+        client.init(&admin);
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &owner);
+
+        // Unlisted shops can't write to the record
+        assert!(client
+            .try_add_service_record(&center, &cert_id, &symbol_short!("OVERHAUL"), &report)
+            .is_err());
+
+        client.set_role(&center, &Role::ServiceCenter, &true);
+        client.add_service_record(&center, &cert_id, &symbol_short!("OVERHAUL"), &report);
+        client.add_service_record(&center, &cert_id, &symbol_short!("POLISH"), &report);
+
+        let records = client.get_service_history(&cert_id);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records.get(0).unwrap().service_type, symbol_short!("OVERHAUL"));
+        assert_eq!(records.get(1).unwrap().center, center);
+    }
+
+    /// Test licensed appraisers building a valuation history
+    #[test]
+    fn test_appraisals() {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(1_700_000_000);
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let appraiser = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+        let report = String::from_str(&env, "QmAppraisal1");
+
+        client.init(&admin);
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &owner);
+        client.set_role(&appraiser, &Role::Appraiser, &true);
+
+        client.add_appraisal(&appraiser, &cert_id, &450_000, &symbol_short!("USD"), &1_600_000_000, &report);
+        client.add_appraisal(&appraiser, &cert_id, &520_000, &symbol_short!("USD"), &1_690_000_000, &report);
+        assert!(client
+            .try_add_appraisal(&appraiser, &cert_id, &1, &symbol_short!("USD"), &1_800_000_000, &report)
+            .is_err());
+
+        let appraisals = client.get_appraisals(&cert_id);
+        assert_eq!(appraisals.len(), 2);
+        assert_eq!(appraisals.get(1).unwrap().value, 520_000);
+
+        // Withdrawn appraisers can't add valuations
+        client.set_role(&appraiser, &Role::Appraiser, &false);
+        assert!(client
+            .try_add_appraisal(&appraiser, &cert_id, &1, &symbol_short!("USD"), &1_600_000_000, &report)
+            .is_err());
+    }
+
+    /// Test warranty status moving from active to expired to revoked
+    #[test]
+    fn test_warranty_status() {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(1_000);
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let hash = String::from_str(&env, "QmHash123");
+        let cert_id = String::from_str(&env, "CERT001");
+        let plain_id = String::from_str(&env, "CERT002");
+
+        client.init(&admin);
+        let options = IssueOptions { warranty_expires_at: Some(2_000), ..Default::default() };
+        client.issue_with_options(&cert_id, &hash, &owner, &options);
+        client.issue_certificate(&plain_id, &String::from_str(&env, "QmHash456"), &owner);
+
+        assert_eq!(client.warranty_status(&cert_id), WarrantyStatus::Active);
+        assert_eq!(client.warranty_status(&plain_id), WarrantyStatus::NoWarranty);
+
+        // Out of warranty is still authentic
+        env.ledger().set_timestamp(2_000);
+        assert_eq!(client.warranty_status(&cert_id), WarrantyStatus::Expired);
+        assert!(client.verify(&cert_id, &hash));
+
+        client.set_warranty(&cert_id, &3_000);
+        assert_eq!(client.warranty_status(&cert_id), WarrantyStatus::Active);
+
+        client.revoke(&cert_id);
+        assert_eq!(client.warranty_status(&cert_id), WarrantyStatus::Revoked);
+    }
+
+    /// Test issuance fees flowing to the treasury
+    #[test]
+    fn test_issuance_fee() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        let hash = String::from_str(&env, "QmHash123");
+        let hash2 = String::from_str(&env, "QmHash456");
+
+        let asset = env.register_stellar_asset_contract_v2(admin.clone());
+        token::StellarAssetClient::new(&env, &asset.address()).mint(&admin, &150);
+        let token_client = token::Client::new(&env, &asset.address());
+
+        client.init(&admin);
+        client.set_issuance_fee(&asset.address(), &100, &treasury);
+
+        client.issue_certificate(&String::from_str(&env, "CERT001"), &hash, &owner);
+        assert_eq!(token_client.balance(&treasury), 100);
+        assert_eq!(token_client.balance(&admin), 50);
+
+        // Issuance fails when the issuer can't cover the fee
+        let cert2 = String::from_str(&env, "CERT002");
+        assert!(client.try_issue_certificate(&cert2, &hash2, &owner).is_err());
+        assert!(!client.certificate_exists(&cert2));
+
+        client.set_issuance_fee(&asset.address(), &0, &treasury);
+        client.issue_certificate(&cert2, &hash2, &owner);
+        assert_eq!(client.get_issuance_fee(), None);
+    }
+
+    /// Test compliance mode only lets certificates reach allowlisted addresses
+    #[test]
+    fn test_allowlist_mode() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner1 = Address::generate(&env);
+        let owner2 = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+
+        client.init(&admin);
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &owner1);
+        client.set_allowlist_mode(&true);
+
+        assert!(client.try_transfer(&cert_id, &owner2, &None, &None, &None).is_err());
+
+        client.add_to_allowlist(&owner2);
+        client.transfer(&cert_id, &owner2, &None, &None, &None);
+        assert_eq!(client.owner_of(&cert_id), owner2);
+
+        client.remove_from_allowlist(&owner2);
+        assert!(!client.is_allowlisted(&owner2));
+    }
+
+    /// Test resolving a certificate from its metadata hash
+    #[test]
+    fn test_find_by_metadata_hash() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+        let hash = String::from_str(&env, "QmHash123");
+
+        client.init(&admin);
+        assert_eq!(client.find_by_metadata_hash(&hash).len(), 0);
+
+        client.issue_certificate(&cert_id, &hash, &owner);
+        let found = client.find_by_metadata_hash(&hash);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found.get(0), Some(cert_id.clone()));
+
+        client.burn(&cert_id);
+        assert_eq!(client.find_by_metadata_hash(&hash).len(), 0);
+    }
+
+    /// Test a metadata hash can only be certified twice on purpose
+    #[test]
+    fn test_duplicate_metadata_hash() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let hash = String::from_str(&env, "QmHash123");
+        let cert2 = String::from_str(&env, "CERT002");
+
+        client.init(&admin);
+        client.issue_certificate(&String::from_str(&env, "CERT001"), &hash, &owner);
+
+        assert!(client.try_issue_certificate(&cert2, &hash, &owner).is_err());
+
+        let options = IssueOptions { allow_duplicate_hash: true, ..Default::default() };
+        client.issue_with_options(&cert2, &hash, &owner, &options);
+        assert_eq!(client.find_by_metadata_hash(&hash).len(), 2);
+    }
+
+    /// Test issuers are confined to their own ID prefix
+    #[test]
+    fn test_issuer_namespaces() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let brand_a = Address::generate(&env);
+        let brand_b = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let options = IssueOptions::default();
+
+        client.init(&admin);
+        client.register_issuer(&brand_a, &String::from_str(&env, "Brand A"), &String::from_str(&env, "BA-"));
+        client.register_issuer(&brand_b, &String::from_str(&env, "Brand B"), &String::from_str(&env, "BB-"));
+
+        // Overlapping ranges can't be reserved, whichever prefix is longer
+        let brand_c = Address::generate(&env);
+        for prefix in ["BA-1", "BA-", "B"] {
+            assert!(client
+                .try_register_issuer(&brand_c, &String::from_str(&env, "Brand C"), &String::from_str(&env, prefix))
+                .is_err());
+        }
+
+        // Sharing a stem isn't overlapping, and prefixes may differ in length
+        client.register_issuer(&brand_c, &String::from_str(&env, "Brand C"), &String::from_str(&env, "BCOUTURE-"));
+        let long_id = String::from_str(&env, "BCOUTURE-0001");
+        assert!(client.try_issue_as_issuer(&brand_a, &long_id, &String::from_str(&env, "QmHash3"), &owner, &options).is_err());
+        client.issue_as_issuer(&brand_c, &long_id, &String::from_str(&env, "QmHash3"), &owner, &options);
+
+        let own_id = String::from_str(&env, "BA-0001");
+        client.issue_as_issuer(&brand_a, &own_id, &String::from_str(&env, "QmHash1"), &owner, &options);
+        assert!(client.certificate_exists(&own_id));
+
+        // Brand A can't squat brand B's range, and neither can the admin
+        let squatted = String::from_str(&env, "BB-0001");
+        let hash = String::from_str(&env, "QmHash2");
+        assert!(client.try_issue_as_issuer(&brand_a, &squatted, &hash, &owner, &options).is_err());
+        assert!(client.try_issue_certificate(&squatted, &hash, &owner).is_err());
+
+        // IDs outside every reserved range stay with the admin
+        client.issue_certificate(&String::from_str(&env, "CERT001"), &hash, &owner);
+    }
+
+    /// Test malformed certificate IDs are rejected with typed errors
+    #[test]
+    fn test_cert_id_validation() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let hash = String::from_str(&env, "QmHash123");
+
+        client.init(&admin);
+
+        let short = String::from_str(&env, "C1");
+        assert_eq!(
+            client.try_issue_certificate(&short, &hash, &owner),
+            Err(Ok(Error::InvalidCertIdLength.into()))
+        );
+        let long = String::from_str(&env, "CERT-0000000000000000000000000000000000000000000000000000000000001");
+        assert_eq!(
+            client.try_issue_certificate(&long, &hash, &owner),
+            Err(Ok(Error::InvalidCertIdLength.into()))
+        );
+        let spaced = String::from_str(&env, "CERT 001");
+        assert_eq!(
+            client.try_issue_certificate(&spaced, &hash, &owner),
+            Err(Ok(Error::InvalidCertIdChar.into()))
+        );
+
+        client.issue_certificate(&String::from_str(&env, "BRAND-FW24_001.A"), &hash, &owner);
+    }
+
+    /// Test init can only run once
+    #[test]
+    #[should_panic(expected = "Contract already initialized")]
+    fn test_init_twice_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        client.init(&Address::generate(&env));
+        client.init(&Address::generate(&env));
+    }
+
+    /// Test migrating a v1 deployment to the current schema
+    #[test]
+    fn test_migrate_from_v1() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let cert1 = String::from_str(&env, "CERT001");
+        let cert2 = String::from_str(&env, "CERT002");
+        let hash = String::from_str(&env, "QmHash123");
+
+        // Simulate a v1 deployment holding two certificates in the instance map
+        env.as_contract(&contract_id, || {
+            let mut certs: Map<String, Certificate> = Map::new(&env);
+            for cert_id in [cert1.clone(), cert2.clone()] {
+                certs.set(cert_id, Certificate {
+                    owner: owner.clone(),
+                    metadata_hash: hash.clone(),
+                    is_valid: true,
+                });
+            }
+            env.storage().instance().set(&ADMIN_KEY, &admin);
+            env.storage().instance().set(&SCHEMA_KEY, &1u32);
+            env.storage().instance().set(&CERTS_KEY, &certs);
+        });
+
+        assert_eq!(client.migrate(), STORAGE_VERSION);
+
+        env.as_contract(&contract_id, || {
+            assert!(!env.storage().instance().has(&CERTS_KEY));
+            assert!(env.storage().persistent().has(&(CERTS_KEY, cert1.clone())));
+        });
+        assert!(client.verify(&cert2, &hash));
+        assert_eq!(client.balance_of(&owner), 2);
+        assert_eq!(client.find_by_metadata_hash(&hash).len(), 2);
+
+        // Running it again has nothing to do
+        assert!(client.try_migrate().is_err());
+    }
+
+    /// Test a dispute freezes transfers until the admin rules on it
+    #[test]
+    fn test_dispute_workflow() {
+        let env = Env::default();
         env.mock_all_auths();
         let contract_id = env.register(FashionAuthContract, ());
         let client = FashionAuthContractClient::new(&env, &contract_id);
 
         let admin = Address::generate(&env);
-        let owner = Address::generate(&env);
+        let owner1 = Address::generate(&env);
+        let owner2 = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+        let reason = String::from_str(&env, "QmReason1");
+        let ruling = String::from_str(&env, "QmRuling1");
 
-        // Initialize contract
         client.init(&admin);
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &owner1);
 
-        // Verify admin is set correctly
-        assert_eq!(client.get_admin(), admin);
+        client.open_dispute(&cert_id, &reason);
+        assert_eq!(client.get_status(&cert_id), CertStatus::Disputed);
+        assert!(client.try_transfer(&cert_id, &owner2, &None, &None, &None).is_err());
 
-        // Issue a certificate
-        client.issue_certificate(
-            &String::from_str(&env, "CERT001"),
-            &String::from_str(&env, "QmHash123"),
-            &owner,
-        );
+        client.resolve_dispute(&cert_id, &DisputeOutcome::Valid, &ruling);
+        assert_eq!(client.get_status(&cert_id), CertStatus::Valid);
+        client.transfer(&cert_id, &owner2, &None, &None, &None);
 
-        // Verify certificate exists
-        assert!(client.certificate_exists(&String::from_str(&env, "CERT001")));
-        
-        // Verify certificate details
-        let cert = client.get_certificate_details(&String::from_str(&env, "CERT001"));
-        assert_eq!(cert.owner, owner);
-        assert_eq!(cert.metadata_hash, String::from_str(&env, "QmHash123"));
-        assert!(cert.is_valid);
+        client.open_dispute(&cert_id, &reason);
+        client.resolve_dispute(&cert_id, &DisputeOutcome::Revoked, &ruling);
+        assert_eq!(client.get_status(&cert_id), CertStatus::Revoked);
+
+        let disputes = client.get_disputes(&cert_id);
+        assert_eq!(disputes.len(), 2);
+        let expected = Ruling {
+            outcome: DisputeOutcome::Revoked,
+            ruling_hash: ruling,
+            resolved_at: env.ledger().timestamp(),
+        };
+        assert_eq!(disputes.get(1).unwrap().status, DisputeStatus::Resolved(expected));
     }
 
-    /// Test certificate verification functionality
+    /// Test a disputed accessory holds back its bundle, and can't be burned
     #[test]
-    fn test_verify_certificate() {
+    fn test_dispute_blocks_bundle_and_burn() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner1 = Address::generate(&env);
+        let owner2 = Address::generate(&env);
+        let bag = String::from_str(&env, "BAG001");
+        let wallet = String::from_str(&env, "WALLET001");
+        let reason = String::from_str(&env, "QmReason1");
+
+        client.init(&admin);
+        client.issue_certificate(&bag, &String::from_str(&env, "QmHash123"), &owner1);
+        client.issue_certificate(&wallet, &String::from_str(&env, "QmHash456"), &owner1);
+        client.link_child(&bag, &wallet);
+        client.set_transfer_children(&bag, &true);
+
+        client.open_dispute(&wallet, &reason);
+        assert!(client.try_transfer(&bag, &owner2, &None, &None, &None).is_err());
+        assert_eq!(client.owner_of(&bag), owner1);
+        assert_eq!(client.owner_of(&wallet), owner1);
+        assert!(client.try_burn(&wallet).is_err());
+
+        client.resolve_dispute(&wallet, &DisputeOutcome::Valid, &String::from_str(&env, "QmRuling1"));
+        client.transfer(&bag, &owner2, &None, &None, &None);
+        assert_eq!(client.owner_of(&wallet), owner2);
+
+        // A revoked accessory can't ride along either
+        client.revoke(&wallet);
+        assert!(client.try_transfer(&bag, &owner1, &None, &None, &None).is_err());
+    }
+
+    /// Test recording item attributes and finding a certificate by serial
+    #[test]
+    fn test_item_attributes() {
         let env = Env::default();
         env.mock_all_auths();
         let contract_id = env.register(FashionAuthContract, ());
@@ -299,65 +5306,191 @@ mod test {
 
         let admin = Address::generate(&env);
         let owner = Address::generate(&env);
+        let cert1 = String::from_str(&env, "CERT001");
+        let cert2 = String::from_str(&env, "CERT002");
+        let brand = String::from_str(&env, "Maison");
+        let serial = String::from_str(&env, "SN-48213");
 
         client.init(&admin);
-        client.issue_certificate(
-            &String::from_str(&env, "CERT001"),
-            &String::from_str(&env, "QmHash123"),
-            &owner,
+        client.issue_certificate(&cert1, &String::from_str(&env, "QmHash1"), &owner);
+        client.issue_certificate(&cert2, &String::from_str(&env, "QmHash2"), &owner);
+
+        let attributes = ItemAttributes {
+            brand: brand.clone(),
+            model: String::from_str(&env, "Classic Flap"),
+            serial_number: serial.clone(),
+            material: String::from_str(&env, "Lambskin"),
+            manufacture_year: 2021,
+        };
+        client.set_attributes(&admin, &cert1, &attributes);
+        assert_eq!(
+            env.events().all(),
+            vec![
+                &env,
+                (
+                    contract_id.clone(),
+                    (symbol_short!("attrs"), cert1.clone()).into_val(&env),
+                    attributes.clone().into_val(&env),
+                ),
+            ]
         );
 
-        // Valid verification should return true
-        assert!(client.verify(
-            &String::from_str(&env, "CERT001"),
-            &String::from_str(&env, "QmHash123")
-        ));
+        assert_eq!(client.get_attributes(&cert1), Some(attributes.clone()));
+        assert_eq!(client.find_by_serial(&brand, &serial), Some(cert1.clone()));
 
-        // Wrong metadata hash should return false
-        assert!(!client.verify(
-            &String::from_str(&env, "CERT001"),
-            &String::from_str(&env, "WrongHash")
-        ));
+        // A serial can't be attached to a second certificate
+        assert!(client.try_set_attributes(&admin, &cert2, &attributes).is_err());
 
-        // Non-existent certificate should return false
-        assert!(!client.verify(
-            &String::from_str(&env, "CERT999"),
-            &String::from_str(&env, "QmHash123")
-        ));
+        // Strangers can't describe certificates
+        let stranger = Address::generate(&env);
+        assert!(client.try_set_attributes(&stranger, &cert1, &attributes).is_err());
+
+        client.burn(&cert1);
+        assert_eq!(client.find_by_serial(&brand, &serial), None);
     }
 
-    /// Test certificate ownership transfer
+    /// Test tuning the transfer cooldown and batch size through the config
     #[test]
-    fn test_transfer_certificate() {
+    fn test_config() {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(1_000);
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner1 = Address::generate(&env);
+        let owner2 = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+
+        client.init(&admin);
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &owner1);
+
+        let mut config = client.get_config();
+        assert_eq!(config.ttl_extend_to, TTL_EXTEND_TO);
+        config.transfer_cooldown = 3_600;
+        config.max_batch_size = 10;
+        client.set_config(&config);
+        assert_eq!(client.get_config(), config);
+
+        client.transfer(&cert_id, &owner2, &None, &None, &None);
+        assert!(client.try_transfer(&cert_id, &owner1, &None, &None, &None).is_err());
+
+        env.ledger().set_timestamp(1_000 + 3_600);
+        client.transfer(&cert_id, &owner1, &None, &None, &None);
+
+        // A fee needs a token before it can be charged
+        config.issuance_fee = 100;
+        assert!(client.try_set_config(&config).is_err());
+    }
+
+    /// Test a buyer self-claiming a certificate with the code from the box
+    #[test]
+    fn test_claim_with_code() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let buyer = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+        let code = Bytes::from_slice(&env, b"K7Q2-9XW4-PL3M");
+        let claim_hash = env.crypto().sha256(&code).to_bytes();
+
+        client.init(&admin);
+        client.issue_claimable(&cert_id, &String::from_str(&env, "QmHash123"), &claim_hash);
+        assert!(client.is_claimable(&cert_id));
+        assert_eq!(client.owner_of(&cert_id), contract_id);
 
+        let wrong = Bytes::from_slice(&env, b"K7Q2-9XW4-PL3N");
+        assert!(client.try_claim(&buyer, &cert_id, &wrong).is_err());
+
+        client.claim(&buyer, &cert_id, &code);
+        assert_eq!(client.owner_of(&cert_id), buyer);
+        assert_eq!(client.balance_of(&buyer), 1);
+        assert!(!client.is_claimable(&cert_id));
+
+        // Codes are single-use
+        let other = Address::generate(&env);
+        assert!(client.try_claim(&other, &cert_id, &code).is_err());
+    }
+
+    /// Test suspending a compromised issuer's certificates pending review
+    #[test]
+    fn test_issuer_deactivation_suspends_certificates() {
         let env = Env::default();
         env.mock_all_auths();
         let contract_id = env.register(FashionAuthContract, ());
         let client = FashionAuthContractClient::new(&env, &contract_id);
 
         let admin = Address::generate(&env);
+        let brand = Address::generate(&env);
         let owner1 = Address::generate(&env);
         let owner2 = Address::generate(&env);
+        let options = IssueOptions::default();
+        let cert1 = String::from_str(&env, "BA-0001");
+        let cert2 = String::from_str(&env, "BA-0002");
+        let hash1 = String::from_str(&env, "QmHash1");
+        let hash2 = String::from_str(&env, "QmHash2");
 
         client.init(&admin);
-        client.issue_certificate(
-            &String::from_str(&env, "CERT001"),
-            &String::from_str(&env, "QmHash123"),
-            &owner1,
-        );
+        client.register_issuer(&brand, &String::from_str(&env, "Brand A"), &String::from_str(&env, "BA-"));
+        client.issue_as_issuer(&brand, &cert1, &hash1, &owner1, &options);
+        client.issue_as_issuer(&brand, &cert2, &hash2, &owner1, &options);
 
-        // Transfer certificate to new owner
-        client.transfer(&String::from_str(&env, "CERT001"), &owner2);
+        client.deactivate_issuer(&brand, &true);
+        assert_eq!(client.get_issuer_status(&brand), IssuerStatus::Suspended);
+        assert_eq!(client.get_status(&cert1), CertStatus::Suspended);
+        assert!(!client.verify(&cert1, &hash1));
+        assert!(client.try_transfer(&cert1, &owner2, &None, &None, &None).is_err());
 
-        // Verify ownership change
-        let cert = client.get_certificate_details(&String::from_str(&env, "CERT001"));
-        assert_eq!(cert.owner, owner2);
-        assert!(cert.is_valid); // Should still be valid
+        // The compromised key can't mint more
+        let cert3 = String::from_str(&env, "BA-0003");
+        let hash3 = String::from_str(&env, "QmHash3");
+        assert!(client.try_issue_as_issuer(&brand, &cert3, &hash3, &owner1, &options).is_err());
+
+        // Reviewed certificates come back one at a time
+        client.clear_suspension(&cert1);
+        assert!(client.verify(&cert1, &hash1));
+        assert!(!client.verify(&cert2, &hash2));
+
+        client.reactivate_issuer(&brand);
+        assert!(client.verify(&cert2, &hash2));
     }
 
-    /// Test certificate revocation
+    /// Test transfer velocity is reported with the certificate details
     #[test]
-    fn test_revoke_certificate() {
+    fn test_transfer_stats() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner1 = Address::generate(&env);
+        let owner2 = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+
+        client.init(&admin);
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &owner1);
+
+        let details = client.get_certificate_details(&cert_id);
+        assert_eq!(details.transfer_count, 0);
+        assert_eq!(details.last_transfer_ledger, 0);
+
+        client.transfer(&cert_id, &owner2, &None, &None, &None);
+        env.ledger().set_sequence_number(42);
+        client.transfer(&cert_id, &owner1, &None, &None, &None);
+
+        let details = client.get_certificate_details(&cert_id);
+        assert_eq!(details.transfer_count, 2);
+        assert_eq!(details.last_transfer_ledger, 42);
+    }
+
+    /// Test validity can be checked as of a past ledger
+    #[test]
+    fn test_was_valid_at() {
         let env = Env::default();
         env.mock_all_auths();
         let contract_id = env.register(FashionAuthContract, ());
@@ -365,32 +5498,53 @@ mod test {
 
         let admin = Address::generate(&env);
         let owner = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
 
         client.init(&admin);
-        client.issue_certificate(
-            &String::from_str(&env, "CERT001"),
-            &String::from_str(&env, "QmHash123"),
-            &owner,
-        );
+        env.ledger().set_sequence_number(100);
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &owner);
+        env.ledger().set_sequence_number(200);
+        client.revoke(&cert_id);
 
-        // Revoke certificate
-        client.revoke(&String::from_str(&env, "CERT001"));
+        assert!(!client.was_valid_at(&cert_id, &99));
+        assert!(client.was_valid_at(&cert_id, &100));
+        assert!(client.was_valid_at(&cert_id, &199));
+        assert!(!client.was_valid_at(&cert_id, &200));
+    }
 
-        // Verify certificate is marked invalid
-        let cert = client.get_certificate_details(&String::from_str(&env, "CERT001"));
-        assert!(!cert.is_valid);
+    /// Test appending and reading typed attachments
+    #[test]
+    fn test_attachments() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
 
-        // Verify verification now fails for revoked certificate
-        assert!(!client.verify(
-            &String::from_str(&env, "CERT001"),
-            &String::from_str(&env, "QmHash123")
-        ));
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+
+        client.init(&admin);
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &owner);
+
+        client.add_attachment(&admin, &cert_id, &symbol_short!("PHOTO"), &String::from_str(&env, "QmFront"));
+        client.add_attachment(&admin, &cert_id, &symbol_short!("PHOTO"), &String::from_str(&env, "QmBack"));
+        client.add_attachment(&admin, &cert_id, &symbol_short!("RECEIPT"), &String::from_str(&env, "QmReceipt"));
+
+        let photos = client.get_attachments(&cert_id, &symbol_short!("PHOTO"));
+        assert_eq!(photos.len(), 2);
+        assert_eq!(photos.get(1), Some(String::from_str(&env, "QmBack")));
+        assert_eq!(client.get_attachments(&cert_id, &symbol_short!("CARE")).len(), 0);
+        assert_eq!(client.get_all_attachments(&cert_id).len(), 2);
+
+        // Owners can't attach documents themselves
+        let kind = symbol_short!("PHOTO");
+        assert!(client.try_add_attachment(&owner, &cert_id, &kind, &String::from_str(&env, "QmFake")).is_err());
     }
 
-    /// Test error cases
+    /// Test limited editions are numbered and capped at their declared size
     #[test]
-    #[should_panic(expected = "Certificate already exists")]
-    fn test_duplicate_certificate_id() {
+    fn test_limited_edition() {
         let env = Env::default();
         env.mock_all_auths();
         let contract_id = env.register(FashionAuthContract, ());
@@ -398,48 +5552,225 @@ mod test {
 
         let admin = Address::generate(&env);
         let owner = Address::generate(&env);
+        let cert1 = String::from_str(&env, "MAISON-001");
+        let cert2 = String::from_str(&env, "MAISON-002");
+        let cert3 = String::from_str(&env, "MAISON-003");
 
+        let issuer = Address::generate(&env);
         client.init(&admin);
-        
-        // Issue first certificate
-        client.issue_certificate(
-            &String::from_str(&env, "CERT001"),
-            &String::from_str(&env, "QmHash123"),
-            &owner,
-        );
+        client.register_issuer(&issuer, &String::from_str(&env, "Maison"), &String::from_str(&env, "MAISON-"));
+        let collection_id = client.create_collection(&issuer, &Collection {
+            brand: String::from_str(&env, "Maison"),
+            season: String::from_str(&env, "SS25"),
+            description: String::from_str(&env, "Numbered capsule"),
+        });
+        client.set_edition_size(&collection_id, &2);
 
-        // Try to issue duplicate - should panic
-        client.issue_certificate(
-            &String::from_str(&env, "CERT001"),
-            &String::from_str(&env, "QmHash456"),
-            &owner,
-        );
+        let options = IssueOptions { collection_id: Some(collection_id), ..Default::default() };
+        client.issue_as_issuer(&issuer, &cert1, &String::from_str(&env, "QmHash1"), &owner, &options);
+        client.issue_as_issuer(&issuer, &cert2, &String::from_str(&env, "QmHash2"), &owner, &options);
+
+        let edition = client.get_edition(&cert2).unwrap();
+        assert_eq!(edition.edition_number, 2);
+        assert_eq!(edition.edition_total, 2);
+
+        // The edition is full, even after a burn
+        client.burn(&cert1);
+        let hash3 = String::from_str(&env, "QmHash3");
+        assert!(client.try_issue_as_issuer(&issuer, &cert3, &hash3, &owner, &options).is_err());
+        assert!(client.try_set_edition_size(&collection_id, &3).is_err());
     }
 
-    /// Test transferring revoked certificate fails
+    /// Test the admin can't spend an issuer's limited edition numbers
     #[test]
-    #[should_panic(expected = "Cannot transfer invalid certificate")]
-    fn test_transfer_revoked_certificate() {
+    #[should_panic(expected = "Collection belongs to another issuer")]
+    fn test_admin_cannot_fill_issuer_edition() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let issuer = Address::generate(&env);
+        client.init(&admin);
+        client.register_issuer(&issuer, &String::from_str(&env, "Maison"), &String::from_str(&env, "MAISON-"));
+        let collection_id = client.create_collection(&issuer, &Collection {
+            brand: String::from_str(&env, "Maison"),
+            season: String::from_str(&env, "SS25"),
+            description: String::from_str(&env, "Numbered capsule"),
+        });
+        client.set_edition_size(&collection_id, &2);
+
+        let options = IssueOptions { collection_id: Some(collection_id), ..Default::default() };
+        client.issue_with_options(&String::from_str(&env, "CERT001"), &String::from_str(&env, "QmHash1"), &owner, &options);
+    }
+
+    /// Policy used by tests: blocks transfers to one configured address
+    #[contract]
+    struct BlockRecipientPolicy;
+
+    #[contractimpl]
+    impl BlockRecipientPolicy {
+        pub fn check_issue(_env: Env, _cert_id: String, _owner: Address) -> bool {
+            true
+        }
+
+        pub fn check_transfer(env: Env, _cert_id: String, _from: Address, to: Address) -> bool {
+            let blocked: Option<Address> = env.storage().instance().get(&symbol_short!("BLOCKED"));
+            blocked != Some(to)
+        }
+    }
+
+    /// Test the registered policy contract can veto transfers
+    #[test]
+    fn test_policy_contract_hook() {
         let env = Env::default();
         env.mock_all_auths();
         let contract_id = env.register(FashionAuthContract, ());
         let client = FashionAuthContractClient::new(&env, &contract_id);
+        let policy_id = env.register(BlockRecipientPolicy, ());
 
         let admin = Address::generate(&env);
         let owner1 = Address::generate(&env);
         let owner2 = Address::generate(&env);
+        let blocked = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+
+        env.as_contract(&policy_id, || {
+            env.storage().instance().set(&symbol_short!("BLOCKED"), &blocked);
+        });
 
         client.init(&admin);
-        client.issue_certificate(
-            &String::from_str(&env, "CERT001"),
-            &String::from_str(&env, "QmHash123"),
-            &owner1,
+        client.set_policy(&Some(policy_id.clone()));
+        assert_eq!(client.get_policy(), Some(policy_id));
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &owner1);
+
+        assert!(client.try_transfer(&cert_id, &blocked, &None, &None, &None).is_err());
+        client.transfer(&cert_id, &owner2, &None, &None, &None);
+
+        client.set_policy(&None);
+        client.transfer(&cert_id, &blocked, &None, &None, &None);
+        assert_eq!(client.owner_of(&cert_id), blocked);
+    }
+
+    /// Test consensual transfers need the recipient's authorization
+    #[test]
+    fn test_transfer_with_consent() {
+        let env = Env::default();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner1 = Address::generate(&env);
+        let owner2 = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+
+        env.mock_all_auths();
+        client.init(&admin);
+        client.issue_certificate(&cert_id, &String::from_str(&env, "QmHash123"), &owner1);
+
+        client.transfer_with_consent(&cert_id, &owner2, &None, &None, &None);
+        let recipient_signed = env.auths().iter().any(|(address, _)| *address == owner2);
+        assert!(recipient_signed);
+        assert_eq!(client.owner_of(&cert_id), owner2);
+    }
+
+    /// Test lifecycle events are published for indexers
+    #[test]
+    fn test_lifecycle_events() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner1 = Address::generate(&env);
+        let owner2 = Address::generate(&env);
+        let cert_id = String::from_str(&env, "CERT001");
+        let metadata_hash = String::from_str(&env, "QmHash123");
+
+        client.init(&admin);
+        client.issue_certificate(&cert_id, &metadata_hash, &owner1);
+        assert_eq!(
+            env.events().all(),
+            vec![
+                &env,
+                (
+                    contract_id.clone(),
+                    (symbol_short!("issued"), cert_id.clone()).into_val(&env),
+                    (owner1.clone(), metadata_hash.clone()).into_val(&env),
+                ),
+            ]
         );
 
-        // Revoke certificate
-        client.revoke(&String::from_str(&env, "CERT001"));
+        client.transfer(&cert_id, &owner2, &None, &None, &None);
+        assert_eq!(
+            env.events().all(),
+            vec![
+                &env,
+                (
+                    contract_id.clone(),
+                    (symbol_short!("transfer"), cert_id.clone()).into_val(&env),
+                    (owner1.clone(), owner2.clone()).into_val(&env),
+                ),
+            ]
+        );
 
-        // Try to transfer revoked certificate - should panic
-        client.transfer(&String::from_str(&env, "CERT001"), &owner2);
+        client.revoke(&cert_id);
+        assert_eq!(
+            env.events().all(),
+            vec![
+                &env,
+                (
+                    contract_id.clone(),
+                    (symbol_short!("revoked"), cert_id.clone()).into_val(&env),
+                    ().into_val(&env),
+                ),
+            ]
+        );
+    }
+
+    /// Test issuing several certificates in one call
+    #[test]
+    fn test_issue_batch() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(FashionAuthContract, ());
+        let client = FashionAuthContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let owner1 = Address::generate(&env);
+        let owner2 = Address::generate(&env);
+        client.init(&admin);
+
+        let entry = |cert_id: &str, metadata_hash: &str, owner: &Address| BatchIssue {
+            cert_id: String::from_str(&env, cert_id),
+            metadata_hash: String::from_str(&env, metadata_hash),
+            owner: owner.clone(),
+        };
+
+        client.issue_batch(&vec![
+            &env,
+            entry("CERT001", "QmHash123", &owner1),
+            entry("CERT002", "QmHash456", &owner2),
+        ]);
+        assert_eq!(client.owner_of(&String::from_str(&env, "CERT001")), owner1);
+        assert!(client.verify(
+            &String::from_str(&env, "CERT002"),
+            &String::from_str(&env, "QmHash456")
+        ));
+
+        // One bad entry rejects the whole batch
+        assert!(client
+            .try_issue_batch(&vec![
+                &env,
+                entry("CERT003", "QmHash789", &owner1),
+                entry("CERT001", "QmHashABC", &owner1),
+            ])
+            .is_err());
+        assert!(!client.certificate_exists(&String::from_str(&env, "CERT003")));
+
+        assert!(client.try_issue_batch(&Vec::new(&env)).is_err());
     }
-}
\ No newline at end of file
+}